@@ -1,32 +1,150 @@
-#![feature(test)]
-
-extern crate test;
-use test::Bencher;
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use std::hint::black_box;
+use std::time::Duration;
 
 use marked_cycles::{
-    dynatomic_cover::DynatomicCover, lamination::Lamination, marked_cycle_cover::MarkedCycleCover,
+    common::{cycle_positions, cycle_reps, get_orbit},
+    dynatomic_cover::{DynatomicCover, DynatomicCoverBuilder},
+    global_state::Context,
+    lamination::Lamination,
+    marked_cycle_cover::{MarkedCycleCover, MarkedCycleCoverBuilder},
+    types::IntAngle,
 };
 
-#[bench]
-fn lamination(b: &mut Bencher)
+fn lamination(c: &mut Criterion)
 {
-    b.iter(|| {
-        let _ = Lamination::new().into_arcs_of_period(16);
+    c.bench_function("lamination", |b| {
+        b.iter(|| Lamination::new().into_arcs_of_period(16));
     });
+
+    // Critical period one takes a specialized path through the lamination; the others share
+    // the generic one
+    let mut group = c.benchmark_group("lamination_crit_period");
+    for crit_period in [1, 2, 3] {
+        group.bench_with_input(
+            BenchmarkId::from_parameter(crit_period),
+            &crit_period,
+            |b, &crit_period| {
+                b.iter(|| {
+                    Lamination::new()
+                        .with_crit_period(crit_period)
+                        .into_arcs_of_period(16)
+                });
+            },
+        );
+    }
+    group.finish();
+
+    // Adding the leaves of one period to a lamination already extended to the previous one
+    let mut group = c.benchmark_group("lamination_extension");
+    for period in [12, 14, 16] {
+        let mut lamination = Lamination::new();
+        lamination.extend_to_period(period - 1);
+        group.bench_with_input(BenchmarkId::from_parameter(period), &period, |b, &period| {
+            b.iter_batched(
+                || lamination.clone(),
+                |mut lamination| lamination.extend_to_period(period),
+                BatchSize::LargeInput,
+            );
+        });
+    }
+    group.finish();
 }
 
-#[bench]
-fn mc_curve(b: &mut Bencher)
+fn orbits(c: &mut Criterion)
 {
-    b.iter(|| {
-        let _curve = MarkedCycleCover::new(18, 1);
+    c.bench_function("orbits", |b| {
+        let _context = Context::new(16).enter();
+        b.iter(|| {
+            for angle in 0..1 << 12 {
+                black_box(get_orbit(IntAngle(angle)));
+            }
+        });
     });
 }
 
-#[bench]
-fn dynatomic(b: &mut Bencher)
+fn cycles(c: &mut Criterion)
+{
+    let mut group = c.benchmark_group("cycle_reps");
+    for period in [14, 16, 18] {
+        let context = Context::new(period);
+        group.bench_with_input(BenchmarkId::from_parameter(period), &context, |b, &context| {
+            b.iter(|| cycle_reps(context));
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("cycle_positions");
+    for period in [14, 16, 18] {
+        let context = Context::new(period);
+        group.bench_with_input(BenchmarkId::from_parameter(period), &context, |b, &context| {
+            b.iter(|| cycle_positions(context));
+        });
+    }
+    group.finish();
+}
+
+/// Time only the face-tracing phase of each build, as reported by `build_timed`
+fn faces(c: &mut Criterion)
 {
-    b.iter(|| {
-        let _curve = DynatomicCover::new(13, 1);
+    let mut group = c.benchmark_group("mc_faces");
+    group.sample_size(10);
+    for period in [12, 15, 18] {
+        group.bench_with_input(BenchmarkId::from_parameter(period), &period, |b, &period| {
+            b.iter_custom(|iters| {
+                (0..iters)
+                    .map(|_| MarkedCycleCoverBuilder::new(period, 1).build_timed().1.faces)
+                    .sum::<Duration>()
+            });
+        });
+    }
+    group.finish();
+
+    let mut group = c.benchmark_group("dyn_faces");
+    group.sample_size(10);
+    for period in [11, 13, 15] {
+        group.bench_with_input(BenchmarkId::from_parameter(period), &period, |b, &period| {
+            b.iter_custom(|iters| {
+                (0..iters)
+                    .map(|_| DynatomicCoverBuilder::new(period, 1).build_timed().1.faces)
+                    .sum::<Duration>()
+            });
+        });
+    }
+    group.finish();
+}
+
+fn builds(c: &mut Criterion)
+{
+    let mut group = c.benchmark_group("mc_curve");
+    group.sample_size(10);
+    for period in [12, 15, 18] {
+        group.bench_with_input(BenchmarkId::from_parameter(period), &period, |b, &period| {
+            b.iter(|| MarkedCycleCover::new(period, 1));
+        });
+    }
+    group.finish();
+
+    // Every period up to 15, extending one lamination versus building each cover separately
+    let mut group = c.benchmark_group("mc_range");
+    group.sample_size(10);
+    group.bench_function("separate", |b| {
+        b.iter(|| (3..=15).for_each(|n| drop(black_box(MarkedCycleCover::new(n, 1)))));
     });
+    group.bench_function("sweep", |b| {
+        b.iter(|| MarkedCycleCover::sweep(3..=15, 1).for_each(|cover| drop(black_box(cover))));
+    });
+    group.finish();
+
+    let mut group = c.benchmark_group("dynatomic");
+    group.sample_size(10);
+    for period in [11, 13, 15] {
+        group.bench_with_input(BenchmarkId::from_parameter(period), &period, |b, &period| {
+            b.iter(|| DynatomicCover::new(period, 1));
+        });
+    }
+    group.finish();
 }
+
+criterion_group!(benches, lamination, orbits, cycles, faces, builds);
+criterion_main!(benches);
@@ -21,13 +21,11 @@ struct Args
 
 fn rel_shift(a: IntAngle, mut b: IntAngle) -> Period
 {
-    let mut res = 0;
-    for _ in 0..PERIOD.get() {
+    for res in 0..PERIOD.get() {
         if a == b {
             return res;
         }
         b = (b * 2) % MAX_ANGLE.get();
-        res += 1
     }
     panic!(
         "rel_shift was called on angles in different cycles: \
@@ -44,7 +42,8 @@ fn main()
     let crit_per = args.crit_period;
 
     let mc = MarkedCycleCover::new(period, crit_per);
-    let max_face = mc.faces.into_iter().max_by_key(Face::len).unwrap();
+    let _context = mc.context.enter();
+    let max_face = mc.faces().into_iter().max_by_key(Face::len).unwrap();
     let shifts = get_shifts(&max_face, mc.edges);
     println!(
         "{}",
@@ -56,9 +55,9 @@ fn main()
     );
 }
 
-fn find_real_edge(face: &MCFace, edges: &Vec<MCEdge>) -> (Aug<MCVertex>, IntAngle, usize)
+fn find_real_edge(face: &MCFace, edges: &[MCEdge]) -> (Aug<MCVertex>, IntAngle, usize)
 {
-    let mut v = face.vertices[0].clone();
+    let mut v = face.vertices[0];
     for _ in 0..2 {
         for (i, e) in edges.iter().enumerate() {
             if e.start == v.vertex {
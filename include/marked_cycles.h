@@ -0,0 +1,93 @@
+/* C API of marked-cycles, built with `--features ffi`. Generated by cbindgen from src/ffi.rs. */
+
+#ifndef MARKED_CYCLES_H
+#define MARKED_CYCLES_H
+
+#include <stdbool.h>
+#include <stdint.h>
+
+// Largest period accepted from C for the closed-form counts of the marked cycle curve, past
+// which they overflow 64 bits
+#define MARKED_CYCLES_MAX_PERIOD 62
+
+// Largest period accepted from C for the closed-form counts of the dynatomic curve, past which
+// its satellite faces overflow 64 bits
+#define MARKED_CYCLES_MAX_DYNATOMIC_PERIOD 58
+
+// Largest period of the covers built from C, whose number of cells grows like `2^period`
+#define MARKED_CYCLES_MAX_COVER_PERIOD 24
+
+// A cover built from C, only handled through pointers
+typedef struct MarkedCyclesCover MarkedCyclesCover;
+
+// The closed-form quantities of [`Combinatorics`] for one period
+typedef struct MarkedCyclesInvariants {
+  int64_t points_of_period_dividing_n;
+  int64_t periodic_points;
+  int64_t cycles;
+  int64_t hyp_components_dividing_n;
+  int64_t hyperbolic_components;
+  int64_t satellite_components;
+  int64_t primitive_components;
+  int64_t self_conjugate_faces;
+  int64_t vertices;
+  int64_t edges;
+  int64_t faces;
+  int64_t genus;
+} MarkedCyclesInvariants;
+
+// Cell counts and genus of a built cover
+typedef struct MarkedCyclesCounts {
+  int64_t vertices;
+  int64_t edges;
+  int64_t faces;
+  int64_t genus;
+} MarkedCyclesCounts;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Fill `out` with the closed-form quantities for the marked cycle curve, or with `dynatomic`
+// the dynatomic curve, of the given period over `Per_crit_period(0)`. Returns false, leaving
+// `out` untouched, if the critical period is not 1 or 2, the period is not between 1 and
+// [`MAX_PERIOD`] ([`MAX_DYNATOMIC_PERIOD`] with `dynatomic`), or `out` is null.
+//
+// # Safety
+//
+// `out` must be null or point to writable memory for an `Invariants`.
+bool marked_cycles_invariants(int64_t period,
+                              int64_t crit_period,
+                              bool dynatomic,
+                              struct MarkedCyclesInvariants *out);
+
+// Build the marked cycle cover, or with `dynatomic` the dynatomic cover, of the given period
+// over `Per_crit_period(0)`. Returns null if the critical period is not 1 or 2 or the period
+// is not between 1 and [`MAX_COVER_PERIOD`]. The cover must be released with
+// [`marked_cycles_cover_free`].
+struct MarkedCyclesCover *marked_cycles_cover_new(int64_t period,
+                                                  int64_t crit_period,
+                                                  bool dynatomic);
+
+// Fill `out` with the cell counts and genus of `cover`. Returns false, leaving `out`
+// untouched, if either pointer is null.
+//
+// # Safety
+//
+// `cover` must be null or returned by [`marked_cycles_cover_new`] and not yet freed, and
+// `out` must be null or point to writable memory for a `Counts`.
+bool marked_cycles_cover_counts(const struct MarkedCyclesCover *cover,
+                                struct MarkedCyclesCounts *out);
+
+// Release a cover built by [`marked_cycles_cover_new`]. Null is ignored.
+//
+// # Safety
+//
+// `cover` must be null or returned by [`marked_cycles_cover_new`] and not yet freed.
+void marked_cycles_cover_free(struct MarkedCyclesCover *cover);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* MARKED_CYCLES_H */
@@ -1,7 +1,30 @@
-use crate::global_state::{MAX_ANGLE, PERIOD};
+use crate::common::orbit_min_and_len_in;
+use crate::global_state::{Context, DisplayIn};
 use crate::types::{IntAngle, KneadingSequence, Period};
 
+/// `Display` and `Binary` for a type implementing [`DisplayIn`], in the ambient context
+macro_rules! ambient_display {
+    ($type: ty) => {
+        impl core::fmt::Display for $type
+        {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+            {
+                self.fmt_in(Context::current(), f)
+            }
+        }
+
+        impl core::fmt::Binary for $type
+        {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+            {
+                self.fmt_binary_in(Context::current(), f)
+            }
+        }
+    };
+}
+
 #[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct AbstractPoint
 {
     pub angle: IntAngle,
@@ -24,44 +47,65 @@ impl AbstractPoint
     #[must_use]
     pub fn orbit_min(&self) -> Self
     {
-        let mut theta = self.angle;
-        let mut min_theta = theta;
+        self.orbit_min_in(Context::current())
+    }
 
-        while theta != self.angle {
-            theta = (theta * 2) % MAX_ANGLE.get();
-            min_theta = min_theta.min(theta);
-        }
-        self.with_angle(min_theta)
+    /// Least point in the orbit of this one under doubling, in `context`
+    #[must_use]
+    pub fn orbit_min_in(&self, context: Context) -> Self
+    {
+        self.with_angle(orbit_min_and_len_in(context, self.angle).0)
     }
 
     #[must_use]
     pub fn rotate(&self, shift: Period) -> Self
     {
-        let rep = (self.angle << shift) % MAX_ANGLE.get();
+        self.rotate_in(shift, Context::current())
+    }
+
+    /// The point `shift` doublings further along the orbit, in `context`
+    #[must_use]
+    pub fn rotate_in(&self, shift: Period, context: Context) -> Self
+    {
+        let rep = (self.angle << shift) % context.max_angle;
         self.with_angle(rep)
     }
 
     #[must_use]
     pub fn bit_flip(&self) -> Self
     {
-        self.with_angle(MAX_ANGLE.get() & !self.angle)
+        self.bit_flip_in(Context::current())
+    }
+
+    /// The point whose angle has the opposite binary digits, in `context`
+    #[must_use]
+    pub fn bit_flip_in(&self, context: Context) -> Self
+    {
+        self.with_angle(context.max_angle & !self.angle)
     }
 
     #[must_use]
     pub fn orbit_min_and_kneading_sequence(&self) -> (Self, KneadingSequence)
     {
-        let mut ks = KneadingSequence::default();
+        self.orbit_min_and_kneading_sequence_in(Context::current())
+    }
+
+    /// [`Self::orbit_min_in`] together with the kneading sequence, in one pass over the orbit
+    #[must_use]
+    pub fn orbit_min_and_kneading_sequence_in(&self, context: Context) -> (Self, KneadingSequence)
+    {
+        let mut ks = KneadingSequence::new(context.period);
         let mut theta = self.angle;
         let mut min_theta = theta;
 
         let u0 = self.angle / 2;
-        let u1 = (MAX_ANGLE.get() + self.angle) / 2;
+        let u1 = (context.max_angle + self.angle) / 2;
 
         loop {
             if theta <= u0 || theta > u1 {
                 ks.increment();
             }
-            theta = (theta * 2) % MAX_ANGLE.get();
+            theta = (theta * 2) % context.max_angle;
 
             if theta == self.angle {
                 break;
@@ -77,18 +121,25 @@ impl AbstractPoint
     #[must_use]
     pub fn kneading_sequence(&self) -> KneadingSequence
     {
-        let mut ks = KneadingSequence::default();
+        self.kneading_sequence_in(Context::current())
+    }
+
+    /// Kneading sequence of the angle, read as a multiple of `1/context.max_angle`
+    #[must_use]
+    pub fn kneading_sequence_in(&self, context: Context) -> KneadingSequence
+    {
+        let mut ks = KneadingSequence::new(context.period);
         let mut theta = self.angle;
 
         let u0 = self.angle / 2;
-        let u1 = (MAX_ANGLE.get() + self.angle) / 2;
+        let u1 = (context.max_angle + self.angle) / 2;
 
-        for _ in 0..PERIOD.get() {
+        for _ in 0..context.period {
             ks.shift();
             if theta <= u0 || theta > u1 {
                 ks.increment();
             }
-            theta = (theta * 2) % MAX_ANGLE.get();
+            theta = (theta * 2) % context.max_angle;
         }
         ks
     }
@@ -96,36 +147,37 @@ impl AbstractPoint
 
 impl PartialOrd for AbstractPoint
 {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering>
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering>
     {
-        Some(self.angle.cmp(&other.angle))
+        Some(self.cmp(other))
     }
 }
 impl Ord for AbstractPoint
 {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering
     {
         self.angle.cmp(&other.angle)
     }
 }
 
-impl std::fmt::Display for AbstractPoint
+impl DisplayIn for AbstractPoint
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    fn fmt_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
     {
-        write!(f, "{}", self.angle)
+        write!(f, "{}", self.angle.display_in(context, f.alternate()))
     }
-}
 
-impl std::fmt::Binary for AbstractPoint
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    fn fmt_binary_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>)
+        -> core::fmt::Result
     {
-        write!(f, "{:0n$b}", self.angle, n = PERIOD.get() as usize)
+        write!(f, "{:0n$b}", self.angle, n = context.period as usize)
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+ambient_display!(AbstractPoint);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct AbstractPointClass
 {
     pub rep: AbstractPoint,
@@ -134,9 +186,16 @@ impl AbstractPointClass
 {
     #[must_use]
     pub fn new(point: AbstractPoint) -> Self
+    {
+        Self::new_in(point, Context::current())
+    }
+
+    /// The class of `point` and the point with the opposite binary digits, in `context`
+    #[must_use]
+    pub fn new_in(point: AbstractPoint, context: Context) -> Self
     {
         Self {
-            rep: point.min(point.bit_flip()),
+            rep: point.min(point.bit_flip_in(context)),
         }
     }
 }
@@ -148,23 +207,24 @@ impl From<AbstractPoint> for AbstractPointClass
     }
 }
 
-impl std::fmt::Binary for AbstractPointClass
+impl DisplayIn for AbstractPointClass
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    fn fmt_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
     {
-        write!(f, "[{:0n$b}]", self.rep.angle, n = PERIOD.get() as usize)
+        write!(f, "[{}]", self.rep.angle.display_in(context, f.alternate()))
     }
-}
 
-impl std::fmt::Display for AbstractPointClass
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    fn fmt_binary_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>)
+        -> core::fmt::Result
     {
-        write!(f, "[{}]", self.rep.angle)
+        write!(f, "[{:0n$b}]", self.rep.angle, n = context.period as usize)
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+ambient_display!(AbstractPointClass);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct AbstractCycle
 {
     pub rep: AbstractPoint,
@@ -174,42 +234,57 @@ impl AbstractCycle
 {
     #[must_use]
     pub fn new_compute(point: AbstractPoint) -> Self
+    {
+        Self::new_compute_in(point, Context::current())
+    }
+
+    /// The cycle through `point`, in `context`
+    #[must_use]
+    pub fn new_compute_in(point: AbstractPoint, context: Context) -> Self
     {
         Self {
-            rep: point.orbit_min(),
+            rep: point.orbit_min_in(context),
         }
     }
 
     #[must_use]
     pub fn compute_cycle_class(&self) -> AbstractCycleClass
     {
-        let dual_rep = self.rep.bit_flip().orbit_min();
+        self.compute_cycle_class_in(Context::current())
+    }
+
+    /// The class of this cycle and the cycle with the opposite binary digits, in `context`
+    #[must_use]
+    pub fn compute_cycle_class_in(&self, context: Context) -> AbstractCycleClass
+    {
+        let dual_rep = self.rep.bit_flip_in(context).orbit_min_in(context);
         AbstractCycleClass {
             rep: self.rep.min(dual_rep),
         }
     }
 }
 
-impl std::fmt::Display for AbstractCycle
+impl DisplayIn for AbstractCycle
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    fn fmt_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
     {
+        let angle = self.rep.angle.display_in(context, f.alternate());
         if let Some(width) = f.width() {
-            write!(f, "({:>width$})", self.rep.angle)
+            write!(f, "({angle:>width$})")
         } else {
-            write!(f, "({})", self.rep.angle)
+            write!(f, "({angle})")
         }
     }
-}
 
-impl std::fmt::Binary for AbstractCycle
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    fn fmt_binary_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>)
+        -> core::fmt::Result
     {
-        write!(f, "({:0n$b})", self.rep.angle, n = PERIOD.get() as usize)
+        write!(f, "({:0n$b})", self.rep.angle, n = context.period as usize)
     }
 }
 
+ambient_display!(AbstractCycle);
+
 impl From<AbstractCycle> for IntAngle
 {
     fn from(cyc: AbstractCycle) -> Self
@@ -219,7 +294,8 @@ impl From<AbstractCycle> for IntAngle
 }
 
 /// Represents an equivalence class of n-cycles modulo monodromy about infinity
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct AbstractCycleClass
 {
     pub rep: AbstractPoint,
@@ -264,23 +340,24 @@ impl From<AbstractCycleClass> for AbstractCycle
     }
 }
 
-impl std::fmt::Binary for AbstractCycleClass
+impl DisplayIn for AbstractCycleClass
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    fn fmt_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
     {
-        write!(f, "<{:0n$b}>", self.rep.angle, n = PERIOD.get() as usize)
+        write!(f, "<{}>", self.rep.angle.display_in(context, f.alternate()))
     }
-}
 
-impl std::fmt::Display for AbstractCycleClass
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    fn fmt_binary_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>)
+        -> core::fmt::Result
     {
-        write!(f, "<{}>", self.rep.angle)
+        write!(f, "<{:0n$b}>", self.rep.angle, n = context.period as usize)
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+ambient_display!(AbstractCycleClass);
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct ShiftedCycle
 {
     pub rep: AbstractPoint,
@@ -308,14 +385,28 @@ impl ShiftedCycle
     #[must_use]
     pub fn relative_shift(&self, other: Self) -> Period
     {
-        (self.shift - other.shift).rem_euclid(PERIOD.get())
+        self.relative_shift_in(other, Context::current())
+    }
+
+    /// Shift of this cycle relative to `other`, modulo the period of `context`
+    #[must_use]
+    pub fn relative_shift_in(&self, other: Self, context: Context) -> Period
+    {
+        (self.shift - other.shift).rem_euclid(context.period)
     }
 
     // Return self, rotated by a given shift
     #[must_use]
     pub fn rotate(self, shift: Period) -> Self
     {
-        let new_shift = (self.shift + shift).rem_euclid(PERIOD.get());
+        self.rotate_in(shift, Context::current())
+    }
+
+    /// This cycle with its marked point moved `shift` doublings along it, in `context`
+    #[must_use]
+    pub fn rotate_in(self, shift: Period, context: Context) -> Self
+    {
+        let new_shift = (self.shift + shift).rem_euclid(context.period);
         Self {
             rep: self.rep,
             shift: new_shift,
@@ -326,14 +417,30 @@ impl ShiftedCycle
     #[inline]
     pub fn to_point(self) -> AbstractPoint
     {
-        self.rep.rotate(self.shift)
+        self.to_point_in(Context::current())
+    }
+
+    /// The marked point, in `context`
+    #[must_use]
+    #[inline]
+    pub fn to_point_in(self, context: Context) -> AbstractPoint
+    {
+        self.rep.rotate_in(self.shift, context)
     }
 
     #[must_use]
     #[inline]
     pub fn to_point_class(self) -> AbstractPointClass
     {
-        self.rep.rotate(self.shift).into()
+        self.to_point_class_in(Context::current())
+    }
+
+    /// The class of the marked point, in `context`
+    #[must_use]
+    #[inline]
+    pub fn to_point_class_in(self, context: Context) -> AbstractPointClass
+    {
+        AbstractPointClass::new_in(self.to_point_in(context), context)
     }
 }
 
@@ -353,24 +460,29 @@ impl From<ShiftedCycle> for AbstractPointClass
     }
 }
 
-impl std::fmt::Binary for ShiftedCycle
+impl DisplayIn for ShiftedCycle
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    fn fmt_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        write!(
+            f,
+            "[{}; {}]",
+            self.rep.angle.display_in(context, f.alternate()),
+            self.shift
+        )
+    }
+
+    fn fmt_binary_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>)
+        -> core::fmt::Result
     {
         write!(
             f,
             "[{:0n$b}; {}]",
             self.rep.angle,
             self.shift,
-            n = PERIOD.get() as usize
+            n = context.period as usize
         )
     }
 }
 
-impl std::fmt::Display for ShiftedCycle
-{
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
-    {
-        write!(f, "[{}; {}]", self.rep.angle, self.shift)
-    }
-}
+ambient_display!(ShiftedCycle);
@@ -1,5 +1,21 @@
-use crate::types::{INum, Period};
+use crate::types::{INum, Period, RatAngle};
+use alloc::vec;
+#[cfg(feature = "parse")]
+use lazy_static::lazy_static;
 pub use num::integer::gcd;
+#[cfg(feature = "parse")]
+use regex::Regex;
+
+#[cfg(feature = "parse")]
+lazy_static! {
+    static ref RE_MERSENNE: Regex =
+        Regex::new(r"^(\d+)\s*/\s*\(\s*2\s*\^\s*(\d+)\s*[-−]\s*1\s*\)$").expect("Invalid regex");
+    static ref RE_FRACTION: Regex = Regex::new(r"^(\d+)\s*/\s*(\d+)$").expect("Invalid regex");
+    static ref RE_BINARY: Regex = Regex::new(r"^(?:0b)?([01]+)$").expect("Invalid regex");
+}
+
+/// Largest period whose angles `k/(2^n - 1)` fit in an `IntAngle`
+pub const MAX_ANGLE_PERIOD: Period = 62;
 
 pub fn divisors(n: Period) -> impl Iterator<Item = Period>
 {
@@ -70,3 +86,62 @@ where
 {
     dirichlet_convolution(moebius, f, n)
 }
+
+/// Exact period of `angle` under doubling modulo one, or `None` if it is not periodic (its
+/// reduced denominator is even) or its period exceeds `MAX_ANGLE_PERIOD`
+#[must_use]
+pub fn doubling_period(angle: RatAngle) -> Option<Period>
+{
+    let q = *angle.denom();
+    if q % 2 == 0 {
+        return None;
+    }
+    let (mut n, mut r) = (1, 2 % q);
+    while r != 1 % q {
+        if n >= MAX_ANGLE_PERIOD {
+            return None;
+        }
+        r = (2 * r) % q;
+        n += 1;
+    }
+    Some(n)
+}
+
+/// Parse an angle written as `k/(2^n-1)`, as a fraction `p/q`, or as the binary digits of one
+/// period of its expansion (optionally prefixed by `0b`). The result is reduced modulo one.
+#[cfg(feature = "parse")]
+pub fn parse_angle(text: &str) -> Result<RatAngle, String>
+{
+    let text = text.trim();
+    let number = |t: &str| {
+        t.parse::<Period>()
+            .map_err(|e| format!("invalid number {t:?}: {e}"))
+    };
+    let mersenne = |n: Period| {
+        if (1..=MAX_ANGLE_PERIOD).contains(&n) {
+            Ok((1 << n) - 1)
+        } else {
+            Err(format!("period {n} is out of range 1..={MAX_ANGLE_PERIOD}"))
+        }
+    };
+
+    let angle = if let Some(caps) = RE_MERSENNE.captures(text) {
+        RatAngle::new(number(&caps[1])?, mersenne(number(&caps[2])?)?)
+    } else if let Some(caps) = RE_FRACTION.captures(text) {
+        let denom = number(&caps[2])?;
+        if denom == 0 {
+            return Err(format!("zero denominator in {text:?}"));
+        }
+        RatAngle::new(number(&caps[1])?, denom)
+    } else if let Some(caps) = RE_BINARY.captures(text) {
+        let digits = &caps[1];
+        let numer = Period::from_str_radix(digits, 2)
+            .map_err(|e| format!("invalid binary angle {digits:?}: {e}"))?;
+        RatAngle::new(numer, mersenne(digits.len() as Period)?)
+    } else {
+        return Err(format!(
+            "cannot parse angle {text:?}; expected k/(2^n-1), p/q, or binary digits"
+        ));
+    };
+    Ok(angle - angle.floor())
+}
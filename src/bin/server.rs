@@ -0,0 +1,343 @@
+use clap::Parser;
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, TrySendError};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use marked_cycles::cache::CoverCache;
+use marked_cycles::cover;
+use marked_cycles::dynatomic_cover::DynatomicCover;
+use marked_cycles::marked_cycle_cover::MarkedCycleCover;
+use marked_cycles::types::{CritPeriod, MarkedPeriod};
+
+/// Serve marked cycle and dynatomic curves as JSON over HTTP
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Cli
+{
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:8080")]
+    bind: String,
+
+    /// Largest marked period of a cover built on request
+    #[arg(long, default_value = "14")]
+    max_period: MarkedPeriod,
+
+    /// Number of threads serving requests
+    #[arg(long, default_value_t = 4)]
+    workers: usize,
+
+    /// Most covers to hold in memory at once, evicting those of largest period to make room
+    #[arg(long, default_value_t = 16)]
+    max_covers: usize,
+
+    /// Read covers from this cache directory if present, and store them there once built, so
+    /// that they outlive the server
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+}
+
+/// How long to wait for a client to send its request
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+/// Most bytes read of a request's line and headers
+const MAX_REQUEST_BYTES: u64 = 8 * 1024;
+/// Most headers read of a request
+const MAX_HEADERS: usize = 32;
+/// Connections waiting for each worker before new ones are turned away
+const QUEUE_PER_WORKER: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum Family
+{
+    Marked,
+    Dynatomic,
+}
+
+/// A built cover of either family, shared between the threads serving it
+type Cover = Box<dyn cover::Cover + Send + Sync>;
+
+/// A cover and its two JSON documents, each computed on first request. Requests for a cover
+/// that is being built wait for it rather than building it again.
+#[derive(Default)]
+struct Entry
+{
+    cover: OnceLock<Cover>,
+    /// The summary, then the document with the cells
+    json: [OnceLock<Arc<str>>; 2],
+}
+
+/// Status code and message of a failed request
+type Failure = (u16, String);
+
+type Key = (Family, MarkedPeriod, CritPeriod);
+
+struct Server
+{
+    max_period: MarkedPeriod,
+    max_covers: usize,
+    disk: Option<CoverCache>,
+    covers: Mutex<HashMap<Key, Arc<Entry>>>,
+}
+
+impl Server
+{
+    fn new(cli: &Cli) -> Self
+    {
+        Self {
+            max_period: cli.max_period,
+            max_covers: cli.max_covers.max(1),
+            disk: cli.cache_dir.as_ref().map(CoverCache::new),
+            covers: Mutex::default(),
+        }
+    }
+
+    fn build(&self, (family, period, crit_period): Key) -> Cover
+    {
+        let (period, crit_period) = (period.get(), crit_period.get());
+        match family {
+            Family::Marked => {
+                let build = || MarkedCycleCover::new(period, crit_period);
+                Box::new(match &self.disk {
+                    Some(disk) => disk.get_or_build(period, crit_period, build),
+                    None => build(),
+                })
+            }
+            Family::Dynatomic => {
+                let build = || DynatomicCover::new(period, crit_period);
+                Box::new(match &self.disk {
+                    Some(disk) => disk.get_or_build(period, crit_period, build),
+                    None => build(),
+                })
+            }
+        }
+    }
+
+    fn cover_json(&self, key: Key, with_cells: bool) -> Arc<str>
+    {
+        // Hold the lock only to find the entry, so that other covers can be served meanwhile
+        let entry = {
+            let mut covers = self
+                .covers
+                .lock()
+                .expect("No request panics while holding the lock");
+            if !covers.contains_key(&key) {
+                // Requests still serving an evicted cover keep it until they finish
+                while covers.len() >= self.max_covers {
+                    let Some(&largest) = covers.keys().max_by_key(|(_, period, _)| *period) else {
+                        break;
+                    };
+                    covers.remove(&largest);
+                }
+            }
+            Arc::clone(covers.entry(key).or_default())
+        };
+        let json = entry.json[usize::from(with_cells)].get_or_init(|| {
+            let cover = entry.cover.get_or_init(|| self.build(key));
+            cover.to_json(with_cells).into()
+        });
+        Arc::clone(json)
+    }
+
+    fn parse_period(&self, text: &str) -> Result<MarkedPeriod, Failure>
+    {
+        let period: MarkedPeriod = text
+            .parse()
+            .map_err(|e| (400, format!("Invalid period {text}: {e}")))?;
+        if period > self.max_period {
+            return Err((
+                400,
+                format!(
+                    "The period must be at most {}, not {period}",
+                    self.max_period
+                ),
+            ));
+        }
+        Ok(period)
+    }
+
+    fn index(&self) -> Arc<str>
+    {
+        format!(
+            concat!(
+                "{{\"endpoints\": [\"/mc/{{period}}/{{crit_period}}\", ",
+                "\"/dyn/{{period}}/{{crit_period}}\"], ",
+                "\"cells\": \"append ?cells=true for the vertices, edges, and faces\", ",
+                "\"max_period\": {}}}"
+            ),
+            self.max_period
+        )
+        .into()
+    }
+
+    /// The body of the response to a request for `target`
+    fn route(&self, method: &str, target: &str) -> Result<Arc<str>, Failure>
+    {
+        if method != "GET" {
+            return Err((405, format!("Only GET is supported, not {method}")));
+        }
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        match segments[..] {
+            [] => Ok(self.index()),
+            [family, period, crit_period] => {
+                let family = match family {
+                    "mc" => Family::Marked,
+                    "dyn" => Family::Dynatomic,
+                    _ => return Err((404, format!("No curve named {family}"))),
+                };
+                let period = self.parse_period(period)?;
+                let crit_period: CritPeriod = crit_period
+                    .parse()
+                    .map_err(|e| (400, format!("Invalid critical period {crit_period}: {e}")))?;
+                let with_cells = query
+                    .split('&')
+                    .any(|param| matches!(param, "cells" | "cells=true" | "cells=1"));
+                Ok(self.cover_json((family, period, crit_period), with_cells))
+            }
+            _ => Err((404, format!("No endpoint at {path}"))),
+        }
+    }
+
+    fn handle(&self, stream: &TcpStream) -> io::Result<()>
+    {
+        let start = Instant::now();
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let (request, (status, body)) = match read_request(stream)? {
+            Ok((method, target)) => {
+                let response = match self.route(&method, &target) {
+                    Ok(body) => (200, body),
+                    Err((status, message)) => (status, error_body(&message)),
+                };
+                (format!("{method} {target}"), response)
+            }
+            Err((status, message)) => (message.clone(), (status, error_body(&message))),
+        };
+        write_response(stream, status, &body)?;
+
+        eprintln!(
+            "{request} {status} {} ({:.1?})",
+            body.len(),
+            start.elapsed()
+        );
+        Ok(())
+    }
+}
+
+/// Read the request line and headers, at most [`MAX_REQUEST_BYTES`] of them, and return the
+/// method and target. No endpoint reads the headers, but they are drained so that the client
+/// sees the whole response rather than a reset connection.
+fn read_request(stream: &TcpStream) -> io::Result<Result<(String, String), Failure>>
+{
+    let mut reader = stream.take(MAX_REQUEST_BYTES);
+    let mut buf = Vec::new();
+    let mut chunk = [0; 1024];
+    loop {
+        let n = reader.read(&mut chunk)?;
+        if n == 0 {
+            return Ok(Err(if buf.len() as u64 >= MAX_REQUEST_BYTES {
+                (431, "Request header too large".to_owned())
+            } else {
+                (400, "Incomplete request".to_owned())
+            }));
+        }
+        buf.extend_from_slice(&chunk[..n]);
+
+        let mut headers = [httparse::EMPTY_HEADER; MAX_HEADERS];
+        let mut request = httparse::Request::new(&mut headers);
+        match request.parse(&buf) {
+            Ok(httparse::Status::Complete(_)) => {
+                let method = request.method.unwrap_or_default().to_owned();
+                let target = request.path.unwrap_or_default().to_owned();
+                return Ok(Ok((method, target)));
+            }
+            Ok(httparse::Status::Partial) => {}
+            Err(e) => return Ok(Err((400, format!("Malformed request: {e}")))),
+        }
+    }
+}
+
+fn error_body(message: &str) -> Arc<str>
+{
+    serde_json::json!({ "error": message }).to_string().into()
+}
+
+const fn reason(status: u16) -> &'static str
+{
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    }
+}
+
+fn write_response(mut out: impl Write, status: u16, body: &str) -> io::Result<()>
+{
+    write!(out, "HTTP/1.1 {status} {}\r\n", reason(status))?;
+    write!(out, "Content-Type: application/json\r\n")?;
+    write!(out, "Content-Length: {}\r\n", body.len())?;
+    if status == 405 {
+        write!(out, "Allow: GET\r\n")?;
+    }
+    // Let front-ends served from elsewhere read the data
+    write!(out, "Access-Control-Allow-Origin: *\r\n")?;
+    write!(out, "Connection: close\r\n\r\n")?;
+    out.write_all(body.as_bytes())?;
+    out.flush()
+}
+
+fn main()
+{
+    let cli = Cli::parse();
+    let listener = TcpListener::bind(&cli.bind).unwrap_or_else(|e| {
+        eprintln!("Error: could not listen on {}: {e}", cli.bind);
+        std::process::exit(1);
+    });
+    eprintln!(
+        "Serving curves up to period {} on http://{}",
+        cli.max_period, cli.bind
+    );
+
+    let server = Arc::new(Server::new(&cli));
+    let workers = cli.workers.max(1);
+    let (queue, connections) = mpsc::sync_channel::<TcpStream>(workers * QUEUE_PER_WORKER);
+    let connections = Arc::new(Mutex::new(connections));
+    for _ in 0..workers {
+        let server = Arc::clone(&server);
+        let connections = Arc::clone(&connections);
+        std::thread::spawn(move || loop {
+            let next = connections
+                .lock()
+                .expect("No worker panics while holding the lock")
+                .recv();
+            let Ok(stream) = next else {
+                break;
+            };
+            if let Err(e) = server.handle(&stream) {
+                eprintln!("Error: {e}");
+            }
+        });
+    }
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => match queue.try_send(stream) {
+                Ok(()) => {}
+                Err(TrySendError::Full(stream)) => {
+                    let body = error_body("The server is busy; try again later");
+                    if let Err(e) = write_response(&stream, 503, &body) {
+                        eprintln!("Error: {e}");
+                    }
+                }
+                Err(TrySendError::Disconnected(_)) => break,
+            },
+            Err(e) => eprintln!("Error: {e}"),
+        }
+    }
+}
@@ -0,0 +1,525 @@
+use clap::Parser;
+use eframe::egui::{
+    self, epaint::QuadraticBezierShape, Align2, Color32, FontId, Pos2, Rect, Sense, Stroke, Vec2,
+};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt::Display;
+use std::hash::Hash;
+use std::path::PathBuf;
+
+use marked_cycles::abstract_cycles::AbstractPoint;
+use marked_cycles::cache::{load_snapshot, CoverCache};
+use marked_cycles::common::{
+    cells::{AugmentedVertex, Edge, Face},
+    get_orbit_in,
+};
+use marked_cycles::dynatomic_cover::DynatomicCover;
+use marked_cycles::global_state::Context;
+use marked_cycles::layout::{place_graph, GraphLayout, GraphPlacement, Point};
+use marked_cycles::marked_cycle_cover::MarkedCycleCover;
+use marked_cycles::types::{CritPeriod, MarkedPeriod, Period};
+
+/// Interactive view of the 1-skeleton of a marked cycle or dynatomic curve
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Cli
+{
+    /// Period of the marked cycle
+    #[arg(required_unless_present = "load")]
+    period: Option<MarkedPeriod>,
+
+    /// Period of the critical cycle (1 or 2)
+    #[arg(short, long, default_value_t = CritPeriod::ONE)]
+    crit_period: CritPeriod,
+
+    /// Show the dynatomic curve instead of the marked cycle curve
+    #[arg(short, long, default_value_t = false)]
+    dynatomic: bool,
+
+    /// Read the cover from a snapshot written by `cache::save_snapshot` instead of building it
+    #[arg(long)]
+    load: Option<PathBuf>,
+
+    /// Read the cover from this cache directory if present, and store it there once built
+    #[arg(long)]
+    cache_dir: Option<PathBuf>,
+
+    /// Number of force-directed iterations used to place the vertices
+    #[arg(long, default_value_t = 200)]
+    iterations: usize,
+}
+
+/// Radius of a vertex on screen, in points
+const NODE_RADIUS: f32 = 5.0;
+/// Distance from a vertex within which a click selects it, in points
+const PICK_RADIUS: f32 = 10.0;
+
+const EDGE_COLOR: Color32 = Color32::from_gray(90);
+const REAL_COLOR: Color32 = Color32::from_rgb(30, 90, 200);
+const HIGHLIGHT_COLOR: Color32 = Color32::from_rgb(230, 120, 20);
+const SELECTED_COLOR: Color32 = Color32::from_rgb(200, 30, 30);
+
+struct VertexInfo
+{
+    orbit: String,
+    kneading_sequence: String,
+    /// Indices of the faces whose boundary passes through the vertex
+    faces: Vec<usize>,
+    /// Number of edge ends at the vertex
+    valence: usize,
+}
+
+struct FaceInfo
+{
+    label: String,
+    degree: Period,
+    size: usize,
+    /// Indices of the boundary vertices among the nodes of the layout
+    nodes: BTreeSet<usize>,
+    edge_ids: BTreeSet<usize>,
+}
+
+/// Everything shown about a cover, computed once with its context in effect
+struct Model
+{
+    title: String,
+    counts: (usize, usize, usize, i64),
+    /// Denominator of the angles in the orbits
+    max_angle: String,
+    graph: GraphLayout,
+    /// One entry per node of `graph`
+    vertices: Vec<VertexInfo>,
+    faces: Vec<FaceInfo>,
+}
+
+impl Model
+{
+    #[allow(clippy::too_many_arguments)]
+    fn new<V, F>(
+        title: String,
+        context: Context,
+        counts: (usize, usize, usize, i64),
+        vertices: &[V],
+        edges: &[Edge<V>],
+        faces: &[Face<AugmentedVertex<V>, F>],
+        iterations: usize,
+        point: impl Fn(V) -> AbstractPoint,
+    ) -> Self
+    where
+        V: Copy + Eq + Hash + Display,
+        F: Display,
+    {
+        let _context = context.enter();
+        let graph = place_graph(faces, edges, 1.0, GraphPlacement::ForceDirected(iterations));
+        let by_label: HashMap<String, V> = vertices.iter().map(|&v| (v.to_string(), v)).collect();
+        let index: HashMap<&str, usize> = graph
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.label.as_str(), i))
+            .collect();
+
+        let faces: Vec<FaceInfo> = faces
+            .iter()
+            .map(|f| FaceInfo {
+                label: f.label.to_string(),
+                degree: f.degree,
+                size: f.len(),
+                nodes: f
+                    .vertices
+                    .iter()
+                    .map(|v| index[v.vertex.to_string().as_str()])
+                    .collect(),
+                edge_ids: f.edge_ids.iter().copied().collect(),
+            })
+            .collect();
+
+        let mut valence = vec![0; graph.nodes.len()];
+        for edge in &graph.edges {
+            valence[edge.source] += 1;
+            valence[edge.target] += 1;
+        }
+
+        let vertices = graph
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| {
+                let point = point(by_label[&node.label]);
+                let orbit: Vec<String> = get_orbit_in(context, point.angle)
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect();
+                VertexInfo {
+                    orbit: orbit.join(" "),
+                    kneading_sequence: point.kneading_sequence_in(context).to_string(),
+                    faces: (0..faces.len())
+                        .filter(|&k| faces[k].nodes.contains(&i))
+                        .collect(),
+                    valence: valence[i],
+                }
+            })
+            .collect();
+
+        Self {
+            title,
+            counts,
+            max_angle: context.max_angle.to_string(),
+            graph,
+            vertices,
+            faces,
+        }
+    }
+
+    fn marked(cov: &MarkedCycleCover, iterations: usize) -> Self
+    {
+        Self::new(
+            format!("MC_{}(Per_{})", cov.context.period, cov.crit_period),
+            cov.context,
+            cov.counts(),
+            &cov.vertices,
+            &cov.edges,
+            &cov.faces(),
+            iterations,
+            |v| v.rep,
+        )
+    }
+
+    fn dynatomic(cov: &DynatomicCover, iterations: usize) -> Self
+    {
+        Self::new(
+            format!("Dyn_{}(Per_{})", cov.context.period, cov.crit_period),
+            cov.context,
+            cov.counts(),
+            &cov.vertices,
+            &cov.edges,
+            &cov.faces(),
+            iterations,
+            |v| v.to_point(),
+        )
+    }
+
+    /// Load, read from the cache, or build the cover named on the command line
+    fn from_cli(cli: &Cli) -> Result<Self, String>
+    {
+        if let Some(path) = &cli.load {
+            let error = |e| format!("Could not load {}: {e}", path.display());
+            return Ok(if cli.dynatomic {
+                Self::dynatomic(&load_snapshot(path).map_err(error)?, cli.iterations)
+            } else {
+                Self::marked(&load_snapshot(path).map_err(error)?, cli.iterations)
+            });
+        }
+
+        let period = cli.period.ok_or("A period is required")?.get();
+        let crit_period = cli.crit_period.get();
+        let cache = cli.cache_dir.as_ref().map(CoverCache::new);
+        Ok(if cli.dynatomic {
+            let build = || DynatomicCover::new(period, crit_period);
+            let cov = match &cache {
+                Some(cache) => cache.get_or_build(period, crit_period, build),
+                None => build(),
+            };
+            Self::dynatomic(&cov, cli.iterations)
+        } else {
+            let build = || MarkedCycleCover::new(period, crit_period);
+            let cov = match &cache {
+                Some(cache) => cache.get_or_build(period, crit_period, build),
+                None => build(),
+            };
+            Self::marked(&cov, cli.iterations)
+        })
+    }
+}
+
+/// Pan and zoom of the graph: a point of the layout is drawn `zoom` points from the center of
+/// the canvas per unit, shifted by `offset`, with the vertical axis flipped
+struct View
+{
+    offset: Vec2,
+    zoom: f32,
+}
+
+impl View
+{
+    fn to_screen(&self, canvas: Rect, p: Point) -> Pos2
+    {
+        canvas.center() + self.offset + self.zoom * Vec2::new(p.x, -p.y)
+    }
+
+    fn to_layout(&self, canvas: Rect, pos: Pos2) -> Point
+    {
+        let v = (pos - canvas.center() - self.offset) / self.zoom;
+        Point::new(v.x, -v.y)
+    }
+
+    /// Fit every node into the canvas, with a margin
+    fn fit(graph: &GraphLayout, canvas: Rect) -> Self
+    {
+        let (mut min, mut max) = (
+            Point::new(f32::INFINITY, f32::INFINITY),
+            Point::new(f32::NEG_INFINITY, f32::NEG_INFINITY),
+        );
+        for node in &graph.nodes {
+            let p = node.position;
+            min = Point::new(min.x.min(p.x), min.y.min(p.y));
+            max = Point::new(max.x.max(p.x), max.y.max(p.y));
+        }
+        if graph.nodes.is_empty() {
+            return Self {
+                offset: Vec2::ZERO,
+                zoom: 1.0,
+            };
+        }
+        let size = canvas.shrink(40.0).size();
+        let zoom = (size.x / (max.x - min.x).max(1.0)).min(size.y / (max.y - min.y).max(1.0));
+        let center = Vec2::new(max.x + min.x, -(max.y + min.y)) / 2.0;
+        Self {
+            offset: -zoom * center,
+            zoom,
+        }
+    }
+
+    /// Zoom by `factor`, keeping the layout point under `anchor` in place
+    fn zoom_about(&mut self, canvas: Rect, anchor: Pos2, factor: f32)
+    {
+        let fixed = self.to_layout(canvas, anchor);
+        self.zoom *= factor;
+        self.offset += anchor - self.to_screen(canvas, fixed);
+    }
+}
+
+struct Viewer
+{
+    model: Model,
+    /// `None` until the first frame, when the canvas size is known
+    view: Option<View>,
+    selected_vertex: Option<usize>,
+    selected_face: Option<usize>,
+    highlight_faces: bool,
+    show_labels: bool,
+}
+
+impl Viewer
+{
+    fn new(model: Model) -> Self
+    {
+        Self {
+            show_labels: model.graph.nodes.len() <= 200,
+            model,
+            view: None,
+            selected_vertex: None,
+            selected_face: None,
+            highlight_faces: true,
+        }
+    }
+
+    fn highlighted_face(&self) -> Option<&FaceInfo>
+    {
+        self.selected_face
+            .filter(|_| self.highlight_faces)
+            .map(|k| &self.model.faces[k])
+    }
+
+    fn side_panel(&mut self, ui: &mut egui::Ui)
+    {
+        let model = &self.model;
+        let (vertices, edges, faces, genus) = model.counts;
+        ui.heading(&model.title);
+        egui::Grid::new("counts").show(ui, |ui| {
+            for (key, value) in [
+                ("vertices", vertices.to_string()),
+                ("edges", edges.to_string()),
+                ("faces", faces.to_string()),
+                ("genus", genus.to_string()),
+            ] {
+                ui.label(key);
+                ui.label(value);
+                ui.end_row();
+            }
+        });
+        ui.separator();
+
+        ui.checkbox(&mut self.show_labels, "Show labels");
+        ui.checkbox(&mut self.highlight_faces, "Highlight faces");
+        if ui.button("Reset view").clicked() {
+            self.view = None;
+        }
+        ui.separator();
+
+        match self.selected_vertex {
+            None => {
+                ui.label("Click a vertex to see its orbit");
+            }
+            Some(i) => {
+                let info = &model.vertices[i];
+                ui.strong(&model.graph.nodes[i].label);
+                egui::Grid::new("vertex").show(ui, |ui| {
+                    ui.label("orbit");
+                    ui.label(format!("{} (over {})", info.orbit, model.max_angle));
+                    ui.end_row();
+                    ui.label("kneading sequence");
+                    ui.label(&info.kneading_sequence);
+                    ui.end_row();
+                    ui.label("valence");
+                    ui.label(info.valence.to_string());
+                    ui.end_row();
+                });
+                ui.label("Faces through this vertex:");
+                for &k in &info.faces {
+                    let face = &model.faces[k];
+                    let text = format!("{} (size {})", face.label, face.size);
+                    if ui
+                        .selectable_label(self.selected_face == Some(k), text)
+                        .clicked()
+                    {
+                        self.selected_face = Some(k);
+                    }
+                }
+            }
+        }
+        ui.separator();
+
+        if self.highlight_faces {
+            ui.label("Faces:");
+            egui::ScrollArea::vertical().show(ui, |ui| {
+                for (k, face) in model.faces.iter().enumerate() {
+                    let text = format!(
+                        "{} (size {}, degree {})",
+                        face.label, face.size, face.degree
+                    );
+                    if ui
+                        .selectable_label(self.selected_face == Some(k), text)
+                        .clicked()
+                    {
+                        self.selected_face = (self.selected_face != Some(k)).then_some(k);
+                    }
+                }
+            });
+        }
+    }
+
+    fn canvas(&mut self, ui: &mut egui::Ui)
+    {
+        let (response, painter) = ui.allocate_painter(ui.available_size(), Sense::click_and_drag());
+        let canvas = response.rect;
+        let view = self
+            .view
+            .get_or_insert_with(|| View::fit(&self.model.graph, canvas));
+
+        if response.dragged() {
+            view.offset += response.drag_delta();
+        }
+        if let Some(pointer) = response.hover_pos() {
+            let (scroll, pinch) = ui.input(|i| (i.smooth_scroll_delta.y, i.zoom_delta()));
+            let factor = pinch * (scroll / 200.0).exp();
+            if factor != 1.0 {
+                view.zoom_about(canvas, pointer, factor);
+            }
+        }
+
+        let graph = &self.model.graph;
+        let positions: Vec<Pos2> = graph
+            .nodes
+            .iter()
+            .map(|node| view.to_screen(canvas, node.position))
+            .collect();
+
+        if response.clicked() {
+            if let Some(pointer) = response.interact_pointer_pos() {
+                self.selected_vertex = positions
+                    .iter()
+                    .enumerate()
+                    .map(|(i, p)| (i, p.distance(pointer)))
+                    .filter(|&(_, d)| d <= PICK_RADIUS)
+                    .min_by(|a, b| a.1.total_cmp(&b.1))
+                    .map(|(i, _)| i);
+            }
+        }
+
+        let zoom = view.zoom;
+        let face = self.highlighted_face();
+        for edge in &graph.edges {
+            let highlighted =
+                face.is_some_and(|f| edge.id.is_some_and(|k| f.edge_ids.contains(&k)));
+            let stroke = if highlighted {
+                Stroke::new(3.5, HIGHLIGHT_COLOR)
+            } else if edge.real {
+                Stroke::new(2.5, REAL_COLOR)
+            } else {
+                Stroke::new(1.5, EDGE_COLOR)
+            };
+            let (a, b) = (positions[edge.source], positions[edge.target]);
+            if edge.source == edge.target {
+                // Draw loops as small circles sitting on top of the node
+                let r = 0.15 * (edge.bend.abs() + 1) as f32 * zoom;
+                painter.circle_stroke(a - Vec2::new(0.0, r), r, stroke);
+            } else if edge.bend == 0 {
+                painter.line_segment([a, b], stroke);
+            } else {
+                // Offset the control point perpendicular to the chord, as the SVG renderer does
+                let chord = b - a;
+                let offset = (15.0 * edge.bend as f32).to_radians().tan() / 2.0;
+                let control = a + chord / 2.0 + offset * Vec2::new(chord.y, -chord.x);
+                painter.add(QuadraticBezierShape::from_points_stroke(
+                    [a, control, b],
+                    false,
+                    Color32::TRANSPARENT,
+                    stroke,
+                ));
+            }
+        }
+
+        let text_color = ui.visuals().text_color();
+        for (i, (&p, node)) in positions.iter().zip(&graph.nodes).enumerate() {
+            let color = if self.selected_vertex == Some(i) {
+                SELECTED_COLOR
+            } else if face.is_some_and(|f| f.nodes.contains(&i)) {
+                HIGHLIGHT_COLOR
+            } else {
+                text_color
+            };
+            painter.circle_filled(p, NODE_RADIUS, color);
+            if self.show_labels {
+                painter.text(
+                    p + Vec2::new(0.0, -NODE_RADIUS - 2.0),
+                    Align2::CENTER_BOTTOM,
+                    &node.label,
+                    FontId::proportional(12.0),
+                    text_color,
+                );
+            }
+        }
+    }
+}
+
+impl eframe::App for Viewer
+{
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame)
+    {
+        egui::SidePanel::right("details")
+            .default_width(280.0)
+            .show(ctx, |ui| self.side_panel(ui));
+        egui::CentralPanel::default().show(ctx, |ui| self.canvas(ui));
+    }
+}
+
+fn main() -> eframe::Result
+{
+    let cli = Cli::parse();
+    let model = Model::from_cli(&cli).unwrap_or_else(|e| {
+        eprintln!("Error: {e}");
+        std::process::exit(1);
+    });
+
+    let title = format!("marked-cycles viewer: {}", model.title);
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default().with_inner_size([1200.0, 800.0]),
+        ..Default::default()
+    };
+    eframe::run_native(
+        &title,
+        options,
+        Box::new(|_| Ok(Box::new(Viewer::new(model)))),
+    )
+}
@@ -0,0 +1,265 @@
+use crate::dynatomic_cover::DynatomicCover;
+use crate::lamination::Lamination;
+use crate::marked_cycle_cover::MarkedCycleCover;
+use crate::types::Period;
+use bincode::Options;
+use flate2::{read::GzDecoder, write::GzEncoder, Compression, CrcReader, CrcWriter};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Format version of cached covers. Bump this whenever the layout of a cover or of its cells
+/// changes, so that files written by older versions are rebuilt rather than misread.
+pub const CACHE_VERSION: u32 = 4;
+
+/// Marks the start of every cache file
+const MAGIC: &[u8; 4] = b"MCYC";
+
+/// Format version of snapshot files. Bump this whenever the layout of a snapshot or of the
+/// values in it changes.
+pub const SNAPSHOT_VERSION: u32 = 3;
+
+/// Marks the start of every snapshot file
+const SNAPSHOT_MAGIC: &[u8; 4] = b"MCSN";
+
+/// Bytes taken by the longest kind of value a snapshot may hold, with its length
+const MAX_KIND_BYTES: u64 = 64;
+
+fn invalid(message: &str) -> io::Error
+{
+    io::Error::new(io::ErrorKind::InvalidData, message.to_owned())
+}
+
+/// Write a value in bincode's fixed-width little-endian layout
+fn encode<T: Serialize + ?Sized, W: Write>(value: &T, w: &mut W) -> io::Result<()>
+{
+    bincode::serialize_into(w, value).map_err(|e| into_io(*e))
+}
+
+/// Read a value written by [`encode`]
+fn decode<T: DeserializeOwned, R: Read>(r: &mut R) -> io::Result<T>
+{
+    bincode::deserialize_from(r).map_err(|e| into_io(*e))
+}
+
+fn into_io(e: bincode::ErrorKind) -> io::Error
+{
+    match e {
+        bincode::ErrorKind::Io(e) => e,
+        e => invalid(&e.to_string()),
+    }
+}
+
+/// A cover that can be kept in a [`CoverCache`]
+pub trait CachedCover: Serialize + DeserializeOwned
+{
+    /// Prefix of the cache file name, e.g. `mc`
+    const FAMILY: &'static str;
+}
+
+impl CachedCover for MarkedCycleCover
+{
+    const FAMILY: &'static str = "mc";
+}
+
+impl CachedCover for DynatomicCover
+{
+    const FAMILY: &'static str = "dyn";
+}
+
+/// A value that can be saved with [`save_snapshot`]
+pub trait Snapshot: Serialize + DeserializeOwned
+{
+    /// What the snapshot holds, e.g. `mc`, written into the file so that it is not read back
+    /// as something else
+    const KIND: &'static str;
+}
+
+impl Snapshot for MarkedCycleCover
+{
+    const KIND: &'static str = "mc";
+}
+
+impl Snapshot for DynatomicCover
+{
+    const KIND: &'static str = "dyn";
+}
+
+impl Snapshot for Lamination
+{
+    const KIND: &'static str = "lamination";
+}
+
+/// Save `value` to `path` uncompressed, with a checksum of its contents, so that it can be
+/// read back with [`load_snapshot`] much faster than it can be computed. The file is written
+/// under a temporary name and then renamed, so that other processes never read a partial file.
+pub fn save_snapshot<T: Snapshot>(path: impl AsRef<Path>, value: &T) -> io::Result<()>
+{
+    let path = path.as_ref();
+    let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+
+    let write = |tmp: &Path| -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(tmp)?);
+        w.write_all(SNAPSHOT_MAGIC)?;
+        encode(&SNAPSHOT_VERSION, &mut w)?;
+        encode(T::KIND, &mut w)?;
+
+        let mut w = CrcWriter::new(w);
+        encode(value, &mut w)?;
+        let checksum = w.crc().sum();
+        let mut w = w.into_inner();
+        encode(&checksum, &mut w)?;
+        w.flush()
+    };
+    write(&tmp)
+        .and_then(|()| fs::rename(&tmp, path))
+        .inspect_err(|_| {
+            let _ = fs::remove_file(&tmp);
+        })
+}
+
+/// Read a value saved with [`save_snapshot`]
+///
+/// # Errors
+///
+/// Fails if the file cannot be read, was written by another version, holds another kind of
+/// value, or does not match its checksum.
+pub fn load_snapshot<T: Snapshot>(path: impl AsRef<Path>) -> io::Result<T>
+{
+    let mut r = BufReader::new(File::open(path)?);
+
+    let mut magic = [0; 4];
+    r.read_exact(&mut magic)?;
+    if &magic != SNAPSHOT_MAGIC {
+        return Err(invalid("not a snapshot file"));
+    }
+    if decode::<u32, _>(&mut r)? != SNAPSHOT_VERSION {
+        return Err(invalid("snapshot written by another version"));
+    }
+    // A damaged length would otherwise be allocated before the checksum is reached
+    let kind: String = bincode::options()
+        .with_fixint_encoding()
+        .with_limit(MAX_KIND_BYTES)
+        .deserialize_from(&mut r)
+        .map_err(|e| into_io(*e))?;
+    if kind != T::KIND {
+        return Err(invalid(&format!(
+            "snapshot holds a {kind}, not a {}",
+            T::KIND
+        )));
+    }
+
+    let mut r = CrcReader::new(r);
+    let value: T = decode(&mut r)?;
+    let checksum = r.crc().sum();
+    let mut r = r.into_inner();
+    if decode::<u32, _>(&mut r)? != checksum {
+        return Err(invalid("snapshot does not match its checksum"));
+    }
+    if r.read(&mut [0])? != 0 {
+        return Err(invalid("trailing data after snapshot"));
+    }
+    Ok(value)
+}
+
+/// Directory of covers saved by earlier runs, one gzipped file per family, period, and critical
+/// period. Each file starts with [`CACHE_VERSION`], and files written by other versions are
+/// ignored and overwritten.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoverCache
+{
+    pub dir: PathBuf,
+}
+
+impl CoverCache
+{
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self
+    {
+        Self { dir: dir.into() }
+    }
+
+    /// Path of the file holding the given cover, e.g. `mc_6_1.bin.gz`
+    #[must_use]
+    pub fn path<C: CachedCover>(&self, period: Period, crit_period: Period) -> PathBuf
+    {
+        self.dir
+            .join(format!("{}_{period}_{crit_period}.bin.gz", C::FAMILY))
+    }
+
+    /// Read a cached cover, or `None` if there is none for this version
+    pub fn load<C: CachedCover>(&self, period: Period, crit_period: Period)
+        -> io::Result<Option<C>>
+    {
+        let file = match File::open(self.path::<C>(period, crit_period)) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let mut r = GzDecoder::new(BufReader::new(file));
+
+        let mut magic = [0; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a cover cache file"));
+        }
+        if decode::<u32, _>(&mut r)? != CACHE_VERSION {
+            return Ok(None);
+        }
+        if decode::<(Period, Period), _>(&mut r)? != (period, crit_period) {
+            return Err(invalid("cached cover has the wrong period"));
+        }
+        let cover: C = decode(&mut r)?;
+        // Reading to the end makes the decoder check the checksum of the whole file
+        if r.read(&mut [0])? != 0 {
+            return Err(invalid("trailing data after cached cover"));
+        }
+        Ok(Some(cover))
+    }
+
+    /// Save a cover, creating the cache directory if needed. The file is written under a
+    /// temporary name and then renamed, so that other processes never read a partial file.
+    pub fn store<C: CachedCover>(
+        &self,
+        cover: &C,
+        period: Period,
+        crit_period: Period,
+    ) -> io::Result<()>
+    {
+        fs::create_dir_all(&self.dir)?;
+        let path = self.path::<C>(period, crit_period);
+        let tmp = path.with_extension(format!("tmp{}", std::process::id()));
+
+        let write = |tmp: &Path| -> io::Result<()> {
+            let mut w = GzEncoder::new(BufWriter::new(File::create(tmp)?), Compression::fast());
+            w.write_all(MAGIC)?;
+            encode(&CACHE_VERSION, &mut w)?;
+            encode(&(period, crit_period), &mut w)?;
+            encode(cover, &mut w)?;
+            w.finish()?.flush()
+        };
+        write(&tmp)
+            .and_then(|()| fs::rename(&tmp, &path))
+            .inspect_err(|_| {
+                let _ = fs::remove_file(&tmp);
+            })
+    }
+
+    /// Load the cover from the cache, or build it with `build` and save it. Unreadable cache
+    /// files are rebuilt, and a cover that cannot be saved is still returned.
+    pub fn get_or_build<C: CachedCover>(
+        &self,
+        period: Period,
+        crit_period: Period,
+        build: impl FnOnce() -> C,
+    ) -> C
+    {
+        if let Ok(Some(cover)) = self.load(period, crit_period) {
+            return cover;
+        }
+        let cover = build();
+        let _ = self.store(&cover, period, crit_period);
+        cover
+    }
+}
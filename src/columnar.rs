@@ -0,0 +1,272 @@
+use crate::combinatorics::Combinatorics;
+use crate::common::cells::Edge;
+use crate::csv::CsvFace;
+use crate::global_state::{Context, DisplayIn};
+use crate::types::Period;
+use arrow_array::{
+    builder::{ListBuilder, StringBuilder, UInt64Builder},
+    ArrayRef, BooleanArray, Int64Array, RecordBatch, StringArray, UInt64Array,
+};
+use arrow_ipc::{reader::FileReader, writer::FileWriter};
+use parquet::{
+    arrow::{arrow_reader::ParquetRecordBatchReaderBuilder, ArrowWriter},
+    basic::Compression,
+    file::properties::WriterProperties,
+};
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    sync::Arc,
+};
+
+/// File format of a columnar table
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ColumnarFormat
+{
+    /// Snappy-compressed Parquet
+    Parquet,
+    /// Arrow IPC file, also known as Feather v2
+    Arrow,
+}
+
+impl ColumnarFormat
+{
+    #[must_use]
+    pub const fn extension(self) -> &'static str
+    {
+        match self {
+            Self::Parquet => "parquet",
+            Self::Arrow => "arrow",
+        }
+    }
+}
+
+/// Columns of the invariant table, after `period`, each named after the method of
+/// [`Combinatorics`] that fills it
+pub const INVARIANT_COLUMNS: [&str; 12] = [
+    "points_of_period_dividing_n",
+    "periodic_points",
+    "cycles",
+    "hyp_components_dividing_n",
+    "hyperbolic_components",
+    "satellite_components",
+    "primitive_components",
+    "self_conjugate_faces",
+    "vertices",
+    "edges",
+    "faces",
+    "genus",
+];
+
+fn batch(columns: Vec<(&str, ArrayRef)>) -> RecordBatch
+{
+    RecordBatch::try_from_iter_with_nullable(
+        columns
+            .into_iter()
+            .map(|(name, column)| (name, column, false)),
+    )
+    .expect("Columns are built with matching lengths")
+}
+
+fn strings<T: Display>(items: impl IntoIterator<Item = T>) -> ArrayRef
+{
+    Arc::new(StringArray::from_iter_values(
+        items.into_iter().map(|x| x.to_string()),
+    ))
+}
+
+/// One row of closed-form invariants per period, with a column for each quantity of
+/// [`Combinatorics`], in the order of [`INVARIANT_COLUMNS`]
+pub fn invariant_table<C>(comb: &C, periods: impl IntoIterator<Item = Period>) -> RecordBatch
+where
+    C: Combinatorics + ?Sized,
+{
+    let periods: Vec<Period> = periods.into_iter().collect();
+    let quantities: [fn(&C, Period) -> i64; 12] = [
+        C::points_of_period_dividing_n,
+        C::periodic_points,
+        C::cycles,
+        C::hyp_components_dividing_n,
+        C::hyperbolic_components,
+        C::satellite_components,
+        C::primitive_components,
+        C::self_conjugate_faces,
+        C::vertices,
+        C::edges,
+        C::faces,
+        C::genus,
+    ];
+
+    let mut columns: Vec<(&str, ArrayRef)> =
+        vec![("period", Arc::new(Int64Array::from(periods.clone())))];
+    for (name, quantity) in INVARIANT_COLUMNS.into_iter().zip(quantities) {
+        let values = periods.iter().map(|&n| quantity(comb, n));
+        columns.push((name, Arc::new(Int64Array::from_iter_values(values))));
+    }
+    batch(columns)
+}
+
+/// One columnar table per cell type, with the columns of [`crate::csv::CsvTables`], except that
+/// ids, angles, and sizes are integers, `real` is a boolean, and the boundary vertices and edge
+/// ids of a face are lists rather than space-separated text
+#[derive(Clone, Debug, PartialEq)]
+pub struct CellBatches
+{
+    pub vertices: RecordBatch,
+    pub edges: RecordBatch,
+    pub faces: RecordBatch,
+}
+
+impl CellBatches
+{
+    /// Tabulate the cells of a cover, with the vertices labeled in `context`
+    pub fn new<V>(context: Context, vertices: &[V], edges: &[Edge<V>], faces: &[CsvFace<V>]) -> Self
+    where
+        V: DisplayIn,
+    {
+        let ids = |n: usize| -> ArrayRef { Arc::new(UInt64Array::from_iter_values(0..n as u64)) };
+
+        let vertex_batch = batch(vec![
+            ("id", ids(vertices.len())),
+            (
+                "vertex",
+                strings(vertices.iter().map(|v| context.display(v))),
+            ),
+        ]);
+
+        let edge_batch = batch(vec![
+            ("id", ids(edges.len())),
+            (
+                "start",
+                strings(edges.iter().map(|e| context.display(&e.start))),
+            ),
+            (
+                "end",
+                strings(edges.iter().map(|e| context.display(&e.end))),
+            ),
+            (
+                "angle0",
+                Arc::new(Int64Array::from_iter_values(
+                    edges.iter().map(|e| e.wake.angle0.0),
+                )),
+            ),
+            (
+                "angle1",
+                Arc::new(Int64Array::from_iter_values(
+                    edges.iter().map(|e| e.wake.angle1.0),
+                )),
+            ),
+            (
+                "real",
+                Arc::new(BooleanArray::from_iter(
+                    edges.iter().map(|e| Some(e.is_real())),
+                )),
+            ),
+            (
+                "kneading_sequence",
+                strings(edges.iter().map(|e| e.kneading_sequence)),
+            ),
+        ]);
+
+        let mut boundaries = ListBuilder::new(StringBuilder::new());
+        let mut edge_ids = ListBuilder::new(UInt64Builder::new());
+        for (_, boundary, ids, _) in faces {
+            for v in boundary {
+                boundaries
+                    .values()
+                    .append_value(context.display(v).to_string());
+            }
+            boundaries.append(true);
+            for &id in ids {
+                edge_ids.values().append_value(id as u64);
+            }
+            edge_ids.append(true);
+        }
+        let face_batch = batch(vec![
+            ("id", ids(faces.len())),
+            ("label", strings(faces.iter().map(|f| &f.0))),
+            (
+                "degree",
+                Arc::new(Int64Array::from_iter_values(faces.iter().map(|f| f.3))),
+            ),
+            (
+                "size",
+                Arc::new(UInt64Array::from_iter_values(
+                    faces.iter().map(|f| f.1.len() as u64),
+                )),
+            ),
+            ("vertices", Arc::new(boundaries.finish())),
+            ("edge_ids", Arc::new(edge_ids.finish())),
+        ]);
+
+        Self {
+            vertices: vertex_batch,
+            edges: edge_batch,
+            faces: face_batch,
+        }
+    }
+
+    /// Write `{stem}_vertices`, `{stem}_edges`, and `{stem}_faces` into `dir` with the extension
+    /// of `format`, creating it if needed
+    pub fn write(&self, dir: impl AsRef<Path>, stem: &str, format: ColumnarFormat)
+        -> io::Result<()>
+    {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        let ext = format.extension();
+        for (cells, batch) in [
+            ("vertices", &self.vertices),
+            ("edges", &self.edges),
+            ("faces", &self.faces),
+        ] {
+            let mut file = BufWriter::new(File::create(dir.join(format!("{stem}_{cells}.{ext}")))?);
+            write_batch(batch, format, &mut file)?;
+            file.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Write a table to `out` as a single Parquet row group or Arrow record batch
+pub fn write_batch<W>(batch: &RecordBatch, format: ColumnarFormat, out: W) -> io::Result<()>
+where
+    W: Write + Send,
+{
+    match format {
+        ColumnarFormat::Parquet => {
+            let props = WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .build();
+            let mut writer =
+                ArrowWriter::try_new(out, batch.schema(), Some(props)).map_err(io::Error::other)?;
+            writer.write(batch).map_err(io::Error::other)?;
+            writer.close().map_err(io::Error::other)?;
+        }
+        ColumnarFormat::Arrow => {
+            let mut writer = FileWriter::try_new(out, &batch.schema()).map_err(io::Error::other)?;
+            writer.write(batch).map_err(io::Error::other)?;
+            writer.finish().map_err(io::Error::other)?;
+        }
+    }
+    Ok(())
+}
+
+/// Read back the record batches of a file written by [`write_batch`]
+pub fn read_batches(path: impl AsRef<Path>, format: ColumnarFormat)
+    -> io::Result<Vec<RecordBatch>>
+{
+    let file = File::open(path)?;
+    match format {
+        ColumnarFormat::Parquet => ParquetRecordBatchReaderBuilder::try_new(file)
+            .and_then(ParquetRecordBatchReaderBuilder::build)
+            .map_err(io::Error::other)?
+            .collect::<Result<_, _>>()
+            .map_err(io::Error::other),
+        ColumnarFormat::Arrow => FileReader::try_new(file, None)
+            .map_err(io::Error::other)?
+            .collect::<Result<_, _>>()
+            .map_err(io::Error::other),
+    }
+}
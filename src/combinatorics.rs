@@ -7,27 +7,43 @@ pub mod marked_cycle;
 
 pub trait Combinatorics
 {
+    #[must_use]
     fn points_of_period_dividing_n(&self, n: Period) -> INum;
 
+    #[must_use]
     fn periodic_points(&self, n: Period) -> INum;
 
+    #[must_use]
     fn cycles(&self, n: Period) -> INum;
 
+    #[must_use]
     fn hyp_components_dividing_n(&self, n: Period) -> INum;
 
+    #[must_use]
     fn hyperbolic_components(&self, n: Period) -> INum;
 
+    #[must_use]
     fn satellite_components(&self, n: Period) -> INum;
 
+    #[must_use]
     fn primitive_components(&self, n: Period) -> INum;
 
+    #[must_use]
     fn self_conjugate_faces(&self, n: Period) -> INum;
 
+    #[must_use]
     fn vertices(&self, n: Period) -> INum;
 
+    #[must_use]
     fn edges(&self, n: Period) -> INum;
 
+    #[must_use]
     fn faces(&self, n: Period) -> INum;
 
+    #[must_use]
     fn genus(&self, n: Period) -> INum;
+
+    /// Largest period whose closed-form counts fit in an [`INum`]
+    #[must_use]
+    fn max_period(&self) -> Period;
 }
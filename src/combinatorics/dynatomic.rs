@@ -1,7 +1,8 @@
-use std::collections::HashMap;
-
 use super::Combinatorics;
 use crate::arithmetic::*;
+#[cfg(feature = "cache")]
+use crate::cache::CoverCache;
+use crate::common::FxHashMap;
 use crate::dynatomic_cover::DynatomicCover;
 use crate::types::{INum, Period};
 use num::pow;
@@ -9,7 +10,13 @@ use num::pow;
 pub struct Comb
 {
     crit_period: Period,
-    curves: HashMap<Period, DynatomicCover>,
+    curves: FxHashMap<Period, DynatomicCover>,
+    /// Vertex, edge, and face counts and genus of each cover built, kept after it is evicted
+    counts: FxHashMap<Period, (usize, usize, usize, i64)>,
+    /// Most covers to hold in memory at once, or `None` for no limit
+    max_curves: Option<usize>,
+    #[cfg(feature = "cache")]
+    cache: Option<CoverCache>,
 }
 
 impl Comb
@@ -17,44 +24,116 @@ impl Comb
     #[must_use]
     pub fn new(crit_period: Period) -> Self
     {
-        let curves = HashMap::new();
+        let curves = FxHashMap::default();
 
         Self {
             crit_period,
             curves,
+            counts: FxHashMap::default(),
+            max_curves: None,
+            #[cfg(feature = "cache")]
+            cache: None,
         }
     }
 
+    /// Load covers from `cache` instead of building them where possible, and save the ones
+    /// that are built, so that later processes can reuse them
+    #[cfg(feature = "cache")]
+    #[must_use]
+    pub fn with_cache(mut self, cache: CoverCache) -> Self
+    {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Hold at most `max_curves` covers (and at least one) in memory, evicting those of
+    /// largest period to make room. Their counts stay available without rebuilding them.
+    #[must_use]
+    pub fn with_max_curves(mut self, max_curves: usize) -> Self
+    {
+        self.max_curves = Some(max_curves.max(1));
+        self
+    }
+
+    /// Whether the cover of period `n` is currently held in memory
+    #[must_use]
+    pub fn holds(&self, n: Period) -> bool
+    {
+        self.curves.contains_key(&n)
+    }
+
     pub fn curve(&mut self, n: Period) -> &mut DynatomicCover
     {
+        if !self.holds(n) {
+            self.make_room();
+        }
         let crit_per = self.crit_period;
-        self.curves
-            .entry(n)
-            .or_insert_with(|| DynatomicCover::new(n, crit_per))
+        #[cfg(feature = "cache")]
+        let cache = self.cache.as_ref();
+        let counts = &mut self.counts;
+        self.curves.entry(n).or_insert_with(|| {
+            let build = || DynatomicCover::new(n, crit_per);
+            #[cfg(feature = "cache")]
+            let curve = match cache {
+                Some(cache) => cache.get_or_build(n, crit_per, build),
+                None => build(),
+            };
+            #[cfg(not(feature = "cache"))]
+            let curve = build();
+            counts.insert(
+                n,
+                (
+                    curve.num_vertices(),
+                    curve.num_edges(),
+                    curve.num_faces(),
+                    curve.genus(),
+                ),
+            );
+            curve
+        })
+    }
+
+    /// Evict the covers of largest period until there is room for one more
+    fn make_room(&mut self)
+    {
+        let Some(max_curves) = self.max_curves else {
+            return;
+        };
+        while self.curves.len() >= max_curves {
+            let Some(&largest) = self.curves.keys().max() else {
+                break;
+            };
+            self.curves.remove(&largest);
+        }
+    }
+
+    fn counts(&mut self, n: Period) -> (usize, usize, usize, i64)
+    {
+        if let Some(&counts) = self.counts.get(&n) {
+            return counts;
+        }
+        self.curve(n);
+        self.counts[&n]
     }
 
     pub fn cover_vertices(&mut self, n: Period) -> usize
     {
-        let curve = self.curve(n);
-        curve.num_vertices()
+        self.counts(n).0
     }
 
     pub fn cover_edges(&mut self, n: Period) -> usize
     {
-        let curve = self.curve(n);
-        curve.num_edges()
+        self.counts(n).1
     }
 
     pub fn cover_faces(&mut self, n: Period) -> usize
     {
-        let curve = self.curve(n);
-        curve.num_faces()
+        self.counts(n).2
     }
 
     pub fn cover_genus(&mut self, n: Period) -> i64
     {
-        let curve = self.curve(n);
-        curve.genus()
+        self.counts(n).3
     }
 
     #[must_use]
@@ -71,7 +150,6 @@ impl Comb
 }
 impl Combinatorics for Comb
 {
-    #[must_use]
     fn points_of_period_dividing_n(&self, n: Period) -> INum
     {
         // Number of points of period dividing n
@@ -84,21 +162,18 @@ impl Combinatorics for Comb
         }
     }
 
-    #[must_use]
     fn periodic_points(&self, n: Period) -> INum
     {
         // Number of n-periodic points for z -> z^(+/- 2)
         moebius_inversion(|d| self.points_of_period_dividing_n(d), n)
     }
 
-    #[must_use]
     fn cycles(&self, n: Period) -> INum
     {
         // Number of n-cycles of z -> z^(+/- 2)
         self.periodic_points(n) / (n as INum)
     }
 
-    #[must_use]
     fn hyp_components_dividing_n(&self, n: Period) -> INum
     {
         // Number of mateable hyperbolic components of period dividing n
@@ -110,7 +185,6 @@ impl Combinatorics for Comb
         }
     }
 
-    #[must_use]
     fn hyperbolic_components(&self, n: Period) -> INum
     {
         // Number of mateable hyperbolic components of period n
@@ -156,25 +230,21 @@ impl Combinatorics for Comb
             / n
     }
 
-    #[must_use]
     fn vertices(&self, n: Period) -> INum
     {
         self.periodic_points(n)
     }
 
-    #[must_use]
     fn edges(&self, n: Period) -> INum
     {
         n * self.hyperbolic_components(n)
     }
 
-    #[must_use]
     fn faces(&self, n: Period) -> INum
     {
         self.primitive_faces(n) + self.satellite_faces(n)
     }
 
-    #[must_use]
     fn genus(&self, n: Period) -> INum
     {
         let hyp = self.hyperbolic_components(n);
@@ -186,4 +256,10 @@ impl Combinatorics for Comb
             _ => 0,
         }
     }
+
+    fn max_period(&self) -> Period
+    {
+        // The satellite faces are the first to overflow, from period 59
+        58
+    }
 }
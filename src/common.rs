@@ -1,41 +1,700 @@
-use crate::global_state::{MAX_ANGLE, PERIOD};
-use crate::types::IntAngle;
+use crate::global_state::Context;
+#[cfg(feature = "std")]
+use crate::lamination::Lamination;
+use crate::progress::ProgressSink;
+#[cfg(feature = "stats")]
+use crate::stats::{BuildStats, HeapCounter, HeapStats};
+#[cfg(feature = "std")]
+use crate::types::RatAngle;
+use crate::types::{IntAngle, Period};
+use alloc::vec::Vec;
+use core::ops::Range;
+use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
+#[cfg(feature = "std")]
+use rayon::prelude::*;
+
+#[cfg(feature = "std")]
+pub use rustc_hash::FxHashMap;
+/// Hash map with the Fx hasher, taken from `hashbrown` since `std`'s map is unavailable
+#[cfg(not(feature = "std"))]
+pub type FxHashMap<K, V> = hashbrown::HashMap<K, V, rustc_hash::FxBuildHasher>;
+
+#[cfg(all(
+    feature = "std",
+    not(all(target_arch = "wasm32", target_os = "unknown"))
+))]
+pub(crate) use std::time::Instant;
+
+/// Stand-in for `std::time::Instant` without `std`, or in the browser, where `std` has no
+/// clock; every build phase is then timed as taking no time
+#[cfg(any(
+    not(feature = "std"),
+    all(target_arch = "wasm32", target_os = "unknown")
+))]
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Instant;
+
+#[cfg(any(
+    not(feature = "std"),
+    all(target_arch = "wasm32", target_os = "unknown")
+))]
+impl Instant
+{
+    pub(crate) const fn now() -> Self
+    {
+        Self
+    }
+
+    pub(crate) const fn elapsed(&self) -> Duration
+    {
+        Duration::ZERO
+    }
+}
+
+/// Time spent in each phase of building a cover
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuildTimings
+{
+    /// Computing the leaves of the lamination
+    pub lamination: Duration,
+    /// Sorting angles into cycles to get the vertices
+    pub cycles: Duration,
+    /// Turning leaves into edges
+    pub edges: Duration,
+    /// Tracing the boundaries of the faces
+    pub faces: Duration,
+    /// Heap usage of each phase, and the number of cells built
+    #[cfg(feature = "stats")]
+    pub stats: BuildStats,
+}
+
+impl BuildTimings
+{
+    #[must_use]
+    pub fn total(&self) -> Duration
+    {
+        self.lamination + self.cycles + self.edges + self.faces
+    }
+
+    /// The phases with their names, in the order they run
+    #[must_use]
+    pub fn phases(&self) -> [(&'static str, Duration); 4]
+    {
+        [
+            (Phase::Lamination.name(), self.lamination),
+            (Phase::Cycles.name(), self.cycles),
+            (Phase::Edges.name(), self.edges),
+            (Phase::Faces.name(), self.faces),
+        ]
+    }
+
+    /// Record what `phase` cost
+    pub(crate) fn record(&mut self, phase: Phase, cost: PhaseCost)
+    {
+        let time = match phase {
+            Phase::Lamination => &mut self.lamination,
+            Phase::Cycles => &mut self.cycles,
+            Phase::Edges => &mut self.edges,
+            Phase::Faces => &mut self.faces,
+        };
+        *time = cost.time;
+        #[cfg(feature = "stats")]
+        {
+            let heap = match phase {
+                Phase::Lamination => &mut self.stats.lamination,
+                Phase::Cycles => &mut self.stats.cycles,
+                Phase::Edges => &mut self.stats.edges,
+                Phase::Faces => &mut self.stats.faces,
+            };
+            *heap = cost.heap;
+        }
+    }
+}
+
+/// Phases of a build, in the order they run
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Phase
+{
+    Lamination,
+    Cycles,
+    Edges,
+    Faces,
+}
+
+impl Phase
+{
+    /// Name of the phase, as in [`BuildTimings::phases`]
+    #[must_use]
+    pub const fn name(self) -> &'static str
+    {
+        match self {
+            Self::Lamination => "lamination",
+            Self::Cycles => "cycles",
+            Self::Edges => "edges",
+            Self::Faces => "faces",
+        }
+    }
+}
+
+/// Clock for one phase of a build, which with the `stats` feature also reads the heap counters
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PhaseClock
+{
+    start: Instant,
+    #[cfg(feature = "stats")]
+    heap: HeapCounter,
+}
+
+/// Time taken by one phase of a build, and with the `stats` feature its heap usage
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct PhaseCost
+{
+    time: Duration,
+    #[cfg(feature = "stats")]
+    heap: HeapStats,
+}
+
+impl PhaseClock
+{
+    pub(crate) fn start() -> Self
+    {
+        Self {
+            start: Instant::now(),
+            #[cfg(feature = "stats")]
+            heap: HeapCounter::start(),
+        }
+    }
+
+    pub(crate) fn stop(self) -> PhaseCost
+    {
+        PhaseCost {
+            time: self.start.elapsed(),
+            #[cfg(feature = "stats")]
+            heap: self.heap.stop(),
+        }
+    }
+}
+
+/// Angles in an orbit under doubling. With the `smallvec` feature, orbits of period up to 16
+/// are stored inline.
+#[cfg(feature = "smallvec")]
+pub type Orbit = smallvec::SmallVec<[IntAngle; 16]>;
+/// Angles in an orbit under doubling
+#[cfg(not(feature = "smallvec"))]
+pub type Orbit = Vec<IntAngle>;
 
 #[must_use]
 #[inline]
-pub fn get_orbit(angle: IntAngle) -> Vec<IntAngle>
+pub fn get_orbit(angle: IntAngle) -> Orbit
 {
-    let mut orbit = Vec::with_capacity(PERIOD.get() as usize);
+    get_orbit_in(Context::current(), angle)
+}
+
+/// Orbit of `angle` under doubling, with angles measured in units of `1/context.max_angle`
+#[must_use]
+pub fn get_orbit_in(context: Context, angle: IntAngle) -> Orbit
+{
+    let mut orbit = Orbit::with_capacity(context.period as usize);
 
     orbit.push(angle);
-    let mut theta = angle * 2 % MAX_ANGLE.get();
+    let mut theta = angle * 2 % context.max_angle;
 
     while theta != angle {
         orbit.push(theta);
-        theta = theta * 2 % MAX_ANGLE.get();
+        theta = theta * 2 % context.max_angle;
     }
 
     orbit
 }
 
+/// Angles that can mark a cycle of period `context.period`. For period one, the angle `1`
+/// stands for the α fixed point, which is distinct from the β fixed point at angle `0`.
+fn cycle_angles(context: Context) -> Range<i64>
+{
+    0..context.max_angle.0 + i64::from(context.period == 1)
+}
+
+/// Doubling modulo `2^period - 1`, which rotates the binary expansion of an angle
+fn rotation(context: Context) -> impl Fn(i64) -> i64
+{
+    let (max_angle, shift) = (context.max_angle.0, context.period - 1);
+    move |theta| ((theta << 1) | (theta >> shift)) & max_angle
+}
+
+/// Walk the orbit of `angle` under doubling without storing it, returning its least angle, the
+/// number of doublings from `angle` to the least angle, and the length of the orbit
+fn orbit_scan(context: Context, angle: i64) -> (i64, Period, Period)
+{
+    let rotate = rotation(context);
+    let (mut theta, mut least, mut steps) = (angle, angle, 0);
+    for k in 1..context.period {
+        theta = rotate(theta);
+        if theta == angle {
+            return (least, steps, k);
+        }
+        if theta < least {
+            (least, steps) = (theta, k);
+        }
+    }
+    (least, steps, context.period)
+}
+
+/// Bit `j` of `LANE_BITS[k]` is bit `k` of `j`, for each lane `j` of a [`SlicedBlock`]
+const LANE_BITS: [u64; 6] = [
+    0xAAAA_AAAA_AAAA_AAAA,
+    0xCCCC_CCCC_CCCC_CCCC,
+    0xF0F0_F0F0_F0F0_F0F0,
+    0xFF00_FF00_FF00_FF00,
+    0xFFFF_0000_FFFF_0000,
+    0xFFFF_FFFF_0000_0000,
+];
+
+/// Least period for which cycles are enumerated a [`SlicedBlock`] at a time
+const SLICED_PERIOD: Period = 6;
+
+/// The 64 angles `base, ..., base + 63`, for `base` a multiple of 64, stored bit-sliced: word
+/// `k` holds bit `k` of every angle, with one bit lane per angle. Doubling rotates the binary
+/// expansion of every angle at once, so in this form it only renames the words, and a word
+/// operation compares a bit of 64 rotations with the same bit of their angles.
+struct SlicedBlock
+{
+    base: i64,
+    period: usize,
+    words: [u64; 64],
+    /// Lanes holding angles in the range being scanned
+    lanes: u64,
+}
+
+impl SlicedBlock
+{
+    /// The block with index `block`, keeping only lanes whose angles lie in `angles`
+    fn new(context: Context, angles: &Range<i64>, block: i64) -> Self
+    {
+        let base = block << 6;
+        let period = context.period as usize;
+        let mut words = [0; 64];
+        for (k, word) in words.iter_mut().enumerate().take(period) {
+            *word = match LANE_BITS.get(k) {
+                Some(&bits) => bits,
+                None if base >> k & 1 == 1 => u64::MAX,
+                None => 0,
+            };
+        }
+        let len = (angles.end - base).min(64);
+        let lanes = if len == 64 { u64::MAX } else { (1 << len) - 1 };
+        Self {
+            base,
+            period,
+            words,
+            lanes,
+        }
+    }
+
+    /// Word `k` of the angles doubled `r` times
+    #[inline]
+    fn doubled(&self, r: usize, k: usize) -> u64
+    {
+        self.words[(k + self.period - r) % self.period]
+    }
+
+    /// Among `lanes`, those where `x` is less than `y` and those where it is greater, comparing
+    /// from the most significant word down and stopping once every lane is decided
+    #[inline]
+    fn compare(&self, lanes: u64, x: impl Fn(usize) -> u64, y: impl Fn(usize) -> u64)
+        -> (u64, u64)
+    {
+        let (mut undecided, mut less, mut greater) = (lanes, 0, 0);
+        for k in (0..self.period).rev() {
+            let (x, y) = (x(k), y(k));
+            less |= undecided & !x & y;
+            greater |= undecided & x & !y;
+            undecided &= !(x ^ y);
+            if undecided == 0 {
+                break;
+            }
+        }
+        (less, greater)
+    }
+
+    /// Lanes whose angle has exact period `period` and is less than every other angle in its
+    /// orbit. Each doubling rules out the lanes where it is not greater than the angle.
+    fn cycle_reps(&self) -> u64
+    {
+        let mut reps = self.lanes;
+        for r in 1..self.period {
+            if reps == 0 {
+                break;
+            }
+            (_, reps) = self.compare(reps, |k| self.doubled(r, k), |k| self.words[k]);
+        }
+        reps
+    }
+
+    /// The position of each lane's angle in its cycle as given by [`cycle_position`], for lanes
+    /// whose angle has exact period `period`. The least double seen so far and the number of
+    /// doublings that reach it are kept bit-sliced as well.
+    fn cycle_positions(self) -> impl Iterator<Item = (IntAngle, Period)>
+    {
+        let n = self.period;
+        let mut least = self.words;
+        let mut steps = [0_u64; 6];
+        let mut exact = self.lanes;
+        for r in 1..n {
+            if n.is_multiple_of(r) {
+                let (less, greater) =
+                    self.compare(exact, |k| self.doubled(r, k), |k| self.words[k]);
+                exact = less | greater;
+            }
+            let (less, _) = self.compare(exact, |k| self.doubled(r, k), |k| least[k]);
+            if less != 0 {
+                for (k, word) in least.iter_mut().enumerate().take(n) {
+                    *word ^= (*word ^ self.doubled(r, k)) & less;
+                }
+                for (i, word) in steps.iter_mut().enumerate() {
+                    let bit = if r >> i & 1 == 1 { u64::MAX } else { 0 };
+                    *word ^= (*word ^ bit) & less;
+                }
+            }
+        }
+
+        let rotate = move |angle: i64, r: usize| {
+            let max_angle = (1 << n) - 1;
+            ((angle << r) | (angle >> (n - r))) & max_angle
+        };
+        lanes(exact).map(move |j| {
+            let r = (0..6).fold(0, |r, i| r | ((steps[i] >> j & 1) as usize) << i);
+            let least = IntAngle(rotate(self.base + j as i64, r));
+            (least, ((n - r) % n) as Period)
+        })
+    }
+}
+
+/// Indices of the set bits of `mask`, in increasing order
+fn lanes(mut mask: u64) -> impl Iterator<Item = usize>
+{
+    core::iter::from_fn(move || {
+        (mask != 0).then(|| {
+            let j = mask.trailing_zeros() as usize;
+            mask &= mask - 1;
+            j
+        })
+    })
+}
+
+/// Indices of the [`SlicedBlock`]s covering `angles`
+fn sliced_blocks(angles: &Range<i64>) -> Range<i64>
+{
+    0..(angles.end + 63) >> 6
+}
+
+/// Least angle in the orbit of `angle` under doubling, and the length of the orbit, without
+/// materializing the orbit as [`get_orbit`] does
+#[must_use]
+#[inline]
+pub fn orbit_min_and_len(angle: IntAngle) -> (IntAngle, Period)
+{
+    orbit_min_and_len_in(Context::current(), angle)
+}
+
+/// Least angle in the orbit of `angle` under doubling, and the length of the orbit, with angles
+/// measured in units of `1/context.max_angle`. Since doubling rotates the binary expansion, this
+/// is the least rotation of a word of at most 62 bits, found by stepping through all of them.
+#[must_use]
+pub fn orbit_min_and_len_in(context: Context, angle: IntAngle) -> (IntAngle, Period)
+{
+    let (least, _, len) = orbit_scan(context, angle.0 % context.max_angle.0);
+    (IntAngle(least), len)
+}
+
+/// Whether `angle` has exact period `context.period` and is the least angle in its orbit
+fn is_cycle_rep(context: Context, angle: IntAngle) -> bool
+{
+    let rotate = rotation(context);
+    let mut theta = angle.0;
+    for _ in 1..context.period {
+        theta = rotate(theta);
+        if theta <= angle.0 {
+            return false;
+        }
+    }
+    rotate(theta) == angle.0
+}
+
+/// The least angle in the orbit of `angle`, and how many times it must be doubled to reach
+/// `angle`, or `None` unless `angle` has exact period `context.period`. This replaces a table
+/// indexed by angle, which would take memory proportional to `2^period`.
+#[must_use]
+pub fn cycle_position(context: Context, angle: IntAngle) -> Option<(IntAngle, Period)>
+{
+    if !cycle_angles(context).contains(&angle.0) {
+        return None;
+    }
+    let (least, steps, len) = orbit_scan(context, angle.0);
+    (len == context.period).then(|| (IntAngle(least), (len - steps) % len))
+}
+
+/// The least angle of each cycle of exact period `context.period`, in increasing order. Above
+/// small periods, the angles are scanned 64 at a time in bit-sliced form, in parallel with
+/// `std`.
+#[must_use]
+pub fn cycle_reps(context: Context) -> Vec<IntAngle>
+{
+    let angles = cycle_angles(context);
+    if context.period < SLICED_PERIOD {
+        return angles
+            .map(IntAngle)
+            .filter(|&angle| is_cycle_rep(context, angle))
+            .collect();
+    }
+    let block = |b: i64| {
+        let block = SlicedBlock::new(context, &angles, b);
+        lanes(block.cycle_reps()).map(move |j| IntAngle(block.base + j as i64))
+    };
+
+    #[cfg(feature = "std")]
+    let reps = sliced_blocks(&angles)
+        .into_par_iter()
+        .flat_map_iter(block)
+        .collect();
+    #[cfg(not(feature = "std"))]
+    let reps = sliced_blocks(&angles).flat_map(block).collect();
+    reps
+}
+
+/// Every angle of exact period `context.period`, in increasing order, with its position as
+/// given by [`cycle_position`]. Above small periods, the orbits are walked 64 angles at a time
+/// in bit-sliced form, in parallel with `std`.
+#[must_use]
+pub fn cycle_positions(context: Context) -> Vec<(IntAngle, Period)>
+{
+    let angles = cycle_angles(context);
+    if context.period < SLICED_PERIOD {
+        return angles
+            .filter_map(|angle| cycle_position(context, IntAngle(angle)))
+            .collect();
+    }
+    let block = |b: i64| SlicedBlock::new(context, &angles, b).cycle_positions();
+
+    #[cfg(feature = "std")]
+    let positions = sliced_blocks(&angles)
+        .into_par_iter()
+        .flat_map_iter(block)
+        .collect();
+    #[cfg(not(feature = "std"))]
+    let positions = sliced_blocks(&angles).flat_map(block).collect();
+    positions
+}
+
+/// Apply `build` to each of `periods` and the leaves of that period, on one thread per period.
+/// The lamination is extended once, up to the largest period, and shared by every thread; each
+/// thread has its own ambient context. The results are returned in the order of `periods`.
+#[cfg(feature = "std")]
+pub(crate) fn build_periods<T, F>(periods: &[Period], crit_period: Period, build: F) -> Vec<T>
+where
+    T: Send,
+    F: Fn(Period, &[(RatAngle, RatAngle)]) -> T + Sync,
+{
+    let mut lamination = Lamination::new().with_crit_period(crit_period);
+    if let Some(&max_period) = periods.iter().max() {
+        lamination.extend_to_period(max_period);
+    }
+    let (lamination, build) = (&lamination, &build);
+    std::thread::scope(|s| {
+        let threads: Vec<_> = periods
+            .iter()
+            .map(|&period| {
+                s.spawn(move || {
+                    let arcs = lamination.arcs(period).expect("lamination is extended");
+                    build(period, arcs)
+                })
+            })
+            .collect();
+        threads
+            .into_iter()
+            .map(|t| t.join().expect("cover thread panicked"))
+            .collect()
+    })
+}
+
+/// Trace every face of a cover, in parallel with `std`. `traverse` walks the face from a
+/// starting vertex and records the ids of the other vertices at which the walk crosses the
+/// positive real axis; starting from any of these would trace the same face. Each face is kept
+/// only from whichever of its crossing vertices comes first in `vertices`, and the faces are
+/// returned in that order, so the result does not depend on scheduling. Vertices already known
+/// to start a face elsewhere are skipped. Each vertex, skipped or not, is reported to `progress`
+/// as an item of [`Phase::Faces`].
+pub fn traverse_faces<V, F, T>(vertices: &[V], traverse: T, progress: &dyn ProgressSink) -> Vec<F>
+where
+    V: Copy + Send + Sync,
+    F: Send,
+    T: Fn(V, &mut Vec<cells::VertexId>) -> F + Sync,
+{
+    let skipped: Vec<AtomicBool> = vertices.iter().map(|_| AtomicBool::new(false)).collect();
+
+    #[cfg(feature = "std")]
+    let vertices = vertices.par_iter();
+    #[cfg(not(feature = "std"))]
+    let vertices = vertices.iter();
+
+    vertices
+        .enumerate()
+        .filter_map(|(i, &v)| trace_face(&skipped, i, v, &traverse, progress))
+        .collect()
+}
+
+/// Trace every face as in [`traverse_faces`], but hand each one to `emit` as soon as it is
+/// traced rather than collecting them. The faces are the same, but arrive in whatever order the
+/// threads finish them.
+#[cfg(feature = "std")]
+pub fn stream_faces<V, F, T, E>(vertices: &[V], traverse: T, emit: E, progress: &dyn ProgressSink)
+where
+    V: Copy + Send + Sync,
+    F: Send,
+    T: Fn(V, &mut Vec<cells::VertexId>) -> F + Sync,
+    E: Fn(F) + Sync,
+{
+    let skipped: Vec<AtomicBool> = vertices.iter().map(|_| AtomicBool::new(false)).collect();
+    vertices
+        .par_iter()
+        .enumerate()
+        .filter_map(|(i, &v)| trace_face(&skipped, i, v, &traverse, progress))
+        .for_each(&emit);
+}
+
+/// Trace the face starting from the `i`th vertex `v`, unless it is known to start elsewhere,
+/// and mark its other crossing vertices as skipped. Returns the face only if `v` is the first
+/// of its crossing vertices.
+fn trace_face<V, F, T>(
+    skipped: &[AtomicBool],
+    i: usize,
+    v: V,
+    traverse: &T,
+    progress: &dyn ProgressSink,
+) -> Option<F>
+where
+    T: Fn(V, &mut Vec<cells::VertexId>) -> F,
+{
+    progress.items_processed(Phase::Faces, 1);
+    if skipped[i].load(Ordering::Relaxed) {
+        return None;
+    }
+    let mut crossings = Vec::new();
+    let face = traverse(v, &mut crossings);
+
+    let first = crossings.iter().map(|&id| id as usize).fold(i, usize::min);
+    for id in crossings.into_iter().map(|id| id as usize) {
+        if id != first {
+            skipped[id].store(true, Ordering::Relaxed);
+        }
+    }
+    (first == i).then_some(face)
+}
+
 pub mod cells
 {
     use crate::{
-        abstract_cycles::AbstractPoint,
-        global_state::{MAX_ANGLE, PERIOD},
-        types::{IntAngle, Period},
+        abstract_cycles::{AbstractCycle, AbstractPoint, ShiftedCycle},
+        global_state::{Context, DisplayIn, InContext},
+        types::{IntAngle, KneadingSequence, Period, RatAngle},
+    };
+    use alloc::{
+        collections::BTreeMap,
+        format,
+        string::{String, ToString},
+        vec,
+        vec::Vec,
     };
 
-    #[derive(Debug, PartialEq, Eq)]
+    /// Index of a vertex in its cover's list of vertices
+    pub type VertexId = u32;
+
+    /// A cell of a cover, as sent by the builders' `build_streaming`
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum Cell<V, F>
+    {
+        Vertex(V),
+        Edge(Edge<V>),
+        Face(F),
+    }
+
+    /// Boundary vertices of a face. With the `smallvec` feature, boundaries of up to 16
+    /// vertices are stored inline.
+    #[cfg(feature = "smallvec")]
+    pub type VertexList<V> = smallvec::SmallVec<[V; 16]>;
+    /// Boundary vertices of a face
+    #[cfg(not(feature = "smallvec"))]
+    pub type VertexList<V> = Vec<V>;
+
+    #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
     pub struct Face<V, F>
     {
         pub label: F,
-        pub vertices: Vec<V>,
+        pub vertices: VertexList<V>,
+        /// Indices into the cover's edge list; `edge_ids[i]` joins `vertices[i]` to the next
+        /// vertex on the boundary
+        pub edge_ids: Vec<usize>,
         pub degree: Period,
     }
 
     impl<V, F> Face<V, F>
     {
+        /// The same face with every vertex marked as non-real, for use where augmented vertices
+        /// are expected
+        #[must_use]
+        pub fn augmented(self) -> Face<AugmentedVertex<V>, F>
+        {
+            Face {
+                label: self.label,
+                vertices: self
+                    .vertices
+                    .into_iter()
+                    .map(|vertex| AugmentedVertex {
+                        vertex,
+                        data: VertexData::NonReal,
+                    })
+                    .collect(),
+                edge_ids: self.edge_ids,
+                degree: self.degree,
+            }
+        }
+
+        /// A copy of the face with each vertex replaced by its image under `f`, e.g. to look up
+        /// vertex ids in the cover's list of vertices
+        #[must_use]
+        pub fn map_vertices<W>(&self, f: impl FnMut(&V) -> W) -> Face<W, F>
+        where
+            F: Clone,
+        {
+            Face {
+                label: self.label.clone(),
+                vertices: self.vertices.iter().map(f).collect(),
+                edge_ids: self.edge_ids.clone(),
+                degree: self.degree,
+            }
+        }
+
+        /// Replace each edge id `i` with `new_ids[i]`, after the cover's edges are reordered
+        pub fn renumber_edges(&mut self, new_ids: &[usize])
+        {
+            for id in &mut self.edge_ids {
+                *id = new_ids[*id];
+            }
+        }
+
+        /// The same face with its label transformed by `f`
+        #[must_use]
+        pub fn map_label<G>(self, f: impl FnOnce(F) -> G) -> Face<V, G>
+        {
+            Face {
+                label: f(self.label),
+                vertices: self.vertices,
+                edge_ids: self.edge_ids,
+                degree: self.degree,
+            }
+        }
+
         pub fn edges(&self) -> Vec<(V, V)>
         where
             V: Copy,
@@ -50,6 +709,49 @@ pub mod cells
             edges
         }
 
+        /// The oriented edges of the boundary, in order, each as its start, its end, and its
+        /// index into the cover's edge list
+        pub fn boundary_edges(&self) -> impl Iterator<Item = (&V, &V, usize)> + '_
+        {
+            let n = self.vertices.len();
+            self.edge_ids
+                .iter()
+                .enumerate()
+                .map(move |(i, &id)| (&self.vertices[i], &self.vertices[(i + 1) % n], id))
+        }
+
+        /// Whether `vertex` lies on the boundary
+        pub fn contains(&self, vertex: &V) -> bool
+        where
+            V: PartialEq,
+        {
+            self.vertices.contains(vertex)
+        }
+
+        /// Rotate the boundary to start at its least vertex, breaking ties between several
+        /// visits to that vertex by the rest of the boundary. Two faces tracing the same
+        /// boundary from different starting points are equal once canonicalized.
+        pub fn canonicalize(&mut self)
+        where
+            V: Ord,
+        {
+            let n = self.vertices.len();
+            let (vertices, edge_ids) = (&self.vertices, &self.edge_ids);
+            let rotated = |start: usize| {
+                (0..n).map(move |i| {
+                    let j = (start + i) % n;
+                    (&vertices[j], edge_ids.get(j))
+                })
+            };
+            let Some(start) = (0..n).min_by(|&a, &b| rotated(a).cmp(rotated(b))) else {
+                return;
+            };
+            self.vertices.rotate_left(start);
+            if self.edge_ids.len() == n {
+                self.edge_ids.rotate_left(start);
+            }
+        }
+
         #[inline]
         pub const fn is_reflexive(&self) -> bool
         {
@@ -69,30 +771,57 @@ pub mod cells
         }
     }
 
-    impl<V, F> std::fmt::Display for Face<V, F>
+    /// Faces on either side of each edge, as indices into `faces`, keyed by edge id and sorted
+    /// by it. A face that meets itself along an edge is listed twice; edges lying on fewer than
+    /// two face sides are omitted.
+    pub fn dual_edges<V, F>(faces: &[Face<V, F>]) -> Vec<(usize, (usize, usize))>
+    {
+        let mut sides: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+        for (k, face) in faces.iter().enumerate() {
+            for &id in &face.edge_ids {
+                sides.entry(id).or_default().push(k);
+            }
+        }
+        sides
+            .into_iter()
+            .filter_map(|(id, fs)| match fs[..] {
+                [a, b] => Some((id, (a, b))),
+                _ => None,
+            })
+            .collect()
+    }
+
+    impl<V, F> core::fmt::Display for Face<V, F>
     where
-        V: std::fmt::Display,
-        F: std::fmt::Display,
+        V: core::fmt::Display,
+        F: core::fmt::Display,
     {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
         {
-            let vertices_as_strings: Vec<String> =
-                self.vertices.iter().map(ToString::to_string).collect();
+            let vertices_as_strings: Vec<String> = if f.alternate() {
+                self.vertices.iter().map(|v| format!("{v:#}")).collect()
+            } else {
+                self.vertices.iter().map(ToString::to_string).collect()
+            };
+            let label = if f.alternate() {
+                format!("{:#}", self.label)
+            } else {
+                self.label.to_string()
+            };
             write!(
                 f,
-                "{} = ({}); deg = {}",
-                self.label,
+                "{label} = ({}); deg = {}",
                 vertices_as_strings.join(" "),
                 self.degree
             )
         }
     }
-    impl<V, F> std::fmt::Binary for Face<V, F>
+    impl<V, F> core::fmt::Binary for Face<V, F>
     where
-        V: std::fmt::Binary,
-        F: std::fmt::Binary,
+        V: core::fmt::Binary,
+        F: core::fmt::Binary,
     {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
         {
             let vertices_as_strings: Vec<String> =
                 self.vertices.iter().map(|v| format!("{v:b}")).collect();
@@ -106,11 +835,45 @@ pub mod cells
         }
     }
 
+    impl<V: DisplayIn, F: DisplayIn> DisplayIn for Face<V, F>
+    {
+        fn fmt_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+        {
+            core::fmt::Display::fmt(&self.in_context(context), f)
+        }
+
+        fn fmt_binary_in(
+            &self,
+            context: Context,
+            f: &mut core::fmt::Formatter<'_>,
+        ) -> core::fmt::Result
+        {
+            core::fmt::Binary::fmt(&self.in_context(context), f)
+        }
+    }
+
+    impl<V: DisplayIn, F: DisplayIn> Face<V, F>
+    {
+        /// The label and vertices, to be formatted in `context`
+        fn in_context(&self, context: Context) -> Face<InContext<'_, V>, InContext<'_, F>>
+        {
+            Face {
+                label: context.display(&self.label),
+                vertices: self.vertices.iter().map(|v| context.display(v)).collect(),
+                edge_ids: Vec::new(),
+                degree: self.degree,
+            }
+        }
+    }
+
     #[derive(Debug, Clone, PartialEq, Eq)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
     pub struct Wake
     {
         pub angle0: IntAngle,
         pub angle1: IntAngle,
+        /// Period of the cover the wake belongs to, relative to which its angles are read
+        pub context: Context,
     }
 
     impl Wake
@@ -118,25 +881,38 @@ pub mod cells
         #[must_use]
         pub fn is_real(&self) -> bool
         {
-            self.angle0 + self.angle1 == MAX_ANGLE.get()
+            self.angle0 + self.angle1 == self.context.max_angle
+        }
+
+        /// The two angles bounding the wake, as fractions of a full turn
+        #[must_use]
+        pub fn rat_angles(&self) -> (RatAngle, RatAngle)
+        {
+            let max_angle = self.context.max_angle.0;
+            (
+                RatAngle::new(self.angle0.0, max_angle),
+                RatAngle::new(self.angle1.0, max_angle),
+            )
         }
     }
 
-    impl std::fmt::Display for Wake
+    impl core::fmt::Display for Wake
     {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
         {
+            let angle0 = self.angle0.display_in(self.context, f.alternate());
+            let angle1 = self.angle1.display_in(self.context, f.alternate());
             if let Some(width) = f.width() {
-                write!(f, "{:>width$} <-> {:<width$}", self.angle0, self.angle1)
+                write!(f, "{angle0:>width$} <-> {angle1:<width$}")
             } else {
-                write!(f, "{} <-> {}", self.angle0, self.angle1)
+                write!(f, "{angle0} <-> {angle1}")
             }
         }
     }
 
-    impl std::fmt::Binary for Wake
+    impl core::fmt::Binary for Wake
     {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
         {
             if let Some(width) = f.width() {
                 write!(f, "{:0>width$b} <-> {:0>width$b}", self.angle0, self.angle1)
@@ -146,12 +922,32 @@ pub mod cells
         }
     }
 
+    /// Stably sort `edges` by `key`, returning the new index of each edge by its old index, for
+    /// use with [`Face::renumber_edges`]
+    pub fn sort_edges<V, K: Ord>(
+        edges: &mut Vec<Edge<V>>,
+        mut key: impl FnMut(&Edge<V>) -> K,
+    ) -> Vec<usize>
+    {
+        let mut indexed: Vec<_> = edges.drain(..).enumerate().collect();
+        indexed.sort_by_key(|(_, e)| key(e));
+        let mut new_ids = vec![0; indexed.len()];
+        for (new_id, (old_id, edge)) in indexed.into_iter().enumerate() {
+            new_ids[old_id] = new_id;
+            edges.push(edge);
+        }
+        new_ids
+    }
+
     #[derive(Debug, PartialEq, Eq, Clone)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
     pub struct Edge<V>
     {
         pub start: V,
         pub end: V,
         pub wake: Wake,
+        /// Kneading sequence of the first angle of the wake, computed when the cover is built
+        pub kneading_sequence: KneadingSequence,
     }
 
     impl<V> Edge<V>
@@ -173,44 +969,386 @@ pub mod cells
         }
     }
 
-    impl<V> std::fmt::Display for Edge<V>
-    where
-        V: std::fmt::Display,
+    /// The endpoints are formatted in `context`, and the wake and kneading sequence in the
+    /// context they carry
+    impl<V: DisplayIn> DisplayIn for Edge<V>
     {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+        fn fmt_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
         {
-            let ks = AbstractPoint::new(self.wake.angle0).kneading_sequence();
+            let ks = self.kneading_sequence;
             let connector = self.connector();
-            write!(
-                f,
-                "{:>digits$} {connector} {:<digits$} \twake: {:digits$} \tKS = {ks:>period$}",
-                self.start,
-                self.end,
-                self.wake,
-                digits = (PERIOD.get() / 3 + 1) as usize,
-                period = PERIOD.get() as usize
-            )
+            let (start, end) = (context.display(&self.start), context.display(&self.end));
+            let digits = (context.period / 3 + 1) as usize;
+            let period = context.period as usize;
+            if f.alternate() {
+                // Numerator, slash, and denominator
+                let digits = 2 * digits + 1;
+                write!(
+                    f,
+                    "{start:>#digits$} {connector} {end:<#digits$} \twake: {:#digits$} \tKS = {ks:>period$}",
+                    self.wake,
+                )
+            } else {
+                write!(
+                    f,
+                    "{start:>digits$} {connector} {end:<digits$} \twake: {:digits$} \tKS = {ks:>period$}",
+                    self.wake,
+                )
+            }
         }
-    }
 
-    impl<V> std::fmt::Binary for Edge<V>
-    where
-        V: std::fmt::Binary,
-    {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+        fn fmt_binary_in(
+            &self,
+            context: Context,
+            f: &mut core::fmt::Formatter<'_>,
+        ) -> core::fmt::Result
         {
-            let ks = AbstractPoint::new(self.wake.angle0).kneading_sequence();
+            let ks = self.kneading_sequence;
             write!(
                 f,
                 "{:b} -- {:b}   wake = {wake:period$b}   KS = {ks:>period$}",
-                self.start,
-                self.end,
+                context.display(&self.start),
+                context.display(&self.end),
                 wake = self.wake,
-                period = PERIOD.get() as usize
+                period = context.period as usize
             )
         }
     }
 
+    impl<V: DisplayIn> core::fmt::Display for Edge<V>
+    {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+        {
+            self.fmt_in(self.wake.context, f)
+        }
+    }
+
+    impl<V: DisplayIn> core::fmt::Binary for Edge<V>
+    {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+        {
+            self.fmt_binary_in(self.wake.context, f)
+        }
+    }
+
+    impl Wake
+    {
+        /// Whether `theta`, as a fraction of a full turn, lies between the two angles bounding
+        /// the wake
+        #[must_use]
+        pub fn contains(&self, theta: RatAngle) -> bool
+        {
+            let (theta0, theta1) = self.rat_angles();
+            theta0.min(theta1) <= theta && theta <= theta0.max(theta1)
+        }
+    }
+
+    /// Vertices that can be matched against a periodic angle
+    pub trait ContainsAngle
+    {
+        /// Whether the vertex marks the point at `angle`, read in `context`
+        fn contains_angle(&self, angle: IntAngle, context: Context) -> bool;
+    }
+
+    impl ContainsAngle for AbstractCycle
+    {
+        fn contains_angle(&self, angle: IntAngle, context: Context) -> bool
+        {
+            AbstractCycle::new_compute_in(AbstractPoint::new(angle), context) == *self
+        }
+    }
+
+    impl ContainsAngle for ShiftedCycle
+    {
+        fn contains_angle(&self, angle: IntAngle, context: Context) -> bool
+        {
+            self.to_point_in(context).angle == angle
+        }
+    }
+
+    impl<V: ContainsAngle> ContainsAngle for AugmentedVertex<V>
+    {
+        fn contains_angle(&self, angle: IntAngle, context: Context) -> bool
+        {
+            self.vertex.contains_angle(angle, context)
+        }
+    }
+
+    /// Criteria for selecting which cells of a cover to list. Unset criteria match everything.
+    #[derive(Clone, Debug, Default, PartialEq, Eq)]
+    pub struct CellFilter
+    {
+        /// Keep only the vertex marking this angle, and the edges and faces that meet it
+        pub vertex: Option<RatAngle>,
+        /// Keep only edges whose wake contains this angle
+        pub wake: Option<RatAngle>,
+        /// Keep only faces with at least this many vertices
+        pub min_size: Option<usize>,
+        /// Keep only faces with at most this many vertices
+        pub max_size: Option<usize>,
+        /// Keep only reflexive faces
+        pub reflexive: bool,
+    }
+
+    impl CellFilter
+    {
+        #[must_use]
+        pub fn is_empty(&self) -> bool
+        {
+            *self == Self::default()
+        }
+
+        /// The vertex angle as a multiple of `1/(2^period - 1)`, or `None` if it is not of the
+        /// period of `context`
+        fn vertex_angle(&self, context: Context) -> Option<Option<IntAngle>>
+        {
+            self.vertex.map(|theta| {
+                let scaled = theta * context.max_angle.0;
+                scaled.is_integer().then(|| IntAngle(scaled.to_integer()))
+            })
+        }
+
+        fn keeps_point<V: ContainsAngle>(&self, v: &V, context: Context) -> bool
+        {
+            match self.vertex_angle(context) {
+                None => true,
+                Some(None) => false,
+                Some(Some(angle)) => v.contains_angle(angle, context),
+            }
+        }
+
+        /// Whether to keep the vertex `v` of a cover of the period of `context`
+        pub fn keeps_vertex<V: ContainsAngle>(&self, v: &V, context: Context) -> bool
+        {
+            self.keeps_point(v, context)
+        }
+
+        pub fn keeps_edge<V: ContainsAngle>(&self, e: &Edge<V>) -> bool
+        {
+            let context = e.wake.context;
+            let incident = self.vertex.is_none()
+                || self.keeps_point(&e.start, context)
+                || self.keeps_point(&e.end, context);
+            incident && self.wake.is_none_or(|theta| e.wake.contains(theta))
+        }
+
+        /// Whether to keep the face `f` of a cover of the period of `context`
+        pub fn keeps_face<V: ContainsAngle, F>(&self, f: &Face<V, F>, context: Context) -> bool
+        {
+            (self.vertex.is_none() || f.vertices.iter().any(|v| self.keeps_point(v, context)))
+                && self.min_size.is_none_or(|n| f.len() >= n)
+                && self.max_size.is_none_or(|n| f.len() <= n)
+                && (!self.reflexive || f.is_reflexive())
+        }
+    }
+
+    /// Order in which to list cells
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum CellOrder
+    {
+        /// Faces by number of vertices and edges by the width of their wake, smallest first
+        Size,
+        /// Vertices by their cycle, edges by their endpoints, and faces by their label
+        Label,
+        /// Vertices by the angle they mark, edges by the lower angle of their wake, and faces
+        /// by the smallest angle among their vertices
+        Angle,
+    }
+
+    /// Keys by which a cell can be ordered when listed
+    pub trait CellKey
+    {
+        type Label: Ord;
+
+        /// Number of vertices of a face, or width of the wake of an edge; zero for vertices
+        fn size(&self) -> i64;
+
+        fn label(&self) -> Self::Label;
+
+        /// The angle the cell is ordered by, read in `context`
+        fn angle(&self, context: Context) -> IntAngle;
+    }
+
+    impl CellKey for AbstractCycle
+    {
+        type Label = Self;
+
+        fn size(&self) -> i64
+        {
+            0
+        }
+
+        fn label(&self) -> Self
+        {
+            *self
+        }
+
+        fn angle(&self, _context: Context) -> IntAngle
+        {
+            self.rep.angle
+        }
+    }
+
+    impl CellKey for ShiftedCycle
+    {
+        type Label = Self;
+
+        fn size(&self) -> i64
+        {
+            0
+        }
+
+        fn label(&self) -> Self
+        {
+            *self
+        }
+
+        fn angle(&self, context: Context) -> IntAngle
+        {
+            self.to_point_in(context).angle
+        }
+    }
+
+    impl<V: CellKey> CellKey for AugmentedVertex<V>
+    {
+        type Label = V::Label;
+
+        fn size(&self) -> i64
+        {
+            self.vertex.size()
+        }
+
+        fn label(&self) -> V::Label
+        {
+            self.vertex.label()
+        }
+
+        fn angle(&self, context: Context) -> IntAngle
+        {
+            self.vertex.angle(context)
+        }
+    }
+
+    impl<V: CellKey> CellKey for Edge<V>
+    {
+        type Label = (V::Label, V::Label);
+
+        fn size(&self) -> i64
+        {
+            (self.wake.angle1 - self.wake.angle0).0
+        }
+
+        fn label(&self) -> Self::Label
+        {
+            (self.start.label(), self.end.label())
+        }
+
+        fn angle(&self, _context: Context) -> IntAngle
+        {
+            self.wake.angle0
+        }
+    }
+
+    impl<V: CellKey, F: Ord + Copy> CellKey for Face<V, F>
+    {
+        type Label = F;
+
+        fn size(&self) -> i64
+        {
+            self.len() as i64
+        }
+
+        fn label(&self) -> F
+        {
+            self.label
+        }
+
+        fn angle(&self, context: Context) -> IntAngle
+        {
+            self.vertices
+                .iter()
+                .map(|v| v.angle(context))
+                .min()
+                .unwrap_or(IntAngle(0))
+        }
+    }
+
+    /// How to order and truncate the cells listed in a text summary
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct CellListing
+    {
+        /// Order to list cells in, or `None` to list them in the order they were built
+        pub sort: Option<CellOrder>,
+        /// List at most this many cells of each kind, or all of them if `None`
+        pub limit: Option<usize>,
+    }
+
+    impl Default for CellListing
+    {
+        fn default() -> Self
+        {
+            Self {
+                sort: None,
+                limit: Some(crate::MAX_DISPLAY_ITEMS),
+            }
+        }
+    }
+
+    impl CellListing
+    {
+        /// Whether a list of `len` cells would be shown in full
+        #[must_use]
+        pub fn shows_all(&self, len: usize) -> bool
+        {
+            self.limit.is_none_or(|n| len <= n)
+        }
+
+        /// Sort `items`, cells of a cover of the period of `context`, and drop those past the
+        /// limit, returning how many were dropped
+        pub fn arrange<T: CellKey>(&self, items: &mut Vec<&T>, context: Context) -> usize
+        {
+            match self.sort {
+                None => {}
+                Some(CellOrder::Size) => items.sort_by_key(|x| (x.size(), x.label())),
+                Some(CellOrder::Label) => items.sort_by_key(|x| x.label()),
+                Some(CellOrder::Angle) => items.sort_by_key(|x| (x.angle(context), x.label())),
+            }
+            let len = items.len();
+            items.truncate(self.limit.unwrap_or(len));
+            len - items.len()
+        }
+    }
+
+    /// How to lay out the text summary of a cover
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub struct SummaryOptions
+    {
+        /// How far to indent each listed cell
+        pub indent: usize,
+        /// Display cell ids in binary
+        pub binary: bool,
+        /// Display angles as fractions `k/(2^n - 1)` of a full turn, unless `binary` is set
+        pub fractions: bool,
+        /// Which cells to list
+        pub filter: CellFilter,
+        /// How to order and truncate the listed cells
+        pub listing: CellListing,
+    }
+
+    impl Default for SummaryOptions
+    {
+        fn default() -> Self
+        {
+            Self {
+                indent: 4,
+                binary: false,
+                fractions: false,
+                filter: CellFilter::default(),
+                listing: CellListing::default(),
+            }
+        }
+    }
+
     #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
     pub enum HalfPlane
     {
@@ -221,12 +1359,14 @@ pub mod cells
         NegReal,
     }
 
-    impl From<IntAngle> for HalfPlane
+    impl HalfPlane
     {
-        fn from(angle: IntAngle) -> Self
+        /// The half plane containing the point at `angle`, read in `context`
+        #[must_use]
+        pub fn new(angle: IntAngle, context: Context) -> Self
         {
-            use std::cmp::Ordering::*;
-            match (angle * 2).cmp(&MAX_ANGLE.get()) {
+            use core::cmp::Ordering::*;
+            match (angle * 2).cmp(&context.max_angle) {
                 Less => Self::Upper,
                 Equal => Self::NegReal,
                 Greater => Self::Lower,
@@ -234,7 +1374,8 @@ pub mod cells
         }
     }
 
-    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
     pub enum VertexData
     {
         PosReal,
@@ -266,37 +1407,41 @@ pub mod cells
         }
     }
 
-    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+    #[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
     pub struct AugmentedVertex<V>
     {
         pub vertex: V,
         pub data: VertexData,
     }
 
-    impl<V> std::fmt::Display for AugmentedVertex<V>
+    impl<V> core::fmt::Display for AugmentedVertex<V>
     where
-        V: std::fmt::Display,
+        V: core::fmt::Display,
     {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
         {
             use VertexData::*;
-            match self.data {
-                NonReal => self.vertex.fmt(f),
-                PosReal => write!(f, "+{}", self.vertex),
-                NegReal => write!(f, "-{}", self.vertex),
-                PosNeg => write!(f, "+-{}", self.vertex),
-                NegPos => write!(f, "-+{}", self.vertex),
-                NegEdge => write!(f, "{} ===", self.vertex),
-                NegEdgePos => write!(f, "+{} ===", self.vertex),
-            }
+            let (prefix, suffix) = match self.data {
+                NonReal => ("", ""),
+                PosReal => ("+", ""),
+                NegReal => ("-", ""),
+                PosNeg => ("+-", ""),
+                NegPos => ("-+", ""),
+                NegEdge => ("", " ==="),
+                NegEdgePos => ("+", " ==="),
+            };
+            f.write_str(prefix)?;
+            self.vertex.fmt(f)?;
+            f.write_str(suffix)
         }
     }
 
-    impl<V> std::fmt::Binary for AugmentedVertex<V>
+    impl<V> core::fmt::Binary for AugmentedVertex<V>
     where
-        V: std::fmt::Binary,
+        V: core::fmt::Binary,
     {
-        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
         {
             use VertexData::*;
             match self.data {
@@ -310,4 +1455,29 @@ pub mod cells
             }
         }
     }
+
+    impl<V: DisplayIn> DisplayIn for AugmentedVertex<V>
+    {
+        fn fmt_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+        {
+            let vertex = AugmentedVertex {
+                vertex: context.display(&self.vertex),
+                data: self.data,
+            };
+            core::fmt::Display::fmt(&vertex, f)
+        }
+
+        fn fmt_binary_in(
+            &self,
+            context: Context,
+            f: &mut core::fmt::Formatter<'_>,
+        ) -> core::fmt::Result
+        {
+            let vertex = AugmentedVertex {
+                vertex: context.display(&self.vertex),
+                data: self.data,
+            };
+            core::fmt::Binary::fmt(&vertex, f)
+        }
+    }
 }
@@ -0,0 +1,31 @@
+use crate::tikz::TikzStyle;
+use crate::types::Period;
+use serde::Deserialize;
+
+/// Name of the configuration file looked for in the working directory
+pub const CONFIG_FILE: &str = "marked-cycles.toml";
+
+/// Defaults for command-line options, read from a TOML file with top-level options and an
+/// optional `[tikz]` table. Options given on the command line take precedence.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Config
+{
+    /// Default period of the critical cycle
+    pub crit_period: Option<Period>,
+    /// Default output format of `curve`, e.g. `json`
+    pub format: Option<String>,
+    /// Default indentation of cell listings
+    pub indent: Option<usize>,
+    /// Style for TikZ output, starting from the default style
+    pub tikz: TikzStyle,
+}
+
+impl Config
+{
+    /// Read a configuration file, reporting the line of the first error
+    pub fn parse(text: &str) -> Result<Self, String>
+    {
+        toml::from_str(text).map_err(|e| e.to_string())
+    }
+}
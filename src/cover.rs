@@ -0,0 +1,382 @@
+#[cfg(feature = "parquet")]
+use crate::columnar::CellBatches;
+#[cfg(feature = "std")]
+use crate::common::cells::{CellFilter, SummaryOptions};
+#[cfg(feature = "std")]
+use crate::csv::{CellRecords, CsvTables};
+use crate::dynatomic_cover::DynatomicCover;
+use crate::global_state::Context;
+use crate::marked_cycle_cover::MarkedCycleCover;
+#[cfg(feature = "std")]
+use crate::matrices::CoverMatrices;
+use crate::topology::RelativeTopology;
+use crate::types::Period;
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+/// Counts and histograms describing a cover's cells at a glance, as given by its `stats`
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CoverStats
+{
+    pub num_vertices: usize,
+    pub num_edges: usize,
+    pub num_faces: usize,
+    /// Genus of the closed surface, assuming that it is connected
+    pub genus: i64,
+    /// Number of faces with each number of vertices, omitting sizes no face has
+    pub face_sizes: BTreeMap<usize, usize>,
+    /// Number of faces of degree greater than 1 with each number of vertices
+    pub irreflexive_face_sizes: BTreeMap<usize, usize>,
+    /// Number of vertices with each number of edge ends at them, a loop counting twice
+    pub vertex_degrees: BTreeMap<usize, usize>,
+    /// Number of faces of degree 1
+    pub num_reflexive: usize,
+    /// Number of faces with an odd number of vertices
+    pub num_odd: usize,
+    /// Number of faces of degree greater than 1 with an odd number of vertices
+    pub num_odd_irreflexive: usize,
+    /// Number of edges whose wake is symmetric about the real axis
+    pub num_real_edges: usize,
+}
+
+impl CoverStats
+{
+    /// Tally the cells of a cover from its number of vertices, the endpoints of its edges as
+    /// vertex indices, its number of real edges, and the size and degree of each face
+    #[must_use]
+    pub fn new(
+        num_vertices: usize,
+        edge_endpoints: &[(usize, usize)],
+        num_real_edges: usize,
+        faces: impl IntoIterator<Item = (usize, Period)>,
+        genus: i64,
+    ) -> Self
+    {
+        let mut degrees = vec![0; num_vertices];
+        for &(start, end) in edge_endpoints {
+            degrees[start] += 1;
+            degrees[end] += 1;
+        }
+        let mut vertex_degrees = BTreeMap::new();
+        for degree in degrees {
+            *vertex_degrees.entry(degree).or_default() += 1;
+        }
+
+        let mut stats = Self {
+            num_vertices,
+            num_edges: edge_endpoints.len(),
+            genus,
+            vertex_degrees,
+            num_real_edges,
+            ..Self::default()
+        };
+        for (size, degree) in faces {
+            stats.num_faces += 1;
+            *stats.face_sizes.entry(size).or_default() += 1;
+            let odd = size % 2 == 1;
+            stats.num_odd += usize::from(odd);
+            if degree == 1 {
+                stats.num_reflexive += 1;
+            } else {
+                *stats.irreflexive_face_sizes.entry(size).or_default() += 1;
+                stats.num_odd_irreflexive += usize::from(odd);
+            }
+        }
+        stats
+    }
+
+    /// Number of vertices of the smallest face, or 0 if there are no faces
+    #[must_use]
+    pub fn min_face(&self) -> usize
+    {
+        self.face_sizes.keys().next().copied().unwrap_or_default()
+    }
+
+    /// Number of vertices of the largest face, or 0 if there are no faces
+    #[must_use]
+    pub fn max_face(&self) -> usize
+    {
+        self.face_sizes
+            .keys()
+            .next_back()
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Number of vertices of the smallest face of degree greater than 1, or 0 if there is none
+    #[must_use]
+    pub fn min_face_irreflexive(&self) -> usize
+    {
+        self.irreflexive_face_sizes
+            .keys()
+            .next()
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Number of faces with `size` vertices
+    #[must_use]
+    pub fn num_faces_of_size(&self, size: usize) -> usize
+    {
+        self.face_sizes.get(&size).copied().unwrap_or_default()
+    }
+
+    /// Number of faces of degree greater than 1 with `size` vertices
+    #[must_use]
+    pub fn num_irreflexive_faces_of_size(&self, size: usize) -> usize
+    {
+        self.irreflexive_face_sizes
+            .get(&size)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// The API shared by [`MarkedCycleCover`] and [`DynatomicCover`], so that code can handle
+/// either kind of cover without matching on it. Each method is the inherent method of the same
+/// name; the trait is object safe, so a `&dyn Cover` may stand for a cover of either kind.
+pub trait Cover
+{
+    fn context(&self) -> Context;
+
+    fn crit_period(&self) -> Period;
+
+    /// Name of the curve, as in `MC_5(Per_1)`
+    #[cfg(feature = "std")]
+    fn surface_name(&self) -> String;
+
+    fn num_vertices(&self) -> usize;
+
+    fn num_edges(&self) -> usize;
+
+    fn num_faces(&self) -> usize;
+
+    fn num_components(&self) -> usize;
+
+    /// Number of vertices of each face, in order
+    fn face_sizes(&self) -> Vec<usize>;
+
+    /// Where the cover is punctured and ramified over its base, or `None` if the critical
+    /// period is not 1 or 2
+    fn relative_topology(&self) -> Option<RelativeTopology>;
+
+    /// Counts and histograms of the cells
+    fn stats(&self) -> CoverStats;
+
+    fn period(&self) -> Period
+    {
+        self.context().period
+    }
+
+    fn euler_characteristic(&self) -> i64
+    {
+        self.num_vertices() as i64 - self.num_edges() as i64 + self.num_faces() as i64
+    }
+
+    /// Genus of the closed surface, assuming that it is connected
+    fn genus(&self) -> i64
+    {
+        1 - self.euler_characteristic() / 2
+    }
+
+    /// Numbers of vertices, edges, and faces, and the genus
+    fn counts(&self) -> (usize, usize, usize, i64)
+    {
+        (
+            self.num_vertices(),
+            self.num_edges(),
+            self.num_faces(),
+            self.genus(),
+        )
+    }
+
+    /// Print the cells and the genus to stdout
+    #[cfg(feature = "std")]
+    fn summarize(&self, opts: &SummaryOptions);
+
+    /// Write the cells that pass `opts.filter`, then the face sizes and genus
+    #[cfg(feature = "std")]
+    fn summarize_to(&self, w: &mut dyn Write, opts: &SummaryOptions) -> io::Result<()>;
+
+    #[cfg(feature = "std")]
+    fn to_json(&self, with_cells: bool) -> String;
+
+    #[cfg(feature = "std")]
+    fn write_ndjson(&self, w: &mut dyn Write, filter: &CellFilter) -> io::Result<()>;
+
+    #[cfg(feature = "std")]
+    fn to_csv(&self) -> CsvTables;
+
+    #[cfg(feature = "std")]
+    fn records(&self) -> CellRecords;
+
+    #[cfg(feature = "parquet")]
+    fn to_columnar(&self) -> CellBatches;
+
+    #[cfg(feature = "std")]
+    fn matrices(&self) -> CoverMatrices;
+
+    #[cfg(feature = "std")]
+    fn to_dot(&self, with_faces: bool) -> String;
+
+    #[cfg(feature = "std")]
+    fn to_graphml(&self, with_faces: bool) -> String;
+
+    #[cfg(feature = "std")]
+    fn to_gexf(&self, with_faces: bool) -> String;
+
+    #[cfg(feature = "std")]
+    fn to_polymake(&self) -> String;
+
+    #[cfg(feature = "std")]
+    fn to_obj(&self, iterations: usize) -> String;
+
+    #[cfg(feature = "std")]
+    fn to_ply(&self, iterations: usize) -> String;
+}
+
+macro_rules! impl_cover {
+    ($cover: ty) => {
+        impl Cover for $cover
+        {
+            fn context(&self) -> Context
+            {
+                self.context
+            }
+
+            fn crit_period(&self) -> Period
+            {
+                self.crit_period
+            }
+
+            #[cfg(feature = "std")]
+            fn surface_name(&self) -> String
+            {
+                <$cover>::surface_name(self)
+            }
+
+            fn num_vertices(&self) -> usize
+            {
+                <$cover>::num_vertices(self)
+            }
+
+            fn num_edges(&self) -> usize
+            {
+                <$cover>::num_edges(self)
+            }
+
+            fn num_faces(&self) -> usize
+            {
+                <$cover>::num_faces(self)
+            }
+
+            fn num_components(&self) -> usize
+            {
+                <$cover>::num_components(self)
+            }
+
+            fn face_sizes(&self) -> Vec<usize>
+            {
+                <$cover>::face_sizes(self).into_iter().collect()
+            }
+
+            fn relative_topology(&self) -> Option<RelativeTopology>
+            {
+                <$cover>::relative_topology(self)
+            }
+
+            fn stats(&self) -> CoverStats
+            {
+                <$cover>::stats(self)
+            }
+
+            #[cfg(feature = "std")]
+            fn summarize(&self, opts: &SummaryOptions)
+            {
+                <$cover>::summarize(self, opts);
+            }
+
+            #[cfg(feature = "std")]
+            fn summarize_to(&self, mut w: &mut dyn Write, opts: &SummaryOptions) -> io::Result<()>
+            {
+                <$cover>::summarize_to(self, &mut w, opts)
+            }
+
+            #[cfg(feature = "std")]
+            fn to_json(&self, with_cells: bool) -> String
+            {
+                <$cover>::to_json(self, with_cells)
+            }
+
+            #[cfg(feature = "std")]
+            fn write_ndjson(&self, mut w: &mut dyn Write, filter: &CellFilter) -> io::Result<()>
+            {
+                <$cover>::write_ndjson(self, &mut w, filter)
+            }
+
+            #[cfg(feature = "std")]
+            fn to_csv(&self) -> CsvTables
+            {
+                <$cover>::to_csv(self)
+            }
+
+            #[cfg(feature = "std")]
+            fn records(&self) -> CellRecords
+            {
+                <$cover>::records(self)
+            }
+
+            #[cfg(feature = "parquet")]
+            fn to_columnar(&self) -> CellBatches
+            {
+                <$cover>::to_columnar(self)
+            }
+
+            #[cfg(feature = "std")]
+            fn matrices(&self) -> CoverMatrices
+            {
+                <$cover>::matrices(self)
+            }
+
+            #[cfg(feature = "std")]
+            fn to_dot(&self, with_faces: bool) -> String
+            {
+                <$cover>::to_dot(self, with_faces)
+            }
+
+            #[cfg(feature = "std")]
+            fn to_graphml(&self, with_faces: bool) -> String
+            {
+                <$cover>::to_graphml(self, with_faces)
+            }
+
+            #[cfg(feature = "std")]
+            fn to_gexf(&self, with_faces: bool) -> String
+            {
+                <$cover>::to_gexf(self, with_faces)
+            }
+
+            #[cfg(feature = "std")]
+            fn to_polymake(&self) -> String
+            {
+                <$cover>::to_polymake(self)
+            }
+
+            #[cfg(feature = "std")]
+            fn to_obj(&self, iterations: usize) -> String
+            {
+                <$cover>::to_obj(self, iterations)
+            }
+
+            #[cfg(feature = "std")]
+            fn to_ply(&self, iterations: usize) -> String
+            {
+                <$cover>::to_ply(self, iterations)
+            }
+        }
+    };
+}
+
+impl_cover!(MarkedCycleCover);
+impl_cover!(DynatomicCover);
@@ -0,0 +1,346 @@
+use crate::combinatorics::Combinatorics;
+use crate::common::cells::Edge;
+use crate::global_state::{Context, DisplayIn};
+use crate::types::Period;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    io::{self, Read, Write},
+    path::Path,
+};
+
+/// Face data for CSV output: the face label, its boundary vertices, the ids of its boundary
+/// edges, and its degree
+pub type CsvFace<V> = (String, Vec<V>, Vec<usize>, Period);
+
+/// Header of the vertex table
+pub const VERTEX_COLUMNS: &str = "id,vertex";
+/// Header of the edge table
+pub const EDGE_COLUMNS: &str = "id,start,end,angle0,angle1,real,kneading_sequence";
+/// Header of the face table. Boundary vertices and edge ids are separated by spaces.
+pub const FACE_COLUMNS: &str = "id,label,degree,size,vertices,edge_ids";
+
+/// Field separator of a delimited table
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Separator
+{
+    #[default]
+    Comma,
+    Tab,
+}
+
+impl Separator
+{
+    #[must_use]
+    pub const fn byte(self) -> u8
+    {
+        match self {
+            Self::Comma => b',',
+            Self::Tab => b'\t',
+        }
+    }
+
+    #[must_use]
+    pub const fn extension(self) -> &'static str
+    {
+        match self {
+            Self::Comma => "csv",
+            Self::Tab => "tsv",
+        }
+    }
+}
+
+/// Lists stored in a single field, with their items separated by spaces
+mod spaced
+{
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use std::{fmt::Display, str::FromStr};
+
+    pub fn serialize<S, T>(items: &[T], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Display,
+    {
+        let items: Vec<String> = items.iter().map(ToString::to_string).collect();
+        serializer.serialize_str(&items.join(" "))
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromStr,
+        T::Err: Display,
+    {
+        String::deserialize(deserializer)?
+            .split_whitespace()
+            .map(|item| item.parse().map_err(D::Error::custom))
+            .collect()
+    }
+}
+
+/// A row of the vertex table
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct VertexRecord
+{
+    pub id: usize,
+    pub vertex: String,
+}
+
+/// A row of the edge table. The angles bounding the wake are numerators over `2^n - 1`, where
+/// `n` is the period of the cover.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EdgeRecord
+{
+    pub id: usize,
+    pub start: String,
+    pub end: String,
+    pub angle0: Period,
+    pub angle1: Period,
+    pub real: bool,
+    pub kneading_sequence: String,
+}
+
+/// A row of the face table
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct FaceRecord
+{
+    pub id: usize,
+    pub label: String,
+    pub degree: Period,
+    pub size: usize,
+    /// Boundary vertices, as in the vertex table, separated by spaces. Vertices of dynatomic
+    /// covers contain spaces themselves, so look them up through `edge_ids` to split them.
+    pub vertices: String,
+    #[serde(with = "spaced")]
+    pub edge_ids: Vec<usize>,
+}
+
+/// The closed-form invariants of a curve at one period, with the columns of
+/// [`crate::columnar::invariant_table`] when that is enabled
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct InvariantRecord
+{
+    pub period: Period,
+    pub points_of_period_dividing_n: i64,
+    pub periodic_points: i64,
+    pub cycles: i64,
+    pub hyp_components_dividing_n: i64,
+    pub hyperbolic_components: i64,
+    pub satellite_components: i64,
+    pub primitive_components: i64,
+    pub self_conjugate_faces: i64,
+    pub vertices: i64,
+    pub edges: i64,
+    pub faces: i64,
+    pub genus: i64,
+}
+
+impl InvariantRecord
+{
+    pub fn new<C>(comb: &C, period: Period) -> Self
+    where
+        C: Combinatorics + ?Sized,
+    {
+        Self {
+            period,
+            points_of_period_dividing_n: comb.points_of_period_dividing_n(period),
+            periodic_points: comb.periodic_points(period),
+            cycles: comb.cycles(period),
+            hyp_components_dividing_n: comb.hyp_components_dividing_n(period),
+            hyperbolic_components: comb.hyperbolic_components(period),
+            satellite_components: comb.satellite_components(period),
+            primitive_components: comb.primitive_components(period),
+            self_conjugate_faces: comb.self_conjugate_faces(period),
+            vertices: comb.vertices(period),
+            edges: comb.edges(period),
+            faces: comb.faces(period),
+            genus: comb.genus(period),
+        }
+    }
+
+    /// One record per period
+    pub fn table<C>(comb: &C, periods: impl IntoIterator<Item = Period>) -> Vec<Self>
+    where
+        C: Combinatorics + ?Sized,
+    {
+        periods.into_iter().map(|n| Self::new(comb, n)).collect()
+    }
+}
+
+/// Writes records of one type as delimited rows, quoting fields only where needed
+pub struct RecordWriter<W: Write>
+{
+    inner: ::csv::Writer<W>,
+}
+
+impl<W: Write> RecordWriter<W>
+{
+    /// A writer whose first row is the header, named after the fields of the records
+    pub fn new(out: W, separator: Separator) -> Self
+    {
+        Self::build(out, separator, true)
+    }
+
+    /// A writer for rows only, such as to follow a header written by hand
+    pub fn without_header(out: W, separator: Separator) -> Self
+    {
+        Self::build(out, separator, false)
+    }
+
+    fn build(out: W, separator: Separator, header: bool) -> Self
+    {
+        let inner = ::csv::WriterBuilder::new()
+            .delimiter(separator.byte())
+            .has_headers(header)
+            .from_writer(out);
+        Self { inner }
+    }
+
+    pub fn write<R: Serialize>(&mut self, record: &R) -> io::Result<()>
+    {
+        self.inner.serialize(record).map_err(io::Error::other)
+    }
+
+    /// Flush the rows written so far and hand back the underlying writer
+    pub fn finish(self) -> io::Result<W>
+    {
+        self.inner.into_inner().map_err(|e| e.into_error())
+    }
+}
+
+/// Write a header followed by one row per record
+pub fn write_records<W, R>(out: W, records: &[R], separator: Separator) -> io::Result<()>
+where
+    W: Write,
+    R: Serialize,
+{
+    let mut writer = RecordWriter::new(out, separator);
+    for record in records {
+        writer.write(record)?;
+    }
+    writer.finish()?;
+    Ok(())
+}
+
+/// Read the records of a table written by [`write_records`]
+pub fn read_records<R, T>(input: R, separator: Separator) -> io::Result<Vec<T>>
+where
+    R: Read,
+    T: DeserializeOwned,
+{
+    ::csv::ReaderBuilder::new()
+        .delimiter(separator.byte())
+        .from_reader(input)
+        .deserialize()
+        .collect::<Result<_, _>>()
+        .map_err(io::Error::other)
+}
+
+/// The rows of the three cell tables of a cover
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CellRecords
+{
+    pub vertices: Vec<VertexRecord>,
+    pub edges: Vec<EdgeRecord>,
+    pub faces: Vec<FaceRecord>,
+}
+
+impl CellRecords
+{
+    /// Tabulate the cells of a cover, with the vertices labeled in `context`
+    pub fn new<V>(context: Context, vertices: &[V], edges: &[Edge<V>], faces: &[CsvFace<V>]) -> Self
+    where
+        V: DisplayIn,
+    {
+        let name = |v: &V| context.display(v).to_string();
+        let vertices = vertices
+            .iter()
+            .enumerate()
+            .map(|(id, v)| VertexRecord {
+                id,
+                vertex: name(v),
+            })
+            .collect();
+
+        let edges = edges
+            .iter()
+            .enumerate()
+            .map(|(id, e)| EdgeRecord {
+                id,
+                start: name(&e.start),
+                end: name(&e.end),
+                angle0: e.wake.angle0.0,
+                angle1: e.wake.angle1.0,
+                real: e.is_real(),
+                kneading_sequence: e.kneading_sequence.to_string(),
+            })
+            .collect();
+
+        let faces = faces
+            .iter()
+            .enumerate()
+            .map(|(id, (label, boundary, edge_ids, degree))| FaceRecord {
+                id,
+                label: label.clone(),
+                degree: *degree,
+                size: boundary.len(),
+                vertices: boundary.iter().map(name).collect::<Vec<_>>().join(" "),
+                edge_ids: edge_ids.clone(),
+            })
+            .collect();
+
+        Self {
+            vertices,
+            edges,
+            faces,
+        }
+    }
+
+    #[must_use]
+    pub fn to_tables(&self, separator: Separator) -> CsvTables
+    {
+        let table = |write: &dyn Fn(&mut Vec<u8>) -> io::Result<()>| {
+            let mut bytes = Vec::new();
+            write(&mut bytes).expect("Writing to memory cannot fail");
+            String::from_utf8(bytes).expect("Records hold UTF-8 text")
+        };
+        CsvTables {
+            vertices: table(&|out| write_records(out, &self.vertices, separator)),
+            edges: table(&|out| write_records(out, &self.edges, separator)),
+            faces: table(&|out| write_records(out, &self.faces, separator)),
+            separator,
+        }
+    }
+}
+
+/// One CSV or TSV table per cell type
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CsvTables
+{
+    pub vertices: String,
+    pub edges: String,
+    pub faces: String,
+    pub separator: Separator,
+}
+
+impl CsvTables
+{
+    /// Tabulate the cells of a cover as CSV, with the vertices labeled in `context`
+    pub fn new<V>(context: Context, vertices: &[V], edges: &[Edge<V>], faces: &[CsvFace<V>]) -> Self
+    where
+        V: DisplayIn,
+    {
+        CellRecords::new(context, vertices, edges, faces).to_tables(Separator::Comma)
+    }
+
+    /// Write `{stem}_vertices`, `{stem}_edges`, and `{stem}_faces` into `dir`, with the extension
+    /// of the separator, creating it if needed
+    pub fn write(&self, dir: impl AsRef<Path>, stem: &str) -> io::Result<()>
+    {
+        let dir = dir.as_ref();
+        let ext = self.separator.extension();
+        std::fs::create_dir_all(dir)?;
+        std::fs::write(dir.join(format!("{stem}_vertices.{ext}")), &self.vertices)?;
+        std::fs::write(dir.join(format!("{stem}_edges.{ext}")), &self.edges)?;
+        std::fs::write(dir.join(format!("{stem}_faces.{ext}")), &self.faces)
+    }
+}
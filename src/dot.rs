@@ -0,0 +1,66 @@
+use crate::common::cells::Edge;
+use crate::global_state::{Context, DisplayIn};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+/// Face data for DOT output: the face label and its boundary vertices
+pub type DotFace<V> = (String, Vec<V>);
+
+fn quote(text: &str) -> String
+{
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Write an undirected Graphviz graph with one node per vertex and one edge per cell edge,
+/// labeled by its wake. Each face, if given, becomes a cluster holding a face node that is
+/// joined to the vertices on its boundary by dashed edges. The vertices are labeled in `context`.
+pub fn to_dot<V>(
+    name: &str,
+    context: Context,
+    vertices: &[V],
+    edges: &[Edge<V>],
+    faces: Option<&[DotFace<V>]>,
+) -> String
+where
+    V: Copy + Eq + Hash + DisplayIn,
+{
+    let indices: HashMap<V, usize> = vertices.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+
+    let mut lines = vec![format!("graph {} {{", quote(name))];
+
+    for (i, v) in vertices.iter().enumerate() {
+        lines.push(format!(
+            "    v{i} [label={}];",
+            quote(&context.display(v).to_string())
+        ));
+    }
+
+    for e in edges {
+        let (Some(i), Some(j)) = (indices.get(&e.start), indices.get(&e.end)) else {
+            continue;
+        };
+        let style = if e.is_real() { ", style=bold" } else { "" };
+        lines.push(format!(
+            "    v{i} -- v{j} [label={}{style}];",
+            quote(&e.wake.to_string())
+        ));
+    }
+
+    for (k, (label, boundary)) in faces.unwrap_or_default().iter().enumerate() {
+        lines.push(format!("    subgraph cluster_f{k} {{"));
+        lines.push(format!("        label={};", quote(label)));
+        lines.push(format!("        f{k} [label={}, shape=box];", quote(label)));
+        lines.push("    }".to_owned());
+        let mut seen = HashSet::new();
+        for v in boundary.iter().filter(|v| seen.insert(**v)) {
+            if let Some(i) = indices.get(v) {
+                lines.push(format!("    f{k} -- v{i} [style=dashed];"));
+            }
+        }
+    }
+
+    lines.push("}".to_owned());
+    lines.join("\n")
+}
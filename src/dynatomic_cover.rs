@@ -1,18 +1,108 @@
 use crate::abstract_cycles::{AbstractPoint, AbstractPointClass, ShiftedCycle};
+#[cfg(feature = "parquet")]
+use crate::columnar::CellBatches;
+#[cfg(feature = "std")]
+use crate::common::cells::Cell;
+#[cfg(feature = "std")]
+use crate::common::cells::{CellFilter, SummaryOptions};
+#[cfg(feature = "std")]
+use crate::common::{build_periods, stream_faces};
 use crate::common::{
-    cells::{self, Wake},
-    get_orbit,
+    cells::{self, AugmentedVertex, VertexId, VertexList, Wake},
+    cycle_position, cycle_positions, traverse_faces, BuildTimings, FxHashMap, Phase, PhaseClock,
 };
-use crate::global_state::{set_period, MAX_ANGLE, PERIOD};
+use crate::cover::CoverStats;
+#[cfg(feature = "std")]
+use crate::csv::{CellRecords, CsvFace, CsvTables};
+#[cfg(feature = "std")]
+use crate::dot::{self, DotFace};
+#[cfg(feature = "std")]
+use crate::fundamental_polygon::FundamentalPolygon;
+#[cfg(feature = "std")]
+use crate::gexf;
+use crate::global_state::{Context, DisplayIn, InContext};
+#[cfg(feature = "std")]
+use crate::graphml;
+#[cfg(feature = "std")]
+use crate::json::{self, FaceKind, JsonCellFace, JsonHeader};
 use crate::lamination::Lamination;
-use crate::types::{IntAngle, Period};
+use crate::limbs::LimbDecomposition;
+#[cfg(feature = "std")]
+use crate::matrices::CoverMatrices;
+use crate::monodromy::{MonodromyGroup, Permutation};
+use crate::progress::{Progress, ProgressSink};
+use crate::real_axis::{real_components, real_crossings, RealComponent, RealCrossingStats};
+#[cfg(feature = "std")]
+use crate::surface::{Mesh, Triangulation};
+use crate::topology::{
+    count_components, BaseCurve, Compactification, Ends, RelativeTopology, Spine,
+};
+use crate::types::{CritPeriod, IntAngle, MarkedPeriod, Period, RatAngle};
+use alloc::{format, string::String, sync::Arc, vec::Vec};
 use num::Integer;
-use std::collections::{HashMap, HashSet};
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+#[cfg(feature = "std")]
+use std::sync::mpsc::Sender;
 
 type Vertex = ShiftedCycle;
 type Edge = cells::Edge<Vertex>;
-type PrimitiveFace = cells::Face<Vertex, AbstractPointClass>;
-type SatelliteFace = cells::Face<Vertex, Vertex>;
+type PrimitiveFace = cells::Face<VertexId, AbstractPointClass>;
+type SatelliteFace = cells::Face<VertexId, Vertex>;
+
+/// Face of either kind, with augmented vertices so that it can be passed to the renderers
+pub type DynFace = cells::Face<AugmentedVertex<Vertex>, DynFaceLabel>;
+/// Cell as sent by [`DynatomicCoverBuilder::build_streaming`]
+pub type DynCell = cells::Cell<Vertex, DynFace>;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DynFaceLabel
+{
+    Primitive(AbstractPointClass),
+    Satellite(ShiftedCycle),
+}
+
+impl core::fmt::Display for DynFaceLabel
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        match self {
+            Self::Primitive(label) => label.fmt(f),
+            Self::Satellite(label) => label.fmt(f),
+        }
+    }
+}
+
+impl core::fmt::Binary for DynFaceLabel
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        match self {
+            Self::Primitive(label) => label.fmt(f),
+            Self::Satellite(label) => label.fmt(f),
+        }
+    }
+}
+
+impl DisplayIn for DynFaceLabel
+{
+    fn fmt_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        match self {
+            Self::Primitive(label) => label.fmt_in(context, f),
+            Self::Satellite(label) => label.fmt_in(context, f),
+        }
+    }
+
+    fn fmt_binary_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>)
+        -> core::fmt::Result
+    {
+        match self {
+            Self::Primitive(label) => label.fmt_binary_in(context, f),
+            Self::Satellite(label) => label.fmt_binary_in(context, f),
+        }
+    }
+}
 
 #[derive(PartialEq, Eq)]
 struct EdgeRep(pub Edge);
@@ -25,12 +115,16 @@ impl EdgeRep
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq)]
 pub struct DynatomicCoverBuilder
 {
     pub period: Period,
     pub crit_period: Period,
-    adjacency_map: HashMap<AbstractPoint, Vec<(ShiftedCycle, Period, IntAngle)>>,
+    context: Context,
+    adjacency_map: FxHashMap<AbstractPoint, Vec<(ShiftedCycle, Period, IntAngle, usize)>>,
+    /// Lamination to extend instead of computing one from scratch; see [`Self::with_lamination`]
+    lamination: Option<Lamination>,
+    progress: Progress,
 }
 
 impl DynatomicCoverBuilder
@@ -41,118 +135,317 @@ impl DynatomicCoverBuilder
         Self {
             period,
             crit_period,
-            adjacency_map: HashMap::new(),
+            context: Context::new(period),
+            adjacency_map: FxHashMap::default(),
+            lamination: None,
+            progress: Progress::default(),
         }
     }
 
+    /// The builder for periods checked to be in range, as by the CLI
+    #[must_use]
+    pub fn from_periods(period: MarkedPeriod, crit_period: CritPeriod) -> Self
+    {
+        Self::new(period.get(), crit_period.get())
+    }
+
+    /// Take the leaves from `lamination`, extending it as needed, instead of computing the
+    /// lamination from scratch. It is kept when the cover is built, and can be taken back with
+    /// [`Self::take_lamination`] to build the cover of the next period.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lamination` is for a different critical period.
+    #[must_use]
+    pub fn with_lamination(mut self, lamination: Lamination) -> Self
+    {
+        assert_eq!(
+            lamination.crit_period, self.crit_period,
+            "lamination has the wrong critical period"
+        );
+        self.lamination = Some(lamination);
+        self
+    }
+
+    /// The lamination given to [`Self::with_lamination`], extended to this period once the
+    /// cover is built
+    pub fn take_lamination(&mut self) -> Option<Lamination>
+    {
+        self.lamination.take()
+    }
+
+    /// Period of the cover being built and denominator of its angles, in which the cells sent
+    /// by [`Self::build_streaming`] are read
+    #[must_use]
+    pub fn context(&self) -> Context
+    {
+        Context::new(self.period)
+    }
+
+    /// Report the progress of each phase of the build to `sink`, including the lamination
+    /// phase, even when the lamination is given by [`Self::with_lamination`]
+    #[must_use]
+    pub fn with_progress(mut self, sink: Arc<dyn ProgressSink>) -> Self
+    {
+        self.progress = Progress::new(sink);
+        self
+    }
+
     #[must_use]
     pub fn build(&mut self) -> DynatomicCover
     {
-        set_period(self.period);
-        let cycles = self.cycles();
-        let edge_reps = self.edge_reps(&cycles);
-        let vertices = Self::vertices(&cycles);
-        let edges = self.edges(&edge_reps);
-        let primitive_faces = self.primitive_faces(&vertices);
-        let satellite_faces = self.satellite_faces(&edge_reps);
+        self.build_timed().0
+    }
+
+    /// Build the cover, recording how long each phase took
+    #[must_use]
+    pub fn build_timed(&mut self) -> (DynatomicCover, BuildTimings)
+    {
+        let clock = PhaseClock::start();
+        self.with_arcs(|builder, arcs| {
+            let lamination = clock.stop();
+            let (cover, mut timings) = builder.build_with_arcs(arcs);
+            timings.record(Phase::Lamination, lamination);
+            (cover, timings)
+        })
+    }
+
+    /// Build the cover, sending each cell through `cells` as soon as it is known instead of
+    /// keeping it, so that the cells can be written out on another thread while the faces are
+    /// still being traced. The vertices are sent first, in increasing order of the marked
+    /// angle, then the edges in the order of the lamination, then the primitive faces in
+    /// whatever order they are traced, and last the satellite faces. The `edge_ids` of each
+    /// face index into the edges in the order they were sent. Cells sent after the receiver
+    /// hangs up are dropped.
+    ///
+    /// The consumer should format the cells in [`Self::context`], e.g. with
+    /// [`Context::display`].
+    #[cfg(feature = "std")]
+    pub fn build_streaming(&mut self, cells: &Sender<DynCell>)
+    {
+        self.with_arcs(|builder, arcs| {
+            builder.context = builder.context();
+
+            let progress = builder.progress.clone();
+            let sink = progress.sink();
+
+            sink.phase_started(Phase::Cycles, None);
+            let vertices = builder.vertices();
+            for &v in &vertices {
+                cells.send(Cell::Vertex(v)).ok();
+            }
+            sink.items_processed(Phase::Cycles, vertices.len());
+            sink.phase_finished(Phase::Cycles);
+
+            sink.phase_started(Phase::Edges, Some(arcs.len()));
+            let edge_reps = builder.edge_reps(arcs);
+            for edge in builder.edges(&edge_reps) {
+                cells.send(Cell::Edge(edge)).ok();
+            }
+            sink.items_processed(Phase::Edges, arcs.len());
+            sink.phase_finished(Phase::Edges);
+
+            sink.phase_started(Phase::Faces, Some(vertices.len()));
+            let ids = Self::vertex_ids(&vertices);
+            let resolve = |&v: &VertexId| vertices[v as usize];
+            stream_faces(
+                &vertices,
+                |cyc, crossings| builder.traverse_face(cyc, &ids, crossings),
+                |face| {
+                    let face = face
+                        .map_vertices(resolve)
+                        .augmented()
+                        .map_label(DynFaceLabel::Primitive);
+                    cells.send(Cell::Face(face)).ok();
+                },
+                sink,
+            );
+            for face in builder.satellite_faces(&edge_reps, &ids) {
+                let face = face
+                    .map_vertices(resolve)
+                    .augmented()
+                    .map_label(DynFaceLabel::Satellite);
+                cells.send(Cell::Face(face)).ok();
+            }
+            sink.phase_finished(Phase::Faces);
+        });
+    }
+
+    /// Apply `f` to the builder and the leaves of this period, taken from the lamination given
+    /// to [`Self::with_lamination`] if there is one
+    fn with_arcs<T>(&mut self, f: impl FnOnce(&mut Self, &[(RatAngle, RatAngle)]) -> T) -> T
+    {
+        let mut lamination = self.lamination.take();
+        // A given lamination reports to the builder's sink while it is extended here
+        let previous = lamination
+            .as_mut()
+            .filter(|_| self.progress.is_set())
+            .map(|lamination| lamination.replace_progress(self.progress.clone()));
+        let owned_arcs;
+        let arcs = match &mut lamination {
+            Some(lamination) => lamination.arcs_of_period(self.period),
+            None => {
+                let mut fresh = Lamination::new().with_crit_period(self.crit_period);
+                fresh.replace_progress(self.progress.clone());
+                owned_arcs = fresh.into_arcs_of_period(self.period);
+                &owned_arcs
+            }
+        };
+        let result = f(self, arcs);
+        if let (Some(lamination), Some(previous)) = (&mut lamination, previous) {
+            lamination.replace_progress(previous);
+        }
+        self.lamination = lamination;
+        result
+    }
 
-        DynatomicCover {
+    /// Build the cover from `arcs`, the leaves of this period, e.g. as read from a lamination
+    /// shared with other builders through [`Lamination::arcs`]. The lamination phase is left
+    /// out of the timings.
+    #[must_use]
+    pub fn build_with_arcs(
+        &mut self,
+        arcs: &[(RatAngle, RatAngle)],
+    ) -> (DynatomicCover, BuildTimings)
+    {
+        self.context = self.context();
+        let mut timings = BuildTimings::default();
+        let progress = self.progress.clone();
+        let sink = progress.sink();
+
+        let clock = PhaseClock::start();
+        sink.phase_started(Phase::Cycles, None);
+        let vertices = self.vertices();
+        sink.items_processed(Phase::Cycles, vertices.len());
+        sink.phase_finished(Phase::Cycles);
+        timings.record(Phase::Cycles, clock.stop());
+
+        let clock = PhaseClock::start();
+        sink.phase_started(Phase::Edges, Some(arcs.len()));
+        let edge_reps = self.edge_reps(arcs);
+        let mut edges = self.edges(&edge_reps);
+        sink.items_processed(Phase::Edges, arcs.len());
+        sink.phase_finished(Phase::Edges);
+        timings.record(Phase::Edges, clock.stop());
+
+        let clock = PhaseClock::start();
+        sink.phase_started(Phase::Faces, Some(vertices.len()));
+        let ids = Self::vertex_ids(&vertices);
+        let mut primitive_faces = self.primitive_faces(&vertices, &ids);
+        let mut satellite_faces = self.satellite_faces(&edge_reps, &ids);
+        // Put the cells in canonical order, so that covers of the same curve are equal however
+        // they were built. The vertices are already ordered by the angle they mark.
+        debug_assert!(vertices.is_sorted_by_key(|v| v.to_point_in(self.context).angle));
+        let new_ids = cells::sort_edges(&mut edges, |e| (e.wake.angle0, e.wake.angle1, e.start));
+        for face in &mut primitive_faces {
+            face.renumber_edges(&new_ids);
+        }
+        for face in &mut satellite_faces {
+            face.renumber_edges(&new_ids);
+        }
+        primitive_faces.sort_by_key(|f| f.label);
+        satellite_faces.sort_by_key(|f| f.label);
+        sink.phase_finished(Phase::Faces);
+        timings.record(Phase::Faces, clock.stop());
+
+        let cover = DynatomicCover {
+            context: self.context,
             crit_period: self.crit_period,
             vertices,
             edges,
             primitive_faces,
             satellite_faces,
+        };
+        #[cfg(feature = "stats")]
+        {
+            timings.stats.num_vertices = cover.num_vertices();
+            timings.stats.num_edges = cover.num_edges();
+            timings.stats.num_faces = cover.num_faces();
         }
+        (cover, timings)
     }
 
-    #[inline]
-    fn orbit(angle: IntAngle) -> Vec<IntAngle>
+    /// The cycle marked by `angle`, shifted so that `angle` is the marked point, if it has
+    /// exact period
+    fn cycle_at(&self, angle: IntAngle) -> Option<ShiftedCycle>
     {
-        get_orbit(angle)
+        let (rep, shift) = cycle_position(self.context, angle)?;
+        Some(ShiftedCycle {
+            rep: AbstractPoint::new(rep),
+            shift,
+        })
     }
 
-    fn cycles(&self) -> Vec<Option<ShiftedCycle>>
+    /// Vertices, labeled by shifted cycle, in increasing order of the marked angle
+    fn vertices(&self) -> Vec<ShiftedCycle>
     {
-        let mut cycles = vec![
-            None;
-            usize::try_from(MAX_ANGLE.get())
-                .expect("MAX_ANGLE appears to be negative!")
-        ];
-        for theta in 0..MAX_ANGLE.get().into() {
-            let theta_usize = theta as usize;
-            if cycles[theta_usize].is_some() {
-                continue;
-            }
-
-            let orbit = get_orbit(theta.into());
-            if orbit.len() == self.period as usize {
-                let cycle_rep = orbit[0]; // Always the minimum in the orbit
-                let cycle_rep = AbstractPoint::new(cycle_rep);
-
-                orbit
-                    .iter()
-                    .map(|x| usize::try_from(*x).unwrap_or_default())
-                    .enumerate()
-                    .for_each(|(i, x)| {
-                        let shift = i as i64;
-                        let shifted_cycle = ShiftedCycle {
-                            rep: cycle_rep,
-                            shift,
-                        };
-                        cycles[x] = Some(shifted_cycle);
-                    });
-            }
-        }
-        if PERIOD.get() == 1 {
-            let alpha_fp = AbstractPoint::new(IntAngle(1));
-            cycles.push(Some(ShiftedCycle {
-                rep: alpha_fp,
-                shift: 0,
-            }));
-        }
-        cycles
+        cycle_positions(self.context)
+            .into_iter()
+            .map(|(rep, shift)| ShiftedCycle {
+                rep: AbstractPoint::new(rep),
+                shift,
+            })
+            .collect()
     }
 
-    fn vertices(cycles: &[Option<ShiftedCycle>]) -> Vec<ShiftedCycle>
+    /// Index of each vertex in `vertices`
+    fn vertex_ids(vertices: &[ShiftedCycle]) -> FxHashMap<ShiftedCycle, VertexId>
     {
-        // Vertices, labeled by abstract point
-        cycles.iter().filter_map(|&v| v).collect::<Vec<_>>()
+        vertices
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| (v, i as VertexId))
+            .collect()
     }
 
-    fn edge_reps(&mut self, cycles: &[Option<ShiftedCycle>]) -> Vec<EdgeRep>
+    fn edge_reps(&mut self, arcs: &[(RatAngle, RatAngle)]) -> Vec<EdgeRep>
     {
         // Leaves of lamination, labeled by shifted cycle
-        Lamination::new()
-            .with_crit_period(self.crit_period)
-            .into_arcs_of_period(self.period)
-            .into_iter()
-            .filter_map(|(theta0, theta1)| {
-                let angle0 = MAX_ANGLE.get().scale_by_ratio(&theta0);
-                let angle1 = MAX_ANGLE.get().scale_by_ratio(&theta1);
-
-                let k0 = usize::try_from(angle0).ok()?;
-                let k1 = usize::try_from(angle1).ok()?;
+        let mut rep_id = 0;
+        let edge_reps = arcs
+            .iter()
+            .filter_map(|&(theta0, theta1)| {
+                let angle0 = self.context.max_angle.scale_by_ratio(&theta0);
+                let angle1 = self.context.max_angle.scale_by_ratio(&theta1);
 
-                let cyc0 = cycles[k0]?;
-                let cyc1 = cycles[k1]?;
+                let cyc0 = self.cycle_at(angle0)?;
+                let cyc1 = self.cycle_at(angle1)?;
 
                 let tag = angle0.max(angle1);
                 self.adjacency_map
                     .entry(cyc0.rep)
                     .or_default()
-                    .push((cyc1, cyc0.shift, tag));
+                    .push((cyc1, cyc0.shift, tag, rep_id));
                 self.adjacency_map
                     .entry(cyc1.rep)
                     .or_default()
-                    .push((cyc0, cyc1.shift, tag));
+                    .push((cyc0, cyc1.shift, tag, rep_id));
+                rep_id += 1;
 
                 Some(EdgeRep(Edge {
                     start: cyc0,
                     end: cyc1,
-                    wake: Wake { angle0, angle1 },
+                    wake: Wake {
+                        angle0,
+                        angle1,
+                        context: self.context,
+                    },
+                    kneading_sequence: AbstractPoint::new(angle0)
+                        .kneading_sequence_in(self.context),
                 }))
             })
-            .collect()
+            .collect();
+        // Sort each cycle's wakes by angle, so that the next one can be found by bisection
+        for adjacent in self.adjacency_map.values_mut() {
+            adjacent.sort_by_key(|&(_, _, tag, _)| tag);
+        }
+        edge_reps
+    }
+
+    /// Index in the edge list of the rotation by `shift` of the `rep_id`th edge representative
+    fn edge_id(&self, rep_id: usize, shift: Period) -> usize
+    {
+        rep_id * self.period as usize + shift.rem_euclid(self.period) as usize
     }
 
     fn edges(&mut self, edge_reps: &[EdgeRep]) -> Vec<Edge>
@@ -161,29 +454,38 @@ impl DynatomicCoverBuilder
             .iter()
             .flat_map(|EdgeRep(e)| {
                 (0..self.period).map(|i| Edge {
-                    start: e.start.rotate(i),
-                    end: e.end.rotate(i),
+                    start: e.start.rotate_in(i, self.context),
+                    end: e.end.rotate_in(i, self.context),
                     wake: e.wake.clone(),
+                    kneading_sequence: e.kneading_sequence,
                 })
             })
             .collect()
     }
 
-    fn satellite_faces(&self, wakes: &[EdgeRep]) -> Vec<SatelliteFace>
+    fn satellite_faces(
+        &self,
+        wakes: &[EdgeRep],
+        ids: &FxHashMap<ShiftedCycle, VertexId>,
+    ) -> Vec<SatelliteFace>
     {
         wakes
             .iter()
-            .filter(|e| e.is_satellite())
-            .flat_map(|EdgeRep(e)| {
-                let shift = e.end.relative_shift(e.start);
+            .enumerate()
+            .filter(|(_, e)| e.is_satellite())
+            .flat_map(|(rep_id, EdgeRep(e))| {
+                let shift = e.end.relative_shift_in(e.start, self.context);
                 let num_faces = shift.gcd(&self.period);
                 let face_period = self.period / num_faces;
                 (0..num_faces).map(move |i| {
-                    let base_point = e.start.with_shift(0).rotate(i);
+                    let base_point = e.start.with_shift(0).rotate_in(i, self.context);
                     SatelliteFace {
                         label: base_point,
                         vertices: (0..face_period)
-                            .map(|j| base_point.rotate(j * shift))
+                            .map(|j| ids[&base_point.rotate_in(j * shift, self.context)])
+                            .collect(),
+                        edge_ids: (0..face_period)
+                            .map(|j| self.edge_id(rep_id, i + j * shift - e.start.shift))
                             .collect(),
                         degree: 1,
                     }
@@ -192,24 +494,24 @@ impl DynatomicCoverBuilder
             .collect()
     }
 
-    fn primitive_faces(&self, vertices: &[ShiftedCycle]) -> Vec<PrimitiveFace>
+    fn primitive_faces(
+        &self,
+        vertices: &[ShiftedCycle],
+        ids: &FxHashMap<ShiftedCycle, VertexId>,
+    ) -> Vec<PrimitiveFace>
     {
-        let mut visited = HashSet::new();
-        vertices
-            .iter()
-            .filter_map(|cyc| {
-                if visited.contains(cyc) {
-                    return None;
-                }
-                Some(self.traverse_face(*cyc, &mut visited))
-            })
-            .collect()
+        traverse_faces(
+            vertices,
+            |cyc, crossings| self.traverse_face(cyc, ids, crossings),
+            self.progress.sink(),
+        )
     }
 
     fn traverse_face(
         &self,
         starting_point: ShiftedCycle,
-        visited: &mut HashSet<ShiftedCycle>,
+        ids: &FxHashMap<ShiftedCycle, VertexId>,
+        crossings: &mut Vec<VertexId>,
     ) -> PrimitiveFace
     {
         // Cycle that is currently marked
@@ -217,32 +519,37 @@ impl DynatomicCoverBuilder
 
         // Angle of the current parameter
         let mut curr_angle = IntAngle(0);
-        let mut nodes = Vec::new();
+        let mut nodes = VertexList::new();
+        let mut edge_ids = Vec::new();
         let mut face_degree = 1;
 
-        while let Some((next_node, next_angle)) = self.get_next_vertex_and_angle(node, curr_angle) {
+        while let Some((next_node, next_angle, edge_id)) =
+            self.get_next_vertex_and_angle(node, curr_angle)
+        {
             // If we are crossing the real axis
             if curr_angle >= next_angle {
                 if node == starting_point {
                     break;
                 }
-                visited.insert(node);
+                crossings.push(ids[&node]);
                 face_degree += 1;
             }
 
-            nodes.push(node);
+            nodes.push(ids[&node]);
+            edge_ids.push(edge_id);
             node = next_node;
 
             curr_angle = next_angle;
         }
 
         if nodes.is_empty() {
-            nodes.push(node);
+            nodes.push(ids[&node]);
         }
 
         PrimitiveFace {
-            label: starting_point.to_point_class(),
+            label: starting_point.to_point_class_in(self.context),
             vertices: nodes,
+            edge_ids,
             degree: face_degree,
         }
     }
@@ -251,23 +558,42 @@ impl DynatomicCoverBuilder
         &self,
         node: ShiftedCycle,
         curr_angle: IntAngle,
-    ) -> Option<(ShiftedCycle, IntAngle)>
+    ) -> Option<(ShiftedCycle, IntAngle, usize)>
     {
-        self.adjacency_map
-            .get(&node.rep)?
-            .iter()
-            .min_by_key(|(_, _, ang)| (ang.0 - curr_angle.0 - 1).rem_euclid(MAX_ANGLE.get().0))
-            .map(|(beta, alpha_shift, ang)| (beta.rotate(node.shift - alpha_shift), *ang))
+        // The first wake past the current angle, going around the circle
+        let adjacent = self.adjacency_map.get(&node.rep)?;
+        let next = adjacent.partition_point(|&(_, _, ang, _)| ang <= curr_angle);
+        adjacent
+            .get(next)
+            .or(adjacent.first())
+            .map(|(beta, alpha_shift, ang, rep_id)| {
+                let shift = node.shift - alpha_shift;
+                (
+                    beta.rotate_in(shift, self.context),
+                    *ang,
+                    self.edge_id(*rep_id, shift),
+                )
+            })
     }
 }
 
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct DynatomicCover
 {
+    /// Period of the cover and denominator of its angles, in which its cells are read and
+    /// formatted
+    pub context: Context,
     pub crit_period: Period,
+    /// Vertices, in order of the angle of the point they mark
     pub vertices: Vec<ShiftedCycle>,
+    /// Edges, in order of their wakes and then of their starting vertices
     pub edges: Vec<Edge>,
+    /// Faces traced around punctures in order of label, whose boundaries refer to `vertices`
+    /// by index
     pub primitive_faces: Vec<PrimitiveFace>,
+    /// Faces bounded by satellite edges in order of label, whose boundaries refer to
+    /// `vertices` by index
     pub satellite_faces: Vec<SatelliteFace>,
 }
 
@@ -279,6 +605,72 @@ impl DynatomicCover
         DynatomicCoverBuilder::new(period, crit_period).build()
     }
 
+    #[must_use]
+    pub fn from_periods(period: MarkedPeriod, crit_period: CritPeriod) -> Self
+    {
+        DynatomicCoverBuilder::from_periods(period, crit_period).build()
+    }
+
+    /// Build the cover for each of `periods` in turn, extending one lamination from each
+    /// period to the next instead of computing it from scratch every time
+    pub fn sweep(
+        periods: impl IntoIterator<Item = Period>,
+        crit_period: Period,
+    ) -> impl Iterator<Item = Self>
+    {
+        let mut lamination = Some(Lamination::new().with_crit_period(crit_period));
+        periods.into_iter().map(move |period| {
+            let mut builder = DynatomicCoverBuilder::new(period, crit_period).with_lamination(
+                lamination
+                    .take()
+                    .expect("each build gives back the lamination"),
+            );
+            let cover = builder.build();
+            lamination = builder.take_lamination();
+            cover
+        })
+    }
+
+    /// Build the cover for each of `periods` at once, one thread per period, and apply `f` to
+    /// each. The lamination is extended once and shared between the threads. The results are
+    /// in the order of `periods`.
+    #[cfg(feature = "std")]
+    pub fn map_periods<T, F>(periods: &[Period], crit_period: Period, f: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(&Self) -> T + Sync,
+    {
+        build_periods(periods, crit_period, |period, arcs| {
+            let (cover, _) = DynatomicCoverBuilder::new(period, crit_period).build_with_arcs(arcs);
+            f(&cover)
+        })
+    }
+
+    /// The [`Self::counts`] of the cover for each of `periods`, built at once as in
+    /// [`Self::map_periods`]
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn counts_for_periods(
+        periods: &[Period],
+        crit_period: Period,
+    ) -> Vec<(usize, usize, usize, i64)>
+    {
+        Self::map_periods(periods, crit_period, Self::counts)
+    }
+
+    #[must_use]
+    pub fn period(&self) -> Period
+    {
+        self.context.period
+    }
+
+    /// Format a cell of the cover in the cover's context, whatever the ambient context is
+    #[must_use]
+    pub const fn display<'a, T: DisplayIn + ?Sized>(&self, cell: &'a T) -> InContext<'a, T>
+    {
+        self.context.display(cell)
+    }
+
     #[must_use]
     pub fn euler_characteristic(&self) -> i64
     {
@@ -303,12 +695,210 @@ impl DynatomicCover
         self.primitive_faces.len() + self.satellite_faces.len()
     }
 
+    /// Genus of the closed surface, assuming that it is connected; see
+    /// [`Self::relative_topology`] for covers that are not
     #[must_use]
     pub fn genus(&self) -> i64
     {
         1 - self.euler_characteristic() / 2
     }
 
+    #[must_use]
+    pub fn num_components(&self) -> usize
+    {
+        count_components(&self.vertices, &self.edges)
+    }
+
+    /// The ends of the cover over the puncture of its base, with their local degrees, or `None`
+    /// if the critical period is not 1 or 2
+    #[must_use]
+    pub fn ends_at_infinity(&self) -> Option<Ends>
+    {
+        let base = BaseCurve::from_crit_period(self.crit_period)?;
+        Some(Ends::new(
+            base,
+            self.num_vertices(),
+            self.primitive_faces.iter().map(|f| f.degree),
+        ))
+    }
+
+    /// The monodromy group of the cover over its base, acting on the vertices by index, with
+    /// a generator for each wake
+    #[must_use]
+    pub fn monodromy_group(&self) -> MonodromyGroup
+    {
+        MonodromyGroup::from_edges(&self.vertices, &self.edges)
+    }
+
+    /// The doubling map on the marked points, acting on the vertices by index: it sends each
+    /// vertex to the next point of its cycle
+    #[must_use]
+    pub fn doubling_permutation(&self) -> Permutation
+    {
+        let index: FxHashMap<ShiftedCycle, usize> = self
+            .vertices
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| (v, i))
+            .collect();
+        let images = self
+            .vertices
+            .iter()
+            .map(|v| index[&v.rotate_in(1, self.context)])
+            .collect();
+        Permutation::from_images(images).expect("Doubling permutes the points of each cycle")
+    }
+
+    /// The vertices, edges, and faces grouped by the limb of the main cardioid they lie in,
+    /// with faces in the order of `faces()`
+    #[must_use]
+    pub fn limbs(&self) -> LimbDecomposition
+    {
+        let face_edges = self
+            .primitive_faces
+            .iter()
+            .map(|f| &f.edge_ids[..])
+            .chain(self.satellite_faces.iter().map(|f| &f.edge_ids[..]));
+        LimbDecomposition::new(self.context, &self.vertices, &self.edges, face_edges)
+    }
+
+    /// The components of the cover's period meeting the real axis, with the edges lying in
+    /// them, in order along the axis from `c = 1/4` towards `c = -2`
+    #[must_use]
+    pub fn real_components(&self) -> Vec<RealComponent>
+    {
+        real_components(&self.edges)
+    }
+
+    /// For each face, in order, the number of times its boundary crosses the real axis along a
+    /// real edge
+    #[must_use]
+    pub fn real_crossings(&self) -> Vec<usize>
+    {
+        let face_edges = self
+            .primitive_faces
+            .iter()
+            .map(|f| &f.edge_ids[..])
+            .chain(self.satellite_faces.iter().map(|f| &f.edge_ids[..]));
+        real_crossings(&self.edges, face_edges)
+    }
+
+    /// How many faces cross the real axis along real edges each number of times
+    #[must_use]
+    pub fn real_crossing_stats(&self) -> RealCrossingStats
+    {
+        RealCrossingStats::new(&self.real_crossings())
+    }
+
+    /// Counts and histograms of the cells: the numbers of cells, the genus, the face sizes and
+    /// vertex degrees, the reflexive and odd faces, and the real edges
+    #[must_use]
+    pub fn stats(&self) -> CoverStats
+    {
+        let num_real_edges = self.edges.iter().filter(|e| e.is_real()).count();
+        CoverStats::new(
+            self.num_vertices(),
+            &self.edge_endpoints(),
+            num_real_edges,
+            self.primitive_faces
+                .iter()
+                .map(|f| (f.len(), f.degree))
+                .chain(self.satellite_faces.iter().map(|f| (f.len(), f.degree))),
+            self.genus(),
+        )
+    }
+
+    /// Where the cover is punctured and ramified over its base, and the genus summed over its
+    /// components, or `None` if the critical period is not 1 or 2
+    #[must_use]
+    pub fn relative_topology(&self) -> Option<RelativeTopology>
+    {
+        let base = BaseCurve::from_crit_period(self.crit_period)?;
+        let (vertices, edges, faces, _) = self.counts();
+        Some(RelativeTopology::new(
+            base,
+            (vertices, edges, faces),
+            self.num_components(),
+            self.primitive_faces.iter().map(|f| f.degree),
+        ))
+    }
+
+    /// A graph made of cells of the cover onto which the open cover deformation retracts, or
+    /// `None` if the critical period is not 1 or 2
+    #[must_use]
+    pub fn spine(&self) -> Option<Spine>
+    {
+        let base = BaseCurve::from_crit_period(self.crit_period)?;
+        let edges = self.edge_endpoints();
+        let face_edges = self
+            .primitive_faces
+            .iter()
+            .map(|f| &f.edge_ids[..])
+            .chain(self.satellite_faces.iter().map(|f| &f.edge_ids[..]));
+        Some(Spine::new(
+            base,
+            self.num_vertices(),
+            &edges,
+            face_edges,
+            self.primitive_faces.len(),
+        ))
+    }
+
+    /// The closed surface of the cover with a cell filling in each of its ends, checked against
+    /// the Riemann-Hurwitz formula for its monodromy
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the critical period is not 1 or 2, or if the compactification
+    /// disagrees with the Riemann-Hurwitz formula for [`Self::monodromy_group`].
+    pub fn compactification(&self) -> Result<Compactification, String>
+    {
+        let Some(ends) = self.ends_at_infinity() else {
+            return Err(format!(
+                "No base curve is known for critical period {}",
+                self.crit_period
+            ));
+        };
+        let primitive = self
+            .primitive_faces
+            .iter()
+            .map(|f| (&f.vertices, &f.edge_ids));
+        let satellite = self
+            .satellite_faces
+            .iter()
+            .map(|f| (&f.vertices, &f.edge_ids));
+        let faces = primitive.chain(satellite).map(|(vertices, edge_ids)| {
+            let corners = vertices.iter().map(|&v| v as usize).collect();
+            (corners, &edge_ids[..])
+        });
+        let compactification =
+            Compactification::new(&ends, self.num_vertices(), &self.edge_endpoints(), faces);
+        compactification.check(&self.monodromy_group())?;
+        Ok(compactification)
+    }
+
+    /// The vertices joined by each edge, by index
+    fn edge_endpoints(&self) -> Vec<(usize, usize)>
+    {
+        let ids = DynatomicCoverBuilder::vertex_ids(&self.vertices);
+        self.edges
+            .iter()
+            .map(|e| (ids[&e.start] as usize, ids[&e.end] as usize))
+            .collect()
+    }
+
+    /// Numbers of vertices, edges, and faces, and the genus
+    #[must_use]
+    pub fn counts(&self) -> (usize, usize, usize, i64)
+    {
+        (
+            self.num_vertices(),
+            self.num_edges(),
+            self.num_faces(),
+            self.genus(),
+        )
+    }
+
     #[must_use]
     pub fn face_sizes(&self) -> Vec<usize>
     {
@@ -325,56 +915,517 @@ impl DynatomicCover
         self.face_sizes().iter().filter(|&s| s % 2 == 1).count()
     }
 
-    pub fn summarize(&self, indent: usize, binary: bool)
+    /// A face with its boundary vertices looked up in `vertices`
+    #[must_use]
+    pub fn resolve<F: Clone>(&self, face: &cells::Face<VertexId, F>) -> cells::Face<Vertex, F>
+    {
+        face.map_vertices(|&v| self.vertices[v as usize])
+    }
+
+    /// Boundary vertices of a face
+    fn boundary<F>(&self, face: &cells::Face<VertexId, F>) -> Vec<Vertex>
+    {
+        face.vertices
+            .iter()
+            .map(|&v| self.vertices[v as usize])
+            .collect()
+    }
+
+    /// Primitive faces followed by satellite faces, in the form expected by the renderers
+    #[must_use]
+    pub fn faces(&self) -> Vec<DynFace>
+    {
+        let primitive = self.primitive_faces.iter().map(|f| {
+            self.resolve(f)
+                .augmented()
+                .map_label(DynFaceLabel::Primitive)
+        });
+        let satellite = self.satellite_faces.iter().map(|f| {
+            self.resolve(f)
+                .augmented()
+                .map_label(DynFaceLabel::Satellite)
+        });
+        primitive.chain(satellite).collect()
+    }
+
+    /// Faces on either side of each edge, as indices into `faces()`, keyed by edge id
+    #[must_use]
+    pub fn dual_graph(&self) -> Vec<(usize, (usize, usize))>
+    {
+        cells::dual_edges(&self.faces())
+    }
+}
+
+/// Exporters and summaries, which need `std`
+#[cfg(feature = "std")]
+impl DynatomicCover
+{
+    /// Glue all faces into a single polygon whose side pairing recovers the cover
+    #[must_use]
+    pub fn fundamental_polygon(&self) -> FundamentalPolygon<Vertex>
+    {
+        let primitive = self
+            .primitive_faces
+            .iter()
+            .map(|f| (self.boundary(f), f.edge_ids.clone()));
+        let satellite = self
+            .satellite_faces
+            .iter()
+            .map(|f| (self.boundary(f), f.edge_ids.clone()));
+        FundamentalPolygon::new(primitive.chain(satellite))
+    }
+
+    /// Triangulation of the surface, with every face subdivided so that it is a simplicial
+    /// complex
+    #[must_use]
+    pub fn triangulation(&self) -> Triangulation
+    {
+        Triangulation::new(&self.vertices, &self.edges, self.oriented_faces())
+    }
+
+    /// Boundary vertices and edge ids of each face, all oriented the same way
+    fn oriented_faces(&self) -> impl Iterator<Item = (Vec<ShiftedCycle>, Vec<usize>)> + '_
+    {
+        let primitive = self
+            .primitive_faces
+            .iter()
+            .map(|f| (self.boundary(f), f.edge_ids.clone()));
+        let satellite = self.satellite_faces.iter().map(|f| {
+            // Satellite faces are traced against the orientation of the primitive ones
+            let mut boundary = self.boundary(f);
+            boundary.reverse();
+            boundary.rotate_right(1);
+            let mut edge_ids = f.edge_ids.clone();
+            edge_ids.reverse();
+            (boundary, edge_ids)
+        });
+        primitive.chain(satellite)
+    }
+
+    /// Boundary operators and adjacency matrix of the cover, with the faces oriented as in
+    /// [`Self::triangulation`]
+    #[must_use]
+    pub fn matrices(&self) -> CoverMatrices
+    {
+        CoverMatrices::new(&self.vertices, &self.edges, self.oriented_faces())
+    }
+
+    /// polymake script building the surface from [`Self::triangulation`] and printing its
+    /// genus and whether it is orientable, to check the genus computed here
+    #[must_use]
+    pub fn to_polymake(&self) -> String
+    {
+        self.triangulation()
+            .to_polymake(&self.surface_name(), self.genus())
+    }
+
+    /// The surface placed in space, with the 1-skeleton relaxed for the given number of
+    /// iterations
+    #[must_use]
+    pub fn mesh(&self, iterations: usize) -> Mesh
+    {
+        Mesh::new(&self.triangulation(), iterations)
+    }
+
+    /// Wavefront OBJ mesh of the surface; see [`Self::mesh`]
+    #[must_use]
+    pub fn to_obj(&self, iterations: usize) -> String
+    {
+        self.mesh(iterations).to_obj(&self.surface_name())
+    }
+
+    /// PLY mesh of the surface; see [`Self::mesh`]
+    #[must_use]
+    pub fn to_ply(&self, iterations: usize) -> String
+    {
+        self.mesh(iterations).to_ply(&self.surface_name())
+    }
+
+    /// Name of the curve, as in `Dyn_5(Per_1)`
+    pub(crate) fn surface_name(&self) -> String
+    {
+        format!("Dyn_{}(Per_{})", self.context.period, self.crit_period)
+    }
+
+    /// Label and boundary vertices of each face, for the graph exporters
+    fn labeled_boundaries(&self) -> Vec<DotFace<Vertex>>
+    {
+        let primitive = self
+            .primitive_faces
+            .iter()
+            .map(|f| (self.context.display(&f.label).to_string(), self.boundary(f)));
+        let satellite = self
+            .satellite_faces
+            .iter()
+            .map(|f| (self.context.display(&f.label).to_string(), self.boundary(f)));
+        primitive.chain(satellite).collect()
+    }
+
+    /// Graphviz description of the 1-skeleton, optionally with one cluster per face
+    #[must_use]
+    pub fn to_dot(&self, with_faces: bool) -> String
+    {
+        let faces = with_faces.then(|| self.labeled_boundaries());
+        dot::to_dot(
+            "Dyn",
+            self.context,
+            &self.vertices,
+            &self.edges,
+            faces.as_deref(),
+        )
+    }
+
+    /// GraphML description of the 1-skeleton, optionally with one node per face
+    #[must_use]
+    pub fn to_graphml(&self, with_faces: bool) -> String
+    {
+        let faces = with_faces.then(|| self.labeled_boundaries());
+        graphml::to_graphml(
+            "Dyn",
+            self.context,
+            &self.vertices,
+            &self.edges,
+            faces.as_deref(),
+        )
+    }
+
+    /// GEXF description of the 1-skeleton with the same attributes as [`Self::to_graphml`],
+    /// optionally with one node per face
+    #[must_use]
+    pub fn to_gexf(&self, with_faces: bool) -> String
+    {
+        let faces = with_faces.then(|| self.labeled_boundaries());
+        gexf::to_gexf(
+            "Dyn",
+            self.context,
+            &self.vertices,
+            &self.edges,
+            faces.as_deref(),
+        )
+    }
+
+    /// A face as listed in the cells of [`Self::to_json`], marked as of the given `kind`
+    fn json_face<F: DisplayIn>(&self, f: &cells::Face<VertexId, F>, kind: FaceKind)
+        -> JsonCellFace
+    {
+        JsonCellFace {
+            kind: Some(kind),
+            ..JsonCellFace::new(
+                (self.label(&f.label), self.boundary(f), f.degree),
+                f.edge_ids.clone(),
+                self.context,
+            )
+        }
+    }
+
+    /// The label of a face, in the cover's context
+    fn label<F: DisplayIn>(&self, label: &F) -> String
+    {
+        self.context.display(label).to_string()
+    }
+
+    /// JSON summary of the cover, optionally listing every cell
+    #[must_use]
+    pub fn to_json(&self, with_cells: bool) -> String
+    {
+        let header = JsonHeader {
+            curve: "Dyn",
+            context: self.context,
+            crit_period: self.crit_period,
+            genus: self.genus(),
+        };
+        let primitive = self
+            .primitive_faces
+            .iter()
+            .map(|f| self.json_face(f, FaceKind::Primitive));
+        let satellite = self
+            .satellite_faces
+            .iter()
+            .map(|f| self.json_face(f, FaceKind::Satellite));
+        let faces: Vec<_> = primitive.chain(satellite).collect();
+        json::to_json(header, &self.vertices, &self.edges, faces, with_cells)
+    }
+
+    /// Read a cover back from the output of [`Self::to_json`] with its cells, laid out as in
+    /// [`json::SCHEMA_VERSION`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `text` is not such a document for a dynatomic curve, or if its
+    /// cells do not fit together.
+    pub fn from_json(text: &str) -> Result<Self, String>
+    {
+        let cells = json::read_cells(text, "Dyn")?;
+        let context = Context::new(cells.period);
+
+        let shifted_cycle = |label: &str| match json::label_numbers(label, '[', ']')?[..] {
+            [angle, shift] => Ok(ShiftedCycle {
+                rep: AbstractPoint::new(IntAngle(angle)),
+                shift,
+            }),
+            _ => Err(format!("malformed label `{label}`")),
+        };
+        let vertices = cells
+            .vertices
+            .iter()
+            .map(|v| shifted_cycle(v))
+            .collect::<Result<Vec<_>, String>>()?;
+        let ids = DynatomicCoverBuilder::vertex_ids(&vertices);
+        let vertex_id = |label: &str| {
+            let v = shifted_cycle(label)?;
+            ids.get(&v)
+                .copied()
+                .ok_or_else(|| format!("{label} is not a vertex of the cover"))
+        };
+
+        let edges = cells
+            .edges
+            .iter()
+            .map(|e| {
+                let [angle0, angle1] = e.wake.map(IntAngle);
+                Ok(Edge {
+                    start: vertices[vertex_id(&e.start)? as usize],
+                    end: vertices[vertex_id(&e.end)? as usize],
+                    wake: Wake {
+                        angle0,
+                        angle1,
+                        context,
+                    },
+                    kneading_sequence: AbstractPoint::new(angle0).kneading_sequence_in(context),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let mut primitive_faces = Vec::new();
+        let mut satellite_faces = Vec::new();
+        for f in cells.faces {
+            let vertices = f
+                .vertices
+                .iter()
+                .map(|v| vertex_id(v))
+                .collect::<Result<_, String>>()?;
+            match f.kind {
+                Some(FaceKind::Primitive) => {
+                    let [angle] = json::label_numbers(&f.label, '[', ']')?[..] else {
+                        return Err(format!("malformed face label `{}`", f.label));
+                    };
+                    primitive_faces.push(PrimitiveFace {
+                        label: AbstractPointClass {
+                            rep: AbstractPoint::new(IntAngle(angle)),
+                        },
+                        vertices,
+                        edge_ids: f.edge_ids,
+                        degree: f.degree,
+                    });
+                }
+                Some(FaceKind::Satellite) => satellite_faces.push(SatelliteFace {
+                    label: shifted_cycle(&f.label)?,
+                    vertices,
+                    edge_ids: f.edge_ids,
+                    degree: f.degree,
+                }),
+                None => {
+                    return Err(format!(
+                        "face {} is neither primitive nor satellite",
+                        f.label
+                    ))
+                }
+            }
+        }
+
+        Ok(Self {
+            context,
+            crit_period: cells.crit_period,
+            vertices,
+            edges,
+            primitive_faces,
+            satellite_faces,
+        })
+    }
+
+    /// Stream the cells that pass `filter` as newline-delimited JSON, one object per line
+    pub fn write_ndjson<W: Write>(&self, w: &mut W, filter: &CellFilter) -> io::Result<()>
+    {
+        let header = JsonHeader {
+            curve: "Dyn",
+            context: self.context,
+            crit_period: self.crit_period,
+            genus: self.genus(),
+        };
+        let vertices = self
+            .vertices
+            .iter()
+            .filter(|v| filter.keeps_vertex(*v, self.context));
+        let edges = self.edges.iter().filter(|e| filter.keeps_edge(e));
+        let primitive = self
+            .primitive_faces
+            .iter()
+            .filter(|f| filter.keeps_face(&self.resolve(f), self.context))
+            .map(|f| (self.label(&f.label), self.boundary(f), f.degree));
+        let satellite = self
+            .satellite_faces
+            .iter()
+            .filter(|f| filter.keeps_face(&self.resolve(f), self.context))
+            .map(|f| (self.label(&f.label), self.boundary(f), f.degree));
+        let faces = primitive.chain(satellite);
+        json::write_ndjson(w, header, vertices, edges, faces)
+    }
+
+    /// Label, boundary, edge ids, and degree of each face, for the tabular exports
+    fn table_faces(&self) -> Vec<CsvFace<ShiftedCycle>>
+    {
+        let primitive = self.primitive_faces.iter().map(|f| {
+            let label = self.label(&f.label);
+            (label, self.boundary(f), f.edge_ids.clone(), f.degree)
+        });
+        let satellite = self.satellite_faces.iter().map(|f| {
+            let label = self.label(&f.label);
+            (label, self.boundary(f), f.edge_ids.clone(), f.degree)
+        });
+        primitive.chain(satellite).collect()
+    }
+
+    /// One CSV table each for the vertices, edges, and faces
+    #[must_use]
+    pub fn to_csv(&self) -> CsvTables
+    {
+        CsvTables::new(
+            self.context,
+            &self.vertices,
+            &self.edges,
+            &self.table_faces(),
+        )
+    }
+
+    /// Typed rows of the vertex, edge, and face tables, for writing with
+    /// [`crate::csv::RecordWriter`] or serializing some other way
+    #[must_use]
+    pub fn records(&self) -> CellRecords
+    {
+        CellRecords::new(
+            self.context,
+            &self.vertices,
+            &self.edges,
+            &self.table_faces(),
+        )
+    }
+
+    /// One Parquet or Arrow table each for the vertices, edges, and faces
+    #[cfg(feature = "parquet")]
+    #[must_use]
+    pub fn to_columnar(&self) -> CellBatches
     {
-        let indent_str = " ".repeat(indent);
+        CellBatches::new(
+            self.context,
+            &self.vertices,
+            &self.edges,
+            &self.table_faces(),
+        )
+    }
+
+    /// Print the cells, face sizes, and genus to stdout
+    pub fn summarize(&self, opts: &SummaryOptions)
+    {
+        self.summarize_to(&mut std::io::stdout().lock(), opts)
+            .expect("Failed to write summary to stdout");
+    }
+
+    /// Write the cells that pass `opts.filter`, ordered and truncated according to
+    /// `opts.listing`, then the face sizes and genus
+    pub fn summarize_to<W: Write>(&self, w: &mut W, opts: &SummaryOptions) -> io::Result<()>
+    {
+        let indent_str = " ".repeat(opts.indent);
         macro_rules! print_elements {
-            ($title: expr, $iter: expr, $count: expr) => {
-                if $count > crate::MAX_DISPLAY_ITEMS {
-                    println!("\n{} {}", $count, $title);
+            ($title: expr, $iter: expr, $total: expr) => {
+                print_elements!($title, $iter, $total, |elem| elem)
+            };
+            ($title: expr, $iter: expr, $total: expr, $show: expr) => {
+                let mut items: Vec<_> = $iter.collect();
+                let count = if opts.filter.is_empty() {
+                    items.len().to_string()
                 } else {
-                    println!("\n{} {}:", $count, $title);
-                    for elem in $iter {
-                        if binary {
-                            println!("{indent_str}{elem:b}");
-                        } else {
-                            println!("{indent_str}{elem}");
-                        }
+                    format!("{} of {}", items.len(), $total)
+                };
+                let hidden = opts.listing.arrange(&mut items, self.context);
+                writeln!(w, "\n{count} {}:", $title)?;
+                for elem in items.into_iter().map($show) {
+                    let elem = self.context.display(&elem);
+                    if opts.binary {
+                        writeln!(w, "{indent_str}{elem:b}")?;
+                    } else if opts.fractions {
+                        writeln!(w, "{indent_str}{elem:#}")?;
+                    } else {
+                        writeln!(w, "{indent_str}{elem}")?;
                     }
                 }
+                if hidden > 0 {
+                    writeln!(w, "{indent_str}... and {hidden} more")?;
+                }
             };
         }
 
         print_elements!(
             "vertices",
-            self.vertices.iter().map(|v| v.to_point()),
-            self.vertices.len()
+            self.vertices
+                .iter()
+                .filter(|v| opts.filter.keeps_vertex(*v, self.context)),
+            self.vertices.len(),
+            |v| v.to_point_in(self.context)
+        );
+        print_elements!(
+            "edges",
+            self.edges.iter().filter(|e| opts.filter.keeps_edge(e)),
+            self.edges.len()
         );
-        print_elements!("edges", &self.edges, self.edges.len());
+        let primitive_faces: Vec<_> = self
+            .primitive_faces
+            .iter()
+            .map(|f| self.resolve(f))
+            .collect();
+        let satellite_faces: Vec<_> = self
+            .satellite_faces
+            .iter()
+            .map(|f| self.resolve(f))
+            .collect();
         print_elements!(
             "primitive faces",
-            &self.primitive_faces,
-            self.primitive_faces.len()
+            primitive_faces
+                .iter()
+                .filter(|f| opts.filter.keeps_face(f, self.context)),
+            primitive_faces.len()
         );
         print_elements!(
             "satellite faces",
-            &self.satellite_faces,
-            self.satellite_faces.len()
+            satellite_faces
+                .iter()
+                .filter(|f| opts.filter.keeps_face(f, self.context)),
+            satellite_faces.len()
         );
 
-        if self.primitive_faces.len() < crate::MAX_DISPLAY_ITEMS {
-            println!("\nFace sizes:");
-            println!("{}{:?}", indent_str, self.face_sizes());
+        if opts.listing.shows_all(self.primitive_faces.len()) {
+            writeln!(w, "\nFace sizes:")?;
+            writeln!(w, "{}{:?}", indent_str, self.face_sizes())?;
         }
 
-        println!(
+        writeln!(
+            w,
             "\nSmallest face: {}",
             self.face_sizes().iter().min().unwrap_or(&usize::MAX)
-        );
-        println!(
+        )?;
+        writeln!(
+            w,
             "\nLargest face: {}",
             self.face_sizes().iter().max().unwrap_or(&0)
-        );
-        println!("\nGenus is {}", self.genus());
+        )?;
+        writeln!(w, "\nGenus is {}", self.genus())?;
+        if let Some(topology) = self.relative_topology().filter(|t| t.components > 1) {
+            writeln!(
+                w,
+                "\nThe surface has {} components, of total genus {}",
+                topology.components,
+                topology.genus()
+            )?;
+        }
+        Ok(())
     }
 }
@@ -0,0 +1,180 @@
+use crate::dynatomic_cover::DynatomicCover;
+use crate::graphml::escape;
+use crate::json::JsonSummary;
+use crate::lamination::Lamination;
+use crate::marked_cycle_cover::MarkedCycleCover;
+use crate::svg::SvgRenderer;
+use crate::tikz::lamination::LaminationRenderer;
+use crate::types::RatAngle;
+use std::collections::BTreeMap;
+
+/// Print content for evcxr to show in place of the value, with the given MIME type
+fn display(mime: &str, content: &str)
+{
+    println!("EVCXR_BEGIN_CONTENT {mime}\n{content}\nEVCXR_END_CONTENT");
+}
+
+/// HTML table with one row per field, followed by the number of faces of each size
+fn summary_table(fields: &[(&str, String)], face_sizes: impl IntoIterator<Item = usize>) -> String
+{
+    let mut histogram = BTreeMap::new();
+    for size in face_sizes {
+        *histogram.entry(size).or_insert(0) += 1;
+    }
+    let sizes: Vec<String> = histogram
+        .into_iter()
+        .map(|(size, count)| format!("{size}: {count}"))
+        .collect();
+
+    let mut rows: Vec<String> = fields
+        .iter()
+        .map(|(key, value)| format!("<tr><th>{key}</th><td>{}</td></tr>", escape(value)))
+        .collect();
+    rows.push(format!(
+        "<tr><th>face sizes</th><td>{}</td></tr>",
+        sizes.join(", ")
+    ));
+    format!("<table>\n{}\n</table>", rows.join("\n"))
+}
+
+/// SVG markup shown inline by evcxr, such as the output of [`SvgRenderer`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Svg(pub String);
+
+impl Svg
+{
+    pub fn evcxr_display(&self)
+    {
+        display("image/svg+xml", &self.0);
+    }
+}
+
+macro_rules! cover_html {
+    ($cov: expr, $curve: expr) => {{
+        let cov = $cov;
+        let _context = cov.context.enter();
+        let (vertices, edges, faces, genus) = cov.counts();
+        let table = summary_table(
+            &[
+                ("curve", $curve),
+                ("vertices", vertices.to_string()),
+                ("edges", edges.to_string()),
+                ("faces", faces.to_string()),
+                ("genus", genus.to_string()),
+            ],
+            cov.face_sizes(),
+        );
+        let largest = SvgRenderer::new(cov.faces())
+            .with_edges(cov.edges.clone())
+            .draw_largest_face();
+        format!("{table}\n<p>Largest face</p>\n{largest}")
+    }};
+}
+
+impl MarkedCycleCover
+{
+    /// HTML table of the counts, genus, and face sizes, followed by an SVG drawing of the
+    /// largest face
+    #[must_use]
+    pub fn to_html(&self) -> String
+    {
+        cover_html!(self, self.surface_name())
+    }
+
+    /// Show [`Self::to_html`] when the cover is the value of a cell in an evcxr notebook
+    pub fn evcxr_display(&self)
+    {
+        display("text/html", &self.to_html());
+    }
+}
+
+impl DynatomicCover
+{
+    /// HTML table of the counts, genus, and face sizes, followed by an SVG drawing of the
+    /// largest face
+    #[must_use]
+    pub fn to_html(&self) -> String
+    {
+        cover_html!(self, self.surface_name())
+    }
+
+    /// Show [`Self::to_html`] when the cover is the value of a cell in an evcxr notebook
+    pub fn evcxr_display(&self)
+    {
+        display("text/html", &self.to_html());
+    }
+}
+
+impl JsonSummary
+{
+    /// HTML table of the counts, genus, and face sizes
+    #[must_use]
+    pub fn to_html(&self) -> String
+    {
+        summary_table(
+            &[
+                ("curve", self.curve.clone()),
+                ("period", self.period.to_string()),
+                ("critical period", self.crit_period.to_string()),
+                ("vertices", self.vertices.to_string()),
+                ("edges", self.edges.to_string()),
+                ("faces", self.faces.to_string()),
+                ("genus", self.genus.to_string()),
+            ],
+            self.face_sizes.iter().copied(),
+        )
+    }
+
+    pub fn evcxr_display(&self)
+    {
+        display("text/html", &self.to_html());
+    }
+}
+
+/// Point of the unit circle at an angle measured in turns, with the vertical axis pointing down
+/// as in SVG
+fn boundary_point(angle: RatAngle) -> (f64, f64)
+{
+    let turns = *angle.numer() as f64 / *angle.denom() as f64;
+    let theta = core::f64::consts::TAU * turns;
+    (theta.cos(), -theta.sin())
+}
+
+impl Lamination
+{
+    /// Every leaf computed so far, drawn as a chord of the unit disk and colored by its
+    /// period, as SVG
+    #[must_use]
+    pub fn to_svg(&self) -> String
+    {
+        let mut elements = vec![
+            concat!(
+                r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-1.05 -1.05 2.1 2.1" "#,
+                r#"width="400" height="400">"#
+            )
+            .to_owned(),
+            r#"<circle r="1" fill="none" stroke="black" stroke-width="0.01"/>"#.to_owned(),
+        ];
+        let leaves = (1..).map_while(|per| self.arcs(per).map(|arcs| (per, arcs)));
+        for (per, arcs) in leaves {
+            let color = LaminationRenderer::color(per);
+            for &(theta0, theta1) in arcs {
+                // The period 1 leaf joins 0 to 1, a single point on the circle
+                if (theta1 - theta0).is_integer() {
+                    continue;
+                }
+                let ((x0, y0), (x1, y1)) = (boundary_point(theta0), boundary_point(theta1));
+                let line = format!(r#"<line x1="{x0:.4}" y1="{y0:.4}" x2="{x1:.4}" y2="{y1:.4}""#);
+                elements.push(format!(r#"{line} stroke="{color}" stroke-width="0.005"/>"#));
+            }
+        }
+        elements.push("</svg>".to_owned());
+        elements.join("\n")
+    }
+
+    /// Show [`Self::to_svg`] when the lamination is the value of a cell in an evcxr notebook
+    pub fn evcxr_display(&self)
+    {
+        display("image/svg+xml", &self.to_svg());
+    }
+}
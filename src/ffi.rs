@@ -0,0 +1,177 @@
+use crate::combinatorics::{dynatomic, marked_cycle, Combinatorics};
+use crate::dynatomic_cover::DynatomicCover;
+use crate::marked_cycle_cover::MarkedCycleCover;
+use crate::types::Period;
+use alloc::boxed::Box;
+
+/// Largest period accepted from C for the closed-form counts of the marked cycle curve, past
+/// which they overflow 64 bits
+pub const MAX_PERIOD: i64 = 62;
+
+/// Largest period accepted from C for the closed-form counts of the dynatomic curve, past which
+/// its satellite faces overflow 64 bits
+pub const MAX_DYNATOMIC_PERIOD: i64 = 58;
+
+/// Largest period of the covers built from C, whose number of cells grows like `2^period`
+pub const MAX_COVER_PERIOD: i64 = 24;
+
+/// The closed-form quantities of [`Combinatorics`] for one period
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Invariants
+{
+    pub points_of_period_dividing_n: i64,
+    pub periodic_points: i64,
+    pub cycles: i64,
+    pub hyp_components_dividing_n: i64,
+    pub hyperbolic_components: i64,
+    pub satellite_components: i64,
+    pub primitive_components: i64,
+    pub self_conjugate_faces: i64,
+    pub vertices: i64,
+    pub edges: i64,
+    pub faces: i64,
+    pub genus: i64,
+}
+
+impl Invariants
+{
+    fn of(comb: &impl Combinatorics, n: Period) -> Self
+    {
+        Self {
+            points_of_period_dividing_n: comb.points_of_period_dividing_n(n),
+            periodic_points: comb.periodic_points(n),
+            cycles: comb.cycles(n),
+            hyp_components_dividing_n: comb.hyp_components_dividing_n(n),
+            hyperbolic_components: comb.hyperbolic_components(n),
+            satellite_components: comb.satellite_components(n),
+            primitive_components: comb.primitive_components(n),
+            self_conjugate_faces: comb.self_conjugate_faces(n),
+            vertices: comb.vertices(n),
+            edges: comb.edges(n),
+            faces: comb.faces(n),
+            genus: comb.genus(n),
+        }
+    }
+}
+
+/// Cell counts and genus of a built cover
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Counts
+{
+    pub vertices: i64,
+    pub edges: i64,
+    pub faces: i64,
+    pub genus: i64,
+}
+
+/// A cover built from C, only handled through pointers
+pub enum Cover
+{
+    Marked(MarkedCycleCover),
+    Dynatomic(DynatomicCover),
+}
+
+const fn valid(period: Period, crit_period: Period, max_period: Period) -> bool
+{
+    1 <= period && period <= max_period && (crit_period == 1 || crit_period == 2)
+}
+
+/// Fill `out` with the closed-form quantities for the marked cycle curve, or with `dynatomic`
+/// the dynatomic curve, of the given period over `Per_crit_period(0)`. Returns false, leaving
+/// `out` untouched, if the critical period is not 1 or 2, the period is not between 1 and
+/// [`MAX_PERIOD`] ([`MAX_DYNATOMIC_PERIOD`] with `dynatomic`), or `out` is null.
+///
+/// # Safety
+///
+/// `out` must be null or point to writable memory for an `Invariants`.
+#[no_mangle]
+pub unsafe extern "C" fn marked_cycles_invariants(
+    period: i64,
+    crit_period: i64,
+    dynatomic: bool,
+    out: *mut Invariants,
+) -> bool
+{
+    let max_period = if dynatomic {
+        MAX_DYNATOMIC_PERIOD
+    } else {
+        MAX_PERIOD
+    };
+    if out.is_null() || !valid(period, crit_period, max_period) {
+        return false;
+    }
+    let invariants = if dynatomic {
+        Invariants::of(&dynatomic::Comb::new(crit_period), period)
+    } else {
+        Invariants::of(&marked_cycle::Comb::new(crit_period), period)
+    };
+    unsafe { out.write(invariants) };
+    true
+}
+
+/// Build the marked cycle cover, or with `dynatomic` the dynatomic cover, of the given period
+/// over `Per_crit_period(0)`. Returns null if the critical period is not 1 or 2 or the period
+/// is not between 1 and [`MAX_COVER_PERIOD`]. The cover must be released with
+/// [`marked_cycles_cover_free`].
+#[no_mangle]
+pub extern "C" fn marked_cycles_cover_new(
+    period: i64,
+    crit_period: i64,
+    dynatomic: bool,
+) -> *mut Cover
+{
+    if !valid(period, crit_period, MAX_COVER_PERIOD) {
+        return core::ptr::null_mut();
+    }
+    let cover = if dynatomic {
+        Cover::Dynatomic(DynatomicCover::new(period, crit_period))
+    } else {
+        Cover::Marked(MarkedCycleCover::new(period, crit_period))
+    };
+    Box::into_raw(Box::new(cover))
+}
+
+/// Fill `out` with the cell counts and genus of `cover`. Returns false, leaving `out`
+/// untouched, if either pointer is null.
+///
+/// # Safety
+///
+/// `cover` must be null or returned by [`marked_cycles_cover_new`] and not yet freed, and
+/// `out` must be null or point to writable memory for a `Counts`.
+#[no_mangle]
+pub unsafe extern "C" fn marked_cycles_cover_counts(cover: *const Cover, out: *mut Counts) -> bool
+{
+    let Some(cover) = (unsafe { cover.as_ref() }) else {
+        return false;
+    };
+    if out.is_null() {
+        return false;
+    }
+    let (vertices, edges, faces, genus) = match cover {
+        Cover::Marked(cov) => cov.counts(),
+        Cover::Dynatomic(cov) => cov.counts(),
+    };
+    let counts = Counts {
+        vertices: vertices as i64,
+        edges: edges as i64,
+        faces: faces as i64,
+        genus,
+    };
+    unsafe { out.write(counts) };
+    true
+}
+
+/// Release a cover built by [`marked_cycles_cover_new`]. Null is ignored.
+///
+/// # Safety
+///
+/// `cover` must be null or returned by [`marked_cycles_cover_new`] and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn marked_cycles_cover_free(cover: *mut Cover)
+{
+    if !cover.is_null() {
+        drop(unsafe { Box::from_raw(cover) });
+    }
+}
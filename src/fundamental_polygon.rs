@@ -0,0 +1,139 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// One side of a fundamental polygon: a copy of an edge of the cover, traversed from `start`
+/// to `end`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Side<V>
+{
+    pub edge_id: usize,
+    pub start: V,
+    pub end: V,
+}
+
+/// A polygon obtained by gluing the faces of a cover along a spanning tree of the dual graph.
+/// Every edge not used in the gluing occurs on exactly two sides, and identifying those sides
+/// recovers the cover.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FundamentalPolygon<V>
+{
+    pub sides: Vec<Side<V>>,
+}
+
+impl<V> FundamentalPolygon<V>
+where
+    V: Copy + Eq,
+{
+    /// Glue faces, given as their boundary vertices and edge ids, into a single polygon.
+    /// `vertices[i]` and `edge_ids[i]` describe the side from `vertices[i]` to `vertices[i + 1]`.
+    /// Faces that cannot be reached from the first face through shared edges are ignored.
+    pub fn new<I>(faces: I) -> Self
+    where
+        I: IntoIterator<Item = (Vec<V>, Vec<usize>)>,
+    {
+        let faces: Vec<Vec<Side<V>>> = faces
+            .into_iter()
+            .map(|(vertices, edge_ids)| {
+                let n = vertices.len();
+                edge_ids
+                    .iter()
+                    .enumerate()
+                    .map(|(i, &edge_id)| Side {
+                        edge_id,
+                        start: vertices[i],
+                        end: vertices[(i + 1) % n],
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Faces on either side of each edge
+        let mut incidence: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (k, face) in faces.iter().enumerate() {
+            for side in face {
+                incidence.entry(side.edge_id).or_default().push(k);
+            }
+        }
+
+        let Some(first) = faces.first() else {
+            return Self { sides: Vec::new() };
+        };
+
+        let mut sides = first.clone();
+        let mut glued: HashSet<usize> = HashSet::from([0]);
+        let mut queue: VecDeque<usize> = first.iter().map(|s| s.edge_id).collect();
+
+        while let Some(edge_id) = queue.pop_front() {
+            let Some(other) = incidence[&edge_id]
+                .iter()
+                .copied()
+                .find(|k| !glued.contains(k))
+            else {
+                continue;
+            };
+            let Some(pos) = sides.iter().position(|s| s.edge_id == edge_id) else {
+                continue;
+            };
+
+            let face = &faces[other];
+            let j = face
+                .iter()
+                .position(|s| s.edge_id == edge_id)
+                .expect("Face is incident to edge");
+            let n = face.len();
+
+            // Walk around the new face from one endpoint of the shared side to the other
+            let replacement: Vec<Side<V>> = if face[j].start == sides[pos].end {
+                (1..n).map(|t| face[(j + t) % n]).collect()
+            } else {
+                (1..n)
+                    .rev()
+                    .map(|t| {
+                        let s = face[(j + t) % n];
+                        Side {
+                            edge_id: s.edge_id,
+                            start: s.end,
+                            end: s.start,
+                        }
+                    })
+                    .collect()
+            };
+
+            queue.extend(replacement.iter().map(|s| s.edge_id));
+            sides.splice(pos..=pos, replacement);
+            glued.insert(other);
+        }
+
+        Self { sides }
+    }
+
+    #[must_use]
+    pub fn num_sides(&self) -> usize
+    {
+        self.sides.len()
+    }
+
+    /// Pairs of side indices that are identified with each other
+    #[must_use]
+    pub fn pairing(&self) -> Vec<(usize, usize)>
+    {
+        let mut first_seen: HashMap<usize, usize> = HashMap::new();
+        let mut pairs = Vec::new();
+        for (i, side) in self.sides.iter().enumerate() {
+            if let Some(j) = first_seen.remove(&side.edge_id) {
+                pairs.push((j, i));
+            } else {
+                first_seen.insert(side.edge_id, i);
+            }
+        }
+        pairs
+    }
+
+    /// Whether the paired sides `i` and `j` are glued with opposite orientations, as they are
+    /// on an orientable surface
+    #[must_use]
+    pub fn is_orientation_reversing(&self, i: usize, j: usize) -> bool
+    {
+        let (a, b) = (self.sides[i], self.sides[j]);
+        a.start == b.end && a.end == b.start
+    }
+}
@@ -0,0 +1,106 @@
+use crate::common::cells::{CellKey, Edge};
+use crate::dot::DotFace;
+use crate::global_state::{Context, DisplayIn};
+use crate::graphml::{
+    boundary_vertices, edge_attributes, escape, face_attributes, vertex_attributes, ATTRIBUTES,
+};
+use std::{collections::HashMap, hash::Hash};
+
+/// Declare the attributes carried by one class of elements, `node` or `edge`
+fn attribute_class(class: &str) -> Vec<String>
+{
+    let mut lines = vec![format!("    <attributes class=\"{class}\">")];
+    for (key, domain, ty) in ATTRIBUTES {
+        if domain == class || domain == "all" {
+            let ty = if ty == "int" { "integer" } else { ty };
+            lines.push(format!(
+                "      <attribute id=\"{key}\" title=\"{key}\" type=\"{ty}\"/>"
+            ));
+        }
+    }
+    lines.push("    </attributes>".to_owned());
+    lines
+}
+
+/// The label of an element, followed by its other attributes as `attvalues`
+fn attvalues(attributes: &[(&str, String)]) -> String
+{
+    let label = attributes
+        .iter()
+        .find(|(key, _)| *key == "label")
+        .map(|(_, label)| format!(" label=\"{}\"", escape(label)))
+        .unwrap_or_default();
+    let values: String = attributes
+        .iter()
+        .map(|(key, value)| format!("<attvalue for=\"{key}\" value=\"{}\"/>", escape(value)))
+        .collect();
+    format!("{label}><attvalues>{values}</attvalues>")
+}
+
+/// Write a GEXF 1.3 document, as read by Gephi, with the same nodes, edges, and attributes as
+/// [`crate::graphml::to_graphml`]. Vertices are read in `context`.
+pub fn to_gexf<V>(
+    name: &str,
+    context: Context,
+    vertices: &[V],
+    edges: &[Edge<V>],
+    faces: Option<&[DotFace<V>]>,
+) -> String
+where
+    V: Copy + Eq + Hash + DisplayIn + CellKey,
+{
+    let indices: HashMap<V, usize> = vertices.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+
+    let mut lines = vec![
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_owned(),
+        r#"<gexf xmlns="http://gexf.net/1.3" version="1.3">"#.to_owned(),
+        format!("  <meta><description>{}</description></meta>", escape(name)),
+        r#"  <graph mode="static" defaultedgetype="undirected">"#.to_owned(),
+    ];
+    lines.extend(attribute_class("node"));
+    lines.extend(attribute_class("edge"));
+
+    lines.push("    <nodes>".to_owned());
+    for (i, v) in vertices.iter().enumerate() {
+        lines.push(format!(
+            "      <node id=\"v{i}\"{}</node>",
+            attvalues(&vertex_attributes(v, context))
+        ));
+    }
+    for (k, face) in faces.unwrap_or_default().iter().enumerate() {
+        lines.push(format!(
+            "      <node id=\"f{k}\"{}</node>",
+            attvalues(&face_attributes(face))
+        ));
+    }
+    lines.push("    </nodes>".to_owned());
+
+    lines.push("    <edges>".to_owned());
+    for (k, e) in edges.iter().enumerate() {
+        let (Some(i), Some(j)) = (indices.get(&e.start), indices.get(&e.end)) else {
+            continue;
+        };
+        lines.push(format!(
+            "      <edge id=\"e{k}\" source=\"v{i}\" target=\"v{j}\"{}</edge>",
+            attvalues(&edge_attributes(e))
+        ));
+    }
+    // GEXF requires every edge to have an id
+    let mut boundary_id = 0;
+    for (k, face) in faces.unwrap_or_default().iter().enumerate() {
+        for v in boundary_vertices(&face.1) {
+            if let Some(i) = indices.get(&v) {
+                lines.push(format!(
+                    "      <edge id=\"b{boundary_id}\" source=\"f{k}\" target=\"v{i}\"{}</edge>",
+                    attvalues(&[("kind", "boundary".to_owned())])
+                ));
+                boundary_id += 1;
+            }
+        }
+    }
+    lines.push("    </edges>".to_owned());
+
+    lines.push("  </graph>".to_owned());
+    lines.push("</gexf>".to_owned());
+    lines.join("\n")
+}
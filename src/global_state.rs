@@ -1,13 +1,223 @@
-use crate::types::{IntAngle, Period};
+use crate::types::{IntAngle, MarkedPeriod, Period};
+use alloc::string::String;
+#[cfg(not(feature = "std"))]
+use core::sync::atomic::{AtomicU8, Ordering};
+#[cfg(feature = "std")]
 use std::cell::Cell;
 
+#[cfg(feature = "std")]
 thread_local! {
-    pub static PERIOD: Cell<Period> = Cell::new(3);
-    pub static MAX_ANGLE: Cell<IntAngle> = Cell::new(IntAngle(7));
+    pub static PERIOD: Cell<Period> = const { Cell::new(3) };
+    pub static MAX_ANGLE: Cell<IntAngle> = const { Cell::new(IntAngle(7)) };
 }
 
+/// Without `std` there are no thread-locals, so the ambient period is shared by the whole
+/// program. It is kept in a byte, which every target can update atomically.
+#[cfg(not(feature = "std"))]
+pub static PERIOD: AmbientPeriod = AmbientPeriod(AtomicU8::new(3));
+
+/// Without `std`, the ambient denominator is derived from [`PERIOD`]
+#[cfg(not(feature = "std"))]
+pub static MAX_ANGLE: AmbientMaxAngle = AmbientMaxAngle;
+
+/// Ambient period without `std`, read and written like the thread-local `Cell` it replaces
+#[cfg(not(feature = "std"))]
+#[derive(Debug)]
+pub struct AmbientPeriod(AtomicU8);
+
+#[cfg(not(feature = "std"))]
+impl AmbientPeriod
+{
+    pub fn get(&self) -> Period
+    {
+        Period::from(self.0.load(Ordering::Relaxed))
+    }
+
+    pub fn set(&self, period: Period)
+    {
+        let period = u8::try_from(period).expect("period does not fit in a byte");
+        self.0.store(period, Ordering::Relaxed);
+    }
+}
+
+/// Ambient denominator `2^period - 1` without `std`
+#[cfg(not(feature = "std"))]
+#[derive(Debug)]
+pub struct AmbientMaxAngle;
+
+#[cfg(not(feature = "std"))]
+impl AmbientMaxAngle
+{
+    pub fn get(&self) -> IntAngle
+    {
+        Context::new(PERIOD.get()).max_angle
+    }
+}
+
+/// The period of the cycles under consideration, together with the denominator `2^period - 1`
+/// of their angles. Builders, covers, and edges carry their own context, and everything they
+/// compute from it takes it explicitly; the thread-local state is only read by the thin
+/// wrappers kept for compatibility, such as the `Display` impls of vertices and faces.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "std",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(try_from = "Period", into = "Period")
+)]
+pub struct Context
+{
+    pub period: Period,
+    pub max_angle: IntAngle,
+}
+
+impl Default for Context
+{
+    fn default() -> Self
+    {
+        Self::new(3)
+    }
+}
+
+impl Context
+{
+    #[must_use]
+    pub const fn new(period: Period) -> Self
+    {
+        Self {
+            period,
+            max_angle: IntAngle((1 << period) - 1),
+        }
+    }
+
+    /// The ambient context of the current thread
+    #[must_use]
+    pub fn current() -> Self
+    {
+        Self {
+            period: PERIOD.get(),
+            max_angle: MAX_ANGLE.get(),
+        }
+    }
+
+    /// Make this the ambient context of the current thread
+    pub fn install(self)
+    {
+        PERIOD.set(self.period);
+        #[cfg(feature = "std")]
+        MAX_ANGLE.set(self.max_angle);
+    }
+
+    /// Make this the ambient context until the returned guard is dropped, at which point the
+    /// previous context is restored
+    #[must_use = "the context is restored as soon as the guard is dropped"]
+    pub fn enter(self) -> ContextGuard
+    {
+        let previous = Self::current();
+        self.install();
+        ContextGuard { previous }
+    }
+
+    /// Format `value`, such as a cell of a cover, in this context rather than the ambient one
+    #[must_use]
+    pub const fn display<T: DisplayIn + ?Sized>(self, value: &T) -> InContext<'_, T>
+    {
+        InContext {
+            context: self,
+            value,
+        }
+    }
+}
+
+/// Values, such as the cells of a cover, whose text depends on the period they are read in.
+/// [`Context::display`] formats them in a given context; their own `Display` and `Binary` impls,
+/// where they have them, format them in the ambient one.
+pub trait DisplayIn
+{
+    /// Format the value as by `Display`, in `context`
+    fn fmt_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result;
+
+    /// Format the value as by `Binary`, in `context`
+    fn fmt_binary_in(
+        &self,
+        context: Context,
+        f: &mut core::fmt::Formatter<'_>,
+    ) -> core::fmt::Result;
+}
+
+impl<T: DisplayIn + ?Sized> DisplayIn for &T
+{
+    fn fmt_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        (**self).fmt_in(context, f)
+    }
+
+    fn fmt_binary_in(&self, context: Context, f: &mut core::fmt::Formatter<'_>)
+        -> core::fmt::Result
+    {
+        (**self).fmt_binary_in(context, f)
+    }
+}
+
+/// A value formatted in a given context by [`Context::display`]
+#[derive(Clone, Copy, Debug)]
+pub struct InContext<'a, T: ?Sized>
+{
+    context: Context,
+    value: &'a T,
+}
+
+impl<T: DisplayIn + ?Sized> core::fmt::Display for InContext<'_, T>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        self.value.fmt_in(self.context, f)
+    }
+}
+
+impl<T: DisplayIn + ?Sized> core::fmt::Binary for InContext<'_, T>
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        self.value.fmt_binary_in(self.context, f)
+    }
+}
+
+/// The context of a period between 1 and 62
+impl TryFrom<Period> for Context
+{
+    type Error = String;
+
+    fn try_from(period: Period) -> Result<Self, String>
+    {
+        MarkedPeriod::new(period).map(|period| Self::new(period.get()))
+    }
+}
+
+impl From<Context> for Period
+{
+    fn from(context: Context) -> Self
+    {
+        context.period
+    }
+}
+
+/// Restores the previous ambient context when dropped; see [`Context::enter`]
+#[derive(Debug)]
+pub struct ContextGuard
+{
+    previous: Context,
+}
+
+impl Drop for ContextGuard
+{
+    fn drop(&mut self)
+    {
+        self.previous.install();
+    }
+}
+
+/// Set the ambient period of the current thread
 pub fn set_period(period: Period)
 {
-    PERIOD.set(period);
-    MAX_ANGLE.set(IntAngle((1 << period) - 1));
+    Context::new(period).install();
 }
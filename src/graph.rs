@@ -0,0 +1,43 @@
+use crate::abstract_cycles::ShiftedCycle;
+use crate::common::cells::{Edge, Wake};
+use crate::common::FxHashMap;
+use crate::dynatomic_cover::DynatomicCover;
+use crate::marked_cycle_cover::{MCVertex, MarkedCycleCover};
+use core::hash::Hash;
+use petgraph::graph::{Graph, NodeIndex};
+use petgraph::EdgeType;
+
+/// The 1-skeleton as a `petgraph` graph, with the vertices added in order so that node `i` is
+/// `vertices[i]`, and one edge weighted by its wake per cell edge, in order. A directed graph
+/// points each edge from its start to its end.
+fn to_graph<V, Ty>(vertices: &[V], edges: &[Edge<V>]) -> Graph<V, Wake, Ty>
+where
+    V: Copy + Eq + Hash,
+    Ty: EdgeType,
+{
+    let mut graph = Graph::with_capacity(vertices.len(), edges.len());
+    let indices: FxHashMap<V, NodeIndex> =
+        vertices.iter().map(|&v| (v, graph.add_node(v))).collect();
+    for e in edges {
+        if let (Some(&i), Some(&j)) = (indices.get(&e.start), indices.get(&e.end)) {
+            graph.add_edge(i, j, e.wake.clone());
+        }
+    }
+    graph
+}
+
+impl<Ty: EdgeType> From<&MarkedCycleCover> for Graph<MCVertex, Wake, Ty>
+{
+    fn from(cover: &MarkedCycleCover) -> Self
+    {
+        to_graph(&cover.vertices, &cover.edges)
+    }
+}
+
+impl<Ty: EdgeType> From<&DynatomicCover> for Graph<ShiftedCycle, Wake, Ty>
+{
+    fn from(cover: &DynatomicCover) -> Self
+    {
+        to_graph(&cover.vertices, &cover.edges)
+    }
+}
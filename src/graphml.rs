@@ -0,0 +1,161 @@
+use crate::abstract_cycles::AbstractPoint;
+use crate::common::cells::{CellKey, Edge};
+use crate::dot::DotFace;
+use crate::global_state::{Context, DisplayIn};
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+/// Cell attributes written by [`to_graphml`] and [`crate::gexf::to_gexf`]: the name of each,
+/// the kind of cell carrying it (`node` for vertices and faces), and its GraphML type
+pub(crate) const ATTRIBUTES: [(&str, &str, &str); 9] = [
+    ("label", "node", "string"),
+    ("kind", "all", "string"),
+    ("angle", "node", "long"),
+    ("size", "node", "int"),
+    ("wake", "edge", "string"),
+    ("angle0", "edge", "long"),
+    ("angle1", "edge", "long"),
+    ("real", "edge", "boolean"),
+    ("kneading_sequence", "all", "string"),
+];
+
+pub(crate) fn escape(text: &str) -> String
+{
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Attributes of a vertex: the angle it marks and the kneading sequence of that angle, both read
+/// in `context`
+pub(crate) fn vertex_attributes<V>(v: &V, context: Context) -> Vec<(&'static str, String)>
+where
+    V: CellKey + DisplayIn,
+{
+    let angle = v.angle(context);
+    vec![
+        ("label", context.display(v).to_string()),
+        ("kind", "vertex".to_owned()),
+        ("angle", angle.to_string()),
+        (
+            "kneading_sequence",
+            AbstractPoint::new(angle)
+                .kneading_sequence_in(context)
+                .to_string(),
+        ),
+    ]
+}
+
+/// Attributes of an edge: its wake, the angles bounding it, whether it is real, and its
+/// kneading sequence
+pub(crate) fn edge_attributes<V>(e: &Edge<V>) -> Vec<(&'static str, String)>
+{
+    vec![
+        ("kind", "edge".to_owned()),
+        ("wake", e.wake.to_string()),
+        ("angle0", e.wake.angle0.to_string()),
+        ("angle1", e.wake.angle1.to_string()),
+        ("real", e.is_real().to_string()),
+        ("kneading_sequence", e.kneading_sequence.to_string()),
+    ]
+}
+
+/// Attributes of a face: its label and the number of vertices on its boundary
+pub(crate) fn face_attributes<V>((label, boundary): &DotFace<V>) -> Vec<(&'static str, String)>
+{
+    vec![
+        ("label", label.clone()),
+        ("kind", "face".to_owned()),
+        ("size", boundary.len().to_string()),
+    ]
+}
+
+/// Distinct vertices on the boundary of a face, in order of first appearance
+pub(crate) fn boundary_vertices<V: Copy + Eq + Hash>(boundary: &[V]) -> Vec<V>
+{
+    let mut seen = HashSet::new();
+    boundary
+        .iter()
+        .copied()
+        .filter(|v| seen.insert(*v))
+        .collect()
+}
+
+fn data(attributes: &[(&str, String)]) -> String
+{
+    attributes
+        .iter()
+        .map(|(key, value)| format!("<data key=\"{key}\">{}</data>", escape(value)))
+        .collect()
+}
+
+/// Write a GraphML document with one node per vertex, carrying the angle it marks and its
+/// kneading sequence, and one edge per cell edge, carrying its wake, the angles bounding it,
+/// its kneading sequence, and whether it is real. Each face, if given, becomes a node of kind
+/// `face` joined to the vertices on its boundary by edges of kind `boundary`. Vertices are read
+/// in `context`.
+pub fn to_graphml<V>(
+    name: &str,
+    context: Context,
+    vertices: &[V],
+    edges: &[Edge<V>],
+    faces: Option<&[DotFace<V>]>,
+) -> String
+where
+    V: Copy + Eq + Hash + DisplayIn + CellKey,
+{
+    let indices: HashMap<V, usize> = vertices.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+
+    let mut lines = vec![
+        r#"<?xml version="1.0" encoding="UTF-8"?>"#.to_owned(),
+        r#"<graphml xmlns="http://graphml.graphdrawing.org/xmlns">"#.to_owned(),
+    ];
+    for (key, domain, ty) in ATTRIBUTES {
+        lines.push(format!(
+            "  <key id=\"{key}\" for=\"{domain}\" attr.name=\"{key}\" attr.type=\"{ty}\"/>"
+        ));
+    }
+    lines.push(format!(
+        "  <graph id=\"{}\" edgedefault=\"undirected\">",
+        escape(name)
+    ));
+
+    for (i, v) in vertices.iter().enumerate() {
+        lines.push(format!(
+            "    <node id=\"v{i}\">{}</node>",
+            data(&vertex_attributes(v, context))
+        ));
+    }
+
+    for (k, e) in edges.iter().enumerate() {
+        let (Some(i), Some(j)) = (indices.get(&e.start), indices.get(&e.end)) else {
+            continue;
+        };
+        lines.push(format!(
+            "    <edge id=\"e{k}\" source=\"v{i}\" target=\"v{j}\">{}</edge>",
+            data(&edge_attributes(e))
+        ));
+    }
+
+    for (k, face) in faces.unwrap_or_default().iter().enumerate() {
+        lines.push(format!(
+            "    <node id=\"f{k}\">{}</node>",
+            data(&face_attributes(face))
+        ));
+        for v in boundary_vertices(&face.1) {
+            if let Some(i) = indices.get(&v) {
+                lines.push(format!(
+                    "    <edge source=\"f{k}\" target=\"v{i}\">{}</edge>",
+                    data(&[("kind", "boundary".to_owned())])
+                ));
+            }
+        }
+    }
+
+    lines.push("  </graph>".to_owned());
+    lines.push("</graphml>".to_owned());
+    lines.join("\n")
+}
@@ -0,0 +1,374 @@
+use crate::common::cells::{Edge, VertexData};
+use crate::global_state::{Context, DisplayIn};
+use crate::types::{MarkedPeriod, Period};
+use serde::{Deserialize, Serialize};
+use std::io::{self, Write};
+
+/// Version of the layout written by `to_json`, given as `"schema"` at the top of the document.
+/// It is bumped whenever a field changes meaning or goes away; new fields may appear without
+/// a bump.
+///
+/// Version 1 lists the curve (`"MC"` or `"Dyn"`), the period, the critical period, the cell
+/// counts, the genus, and the face sizes. With the cells, `"cells"` holds:
+///
+/// * `"vertices"`: the label of each vertex, `"(a)"` for the marked cycle whose least angle is
+///   `a/(2^period - 1)`, or `"[a; s]"` for that cycle with its `s`th point marked;
+/// * `"edges"`: the labels of the endpoints, the wake as a pair of numerators, whether the
+///   edge is real, and its kneading sequence;
+/// * `"faces"`: the label, the boundary vertices, the degree, and `"edges"`, the index in the
+///   edge list of the edge after each boundary vertex. Marked cycle faces are labeled `"<a>"`
+///   and also give `"vertex_data"`, how the boundary turns at each vertex. Dynatomic faces are
+///   labeled `"[a]"` or `"[a; s]"` and give their `"kind"`, `"primitive"` or `"satellite"`.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Face data for JSON output: the face label, its boundary vertices, and its degree
+pub type JsonFace<V> = (String, Vec<V>, Period);
+
+/// Kind of a dynatomic face, after the cycle labeling it
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FaceKind
+{
+    Primitive,
+    Satellite,
+}
+
+/// Edge as listed in the cells of `to_json`, with its endpoints as labels
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonEdge
+{
+    pub start: String,
+    pub end: String,
+    pub wake: [Period; 2],
+    pub real: bool,
+    pub kneading_sequence: String,
+}
+
+impl JsonEdge
+{
+    /// The edge with its endpoints and kneading sequence written out, in the edge's context
+    pub fn new<V: DisplayIn>(e: &Edge<V>) -> Self
+    {
+        let context = e.wake.context;
+        Self {
+            start: context.display(&e.start).to_string(),
+            end: context.display(&e.end).to_string(),
+            wake: [e.wake.angle0.0, e.wake.angle1.0],
+            real: e.is_real(),
+            kneading_sequence: e.kneading_sequence.to_string(),
+        }
+    }
+}
+
+/// Face as listed in the cells of `to_json`, with its label and boundary vertices as labels
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonCellFace
+{
+    pub label: String,
+    pub vertices: Vec<String>,
+    pub degree: Period,
+    /// Index in the edge list of the edge after each boundary vertex
+    #[serde(rename = "edges")]
+    pub edge_ids: Vec<usize>,
+    /// How the boundary turns at each vertex, given for marked cycle faces
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub vertex_data: Option<Vec<VertexData>>,
+    /// Given for dynatomic faces
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub kind: Option<FaceKind>,
+}
+
+impl JsonCellFace
+{
+    /// The face with the given boundary edges, and neither vertex data nor a kind. The
+    /// boundary vertices are labeled in `context`.
+    pub fn new<V: DisplayIn>(
+        (label, boundary, degree): JsonFace<V>,
+        edge_ids: Vec<usize>,
+        context: Context,
+    ) -> Self
+    {
+        Self {
+            label,
+            vertices: labels(context, &boundary),
+            degree,
+            edge_ids,
+            vertex_data: None,
+            kind: None,
+        }
+    }
+}
+
+/// The cells of a cover, as listed by `to_json`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonCells
+{
+    pub vertices: Vec<String>,
+    pub edges: Vec<JsonEdge>,
+    pub faces: Vec<JsonCellFace>,
+}
+
+/// A document written by `to_json`, laid out as in [`SCHEMA_VERSION`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonDocument
+{
+    pub schema: u32,
+    pub curve: String,
+    pub period: Period,
+    pub crit_period: Period,
+    pub vertices: usize,
+    pub edges: usize,
+    pub faces: usize,
+    pub genus: i64,
+    pub face_sizes: Vec<usize>,
+    pub smallest_face: Option<usize>,
+    pub largest_face: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cells: Option<JsonCells>,
+}
+
+/// Cell counts and genus of a cover, as reported by `to_json`
+#[derive(Clone, Copy, Debug)]
+pub struct JsonHeader<'a>
+{
+    pub curve: &'a str,
+    /// Context of the cover, in which its cells are labeled
+    pub context: Context,
+    pub crit_period: Period,
+    pub genus: i64,
+}
+
+fn labels<V: DisplayIn>(context: Context, items: &[V]) -> Vec<String>
+{
+    items
+        .iter()
+        .map(|v| context.display(v).to_string())
+        .collect()
+}
+
+/// Write a JSON object summarizing a cover: its cell counts, face sizes, and genus, laid out as
+/// in [`SCHEMA_VERSION`]. If `with_cells` is set, the vertices, edges, and faces themselves are
+/// listed under `"cells"`.
+pub fn to_json<V>(
+    header: JsonHeader,
+    vertices: &[V],
+    edges: &[Edge<V>],
+    faces: Vec<JsonCellFace>,
+    with_cells: bool,
+) -> String
+where
+    V: DisplayIn,
+{
+    let face_sizes: Vec<usize> = faces.iter().map(|f| f.vertices.len()).collect();
+    let doc = JsonDocument {
+        schema: SCHEMA_VERSION,
+        curve: header.curve.to_owned(),
+        period: header.context.period,
+        crit_period: header.crit_period,
+        vertices: vertices.len(),
+        edges: edges.len(),
+        faces: faces.len(),
+        genus: header.genus,
+        smallest_face: face_sizes.iter().min().copied(),
+        largest_face: face_sizes.iter().max().copied(),
+        face_sizes,
+        cells: with_cells.then(|| JsonCells {
+            vertices: labels(header.context, vertices),
+            edges: edges.iter().map(JsonEdge::new).collect(),
+            faces,
+        }),
+    };
+    serde_json::to_string_pretty(&doc).expect("JSON documents always serialize")
+}
+
+/// One line of `write_ndjson`: a cell's fields, tagged with its curve, period, and kind
+#[derive(Serialize)]
+struct NdjsonCell<'a, T>
+{
+    curve: &'a str,
+    period: Period,
+    cell: &'static str,
+    #[serde(flatten)]
+    fields: T,
+}
+
+#[derive(Serialize)]
+struct NdjsonVertex
+{
+    vertex: String,
+}
+
+#[derive(Serialize)]
+struct NdjsonFace
+{
+    label: String,
+    vertices: Vec<String>,
+    degree: Period,
+}
+
+/// Stream the cells of a cover as newline-delimited JSON, one object per vertex, edge, or face,
+/// each tagged with the curve, the period, and the kind of cell. Nothing is buffered beyond a
+/// single line, so faces may be produced lazily.
+pub fn write_ndjson<'a, W, V>(
+    w: &mut W,
+    header: JsonHeader,
+    vertices: impl IntoIterator<Item = &'a V>,
+    edges: impl IntoIterator<Item = &'a Edge<V>>,
+    faces: impl IntoIterator<Item = JsonFace<V>>,
+) -> io::Result<()>
+where
+    W: Write,
+    V: DisplayIn + 'a,
+{
+    for v in vertices {
+        let vertex = header.context.display(v).to_string();
+        write_cell(w, header, "vertex", NdjsonVertex { vertex })?;
+    }
+    for e in edges {
+        write_cell(w, header, "edge", JsonEdge::new(e))?;
+    }
+    for (label, boundary, degree) in faces {
+        let vertices = labels(header.context, &boundary);
+        write_cell(
+            w,
+            header,
+            "face",
+            NdjsonFace {
+                label,
+                vertices,
+                degree,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+fn write_cell<W: Write, T: Serialize>(
+    w: &mut W,
+    header: JsonHeader,
+    cell: &'static str,
+    fields: T,
+) -> io::Result<()>
+{
+    let line = NdjsonCell {
+        curve: header.curve,
+        period: header.context.period,
+        cell,
+        fields,
+    };
+    serde_json::to_writer(&mut *w, &line)?;
+    writeln!(w)
+}
+
+/// The counts, genus, and face sizes of a cover, as read back from the output of `to_json`
+#[cfg(feature = "parse")]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize)]
+pub struct JsonSummary
+{
+    pub curve: String,
+    pub period: Period,
+    pub crit_period: Period,
+    pub vertices: usize,
+    pub edges: usize,
+    pub faces: usize,
+    pub genus: i64,
+    pub face_sizes: Vec<usize>,
+}
+
+#[cfg(feature = "parse")]
+impl JsonSummary
+{
+    /// Read a summary written by `to_json`, with or without its cells
+    pub fn parse(text: &str) -> Result<Self, String>
+    {
+        serde_json::from_str(text).map_err(|e| e.to_string())
+    }
+}
+
+/// A cover as read back from the output of `to_json` with its cells
+pub(crate) struct CellsIn
+{
+    pub period: Period,
+    pub crit_period: Period,
+    pub vertices: Vec<String>,
+    pub edges: Vec<JsonEdge>,
+    pub faces: Vec<JsonCellFace>,
+}
+
+/// The curve, `"MC"` or `"Dyn"`, of a cover written by `to_json`
+pub(crate) fn read_curve(text: &str) -> Result<String, String>
+{
+    #[derive(Deserialize)]
+    struct Curve
+    {
+        curve: String,
+    }
+    let doc: Curve = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    Ok(doc.curve)
+}
+
+/// Read the cells of a `curve` cover written by `to_json(true)`, checking the schema version,
+/// the cell counts, and that every edge id is in range
+pub(crate) fn read_cells(text: &str, curve: &str) -> Result<CellsIn, String>
+{
+    // Check the version first, since other versions may not fit the document's fields
+    #[derive(Deserialize)]
+    struct Versioned
+    {
+        schema: u32,
+    }
+    let Versioned { schema } = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    if schema != SCHEMA_VERSION {
+        return Err(format!(
+            "unsupported schema version {schema}; this version reads {SCHEMA_VERSION}"
+        ));
+    }
+
+    let doc: JsonDocument = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    if doc.curve != curve {
+        return Err(format!("expected a {curve} curve, found {}", doc.curve));
+    }
+    MarkedPeriod::new(doc.period)?;
+    let cells = doc
+        .cells
+        .ok_or_else(|| "the document does not list its cells".to_owned())?;
+
+    for (key, count, len) in [
+        ("vertices", doc.vertices, cells.vertices.len()),
+        ("edges", doc.edges, cells.edges.len()),
+        ("faces", doc.faces, cells.faces.len()),
+    ] {
+        if count != len {
+            return Err(format!("expected {count} {key}, found {len}"));
+        }
+    }
+    let edge_ids = cells.faces.iter().flat_map(|f| &f.edge_ids);
+    if edge_ids.into_iter().any(|&id| id >= cells.edges.len()) {
+        return Err("a face refers to an edge that does not exist".to_owned());
+    }
+
+    Ok(CellsIn {
+        period: doc.period,
+        crit_period: doc.crit_period,
+        vertices: cells.vertices,
+        edges: cells.edges,
+        faces: cells.faces,
+    })
+}
+
+/// The numbers in a label such as `"(5)"` or `"[3; 1]"`, written between `open` and `close`
+/// and separated by semicolons
+pub(crate) fn label_numbers(label: &str, open: char, close: char) -> Result<Vec<Period>, String>
+{
+    label
+        .strip_prefix(open)
+        .and_then(|rest| rest.strip_suffix(close))
+        .ok_or_else(|| format!("malformed label `{label}`"))?
+        .split(';')
+        .map(|n| {
+            n.trim()
+                .parse()
+                .map_err(|_| format!("malformed label `{label}`"))
+        })
+        .collect()
+}
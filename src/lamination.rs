@@ -1,7 +1,9 @@
-use std::cmp::Ordering;
+use alloc::{sync::Arc, vec, vec::Vec};
+use core::cmp::Ordering;
 
+use crate::common::Phase;
+use crate::progress::{Progress, ProgressSink};
 use crate::types::{Period, RatAngle};
-use itertools::Itertools;
 
 /// Rational angle with a cached floating point value for faster comparisons in sorting
 #[derive(Clone, Copy, Debug, PartialEq, Default)]
@@ -20,10 +22,10 @@ impl CachedRatAngle
         Self { angle, float_val }
     }
 }
-impl std::cmp::PartialOrd for CachedRatAngle
+impl core::cmp::PartialOrd for CachedRatAngle
 {
     #[inline]
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering>
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering>
     {
         self.float_val.partial_cmp(&other.float_val)
     }
@@ -92,15 +94,58 @@ impl From<Endpoint> for (RatAngle, RatAngle)
     }
 }
 
-impl std::cmp::PartialOrd for Endpoint
+impl core::cmp::PartialOrd for Endpoint
 {
     #[inline]
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering>
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering>
     {
         self.angle.partial_cmp(&other.angle)
     }
 }
 
+/// Endpoints of the leaves found so far, in increasing order of angle. Extending the lamination
+/// only compares angles and asks which end of its leaf each endpoint is, so the two are kept
+/// in separate columns rather than as whole [`Endpoint`]s, which take several times the memory.
+#[derive(Clone, Debug, PartialEq)]
+struct Endpoints
+{
+    /// Angle of each endpoint, as a float for fast comparisons
+    angles: Vec<f64>,
+    /// Whether each endpoint is the left end of its leaf
+    left: Vec<bool>,
+}
+
+impl Endpoints
+{
+    /// Bytes taken by each endpoint
+    const BYTES: usize = core::mem::size_of::<f64>() + core::mem::size_of::<bool>();
+
+    /// Merge in `new`, which must be sorted by angle. The existing endpoints are moved back
+    /// from the end of their columns, so that no second copy of the columns is made.
+    fn merge(&mut self, new: &[Endpoint])
+    {
+        let (old_len, len) = (self.angles.len(), self.angles.len() + new.len());
+        self.angles.resize(len, 0.0);
+        self.left.resize(len, false);
+
+        let (mut i, mut j) = (old_len, new.len());
+        for dest in (0..len).rev() {
+            if j == 0 {
+                break;
+            }
+            if i > 0 && self.angles[i - 1] > new[j - 1].angle.float_val {
+                i -= 1;
+                self.angles[dest] = self.angles[i];
+                self.left[dest] = self.left[i];
+            } else {
+                j -= 1;
+                self.angles[dest] = new[j].angle.float_val;
+                self.left[dest] = new[j].left;
+            }
+        }
+    }
+}
+
 /// Implementation of Lavaurs' algorithm to compute the lamination for the combinatorial Mandelbrot
 /// set.
 #[derive(Clone, Debug, PartialEq)]
@@ -109,15 +154,23 @@ pub struct Lamination
     pub crit_period: Period,
     max_period: Period,
     arcs: Vec<Vec<(RatAngle, RatAngle)>>,
-    endpoints: Vec<Endpoint>,
+    endpoints: Endpoints,
+    progress: Progress,
 }
 
 impl Lamination
 {
+    /// Bytes kept for each endpoint of a leaf, to estimate the memory taken by the lamination
+    pub const ENDPOINT_BYTES: usize = Endpoints::BYTES;
+
     #[must_use]
     pub fn new() -> Self
     {
-        let endpoints = vec![Endpoint::default()];
+        // The first endpoint stands in for the root of the tree of leaves and is never scanned
+        let endpoints = Endpoints {
+            angles: vec![0.0],
+            left: vec![false],
+        };
 
         let arcs = vec![Vec::new(), vec![(RatAngle::new(0, 1), RatAngle::new(1, 1))]];
 
@@ -126,6 +179,7 @@ impl Lamination
             max_period: 1,
             arcs,
             endpoints,
+            progress: Progress::default(),
         }
     }
 
@@ -143,23 +197,55 @@ impl Lamination
         self
     }
 
+    /// Report each period added to the lamination to `sink`, as an item of
+    /// [`Phase::Lamination`]
+    #[must_use]
+    pub fn with_progress(mut self, sink: Arc<dyn ProgressSink>) -> Self
+    {
+        self.progress = Progress::new(sink);
+        self
+    }
+
+    /// Swap in another sink for progress reports, returning the one it had
+    pub(crate) fn replace_progress(&mut self, progress: Progress) -> Progress
+    {
+        core::mem::replace(&mut self.progress, progress)
+    }
+
     fn extend(&mut self)
     {
         self.max_period += 1;
         let n = 2_i64.pow(self.max_period as u32) - 1;
 
+        // Away from critical period one, no leaves land in the wake of the leaf from 1/3 to 2/3.
+        // The filter is a type parameter, so critical period one gets its own copy of the loop
+        // with the check compiled out.
+        if self.crit_period == 1 {
+            self.extend_by(n, |_| true);
+        } else {
+            self.extend_by(n, |k| k * 3 < n || k * 3 > 2 * n);
+        }
+    }
+
+    /// Add the leaves of period `max_period`, with endpoints among the angles `k/n` for which
+    /// `keep(k)` holds
+    fn extend_by(&mut self, n: Period, keep: impl Fn(Period) -> bool)
+    {
         let mut stack: Vec<Period> = Vec::new();
 
         let mut new_endpoints = Vec::new();
-        let mut endpoint_it = self.endpoints.iter().skip(1).peekable();
+        let Endpoints { angles, left } = &self.endpoints;
+        let mut i = 1;
 
-        'outer: for k in (1..n).filter(|k| self.crit_period == 1 || k * 3 < n || k * 3 > 2 * n) {
-            let theta = CachedRatAngle::from(RatAngle::new(k, n));
+        'outer: for k in (1..n).filter(|&k| keep(k)) {
+            // Dividing before reducing gives the same float, so the fraction `k/n` is only
+            // reduced for angles that end up as endpoints
+            let theta = k as f64 / n as f64;
 
-            'inner: while let Some(&curr) = endpoint_it.peek() {
-                match curr.angle.partial_cmp(&theta) {
+            'inner: while let Some(&angle) = angles.get(i) {
+                match angle.partial_cmp(&theta) {
                     Some(Ordering::Less) => {
-                        if curr.left {
+                        if left[i] {
                             stack.push(0);
                         } else {
                             let top = stack.pop();
@@ -167,20 +253,20 @@ impl Lamination
                         }
                     }
                     Some(Ordering::Equal) => {
-                        endpoint_it.next();
+                        i += 1;
                         continue 'outer;
                     }
                     Some(Ordering::Greater) => break 'inner,
-                    None => panic!(
-                        "NaN encountered in comparison! curr.angle = {:?}, theta = {theta:?}",
-                        curr.angle
-                    ),
+                    None => {
+                        panic!("NaN encountered in comparison! angle = {angle}, theta = {theta:?}")
+                    }
                 }
-                endpoint_it.next();
+                i += 1;
             }
 
             match stack.last() {
                 Some(&j) if j != 0 => {
+                    let theta = CachedRatAngle::new(k, n);
                     let other = CachedRatAngle::new(j, n);
                     new_endpoints.push(Endpoint::left(other, theta));
                     new_endpoints.push(Endpoint::right(theta, other));
@@ -195,12 +281,7 @@ impl Lamination
         new_endpoints
             .sort_unstable_by(|a, b| a.partial_cmp(b).expect("NaN encountered during sort"));
 
-        self.endpoints = self
-            .endpoints
-            .iter()
-            .copied()
-            .merge(new_endpoints.iter().copied())
-            .collect();
+        self.endpoints.merge(&new_endpoints);
 
         let new_arcs = new_endpoints
             .into_iter()
@@ -216,11 +297,53 @@ impl Lamination
         self.max_period
     }
 
+    /// Largest period whose leaves have been computed
+    #[must_use]
+    pub const fn max_period(&self) -> Period
+    {
+        self.max_period
+    }
+
+    /// Rebuild a lamination from the leaves of every period up to its largest, indexed by
+    /// period as returned by [`Self::into_arcs`], e.g. to restore one saved to disk. Returns
+    /// `None` unless the leaves of periods zero and one are those every lamination starts with.
+    #[must_use]
+    pub fn from_arcs(crit_period: Period, arcs: Vec<Vec<(RatAngle, RatAngle)>>) -> Option<Self>
+    {
+        let mut lamination = Self::new().with_crit_period(crit_period);
+        if arcs.get(..2)? != lamination.arcs.as_slice() {
+            return None;
+        }
+
+        let mut endpoints: Vec<Endpoint> = arcs[2..]
+            .iter()
+            .flatten()
+            .flat_map(|&(a, b)| {
+                let (a, b) = (a.into(), b.into());
+                [Endpoint::left(a, b), Endpoint::right(b, a)]
+            })
+            .collect();
+        endpoints.sort_unstable_by(|a, b| a.partial_cmp(b).expect("NaN encountered during sort"));
+        lamination.endpoints.merge(&endpoints);
+
+        lamination.max_period = arcs.len() as Period - 1;
+        lamination.arcs = arcs;
+        Some(lamination)
+    }
+
     pub fn extend_to_period(&mut self, period: Period)
     {
-        for _ in self.max_period..(period as Period) {
+        if period <= self.max_period {
+            return;
+        }
+        let progress = self.progress.clone();
+        let sink = progress.sink();
+        sink.phase_started(Phase::Lamination, Some((period - self.max_period) as usize));
+        for _ in self.max_period..period {
             self.extend();
+            sink.items_processed(Phase::Lamination, 1);
         }
+        sink.phase_finished(Phase::Lamination);
     }
 
     #[must_use]
@@ -234,15 +357,24 @@ impl Lamination
         &self.arcs[per as usize]
     }
 
+    /// Leaves of period `per`, or `None` if the lamination has not been extended that far. Unlike
+    /// [`Self::arcs_of_period`], this only needs a shared reference, so several threads can read
+    /// one extended lamination.
+    #[must_use]
+    pub fn arcs(&self, per: Period) -> Option<&[(RatAngle, RatAngle)]>
+    {
+        self.arcs.get(per.max(0) as usize).map(Vec::as_slice)
+    }
+
     #[must_use]
     pub fn into_arcs_of_period(mut self, per: Period) -> Vec<(RatAngle, RatAngle)>
     {
         self.extend_to_period(per);
         if per <= 0 {
-            return std::mem::take(&mut self.arcs[0]);
+            return core::mem::take(&mut self.arcs[0]);
         }
 
-        std::mem::take(&mut self.arcs[per as usize])
+        core::mem::take(&mut self.arcs[per as usize])
     }
 
     #[must_use]
@@ -269,17 +401,32 @@ impl Default for Lamination
     }
 }
 
-fn main()
+/// Only the leaves are written; the rest of the lamination is rebuilt from them
+#[cfg(feature = "std")]
+impl serde::Serialize for Lamination
+{
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    {
+        (self.crit_period, &self.arcs).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'de> serde::Deserialize<'de> for Lamination
 {
-    let mut lamination = Lamination::new();
-    let arcs = lamination.arcs_of_period(9);
-    for (a, b) in arcs {
-        println!(
-            "{:>3}/{:<3} <--> {:>3}/{:<3}",
-            a.numer(),
-            a.denom(),
-            b.numer(),
-            b.denom()
-        );
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error>
+    {
+        use serde::de::Error;
+
+        let (crit_period, arcs): (Period, Vec<Vec<(RatAngle, RatAngle)>>) =
+            serde::Deserialize::deserialize(deserializer)?;
+        if arcs.len() > 63 {
+            return Err(D::Error::custom("period out of range"));
+        }
+        let mut angles = arcs.iter().flatten().flat_map(|&(a, b)| [a, b]);
+        if angles.any(|a| *a.denom() <= 0) {
+            return Err(D::Error::custom("angle with a nonpositive denominator"));
+        }
+        Self::from_arcs(crit_period, arcs).ok_or_else(|| D::Error::custom("not a lamination"))
     }
 }
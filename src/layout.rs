@@ -0,0 +1,736 @@
+use crate::common::cells::{dual_edges, AugmentedVertex as Aug, Edge, Face};
+use crate::types::Period;
+use std::{
+    collections::{HashMap, VecDeque},
+    f32::consts::PI,
+    fmt::Display,
+    hash::Hash,
+};
+
+/// Horizontal gap left between neighboring faces when several are laid out together
+const FACE_SPACING: f32 = 1.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Point
+{
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point
+{
+    #[must_use]
+    pub const fn new(x: f32, y: f32) -> Self
+    {
+        Self { x, y }
+    }
+
+    #[must_use]
+    pub fn polar(center: Self, radius: f32, degrees: f32) -> Self
+    {
+        let theta = degrees.to_radians();
+        Self {
+            x: radius.mul_add(theta.cos(), center.x),
+            y: radius.mul_add(theta.sin(), center.y),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct NodeLayout
+{
+    pub label: String,
+    pub position: Point,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EdgeLayout
+{
+    pub source: usize,
+    pub target: usize,
+    pub real: bool,
+    /// Signed index used to separate parallel edges; 0 means a straight edge
+    pub bend: i32,
+    /// Index of the edge in the cover's edge list, if known
+    pub id: Option<usize>,
+    /// Whether running from `source` to `target` agrees with the orientation chosen for this
+    /// edge by `orient_edges`
+    pub forward: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SpokeKind
+{
+    PosReal,
+    NegReal,
+}
+
+/// Segment from a boundary node to the center of its face, marking a real crossing
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Spoke
+{
+    pub node: usize,
+    pub kind: SpokeKind,
+}
+
+/// A face drawn as a regular polygon, with absolute coordinates for every node
+#[derive(Clone, Debug, PartialEq)]
+pub struct FaceLayout
+{
+    pub label: String,
+    pub center: Point,
+    pub radius: f32,
+    pub nodes: Vec<NodeLayout>,
+    pub edges: Vec<EdgeLayout>,
+    pub spokes: Vec<Spoke>,
+    pub degree: Period,
+    /// Index into the backend's fill palette, or `None` to leave the face unfilled
+    pub color: Option<usize>,
+}
+
+/// Rule for choosing a fill color for each face
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FaceColoring
+{
+    #[default]
+    None,
+    /// A distinct color for each face label, i.e. each cycle class
+    Class,
+    /// One color per covering degree
+    Degree,
+    /// Separate colors for faces with an odd or even number of sides
+    Parity,
+    /// Separate colors for reflexive (degree 1) and irreflexive faces
+    Reflexivity,
+}
+
+impl FaceColoring
+{
+    /// Fill in the `color` of each layout. Class colors are assigned in order of first
+    /// appearance, so faces sharing a label share a color.
+    pub fn assign(self, faces: &mut [FaceLayout])
+    {
+        let mut classes: HashMap<String, usize> = HashMap::new();
+        for face in faces {
+            face.color = match self {
+                Self::None => None,
+                Self::Class => {
+                    let next = classes.len();
+                    Some(*classes.entry(face.label.clone()).or_insert(next))
+                }
+                Self::Degree => Some(face.degree.max(1) as usize - 1),
+                Self::Parity => Some(face.nodes.len() % 2),
+                Self::Reflexivity => Some(usize::from(face.degree != 1)),
+            };
+        }
+    }
+}
+
+/// Which end of the range of face sizes to select from
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Extreme
+{
+    Largest,
+    Smallest,
+}
+
+/// The `k` largest or smallest faces, ordered by size. Ties keep their order in `faces`.
+#[must_use]
+pub fn extreme_faces<V, F>(faces: &[Face<V, F>], k: usize, extreme: Extreme) -> Vec<&Face<V, F>>
+{
+    let mut selected: Vec<&Face<V, F>> = faces.iter().collect();
+    match extreme {
+        Extreme::Largest => selected.sort_by_key(|f| std::cmp::Reverse(f.len())),
+        Extreme::Smallest => selected.sort_by_key(|f| f.len()),
+    }
+    selected.truncate(k);
+    selected
+}
+
+/// The 1-skeleton of a cover, with each vertex appearing exactly once
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GraphLayout
+{
+    pub nodes: Vec<NodeLayout>,
+    pub edges: Vec<EdgeLayout>,
+}
+
+/// How to place the vertices when drawing the whole 1-skeleton
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GraphPlacement
+{
+    /// Evenly spaced on a circle
+    #[default]
+    Circle,
+    /// Start on a circle, then relax with the given number of force-directed iterations
+    ForceDirected(usize),
+}
+
+impl GraphLayout
+{
+    /// Move the nodes by a Fruchterman-Reingold simulation starting from their current
+    /// positions: every pair of nodes repels, and nodes joined by an edge attract, with
+    /// `edge_length` as the ideal distance. The result is deterministic and centered at the
+    /// origin.
+    pub fn relax(&mut self, edge_length: f32, iterations: usize)
+    {
+        let n = self.nodes.len();
+        if n < 2 || iterations == 0 {
+            return;
+        }
+        let k = edge_length;
+        let mut temperature = polygon_radius(n, k).max(k) / 4.0;
+        let cooling = temperature / (iterations as f32);
+
+        for _ in 0..iterations {
+            let mut disp = vec![Point::default(); n];
+
+            for i in 0..n {
+                for j in (i + 1)..n {
+                    let (a, b) = (self.nodes[i].position, self.nodes[j].position);
+                    let (dx, dy) = (a.x - b.x, a.y - b.y);
+                    let dist = dx.hypot(dy).max(0.01);
+                    let force = k * k / dist;
+                    let (fx, fy) = (dx / dist * force, dy / dist * force);
+                    disp[i].x += fx;
+                    disp[i].y += fy;
+                    disp[j].x -= fx;
+                    disp[j].y -= fy;
+                }
+            }
+
+            for edge in &self.edges {
+                let (i, j) = (edge.source, edge.target);
+                if i == j {
+                    continue;
+                }
+                let (a, b) = (self.nodes[i].position, self.nodes[j].position);
+                let (dx, dy) = (a.x - b.x, a.y - b.y);
+                let dist = dx.hypot(dy).max(0.01);
+                let force = dist * dist / k;
+                let (fx, fy) = (dx / dist * force, dy / dist * force);
+                disp[i].x -= fx;
+                disp[i].y -= fy;
+                disp[j].x += fx;
+                disp[j].y += fy;
+            }
+
+            for (node, d) in self.nodes.iter_mut().zip(&disp) {
+                let len = d.x.hypot(d.y);
+                if len > 0.0 {
+                    let step = len.min(temperature) / len;
+                    node.position.x += d.x * step;
+                    node.position.y += d.y * step;
+                }
+            }
+            temperature -= cooling;
+        }
+
+        let cx = self.nodes.iter().map(|v| v.position.x).sum::<f32>() / (n as f32);
+        let cy = self.nodes.iter().map(|v| v.position.y).sum::<f32>() / (n as f32);
+        for node in &mut self.nodes {
+            node.position.x -= cx;
+            node.position.y -= cy;
+        }
+    }
+
+    /// Vertex labels and their positions
+    #[must_use]
+    pub fn coordinates(&self) -> Vec<(&str, Point)>
+    {
+        self.nodes
+            .iter()
+            .map(|v| (v.label.as_str(), v.position))
+            .collect()
+    }
+
+    /// Vertex positions as CSV with columns `vertex,x,y`, for use by external tools
+    #[must_use]
+    pub fn coordinates_csv(&self) -> String
+    {
+        let mut lines = vec!["vertex,x,y".to_owned()];
+        for (label, p) in self.coordinates() {
+            lines.push(format!(
+                "\"{}\",{:.6},{:.6}",
+                label.replace('"', "\"\""),
+                p.x,
+                p.y
+            ));
+        }
+        lines.join("\n")
+    }
+}
+
+/// A drawing target that turns precomputed layouts into some output format.
+pub trait RenderBackend
+{
+    type Output;
+
+    fn draw_face(&mut self, face: &FaceLayout);
+
+    fn draw_graph(&mut self, graph: &GraphLayout);
+
+    fn draw_dual_graph(&mut self, graph: &GraphLayout);
+
+    fn finish(self) -> Self::Output;
+}
+
+/// Radius of the regular n-gon with the given side length
+#[must_use]
+pub fn polygon_radius(n: usize, edge_length: f32) -> f32
+{
+    if n < 2 {
+        return 0.0;
+    }
+    edge_length / (2.0 * (PI / (n as f32)).sin())
+}
+
+/// Largest side length, at most `edge_length`, for which a regular n-gon fits inside a circle
+/// of diameter `max_diameter`
+#[must_use]
+pub fn fit_edge_length(n: usize, edge_length: f32, max_diameter: f32) -> f32
+{
+    if n < 2 {
+        return edge_length;
+    }
+    edge_length.min(max_diameter * (PI / (n as f32)).sin())
+}
+
+/// Lay out a single face as a regular polygon centered at `center`, with vertices in clockwise
+/// order starting just above the positive real axis.
+#[must_use]
+pub fn layout_face<V, F>(face: &Face<Aug<V>, F>, edge_length: f32, center: Point) -> FaceLayout
+where
+    V: Display,
+    F: Display,
+{
+    let start = 180. / (face.len().max(1) as f32);
+    layout_face_oriented(face, edge_length, center, start, true)
+}
+
+/// Lay out a face as a regular polygon with vertex 0 at angle `start` (in degrees) about
+/// `center`, continuing clockwise or counterclockwise.
+fn layout_face_oriented<V, F>(
+    face: &Face<Aug<V>, F>,
+    edge_length: f32,
+    center: Point,
+    start: f32,
+    clockwise: bool,
+) -> FaceLayout
+where
+    V: Display,
+    F: Display,
+{
+    let n = face.len();
+    let radius = polygon_radius(n, edge_length);
+    let step = if clockwise { -360. } else { 360. } / (n.max(1) as f32);
+
+    let nodes = face
+        .vertices
+        .iter()
+        .enumerate()
+        .map(|(i, v)| NodeLayout {
+            label: v.vertex.to_string(),
+            position: Point::polar(center, radius, (i as f32).mul_add(step, start)),
+        })
+        .collect();
+
+    let edges = face
+        .vertices
+        .iter()
+        .enumerate()
+        .map(|(i, v)| EdgeLayout {
+            source: i,
+            target: (i + 1) % n,
+            real: v.data.neg_edge(),
+            bend: 0,
+            id: face.edge_ids.get(i).copied(),
+            forward: true,
+        })
+        .collect();
+
+    let mut spokes = Vec::new();
+    for (i, v) in face.vertices.iter().enumerate() {
+        if v.data.pos_vertex() {
+            spokes.push(Spoke {
+                node: i,
+                kind: SpokeKind::PosReal,
+            });
+        }
+        if v.data.neg_vertex() {
+            spokes.push(Spoke {
+                node: i,
+                kind: SpokeKind::NegReal,
+            });
+        }
+    }
+
+    FaceLayout {
+        label: face.label.to_string(),
+        center,
+        radius,
+        nodes,
+        edges,
+        spokes,
+        degree: face.degree,
+        color: None,
+    }
+}
+
+/// Lay out several faces side by side along the real axis.
+pub fn layout_faces<'a, V, F, I>(faces: I, edge_length: f32) -> Vec<FaceLayout>
+where
+    V: Display + 'a,
+    F: Display + 'a,
+    I: IntoIterator<Item = &'a Face<Aug<V>, F>>,
+{
+    let mut x = 0.0;
+    faces
+        .into_iter()
+        .map(|face| {
+            let radius = polygon_radius(face.len(), edge_length);
+            let center = Point::new(x + radius, 0.0);
+            x += 2.0f32.mul_add(radius, FACE_SPACING);
+            layout_face(face, edge_length, center)
+        })
+        .collect()
+}
+
+/// Lay out a face as a regular polygon with vertex `j` at `a` and vertex `j + 1` at `b`, lying
+/// to the right of the segment from `a` to `b` if `clockwise` and to its left otherwise.
+fn layout_face_along<V, F>(
+    face: &Face<Aug<V>, F>,
+    edge_length: f32,
+    j: usize,
+    (a, b): (Point, Point),
+    clockwise: bool,
+) -> FaceLayout
+where
+    V: Display,
+    F: Display,
+{
+    let n = face.len().max(1) as f32;
+    let apothem = polygon_radius(face.len(), edge_length) * (PI / n).cos();
+    let (dx, dy) = (b.x - a.x, b.y - a.y);
+    let norm = dx.hypot(dy).max(f32::EPSILON);
+    let side = if clockwise { 1.0 } else { -1.0 };
+    let center = Point::new(
+        (side * dy / norm).mul_add(apothem, (a.x + b.x) / 2.0),
+        (-side * dx / norm).mul_add(apothem, (a.y + b.y) / 2.0),
+    );
+
+    let theta = (a.y - center.y).atan2(a.x - center.x).to_degrees();
+    let offset = 360. * (j as f32) / n;
+    let start = if clockwise {
+        theta + offset
+    } else {
+        theta - offset
+    };
+    layout_face_oriented(face, edge_length, center, start, clockwise)
+}
+
+/// Unfold faces into the plane, starting from the first one and reflecting each further face
+/// across an edge it shares with a face already placed, so that identified edges are drawn on
+/// top of each other. With a `pivot`, faces are only glued along edges incident to that
+/// vertex, which fans them out around it. Faces that cannot be reached are left out.
+pub fn layout_net<V, F>(
+    faces: &[&Face<Aug<V>, F>],
+    edge_length: f32,
+    pivot: Option<V>,
+) -> Vec<FaceLayout>
+where
+    V: Copy + Eq + Display,
+    F: Display,
+{
+    let Some(root) = faces.first() else {
+        return Vec::new();
+    };
+    let mut layouts: Vec<Option<(FaceLayout, bool)>> = vec![None; faces.len()];
+    layouts[0] = Some((layout_face(root, edge_length, Point::default()), true));
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(k) = queue.pop_front() {
+        let Some((placed, clockwise)) = layouts[k].clone() else {
+            continue;
+        };
+        let face = faces[k];
+        let n = face.len();
+
+        for (i, &id) in face.edge_ids.iter().enumerate() {
+            let (u, w) = (face.vertices[i].vertex, face.vertices[(i + 1) % n].vertex);
+            if pivot.is_some_and(|p| p != u && p != w) {
+                continue;
+            }
+            let (pu, pw) = (placed.nodes[i].position, placed.nodes[(i + 1) % n].position);
+
+            for (m, other) in faces.iter().enumerate() {
+                if layouts[m].is_some() {
+                    continue;
+                }
+                let Some(j) = other.edge_ids.iter().position(|&e| e == id) else {
+                    continue;
+                };
+                // On an orientable surface the new face runs along the edge the other way; if
+                // it does not, it has to be mirrored to keep the edge in place
+                let next = other.vertices[(j + 1) % other.len()].vertex;
+                let same_direction = u != w && other.vertices[j].vertex == u && next == w;
+                let endpoints = if same_direction { (pu, pw) } else { (pw, pu) };
+                let other_clockwise = clockwise != same_direction;
+
+                layouts[m] = Some((
+                    layout_face_along(other, edge_length, j, endpoints, other_clockwise),
+                    other_clockwise,
+                ));
+                queue.push_back(m);
+            }
+        }
+    }
+
+    layouts.into_iter().flatten().map(|(l, _)| l).collect()
+}
+
+/// The face at index `root` followed by every other face sharing an edge with it
+#[must_use]
+pub fn faces_around_face<V, F>(faces: &[Face<V, F>], root: usize) -> Vec<&Face<V, F>>
+{
+    let Some(center) = faces.get(root) else {
+        return Vec::new();
+    };
+    let mut selected = vec![center];
+    selected.extend(faces.iter().enumerate().filter_map(|(k, f)| {
+        (k != root && f.edge_ids.iter().any(|e| center.edge_ids.contains(e))).then_some(f)
+    }));
+    selected
+}
+
+/// Every face with `vertex` on its boundary
+#[must_use]
+pub fn faces_around_vertex<V, F>(faces: &[Face<Aug<V>, F>], vertex: V) -> Vec<&Face<Aug<V>, F>>
+where
+    V: PartialEq,
+{
+    faces
+        .iter()
+        .filter(|f| f.vertices.iter().any(|v| v.vertex == vertex))
+        .collect()
+}
+
+/// Lay out faces in rows no wider than `page_width`, shrinking any face that would not fit on
+/// its own. Each row is returned separately, with coordinates relative to its own origin.
+pub fn paginate_faces<'a, V, F, I>(
+    faces: I,
+    edge_length: f32,
+    page_width: f32,
+) -> Vec<Vec<FaceLayout>>
+where
+    V: Display + 'a,
+    F: Display + 'a,
+    I: IntoIterator<Item = &'a Face<Aug<V>, F>>,
+{
+    let mut pages: Vec<Vec<FaceLayout>> = vec![Vec::new()];
+    let mut x = 0.0;
+    for face in faces {
+        let length = fit_edge_length(face.len(), edge_length, page_width);
+        let radius = polygon_radius(face.len(), length);
+
+        if x > 0.0 && x + 2.0 * radius > page_width {
+            pages.push(Vec::new());
+            x = 0.0;
+        }
+        let center = Point::new(x + radius, 0.0);
+        x += 2.0f32.mul_add(radius, FACE_SPACING);
+        if let Some(page) = pages.last_mut() {
+            page.push(layout_face(face, length, center));
+        }
+    }
+    pages
+}
+
+/// Orient every copy of each edge consistently across the given faces, so that arrows drawn
+/// along `forward` edges show how the faces are glued. Each edge takes the direction in which it
+/// is first traversed; later copies agree with it if they start at the same vertex. Copies of a
+/// loop alternate, since the faces of an orientable surface traverse a shared edge in opposite
+/// directions.
+pub fn orient_edges(faces: &mut [FaceLayout])
+{
+    let mut first: HashMap<usize, (String, bool)> = HashMap::new();
+    for face in faces {
+        for edge in &mut face.edges {
+            let Some(id) = edge.id else {
+                continue;
+            };
+            let source = &face.nodes[edge.source].label;
+            let is_loop = *source == face.nodes[edge.target].label;
+            match first.get_mut(&id) {
+                None => {
+                    edge.forward = true;
+                    first.insert(id, (source.clone(), true));
+                }
+                Some((_, last)) if is_loop => {
+                    *last = !*last;
+                    edge.forward = *last;
+                }
+                Some((start, _)) => edge.forward = start == source,
+            }
+        }
+    }
+}
+
+/// Lay out the whole 1-skeleton with the given placement of vertices
+pub fn place_graph<V, F>(
+    faces: &[Face<Aug<V>, F>],
+    edges: &[Edge<V>],
+    edge_length: f32,
+    placement: GraphPlacement,
+) -> GraphLayout
+where
+    V: Copy + Eq + Hash + Display,
+{
+    let mut graph = layout_graph(faces, edges, edge_length);
+    if let GraphPlacement::ForceDirected(iterations) = placement {
+        graph.relax(edge_length, iterations);
+    }
+    graph
+}
+
+/// Lay out the whole 1-skeleton with vertices evenly spaced on a circle. Vertices are taken in
+/// order of first appearance in `faces`, then in `edges`.
+pub fn layout_graph<V, F>(
+    faces: &[Face<Aug<V>, F>],
+    edges: &[Edge<V>],
+    edge_length: f32,
+) -> GraphLayout
+where
+    V: Copy + Eq + Hash + Display,
+{
+    let mut vertices: Vec<V> = Vec::new();
+    let mut indices: HashMap<V, usize> = HashMap::new();
+
+    let face_vertices = faces
+        .iter()
+        .flat_map(|f| f.vertices.iter().map(|v| v.vertex));
+    let edge_vertices = edges.iter().flat_map(|e| [e.start, e.end]);
+
+    for v in face_vertices.chain(edge_vertices) {
+        indices.entry(v).or_insert_with(|| {
+            vertices.push(v);
+            vertices.len() - 1
+        });
+    }
+
+    let n = vertices.len();
+    let radius = polygon_radius(n, edge_length);
+    let center = Point::default();
+
+    let nodes = vertices
+        .iter()
+        .enumerate()
+        .map(|(i, v)| NodeLayout {
+            label: v.to_string(),
+            position: on_circle(center, radius, i, n),
+        })
+        .collect();
+
+    let mut multiplicity = HashMap::new();
+    let edges = edges
+        .iter()
+        .enumerate()
+        .map(|(k, e)| {
+            let source = indices[&e.start];
+            let target = indices[&e.end];
+            EdgeLayout {
+                source,
+                target,
+                real: e.is_real(),
+                bend: next_bend(&mut multiplicity, source, target),
+                id: Some(k),
+                forward: true,
+            }
+        })
+        .collect();
+
+    GraphLayout { nodes, edges }
+}
+
+/// Lay out the dual graph, with one node per face and one edge for each edge of the cover,
+/// joining the faces on either side of it. If a primal layout is given, each face is placed at
+/// the centroid of its boundary vertices so that the two graphs can be overlaid; otherwise the
+/// faces are evenly spaced on a circle.
+pub fn layout_dual_graph<V, F>(
+    faces: &[Face<Aug<V>, F>],
+    primal: Option<&GraphLayout>,
+    edge_length: f32,
+) -> GraphLayout
+where
+    V: Display,
+    F: Display,
+{
+    let n = faces.len();
+    let radius = polygon_radius(n, edge_length);
+    let center = Point::default();
+
+    let positions: Option<HashMap<&str, Point>> =
+        primal.map(|g| g.coordinates().into_iter().collect());
+
+    let nodes = faces
+        .iter()
+        .enumerate()
+        .map(|(i, face)| {
+            let centroid = positions.as_ref().and_then(|positions| {
+                let points: Vec<Point> = face
+                    .vertices
+                    .iter()
+                    .filter_map(|v| positions.get(v.vertex.to_string().as_str()).copied())
+                    .collect();
+                let k = points.len() as f32;
+                (k > 0.0).then(|| {
+                    Point::new(
+                        points.iter().map(|p| p.x).sum::<f32>() / k,
+                        points.iter().map(|p| p.y).sum::<f32>() / k,
+                    )
+                })
+            });
+            NodeLayout {
+                label: face.label.to_string(),
+                position: centroid.unwrap_or_else(|| on_circle(center, radius, i, n)),
+            }
+        })
+        .collect();
+
+    let mut multiplicity = HashMap::new();
+    let edges = dual_edges(faces)
+        .into_iter()
+        .map(|(id, (source, target))| EdgeLayout {
+            source,
+            target,
+            real: false,
+            bend: next_bend(&mut multiplicity, source, target),
+            id: Some(id),
+            forward: true,
+        })
+        .collect();
+
+    GraphLayout { nodes, edges }
+}
+
+/// The i-th of n points evenly spaced clockwise on a circle, starting at the top
+fn on_circle(center: Point, radius: f32, i: usize, n: usize) -> Point
+{
+    Point::polar(center, radius, 90. - 360. * (i as f32) / (n as f32))
+}
+
+/// Bend for the next edge between two nodes. Parallel edges alternate sides so that each one
+/// remains visible.
+fn next_bend(multiplicity: &mut HashMap<(usize, usize), i32>, source: usize, target: usize) -> i32
+{
+    let count = multiplicity
+        .entry((source.min(target), source.max(target)))
+        .or_default();
+    let bend = if *count % 2 == 1 {
+        (*count + 1) / 2
+    } else {
+        -*count / 2
+    };
+    *count += 1;
+    bend
+}
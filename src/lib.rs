@@ -1,30 +1,115 @@
 #![allow(dead_code)]
+// Without `std`, the counting code and cover builders only need `alloc`
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
 
 pub mod abstract_cycles;
 pub mod arithmetic;
+#[cfg(feature = "cache")]
+pub mod cache;
+#[cfg(feature = "parquet")]
+pub mod columnar;
 pub mod combinatorics;
 pub mod common;
+#[cfg(feature = "cli")]
+pub mod config;
+pub mod cover;
+#[cfg(feature = "std")]
+pub mod csv;
+#[cfg(feature = "std")]
+pub mod dot;
 pub mod dynatomic_cover;
+#[cfg(feature = "evcxr")]
+pub mod evcxr;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "std")]
+pub mod fundamental_polygon;
+#[cfg(feature = "std")]
+pub mod gexf;
 pub mod global_state;
+#[cfg(feature = "petgraph")]
+pub mod graph;
+#[cfg(feature = "std")]
+pub mod graphml;
+#[cfg(feature = "std")]
+pub mod json;
 pub mod lamination;
+#[cfg(feature = "std")]
+pub mod layout;
+pub mod limbs;
 pub mod marked_cycle_cover;
+#[cfg(feature = "std")]
+pub mod matrices;
+pub mod monodromy;
+#[cfg(feature = "render")]
+pub mod parameter_plane;
 pub mod prelude;
+pub mod progress;
+pub mod real_axis;
+pub mod ribbon;
+#[cfg(feature = "stats")]
+pub mod stats;
+#[cfg(feature = "std")]
+pub mod surface;
+#[cfg(feature = "render")]
+pub mod svg;
+#[cfg(feature = "render")]
 pub mod tikz;
+pub mod topology;
 pub mod types;
+#[cfg(feature = "render")]
+pub mod typst;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
 const MAX_DISPLAY_ITEMS: usize = 100;
 
-#[cfg(test)]
+// The tests also cover the renderers, parsers, and cache, so they need every feature
+#[cfg(all(test, feature = "cli"))]
 mod tests
 {
-    use crate::abstract_cycles::AbstractPoint;
+    use std::collections::HashMap;
+
+    use crate::abstract_cycles::{AbstractCycle, AbstractCycleClass, AbstractPoint};
+    use crate::arithmetic::{doubling_period, parse_angle, MAX_ANGLE_PERIOD};
+    use crate::cache::{load_snapshot, save_snapshot, CoverCache};
     use crate::combinatorics::{dynatomic, marked_cycle, Combinatorics};
-    use crate::dynatomic_cover::DynatomicCover;
-    use crate::global_state::PERIOD;
+    use crate::common::cells::{
+        AugmentedVertex, Cell, CellFilter, CellListing, CellOrder, Edge, SummaryOptions,
+        VertexData, VertexId,
+    };
+    use crate::common::{cycle_position, cycle_reps, get_orbit_in, orbit_min_and_len_in, Phase};
+    use crate::config::Config;
+    use crate::cover::Cover;
+    use crate::csv;
+    use crate::dynatomic_cover::{DynatomicCover, DynatomicCoverBuilder};
+    use crate::global_state::{set_period, Context};
+    use crate::json::JsonSummary;
     use crate::lamination::Lamination;
-    use crate::marked_cycle_cover::MarkedCycleCover;
-    use crate::tikz::TikzRenderer;
-    use crate::types::IntAngle;
+    use crate::layout::{
+        faces_around_face, faces_around_vertex, layout_faces, layout_graph, layout_net,
+        orient_edges, paginate_faces, Extreme, FaceColoring, GraphLayout, Point,
+    };
+    use crate::limbs::Limb;
+    use crate::marked_cycle_cover::{MCEdge, MCFace, MarkedCycleCover, MarkedCycleCoverBuilder};
+    use crate::matrices::{CoverMatrices, SparseMatrix};
+    use crate::monodromy::Permutation;
+    use crate::parameter_plane::{external_ray, RayOverlay};
+    use crate::progress::ProgressSink;
+    use crate::ribbon::RibbonGraph;
+    use crate::surface::Triangulation;
+    use crate::svg::SvgRenderer;
+    use crate::tikz::{
+        fundamental_polygon::FundamentalPolygonRenderer, lamination::LaminationRenderer,
+        pinched_disk::PinchedDiskRenderer, TikzRenderer, TikzStyle,
+    };
+    use crate::topology::{BaseCurve, EndCell};
+    use crate::types::{CritPeriod, IntAngle, MarkedPeriod, Period, RatAngle};
+    use crate::typst::TypstRenderer;
+    use num::complex::Complex64;
+    use plotters::prelude::{IntoDrawingArea, SVGBackend};
 
     #[test]
     fn lamination()
@@ -94,6 +179,359 @@ mod tests
         }
     }
 
+    #[test]
+    fn relative_topology()
+    {
+        // MC_3(Per_2) has no edges, and falls into two spheres, each a copy of the base
+        let mc3 = MarkedCycleCover::new(3, 2);
+        let topology = mc3.relative_topology().unwrap();
+        assert_eq!(topology.base, BaseCurve::Per2);
+        assert_eq!((topology.components, topology.genus()), (2, 0));
+        assert_eq!((topology.punctures, topology.orbifold_points), (2, 2));
+        assert_eq!(topology.ramification(), 0);
+        assert_eq!(
+            DynatomicCover::new(3, 2)
+                .relative_topology()
+                .unwrap()
+                .genus(),
+            0
+        );
+
+        for crit_period in [1, 2] {
+            for period in 4..9 {
+                let mc = MarkedCycleCover::new(period, crit_period);
+                let topology = mc.relative_topology().unwrap();
+                assert_eq!(topology.sheets, mc.num_vertices());
+                assert_eq!(topology.genus(), mc.genus());
+                assert_eq!(topology.ramification(), mc.num_edges() as i64);
+
+                let dyn_cover = DynatomicCover::new(period, crit_period);
+                let topology = dyn_cover.relative_topology().unwrap();
+                assert_eq!(topology.sheets, dyn_cover.num_vertices());
+                assert_eq!(topology.genus(), dyn_cover.genus());
+                assert_eq!(
+                    topology.ramification(),
+                    (dyn_cover.num_edges() - dyn_cover.satellite_faces.len()) as i64
+                );
+            }
+        }
+        assert_eq!(
+            BaseCurve::Per2.orbifold_euler_characteristic(),
+            RatAngle::new(1, 3)
+        );
+        assert!(MarkedCycleCover::new(4, 3).relative_topology().is_none());
+    }
+
+    #[test]
+    fn period_newtypes()
+    {
+        assert_eq!("6".parse::<MarkedPeriod>().map(MarkedPeriod::get), Ok(6));
+        assert!(MarkedPeriod::new(0).is_err());
+        assert!(MarkedPeriod::new(MAX_ANGLE_PERIOD + 1).is_err());
+        assert_eq!(CritPeriod::new(2), Ok(CritPeriod::TWO));
+        assert!("3".parse::<CritPeriod>().is_err());
+        assert_eq!(CritPeriod::ONE.base(), BaseCurve::Per1);
+
+        let cover = MarkedCycleCover::from_periods(MarkedPeriod::new(5).unwrap(), CritPeriod::TWO);
+        assert_eq!(cover.counts(), MarkedCycleCover::new(5, 2).counts());
+    }
+
+    #[test]
+    fn cover_trait()
+    {
+        let covers: Vec<Box<dyn Cover>> = vec![
+            Box::new(MarkedCycleCover::new(6, 1)),
+            Box::new(DynatomicCover::new(5, 2)),
+        ];
+        assert_eq!(covers[0].counts(), MarkedCycleCover::new(6, 1).counts());
+        assert_eq!(covers[1].counts(), DynatomicCover::new(5, 2).counts());
+        for cov in &covers {
+            assert_eq!(cov.face_sizes().len(), cov.num_faces());
+            assert_eq!(cov.genus(), cov.relative_topology().unwrap().genus());
+        }
+        assert_eq!(covers[1].surface_name(), "Dyn_5(Per_2)");
+        assert_eq!(covers[1].period(), 5);
+    }
+
+    #[test]
+    fn compactification()
+    {
+        for crit_period in [1, 2] {
+            for period in 3..9 {
+                let mc = MarkedCycleCover::new(period, crit_period);
+                let compactification = mc.compactification().unwrap();
+                let topology = mc.relative_topology().unwrap();
+                assert_eq!(compactification.genus(), topology.genus());
+                assert_eq!(compactification.ends.len(), topology.punctures);
+
+                let dyn_cover = DynatomicCover::new(period, crit_period);
+                let compactification = dyn_cover.compactification().unwrap();
+                let topology = dyn_cover.relative_topology().unwrap();
+                assert_eq!(compactification.genus(), topology.genus());
+            }
+        }
+
+        // Over Per_1, each end is a new vertex joined to every corner of its face
+        let mc6 = MarkedCycleCover::new(6, 1);
+        let compactification = mc6.compactification().unwrap();
+        let corners: usize = mc6.face_sizes().sum();
+        assert_eq!(
+            compactification.num_vertices,
+            mc6.num_vertices() + mc6.num_faces()
+        );
+        assert_eq!(compactification.num_edges(), mc6.num_edges() + corners);
+        assert_eq!(compactification.num_faces(), corners);
+        assert_eq!(
+            compactification.euler_characteristic(),
+            mc6.euler_characteristic()
+        );
+
+        assert_eq!(
+            MarkedCycleCover::new(3, 2)
+                .compactification()
+                .unwrap()
+                .num_components(),
+            2
+        );
+        assert!(MarkedCycleCover::new(4, 3).compactification().is_err());
+
+        // The check counts the branching from the monodromy, apart from the cells
+        let group = mc6.monodromy_group();
+        assert!(compactification.check(&group).is_ok());
+        let mut missing_face = compactification.clone();
+        missing_face.faces.pop();
+        assert!(missing_face.check(&group).is_err());
+        let mut extra_edge = compactification.clone();
+        extra_edge.edges.push((0, 1));
+        assert!(extra_edge.check(&group).is_err());
+        let mc6_2 = MarkedCycleCover::new(6, 2);
+        assert!(compactification.check(&mc6_2.monodromy_group()).is_err());
+        let mut ends = compactification;
+        ends.ends[0].0.local_degree += 1;
+        ends.ends[1].0.local_degree -= 1;
+        assert!(ends.check(&group).is_err());
+    }
+
+    #[test]
+    fn ends_at_infinity()
+    {
+        let mc6 = MarkedCycleCover::new(6, 1);
+        let ends = mc6.ends_at_infinity().unwrap();
+        assert_eq!(ends.len(), mc6.num_faces());
+        assert_eq!(
+            ends.degree_counts().into_iter().collect::<Vec<_>>(),
+            [(1, 1), (2, 4)]
+        );
+
+        // Riemann-Hurwitz over the compactified base sphere, with the ramification at the ends
+        // and elsewhere
+        let dyn5 = DynatomicCover::new(5, 1);
+        let ends = dyn5.ends_at_infinity().unwrap();
+        let topology = dyn5.relative_topology().unwrap();
+        assert_eq!(ends.sheets() as usize, dyn5.num_vertices());
+        assert_eq!(ends.len(), topology.punctures);
+        assert_eq!(
+            dyn5.euler_characteristic(),
+            2 * ends.sheets() - ends.ramification() - topology.ramification()
+        );
+
+        let ends = MarkedCycleCover::new(6, 2).ends_at_infinity().unwrap();
+        assert!(ends.ends.iter().all(|e| e.local_degree == 1));
+        assert_eq!(ends.ends[2].cell, EndCell::Vertex(2));
+    }
+
+    #[test]
+    fn monodromy_group()
+    {
+        // Each edge of the marked cycle curve has its own wake, so the generators are
+        // transpositions, and generate the whole symmetric group on the vertices
+        let mc6 = MarkedCycleCover::new(6, 1);
+        let group = mc6.monodromy_group();
+        assert_eq!(group.degree(), 9);
+        assert!(group
+            .generators
+            .iter()
+            .all(|g| g.cycle_type() == [1, 1, 1, 1, 1, 1, 1, 2]));
+        assert!(group.is_transitive());
+        assert_eq!(group.order(), (1..=9u32).product());
+
+        // The product of the generators is the monodromy around the faces' center
+        for (product, mut degrees) in [
+            (
+                group.product(),
+                mc6.compact_faces
+                    .iter()
+                    .map(|f| f.degree)
+                    .collect::<Vec<_>>(),
+            ),
+            {
+                let dyn5 = DynatomicCover::new(5, 2);
+                let degrees = dyn5.primitive_faces.iter().map(|f| f.degree).collect();
+                (dyn5.monodromy_group().product(), degrees)
+            },
+        ] {
+            degrees.sort_unstable();
+            let cycle_type: Vec<_> = product.cycle_type().iter().map(|&n| n as Period).collect();
+            assert_eq!(cycle_type, degrees);
+        }
+
+        // The dynatomic group permutes the cycles as blocks, rotating each of them
+        let group = DynatomicCover::new(4, 1).monodromy_group();
+        assert!(group.is_transitive());
+        assert_eq!(group.order(), [4u32, 4, 4, 6].into_iter().product());
+
+        let group = MarkedCycleCover::new(3, 2).monodromy_group();
+        assert!(!group.is_transitive());
+        assert_eq!(group.orbits().len(), 2);
+
+        let g = Permutation::from_images(vec![1, 2, 0, 3]).unwrap();
+        assert_eq!(g.to_string(), "(0 1 2)");
+        assert!(g.then(&g.inverse()).is_identity());
+        assert!(Permutation::from_images(vec![0, 0]).is_none());
+    }
+
+    #[test]
+    fn real_components()
+    {
+        // Dynatomic curves have an edge in every hyperbolic component of their period, so their
+        // real components are all those of that period
+        for (period, count) in (3..=8).zip([1, 2, 3, 5, 9, 16]) {
+            let cover = DynatomicCover::new(period, 1);
+            let components = cover.real_components();
+            assert_eq!(components.len(), count, "Testing period {period}");
+            assert!(components.windows(2).all(|w| w[0].angle < w[1].angle));
+            assert_eq!(
+                components.iter().map(|c| c.edge_ids.len()).sum::<usize>(),
+                cover.edges.iter().filter(|e| e.is_real()).count()
+            );
+        }
+
+        // Marked cycle curves miss the satellite components, such as the period doubling of
+        // the basilica
+        let components = MarkedCycleCover::new(4, 1).real_components();
+        assert_eq!(components.len(), 1);
+        assert_eq!(components[0].angle, RatAngle::new(7, 15));
+        assert_eq!(components[0].edge_ids.len(), 1);
+        assert!(MarkedCycleCover::new(6, 2).real_components().is_empty());
+    }
+
+    #[test]
+    fn real_crossings()
+    {
+        // The annotations from tracing the faces around infinity mark the same crossings, and
+        // each face crosses each half of the real axis as often as its degree
+        for period in 3..=8 {
+            let cover = MarkedCycleCover::new(period, 1);
+            for (face, crossings) in cover.compact_faces.iter().zip(cover.real_crossings()) {
+                let count = |f: fn(&VertexData) -> bool| {
+                    face.vertices.iter().filter(|v| f(&v.data)).count()
+                };
+                assert_eq!(count(VertexData::neg_edge), crossings);
+                assert_eq!(count(VertexData::pos_vertex) as Period, face.degree);
+                assert_eq!(
+                    (count(VertexData::neg_vertex) + crossings) as Period,
+                    face.degree
+                );
+            }
+        }
+
+        let stats = MarkedCycleCover::new(6, 1).real_crossing_stats();
+        assert_eq!(stats.faces_by_crossings, [(1, 2), (2, 3)].into());
+        assert_eq!(stats.num_bi_real(), 3);
+        assert_eq!(stats.num_non_real(), 0);
+        let stats = DynatomicCover::new(6, 1).real_crossing_stats();
+        assert_eq!(stats.num_faces(), DynatomicCover::new(6, 1).num_faces());
+    }
+
+    #[test]
+    fn doubling_permutation()
+    {
+        let doubling = Permutation::doubling(3);
+        assert_eq!(doubling.to_string(), "(1 2 4)(3 6 5)");
+        assert_eq!(doubling.matrix().get(2, 1), 1);
+
+        // Doubling moves each marked point one step along its cycle, as rotating the shift does
+        let dyn6 = DynatomicCover::new(6, 1);
+        let doubling = dyn6.doubling_permutation();
+        assert_eq!(doubling.cycle_type(), vec![6; dyn6.num_vertices() / 6]);
+        let context = dyn6.context;
+        for (v, &image) in dyn6.vertices.iter().zip(doubling.images()) {
+            assert_eq!(
+                dyn6.vertices[image].to_point_in(context),
+                v.to_point_in(context).rotate_in(1, context)
+            );
+        }
+    }
+
+    #[test]
+    fn spine()
+    {
+        let mc = |period, crit_period| {
+            let cov = MarkedCycleCover::new(period, crit_period);
+            (cov.relative_topology().unwrap(), cov.spine().unwrap())
+        };
+        let dc = |period, crit_period| {
+            let cov = DynatomicCover::new(period, crit_period);
+            (cov.relative_topology().unwrap(), cov.spine().unwrap())
+        };
+        for (topology, spine) in [mc(6, 1), dc(5, 1), dc(5, 2), mc(3, 2)] {
+            assert_eq!(
+                spine.euler_characteristic(),
+                topology.euler_characteristic()
+            );
+            assert_eq!(spine.num_components(), topology.components);
+        }
+
+        // Over Per_1, the spine of a marked cycle curve is its 1-skeleton, and each satellite
+        // face of a dynatomic curve is collapsed through one of its edges
+        let mc6 = MarkedCycleCover::new(6, 1);
+        assert_eq!(mc6.spine().unwrap().num_edges(), mc6.num_edges());
+        let dyn5 = DynatomicCover::new(5, 1);
+        let spine = dyn5.spine().unwrap();
+        assert_eq!(spine.num_nodes, dyn5.num_vertices());
+        assert_eq!(
+            spine.num_edges(),
+            dyn5.num_edges() - dyn5.satellite_faces.len()
+        );
+        assert_eq!(spine.rank() as i64, 1 - spine.euler_characteristic());
+    }
+
+    #[test]
+    fn limbs()
+    {
+        let wake = |p, q| Limb::new(RatAngle::new(p, q)).wake();
+        assert_eq!(wake(1, 2), (RatAngle::new(1, 3), RatAngle::new(2, 3)));
+        assert_eq!(wake(1, 3), (RatAngle::new(1, 7), RatAngle::new(2, 7)));
+        assert_eq!(wake(2, 5), (RatAngle::new(9, 31), RatAngle::new(10, 31)));
+        assert_eq!(wake(3, 4), (RatAngle::new(13, 15), RatAngle::new(14, 15)));
+
+        // Every angle of period 5 lies in a limb, ten of them in the 1/2-limb between 1/3 and
+        // 2/3, and each wake lies in the limb of its first angle
+        let dyn5 = DynatomicCover::new(5, 1);
+        let limbs = dyn5.limbs();
+        let half = Limb::new(RatAngle::new(1, 2));
+        assert_eq!(
+            limbs.vertices.values().map(Vec::len).sum::<usize>(),
+            dyn5.num_vertices()
+        );
+        assert_eq!(limbs.vertices[&half].len(), 10);
+        assert_eq!(
+            limbs.edges.values().map(Vec::len).sum::<usize>(),
+            dyn5.num_edges()
+        );
+        for (limb, ids) in &limbs.edges {
+            assert!(ids.iter().all(|&id| {
+                let (theta0, theta1) = dyn5.edges[id].wake.rat_angles();
+                limb.contains(theta0) && limb.contains(theta1)
+            }));
+        }
+
+        // The lamination over Per_2 leaves out the 1/2-limb, so only vertices lie there
+        let limbs = DynatomicCover::new(5, 2).limbs();
+        assert_eq!(limbs.vertices[&half].len(), 10);
+        assert!(!limbs.edges.contains_key(&half) && !limbs.faces.contains_key(&half));
+    }
+
     #[test]
     fn num_faces()
     {
@@ -135,7 +573,7 @@ mod tests
     #[test]
     fn kneading_sequence()
     {
-        PERIOD.set(6);
+        set_period(6);
         let point = AbstractPoint::new(IntAngle(13));
         let ks = point.kneading_sequence();
         assert_eq!(format!("{ks:6}"), "00110*");
@@ -149,7 +587,1689 @@ mod tests
     {
         let per1 = MarkedCycleCover::new(6, 1);
 
-        let tikz = TikzRenderer::new(per1.faces).generate();
+        let tikz = TikzRenderer::new(per1.faces()).generate();
         println!("{tikz}");
     }
+
+    #[test]
+    fn tikz_face_by_label()
+    {
+        let per1 = MarkedCycleCover::new(6, 1);
+        let label = per1.faces()[1].label;
+
+        let tikz = TikzRenderer::new(per1.faces()).draw_face_by_label(&label);
+        assert!(tikz.is_some_and(|s| s.contains(&format!(r"$\abr{{{}}}$", label.rep))));
+
+        let per1 = MarkedCycleCover::new(6, 1);
+        let missing = AbstractCycleClass::new_raw(AbstractPoint::new(IntAngle(0)));
+        assert!(TikzRenderer::new(per1.faces())
+            .draw_face_by_label(&missing)
+            .is_none());
+    }
+
+    #[test]
+    fn tikz_style()
+    {
+        let per1 = MarkedCycleCover::new(6, 1);
+        let style = TikzStyle {
+            edge_length: 2.0,
+            real_edge_style: "very thick".to_owned(),
+            font_size: Some(r"\small".to_owned()),
+            ..TikzStyle::default()
+        };
+
+        let tikz = TikzRenderer::new(per1.faces()).with_style(style).generate();
+
+        assert!(tikz.starts_with(r"\begin{tikzpicture}[every node/.style={font=\small}]"));
+        assert!(tikz.contains(r"\draw[very thick]"));
+        assert!(!tikz.contains("double distance"));
+    }
+
+    #[test]
+    fn face_coloring()
+    {
+        let per1 = MarkedCycleCover::new(6, 1);
+        let num_odd = per1.faces().iter().filter(|f| f.len() % 2 == 1).count();
+
+        let tikz = TikzRenderer::new(per1.faces())
+            .with_coloring(FaceColoring::Parity)
+            .generate();
+        assert_eq!(tikz.matches(r"\fill[blue!20]").count(), num_odd);
+        assert_eq!(tikz.matches(r"\fill[").count(), per1.faces().len());
+
+        let svg = SvgRenderer::new(per1.faces())
+            .with_coloring(FaceColoring::Class)
+            .generate();
+        assert_eq!(svg.matches("<polygon").count(), per1.faces().len());
+
+        let plain = TikzRenderer::new(per1.faces()).generate();
+        assert!(!plain.contains(r"\fill"));
+    }
+
+    #[test]
+    fn edge_identifications()
+    {
+        let cov = MarkedCycleCover::new(5, 1);
+
+        let mut layouts = layout_faces(&cov.faces(), 1.0);
+        orient_edges(&mut layouts);
+
+        // Both copies of an edge must point at the same endpoint
+        let mut heads: HashMap<usize, Vec<String>> = HashMap::new();
+        for face in &layouts {
+            for edge in &face.edges {
+                let (tail, head) = if edge.forward {
+                    (edge.source, edge.target)
+                } else {
+                    (edge.target, edge.source)
+                };
+                if face.nodes[tail].label != face.nodes[head].label {
+                    heads
+                        .entry(edge.id.expect("Face edges carry ids"))
+                        .or_default()
+                        .push(face.nodes[head].label.clone());
+                }
+            }
+        }
+        for copies in heads.values() {
+            assert_eq!(copies.len(), 2);
+            assert_eq!(copies[0], copies[1]);
+        }
+
+        let svg = SvgRenderer::new(cov.faces())
+            .identify_edges(true)
+            .generate();
+        assert_eq!(svg.matches("<polygon").count(), 2 * cov.edges.len());
+
+        let tikz = TikzRenderer::new(cov.faces())
+            .identify_edges(true)
+            .generate();
+        for id in 0..cov.edges.len() {
+            assert_eq!(tikz.matches(&format!("{{${id}$}}")).count(), 2);
+        }
+    }
+
+    #[test]
+    fn force_directed_layout()
+    {
+        let cov = MarkedCycleCover::new(6, 1);
+        let mean_edge_length = |graph: &GraphLayout| {
+            let total: f32 = graph
+                .edges
+                .iter()
+                .map(|e| {
+                    let (a, b) = (
+                        graph.nodes[e.source].position,
+                        graph.nodes[e.target].position,
+                    );
+                    (a.x - b.x).hypot(a.y - b.y)
+                })
+                .sum();
+            total / graph.edges.len() as f32
+        };
+
+        let circle = layout_graph(&cov.faces(), &cov.edges, 1.0);
+        let mut relaxed = circle.clone();
+        relaxed.relax(1.0, 100);
+
+        assert!(relaxed
+            .nodes
+            .iter()
+            .all(|v| v.position.x.is_finite() && v.position.y.is_finite()));
+        assert!(mean_edge_length(&relaxed) < mean_edge_length(&circle));
+
+        let csv = relaxed.coordinates_csv();
+        assert_eq!(csv.lines().count(), relaxed.nodes.len() + 1);
+        assert!(csv.starts_with("vertex,x,y"));
+    }
+
+    #[test]
+    fn tikz_pagination()
+    {
+        let cov = MarkedCycleCover::new(7, 1);
+        let width = 6.0;
+
+        let pages = paginate_faces(&cov.faces(), 1.46, width);
+        assert!(pages.len() > 1);
+        assert_eq!(pages.iter().map(Vec::len).sum::<usize>(), cov.faces().len());
+        for node in pages.iter().flatten().flat_map(|f| &f.nodes) {
+            assert!((-1e-3..=width + 1e-3).contains(&node.position.x));
+        }
+
+        let style = TikzStyle {
+            page_width: Some(width),
+            ..TikzStyle::default()
+        };
+        let doc = TikzRenderer::new(cov.faces())
+            .with_style(style)
+            .standalone(true)
+            .generate();
+        assert_eq!(doc.matches(r"\begin{tikzpicture}").count(), pages.len());
+        assert_eq!(doc.matches(r"\documentclass").count(), 1);
+    }
+
+    #[test]
+    fn dual_graph()
+    {
+        let cov = MarkedCycleCover::new(5, 1);
+        let dual = cov.dual_graph();
+        assert_eq!(dual.len(), cov.num_edges());
+        assert!(dual
+            .iter()
+            .all(|(_, (a, b))| *a < cov.num_faces() && *b < cov.num_faces()));
+
+        let dyn5 = DynatomicCover::new(5, 1);
+        assert_eq!(dyn5.dual_graph().len(), dyn5.num_edges());
+
+        let tikz = TikzRenderer::new(cov.faces())
+            .with_edges(cov.edges.clone())
+            .draw_dual_graph(true);
+        assert_eq!(tikz.matches(r"\node[draw=red").count(), cov.num_faces());
+        assert_eq!(tikz.matches(") to (dual-").count(), cov.num_edges());
+        assert_eq!(tikz.matches(") to (vertex-").count(), cov.num_edges());
+
+        let svg = SvgRenderer::new(cov.faces()).draw_dual_graph(false);
+        assert!(svg.contains(r#"fill="red""#));
+    }
+
+    #[test]
+    fn tikz_deterministic()
+    {
+        let cov = MarkedCycleCover::new(6, 1);
+        let mut reversed = cov.faces();
+        reversed.reverse();
+
+        let tikz = TikzRenderer::new(cov.faces())
+            .identify_edges(true)
+            .generate();
+        let tikz_reversed = TikzRenderer::new(reversed).identify_edges(true).generate();
+        assert_eq!(tikz, tikz_reversed);
+
+        for k in 0..cov.faces().len() {
+            assert!(tikz.contains(&format!("(face-{k})")));
+        }
+    }
+
+    #[test]
+    fn extreme_faces()
+    {
+        let cov = MarkedCycleCover::new(7, 1);
+        let mut sizes: Vec<usize> = cov.face_sizes().collect();
+        sizes.sort_unstable();
+
+        let tikz = TikzRenderer::new(cov.faces()).draw_extreme_faces(3, Extreme::Largest);
+        assert_eq!(tikz.matches(r"\node (face-").count(), 3);
+        let largest: usize = sizes.iter().rev().take(3).sum();
+        assert_eq!(tikz.matches(r"\node (node-").count(), largest);
+
+        let svg = SvgRenderer::new(cov.faces()).draw_extreme_faces(2, Extreme::Smallest);
+        assert!(svg.starts_with("<svg"));
+    }
+
+    #[test]
+    fn unfolded_net()
+    {
+        let cov = MarkedCycleCover::new(6, 1);
+        let close = |p: Point, q: Point| (p.x - q.x).hypot(p.y - q.y) < 1e-3;
+
+        let faces = cov.faces();
+        let net = faces_around_face(&faces, 0);
+        let layouts = layout_net(&net, 1.0, None);
+        assert_eq!(layouts.len(), net.len());
+
+        // Every unfolded face shares an edge, in position, with the central face
+        let root = &layouts[0];
+        for layout in &layouts[1..] {
+            assert!(layout.edges.iter().any(|e| {
+                root.edges.iter().any(|r| {
+                    let (a, b) = (
+                        layout.nodes[e.source].position,
+                        layout.nodes[e.target].position,
+                    );
+                    let (c, d) = (root.nodes[r.source].position, root.nodes[r.target].position);
+                    e.id == r.id && ((close(a, c) && close(b, d)) || (close(a, d) && close(b, c)))
+                })
+            }));
+        }
+
+        let label = faces[0].label;
+        let tikz = TikzRenderer::new(faces.clone())
+            .draw_net(&label)
+            .expect("Face exists");
+        assert_eq!(tikz.matches(r"\node (face-").count(), net.len());
+
+        let vertex = cov.vertices[0];
+        let star = faces_around_vertex(&faces, vertex);
+        let svg = SvgRenderer::new(faces.clone())
+            .draw_vertex_star(vertex)
+            .expect("Vertex exists");
+        assert!(svg.starts_with("<svg"));
+        let star_layouts = layout_net(&star, 1.0, Some(vertex));
+        assert_eq!(star_layouts.len(), star.len());
+
+        // The faces fan out around a single copy of the vertex
+        let label = vertex.to_string();
+        let pivots: Vec<Point> = star_layouts[0]
+            .nodes
+            .iter()
+            .filter(|n| n.label == label)
+            .map(|n| n.position)
+            .collect();
+        for layout in &star_layouts {
+            assert!(layout
+                .nodes
+                .iter()
+                .any(|n| n.label == label && pivots.iter().any(|&p| close(n.position, p))));
+        }
+    }
+
+    #[test]
+    fn parameter_rays()
+    {
+        // The rays at 1/3 and 2/3 approach the root of the period 2 component from either side
+        for (numer, sign) in [(1, 1.0), (2, -1.0)] {
+            let ray = external_ray(RatAngle::new(numer, 3), 40, 8);
+            let end = ray.last().expect("Ray is nonempty");
+            assert!((end - Complex64::new(-0.75, 0.0)).norm() < 0.1);
+            assert!(sign * end.im > 0.0);
+        }
+
+        let cov = MarkedCycleCover::new(3, 1);
+        let overlay = RayOverlay::new().with_size(60, 40).with_edges(&cov.edges);
+        assert_eq!(overlay.escape_time(Complex64::new(0.0, 0.0)), None);
+        assert_eq!(overlay.escape_time(Complex64::new(1.0, 1.0)), Some(2));
+
+        let mut svg = String::new();
+        {
+            let area = SVGBackend::with_string(&mut svg, overlay.size).into_drawing_area();
+            overlay.draw(&area).expect("Drawing succeeds");
+            area.present().expect("Drawing succeeds");
+        }
+        assert_eq!(svg.matches("<polyline").count(), 2 * cov.num_edges());
+    }
+
+    #[test]
+    fn tikz_standalone()
+    {
+        let per1 = MarkedCycleCover::new(6, 1);
+        let doc = TikzRenderer::new(per1.faces())
+            .standalone(true)
+            .draw_smallest_face();
+
+        assert!(doc.starts_with(r"\documentclass"));
+        assert!(doc.contains(r"\newcommand{\abr}"));
+        assert!(doc.contains(r"\newcommand{\del}"));
+        assert!(doc.trim_end().ends_with(r"\end{document}"));
+    }
+
+    #[test]
+    fn tikz_lamination()
+    {
+        let tikz = LaminationRenderer::new(Lamination::new(), 5)
+            .all_periods(true)
+            .color_by_period(true)
+            .geodesics(true)
+            .generate();
+
+        let num_leaves: usize = Lamination::new()
+            .into_arcs(5)
+            .iter()
+            .skip(2)
+            .map(Vec::len)
+            .sum();
+        assert_eq!(tikz.matches(" arc[").count(), num_leaves);
+        assert!(tikz.contains(r"\draw[red]"));
+    }
+
+    #[test]
+    fn tikz_pinched_disk()
+    {
+        let tikz = PinchedDiskRenderer::new(Lamination::new(), 6).generate();
+
+        let num_leaves: usize = Lamination::new()
+            .into_arcs(6)
+            .iter()
+            .skip(2)
+            .map(Vec::len)
+            .sum();
+        assert_eq!(tikz.matches(r"\draw[white").count(), num_leaves);
+        assert_eq!(tikz.matches(r"\fill (").count(), num_leaves);
+        assert!(tikz.contains(r"\fill[gray!40] (0, 0) circle (3);"));
+    }
+
+    #[test]
+    fn tikz_dynatomic()
+    {
+        let dyn5 = DynatomicCover::new(5, 1);
+        let num_faces = dyn5.num_faces();
+        let num_vertices = dyn5.num_vertices();
+        let faces = dyn5.faces();
+        assert_eq!(faces.len(), num_faces);
+
+        let tikz = TikzRenderer::new(faces.clone()).generate();
+        assert_eq!(tikz.matches(r"\node (face").count(), num_faces);
+        assert!(tikz.contains(r"\node (node-0-0)"));
+
+        let tikz = TikzRenderer::new(faces).with_edges(dyn5.edges).draw_graph();
+        assert_eq!(tikz.matches(r"\node").count(), num_vertices);
+    }
+
+    #[test]
+    fn tikz_graph()
+    {
+        let per1 = MarkedCycleCover::new(6, 1);
+        let num_vertices = per1.num_vertices();
+        let num_edges = per1.num_edges();
+
+        let tikz = TikzRenderer::new(per1.faces())
+            .with_edges(per1.edges)
+            .draw_graph();
+
+        assert_eq!(tikz.matches(r"\node").count(), num_vertices);
+        assert_eq!(tikz.matches(r"\draw").count(), num_edges);
+    }
+
+    #[test]
+    fn svg()
+    {
+        let per1 = MarkedCycleCover::new(6, 1);
+        let num_vertices = per1.num_vertices();
+
+        let svg = SvgRenderer::new(per1.faces())
+            .with_edges(per1.edges)
+            .draw_graph();
+
+        assert!(svg.starts_with("<svg"));
+        assert!(svg.ends_with("</svg>"));
+        assert_eq!(svg.matches("<text").count(), num_vertices);
+
+        let per1 = MarkedCycleCover::new(6, 1);
+        let svg = SvgRenderer::new(per1.faces()).draw_largest_face();
+        assert!(svg.contains(">&lt;"));
+    }
+
+    #[test]
+    fn typst()
+    {
+        let per1 = MarkedCycleCover::new(6, 1);
+        let largest = per1.face_sizes().max().unwrap();
+        let figure = TypstRenderer::new(per1.faces()).draw_largest_face();
+        assert!(figure.starts_with("#box(") && figure.ends_with("})"));
+        assert_eq!(figure.matches("text(").count(), largest + 1);
+
+        let figure = TypstRenderer::new(per1.faces())
+            .with_edges(per1.edges)
+            .standalone(true)
+            .draw_graph();
+        assert!(figure.starts_with("#set page("));
+        assert_eq!(figure.matches("text(").count(), per1.vertices.len());
+
+        let table = crate::typst::table(&["period", "genus"], &[vec!["5".into(), "2".into()]]);
+        assert!(table.contains("columns: 2"));
+        assert!(table.contains("table.header([*period*], [*genus*]),\n  [5], [2],"));
+    }
+
+    #[test]
+    fn dot()
+    {
+        let per1 = MarkedCycleCover::new(6, 1);
+        let dot = per1.to_dot(true);
+        assert!(dot.starts_with("graph \"MC\" {"));
+        assert!(dot.matches("[style=dashed]").count() > 0);
+        assert_eq!(dot.matches("subgraph cluster_").count(), per1.num_faces());
+
+        let dyn6 = DynatomicCover::new(6, 1);
+        let dot = dyn6.to_dot(false);
+        assert_eq!(
+            dot.matches("label=").count(),
+            dyn6.num_vertices() + dyn6.num_edges()
+        );
+    }
+
+    #[test]
+    fn json_summary()
+    {
+        let mc = MarkedCycleCover::new(5, 1);
+        let json = mc.to_json(false);
+        assert!(json.contains("\"curve\": \"MC\""));
+        assert!(json.contains(&format!("\"genus\": {}", mc.genus())));
+        assert!(!json.contains("\"cells\""));
+
+        let dyn4 = DynatomicCover::new(4, 1);
+        let json = dyn4.to_json(true);
+        assert_eq!(json.matches("\"degree\"").count(), dyn4.num_faces());
+        assert_eq!(
+            json.matches("\"kneading_sequence\"").count(),
+            dyn4.num_edges()
+        );
+    }
+
+    #[test]
+    fn json_roundtrip()
+    {
+        for crit_period in [1, 2] {
+            let mc = MarkedCycleCover::new(7, crit_period);
+            assert_eq!(MarkedCycleCover::from_json(&mc.to_json(true)), Ok(mc));
+            let dyn_cov = DynatomicCover::new(6, crit_period);
+            assert_eq!(
+                DynatomicCover::from_json(&dyn_cov.to_json(true)),
+                Ok(dyn_cov)
+            );
+        }
+
+        let mc = MarkedCycleCover::new(5, 1);
+        assert!(MarkedCycleCover::from_json(&mc.to_json(false)).is_err());
+        assert!(DynatomicCover::from_json(&mc.to_json(true)).is_err());
+        let json = mc.to_json(true);
+        assert!(
+            MarkedCycleCover::from_json(&json.replace("\"schema\": 1", "\"schema\": 99")).is_err()
+        );
+        let mut doc: serde_json::Value = serde_json::from_str(&json).unwrap();
+        doc["cells"]["faces"][0]["edges"][0] = 99.into();
+        assert!(MarkedCycleCover::from_json(&doc.to_string()).is_err());
+        let mut doc: serde_json::Value = serde_json::from_str(&json).unwrap();
+        doc["period"] = (MAX_ANGLE_PERIOD + 1).into();
+        assert!(MarkedCycleCover::from_json(&doc.to_string()).is_err());
+
+        // Documents nested past serde_json's recursion limit are rejected, not overflowed
+        let deep = format!("{}{}", "[".repeat(100_000), "]".repeat(100_000));
+        assert!(MarkedCycleCover::from_json(&deep).is_err());
+    }
+
+    #[cfg(feature = "wasm")]
+    #[test]
+    fn wasm_cover()
+    {
+        use crate::wasm::Cover;
+
+        let cover = Cover::new(6, 2, true).expect("valid periods");
+        let dyn6 = DynatomicCover::new(6, 2);
+        assert_eq!(
+            (cover.num_vertices(), cover.num_edges(), cover.num_faces()),
+            (dyn6.num_vertices(), dyn6.num_edges(), dyn6.num_faces())
+        );
+        assert_eq!(cover.genus(), dyn6.genus() as f64);
+        assert_eq!(cover.to_json(true), dyn6.to_json(true));
+
+        let read = Cover::from_json(&cover.to_json(true)).expect("valid JSON");
+        assert!(read.dynatomic());
+        assert_eq!((read.period(), read.crit_period()), (6, 2));
+        let mc = Cover::from_json(&MarkedCycleCover::new(5, 1).to_json(true));
+        assert!(mc.is_ok_and(|mc| !mc.dynatomic()));
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn ffi_counts()
+    {
+        use crate::ffi::{
+            marked_cycles_cover_counts, marked_cycles_cover_free, marked_cycles_cover_new,
+            marked_cycles_invariants, Counts, Invariants, MAX_COVER_PERIOD, MAX_DYNATOMIC_PERIOD,
+            MAX_PERIOD,
+        };
+
+        let mut invariants = Invariants::default();
+        assert!(unsafe { marked_cycles_invariants(9, 2, false, &mut invariants) });
+        let comb = marked_cycle::Comb::new(2);
+        assert_eq!(
+            (invariants.vertices, invariants.faces, invariants.genus),
+            (comb.vertices(9), comb.faces(9), comb.genus(9))
+        );
+        assert!(!unsafe { marked_cycles_invariants(9, 3, false, &mut invariants) });
+        assert!(!unsafe { marked_cycles_invariants(0, 1, true, &mut invariants) });
+        assert!(unsafe { marked_cycles_invariants(MAX_PERIOD, 1, false, &mut invariants) });
+        assert!(unsafe {
+            marked_cycles_invariants(MAX_DYNATOMIC_PERIOD, 2, true, &mut invariants)
+        });
+        assert!(!unsafe {
+            marked_cycles_invariants(MAX_DYNATOMIC_PERIOD + 1, 2, true, &mut invariants)
+        });
+        assert_eq!(MAX_PERIOD, marked_cycle::Comb::new(1).max_period());
+        assert_eq!(MAX_DYNATOMIC_PERIOD, dynatomic::Comb::new(1).max_period());
+
+        let cover = marked_cycles_cover_new(6, 1, true);
+        let mut counts = Counts::default();
+        assert!(unsafe { marked_cycles_cover_counts(cover, &mut counts) });
+        let dyn6 = DynatomicCover::new(6, 1);
+        assert_eq!(
+            (counts.vertices, counts.edges, counts.faces, counts.genus),
+            (
+                dyn6.num_vertices() as i64,
+                dyn6.num_edges() as i64,
+                dyn6.num_faces() as i64,
+                dyn6.genus()
+            )
+        );
+        unsafe { marked_cycles_cover_free(cover) };
+        assert!(marked_cycles_cover_new(MAX_COVER_PERIOD + 1, 1, false).is_null());
+        assert!(!unsafe { marked_cycles_cover_counts(core::ptr::null(), &mut counts) });
+    }
+
+    #[cfg(feature = "parquet")]
+    #[test]
+    fn columnar_tables()
+    {
+        use crate::columnar::{invariant_table, read_batches, ColumnarFormat, INVARIANT_COLUMNS};
+        use arrow_array::{cast::AsArray, types::Int64Type};
+
+        let comb = dynatomic::Comb::new(1);
+        let table = invariant_table(&comb, 2..=20);
+        assert_eq!(table.num_rows(), 19);
+        assert_eq!(table.num_columns(), INVARIANT_COLUMNS.len() + 1);
+        let genus = table
+            .column_by_name("genus")
+            .unwrap()
+            .as_primitive::<Int64Type>();
+        assert_eq!(genus.value(10), comb.genus(12));
+
+        let dyn6 = DynatomicCover::new(6, 2);
+        let cells = dyn6.to_columnar();
+        assert_eq!(cells.vertices.num_rows(), dyn6.num_vertices());
+        assert_eq!(cells.edges.num_rows(), dyn6.num_edges());
+        assert_eq!(cells.faces.num_rows(), dyn6.num_faces());
+
+        let dir =
+            std::env::temp_dir().join(format!("marked-cycles-columnar-{}", std::process::id()));
+        for format in [ColumnarFormat::Parquet, ColumnarFormat::Arrow] {
+            cells.write(&dir, "dyn_6_2", format).unwrap();
+            let path = dir.join(format!("dyn_6_2_faces.{}", format.extension()));
+            assert_eq!(
+                read_batches(path, format).unwrap(),
+                vec![cells.faces.clone()]
+            );
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn cover_matrices()
+    {
+        let check = |m: CoverMatrices, (v, e, f): (usize, usize, usize)| {
+            assert_eq!((m.boundary1.rows, m.boundary1.cols), (v, e));
+            assert_eq!((m.boundary2.rows, m.boundary2.cols), (e, f));
+            assert!(m.boundary1.mul(&m.boundary2).entries.is_empty());
+            // The faces are coherently oriented, so each edge borders them once each way
+            let ones = SparseMatrix::from_entries(f, 1, (0..f).map(|j| (j, 0, 1)));
+            assert!(m.boundary2.mul(&ones).entries.is_empty());
+            let degrees: i64 = m.adjacency.entries.iter().map(|e| e.2).sum();
+            assert_eq!(degrees, 2 * e as i64);
+        };
+        let mc = MarkedCycleCover::new(7, 1);
+        check(
+            mc.matrices(),
+            (mc.num_vertices(), mc.num_edges(), mc.num_faces()),
+        );
+        let dyn6 = DynatomicCover::new(6, 2);
+        check(
+            dyn6.matrices(),
+            (dyn6.num_vertices(), dyn6.num_edges(), dyn6.num_faces()),
+        );
+
+        let m = SparseMatrix::from_entries(2, 3, [(1, 2, 4), (0, 0, -1), (1, 2, -1)]);
+        assert_eq!(m.get(1, 2), 3);
+        assert_eq!(
+            m.to_matrix_market("test").lines().collect::<Vec<_>>(),
+            [
+                "%%MatrixMarket matrix coordinate integer general",
+                "% test",
+                "2 3 2",
+                "1 1 -1",
+                "2 3 3"
+            ]
+        );
+    }
+
+    #[cfg(feature = "npz")]
+    #[test]
+    fn npz_matrix()
+    {
+        use std::io::{Cursor, Read};
+
+        let m = SparseMatrix::from_entries(2, 3, [(1, 2, 3), (0, 0, -1)]);
+        let mut bytes = Cursor::new(Vec::new());
+        m.write_npz(&mut bytes).unwrap();
+        let mut archive = zip::ZipArchive::new(bytes).unwrap();
+        let mut names: Vec<&str> = archive.file_names().collect();
+        names.sort_unstable();
+        assert_eq!(
+            names,
+            ["col.npy", "data.npy", "format.npy", "row.npy", "shape.npy"]
+        );
+
+        let mut data = Vec::new();
+        archive
+            .by_name("data.npy")
+            .unwrap()
+            .read_to_end(&mut data)
+            .unwrap();
+        assert!(data.starts_with(b"\x93NUMPY\x01\x00"));
+        let header_len = usize::from(u16::from_le_bytes([data[8], data[9]]));
+        assert_eq!((10 + header_len) % 64, 0);
+        let header = std::str::from_utf8(&data[10..10 + header_len]).unwrap();
+        assert!(header.contains("'descr': '<i8'") && header.contains("'shape': (2,)"));
+        let values: Vec<i64> = data[10 + header_len..]
+            .chunks(8)
+            .map(|b| i64::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+        assert_eq!(values, [-1, 3]);
+    }
+
+    #[cfg(feature = "evcxr")]
+    #[test]
+    fn evcxr_html()
+    {
+        let dyn5 = DynatomicCover::new(5, 1);
+        let html = dyn5.to_html();
+        assert!(html.starts_with("<table>"));
+        assert!(html.contains(&format!("<th>genus</th><td>{}</td>", dyn5.genus())));
+        assert!(html.trim_end().ends_with("</svg>"));
+
+        let summary = JsonSummary::parse(&dyn5.to_json(false)).unwrap();
+        assert!(summary.to_html().contains("<th>faces</th>"));
+
+        let mut lamination = Lamination::new();
+        lamination.extend_to_period(4);
+        let svg = lamination.to_svg();
+        let leaves: usize = (2..=4).map(|per| lamination.arcs(per).unwrap().len()).sum();
+        assert_eq!(svg.matches("<line").count(), leaves);
+    }
+
+    #[test]
+    fn ndjson_cells()
+    {
+        let dyn5 = DynatomicCover::new(5, 1);
+        let mut buf = Vec::new();
+        dyn5.write_ndjson(&mut buf, &CellFilter::default()).unwrap();
+        let text = String::from_utf8(buf).unwrap();
+        assert_eq!(
+            text.lines().count(),
+            dyn5.num_vertices() + dyn5.num_edges() + dyn5.num_faces()
+        );
+        let lines: Vec<serde_json::Value> = text
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert!(lines
+            .iter()
+            .all(|line| line["curve"] == "Dyn" && line["period"] == 5));
+        let faces = lines.iter().filter(|line| line["cell"] == "face").count();
+        assert_eq!(faces, dyn5.num_faces());
+    }
+
+    #[test]
+    fn json_summary_roundtrip()
+    {
+        let dyn5 = DynatomicCover::new(5, 2);
+        for with_cells in [false, true] {
+            let summary = JsonSummary::parse(&dyn5.to_json(with_cells)).unwrap();
+            assert_eq!(summary.curve, "Dyn");
+            assert_eq!((summary.period, summary.crit_period), (5, 2));
+            assert_eq!(summary.vertices, dyn5.num_vertices());
+            assert_eq!(summary.edges, dyn5.num_edges());
+            assert_eq!(summary.faces, dyn5.num_faces());
+            assert_eq!(summary.genus, dyn5.genus());
+            assert_eq!(summary.face_sizes, dyn5.face_sizes());
+        }
+        assert!(JsonSummary::parse("{}").is_err());
+    }
+
+    #[test]
+    fn build_timed()
+    {
+        let (mc, timings) = MarkedCycleCoverBuilder::new(7, 2).build_timed();
+        assert_eq!(mc, MarkedCycleCover::new(7, 2));
+        assert_eq!(
+            timings.total(),
+            timings.phases().iter().map(|(_, t)| *t).sum()
+        );
+
+        let (dyn5, _) = DynatomicCoverBuilder::new(5, 1).build_timed();
+        assert_eq!(dyn5.face_sizes(), DynatomicCover::new(5, 1).face_sizes());
+    }
+
+    #[test]
+    fn config_file()
+    {
+        let config = Config::parse(
+            r#"
+            crit_period = 2 # comment
+            indent = 8
+            [tikz]
+            node_style = "draw, # not a comment"
+            fill_palette = ["red!10", "blue!10"]
+            page_width = false
+            edge_length = 2
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.crit_period, Some(2));
+        assert_eq!(config.indent, Some(8));
+        assert_eq!(config.format, None);
+        assert_eq!(config.tikz.node_style, "draw, # not a comment");
+        assert_eq!(config.tikz.fill_palette, ["red!10", "blue!10"]);
+        assert_eq!(config.tikz.page_width, None);
+        assert_eq!(config.tikz.edge_length, 2.0);
+        assert_eq!(config.tikz.edge_style, TikzStyle::default().edge_style);
+
+        assert!(Config::parse("indent = -1").is_err());
+        assert!(Config::parse("[tikz]\ncrit_period = 1").is_err());
+        assert!(Config::parse("[tikz]\npage_width = true").is_err());
+        assert!(Config::parse("\nformat = json")
+            .unwrap_err()
+            .contains("line 2"));
+
+        // Any valid TOML is read
+        let config = Config::parse(
+            r#"
+            format = 'json'
+            tikz = { node_style = 'draw', fill_palette = [
+                "red!10",
+                'blue!10',
+            ] }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.format.as_deref(), Some("json"));
+        assert_eq!(config.tikz.node_style, "draw");
+        assert_eq!(config.tikz.fill_palette, ["red!10", "blue!10"]);
+        assert_eq!(config.tikz.page_width, TikzStyle::default().page_width);
+    }
+
+    #[test]
+    fn cycle_positions()
+    {
+        for period in 1..=12 {
+            let context = Context::new(period);
+            let mut reps = Vec::new();
+            for theta in 0..context.max_angle.0 {
+                let orbit = get_orbit_in(context, IntAngle(theta));
+                let least = *orbit.iter().min().unwrap();
+                let position = cycle_position(context, IntAngle(theta));
+                assert_eq!(
+                    orbit_min_and_len_in(context, IntAngle(theta)),
+                    (least, orbit.len() as Period)
+                );
+                if orbit.len() == period as usize {
+                    let shift = orbit.iter().position(|&x| x == least).unwrap() as Period;
+                    assert_eq!(position, Some((least, (period - shift) % period)));
+                    if least.0 == theta {
+                        reps.push(least);
+                    }
+                } else {
+                    assert_eq!(position, None);
+                }
+            }
+            if period == 1 {
+                reps.push(IntAngle(1));
+            }
+            assert_eq!(cycle_reps(context), reps);
+
+            // The blocked scan agrees with looking up each angle on its own
+            let positions: Vec<_> = (0..=context.max_angle.0)
+                .filter_map(|theta| cycle_position(context, IntAngle(theta)))
+                .collect();
+            assert_eq!(crate::common::cycle_positions(context), positions);
+        }
+    }
+
+    #[test]
+    fn parallel_faces_deterministic()
+    {
+        let build_with = |threads: usize| {
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(threads)
+                .build()
+                .unwrap();
+            pool.install(|| (MarkedCycleCover::new(10, 1), DynatomicCover::new(8, 2)))
+        };
+        let (mc, dyn_cov) = build_with(1);
+        for _ in 0..3 {
+            let (mc_par, dyn_par) = build_with(8);
+            assert_eq!(mc_par, mc);
+            assert_eq!(dyn_par, dyn_cov);
+        }
+    }
+
+    #[test]
+    fn explicit_context()
+    {
+        let mc = MarkedCycleCover::new(6, 1);
+        let json = mc.to_json(true);
+        let csv = mc.to_csv();
+
+        // Building another cover leaves the ambient period alone, and neither changes the first
+        // cover's output
+        let ambient = Context::current();
+        let dyn_cov = DynatomicCover::new(4, 1);
+        assert_eq!(mc.period(), 6);
+        assert_eq!(dyn_cov.period(), 4);
+        assert_eq!(mc.to_json(true), json);
+        assert_eq!(mc.to_csv(), csv);
+        assert_eq!(Context::current(), ambient);
+
+        {
+            let _context = Context::new(8).enter();
+            assert_eq!(Context::current().max_angle, IntAngle(255));
+
+            // Edges carry their cover's period, and a cover formats its other cells in it,
+            // whatever the ambient one
+            let edge = &dyn_cov.edges[0];
+            assert_eq!(edge.kneading_sequence.to_string().len(), 4);
+            assert_eq!(edge.to_string(), dyn_cov.display(edge).to_string());
+            let v = dyn_cov.vertices[1];
+            assert_eq!(
+                format!("{:b}", dyn_cov.display(&v)),
+                format!("[{:04b}; {}]", v.rep.angle, v.shift)
+            );
+            assert_eq!(Context::current().period, 8);
+        }
+        assert_eq!(Context::current(), ambient);
+    }
+
+    #[test]
+    fn real_edges_outside_context()
+    {
+        // Whether an edge is real, and how it is shown, depend only on the edge, so they come
+        // out the same with the ambient period left at its default
+        let mc = MarkedCycleCover::new(6, 1);
+        let real = mc.edges.iter().filter(|e| e.is_real()).count();
+        assert_eq!(real, 4);
+        assert_eq!(mc.stats().num_real_edges, real);
+
+        let dyn5 = DynatomicCover::new(5, 1);
+        let real = dyn5.edges.iter().filter(|e| e.is_real()).count();
+        assert_eq!(real, 15);
+        assert_eq!(dyn5.stats().num_real_edges, real);
+
+        let edge = mc
+            .edges
+            .iter()
+            .find(|e| !e.is_real())
+            .expect("MC_6 has non-real edges");
+        let shown = edge.to_string();
+        assert!(shown.contains(" --- "));
+        assert!(shown.ends_with(&format!("KS = {}", edge.kneading_sequence)));
+        assert_eq!(edge.kneading_sequence.to_string().len(), 6);
+    }
+
+    #[test]
+    fn csv_tables()
+    {
+        let mc = MarkedCycleCover::new(6, 1);
+        let tables = mc.to_csv();
+        assert!(tables.vertices.starts_with(csv::VERTEX_COLUMNS));
+        assert_eq!(tables.vertices.lines().count(), mc.num_vertices() + 1);
+        assert_eq!(tables.edges.lines().count(), mc.num_edges() + 1);
+        assert_eq!(tables.faces.lines().count(), mc.num_faces() + 1);
+        let columns = csv::FACE_COLUMNS.split(',').count();
+        assert!(tables
+            .faces
+            .lines()
+            .all(|row| row.split(',').count() == columns));
+    }
+
+    #[test]
+    fn typed_records()
+    {
+        let dc = DynatomicCover::new(5, 2);
+        let records = dc.records();
+        assert_eq!(records.faces.len(), dc.num_faces());
+        let tables = records.to_tables(csv::Separator::Tab);
+        let faces: Vec<csv::FaceRecord> =
+            csv::read_records(tables.faces.as_bytes(), csv::Separator::Tab).unwrap();
+        assert_eq!(faces, records.faces);
+        assert!(faces.iter().all(|f| f.edge_ids.len() == f.size));
+
+        let comb = marked_cycle::Comb::new(1);
+        let mut bytes = Vec::new();
+        let invariants = csv::InvariantRecord::table(&comb, 2..=8);
+        csv::write_records(&mut bytes, &invariants, csv::Separator::Comma).unwrap();
+        let read: Vec<csv::InvariantRecord> =
+            csv::read_records(bytes.as_slice(), csv::Separator::Comma).unwrap();
+        assert_eq!(read, invariants);
+        assert_eq!(read[4].genus, MarkedCycleCover::new(6, 1).genus());
+    }
+
+    #[test]
+    fn progress_reports()
+    {
+        #[derive(Default)]
+        struct Recorder(std::sync::Mutex<Vec<(Phase, Option<usize>, usize)>>);
+
+        impl ProgressSink for Recorder
+        {
+            fn phase_started(&self, phase: Phase, total: Option<usize>)
+            {
+                self.0.lock().unwrap().push((phase, total, 0));
+            }
+
+            fn items_processed(&self, phase: Phase, count: usize)
+            {
+                let mut phases = self.0.lock().unwrap();
+                let last = phases.last_mut().unwrap();
+                assert_eq!(last.0, phase);
+                last.2 += count;
+            }
+        }
+
+        let recorder = std::sync::Arc::new(Recorder::default());
+        let lamination = Lamination::new().with_crit_period(1);
+        let mut builder = DynatomicCoverBuilder::new(7, 1)
+            .with_lamination(lamination)
+            .with_progress(recorder.clone());
+        let dc = builder.build();
+        assert_eq!(dc, DynatomicCover::new(7, 1));
+
+        let phases = recorder.0.lock().unwrap().clone();
+        let order: Vec<Phase> = phases.iter().map(|p| p.0).collect();
+        assert_eq!(
+            order,
+            [Phase::Lamination, Phase::Cycles, Phase::Edges, Phase::Faces]
+        );
+        assert_eq!(phases[0].1, Some(6));
+        assert_eq!(phases[0].2, 6);
+        assert_eq!(phases[1].2, dc.num_vertices());
+        assert_eq!(phases[3].1, Some(dc.num_vertices()));
+        assert_eq!(phases[3].2, dc.num_vertices());
+    }
+
+    #[test]
+    fn ribbon_graph()
+    {
+        // Two loops at one vertex, crossing each other: a torus with one square face
+        let torus = RibbonGraph::from_endpoints(1, &[(0, 0), (0, 0)], &[vec![0, 1, 0, 1]]).unwrap();
+        assert_eq!(torus.counts(), (1, 2, 1, 1));
+        assert_eq!(torus.faces[0].edge_ids, vec![0, 1, 0, 1]);
+
+        // A triangle and a disjoint digon, each bounding two faces of a sphere
+        let edges = [(0, 1), (1, 2), (2, 0), (3, 4), (4, 3)];
+        let rotations = [vec![0, 2], vec![1, 0], vec![2, 1], vec![3, 4], vec![4, 3]];
+        let spheres = RibbonGraph::from_endpoints(5, &edges, &rotations).unwrap();
+        assert_eq!(spheres.counts(), (5, 5, 4, 0));
+        assert_eq!(spheres.num_components(), 2);
+        assert_eq!(spheres.triangulation().euler_characteristic(), 4);
+        let svg = SvgRenderer::new(spheres.augmented_faces())
+            .with_edges(spheres.edges.clone())
+            .draw_graph();
+        assert!(svg.starts_with("<svg"));
+
+        assert!(RibbonGraph::from_endpoints(2, &[(0, 1)], &[vec![0], vec![]]).is_err());
+        assert!(RibbonGraph::from_endpoints(2, &[(0, 1)], &[vec![0, 0], vec![0]]).is_err());
+    }
+
+    #[test]
+    fn cover_cache()
+    {
+        let dir = std::env::temp_dir().join(format!("marked-cycles-cache-{}", std::process::id()));
+        let cache = CoverCache::new(&dir);
+
+        let mut comb = marked_cycle::Comb::new(1).with_cache(cache.clone());
+        let built = MarkedCycleCover::new(7, 1);
+        assert_eq!(*comb.curve(7), built);
+        let path = cache.path::<MarkedCycleCover>(7, 1);
+        assert!(path.exists());
+        assert_eq!(cache.load(7, 1).unwrap(), Some(built));
+        assert_eq!(cache.load::<MarkedCycleCover>(8, 1).unwrap(), None);
+
+        let mut comb = dynatomic::Comb::new(2).with_cache(cache.clone());
+        let built = DynatomicCover::new(6, 2);
+        assert_eq!(*comb.curve(6), built);
+        assert_eq!(cache.load(6, 2).unwrap(), Some(built));
+
+        // A damaged file is rebuilt and replaced
+        std::fs::write(&path, b"not a cover").unwrap();
+        assert!(cache.load::<MarkedCycleCover>(7, 1).is_err());
+        assert_eq!(
+            cache.get_or_build(7, 1, || MarkedCycleCover::new(7, 1)),
+            MarkedCycleCover::new(7, 1)
+        );
+        assert!(cache.load::<MarkedCycleCover>(7, 1).unwrap().is_some());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn snapshots()
+    {
+        let dir = std::env::temp_dir().join(format!("marked-cycles-snap-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mc = MarkedCycleCover::new(8, 2);
+        save_snapshot(dir.join("mc"), &mc).unwrap();
+        assert_eq!(
+            load_snapshot::<MarkedCycleCover>(dir.join("mc")).unwrap(),
+            mc
+        );
+        assert!(load_snapshot::<DynatomicCover>(dir.join("mc")).is_err());
+
+        // A restored lamination extends just as the original would
+        let mut lamination = Lamination::new().with_crit_period(2);
+        lamination.extend_to_period(9);
+        save_snapshot(dir.join("lam"), &lamination).unwrap();
+        let mut restored: Lamination = load_snapshot(dir.join("lam")).unwrap();
+        assert_eq!(restored, lamination);
+        lamination.extend_to_period(11);
+        restored.extend_to_period(11);
+        assert_eq!(restored, lamination);
+
+        // Flipping any byte of the contents is caught
+        let mut bytes = std::fs::read(dir.join("mc")).unwrap();
+        let middle = bytes.len() / 2;
+        bytes[middle] ^= 1;
+        std::fs::write(dir.join("mc"), bytes).unwrap();
+        assert!(load_snapshot::<MarkedCycleCover>(dir.join("mc")).is_err());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn bounded_comb()
+    {
+        let mut comb = marked_cycle::Comb::new(1).with_max_curves(2);
+        comb.curve(5);
+        comb.curve(6);
+        comb.curve(7);
+        assert!(comb.holds(5) && !comb.holds(6) && comb.holds(7));
+        assert_eq!(comb.cover_faces(6) as i64, comb.faces(6));
+        assert!(!comb.holds(6));
+        assert_eq!(comb.cover_genus(8), comb.genus(8));
+        assert!(comb.holds(5) && !comb.holds(7) && comb.holds(8));
+
+        let mut comb = dynatomic::Comb::new(1).with_max_curves(0);
+        assert_eq!(comb.cover_vertices(5) as i64, comb.vertices(5));
+        assert_eq!(comb.cover_edges(6) as i64, comb.edges(6));
+        assert!(!comb.holds(5) && comb.holds(6));
+    }
+
+    #[test]
+    fn sweep()
+    {
+        for crit_period in [1, 2] {
+            for (cover, period) in MarkedCycleCover::sweep(3..10, crit_period).zip(3..) {
+                assert_eq!(cover, MarkedCycleCover::new(period, crit_period));
+            }
+            for (cover, period) in DynatomicCover::sweep(3..9, crit_period).zip(3..) {
+                assert_eq!(cover, DynatomicCover::new(period, crit_period));
+            }
+        }
+
+        // A lamination extended past the period works too
+        let mut lamination = Lamination::new();
+        lamination.extend_to_period(10);
+        let mut builder = MarkedCycleCoverBuilder::new(7, 1).with_lamination(lamination.clone());
+        assert_eq!(builder.build(), MarkedCycleCover::new(7, 1));
+        assert_eq!(builder.take_lamination(), Some(lamination));
+    }
+
+    #[test]
+    fn streaming_build()
+    {
+        let cover = MarkedCycleCover::new(8, 1);
+        let (tx, rx) = std::sync::mpsc::channel();
+        let builder = std::thread::spawn(move || {
+            MarkedCycleCoverBuilder::new(8, 1).build_streaming(&tx);
+        });
+        let (mut vertices, mut edges, mut faces) = (Vec::new(), Vec::new(), Vec::new());
+        for cell in rx {
+            match cell {
+                Cell::Vertex(v) => vertices.push(v),
+                Cell::Edge(e) => edges.push(e),
+                Cell::Face(f) => faces.push(f),
+            }
+        }
+        builder.join().unwrap();
+
+        assert_eq!(vertices, cover.vertices);
+        assert_eq!(edges.len(), cover.num_edges());
+        faces.sort_by_key(|f| f.label);
+        assert_eq!(faces.len(), cover.num_faces());
+        for (face, expected) in faces.iter().zip(cover.faces()) {
+            assert_eq!(face.vertices, expected.vertices);
+            let wakes = |edges: &[MCEdge], face: &MCFace| -> Vec<_> {
+                face.edge_ids
+                    .iter()
+                    .map(|&i| edges[i].wake.clone())
+                    .collect()
+            };
+            assert_eq!(wakes(&edges, face), wakes(&cover.edges, &expected));
+        }
+
+        let cover = DynatomicCover::new(6, 2);
+        let (tx, rx) = std::sync::mpsc::channel();
+        std::thread::scope(|s| {
+            s.spawn(move || DynatomicCoverBuilder::new(6, 2).build_streaming(&tx));
+            let mut labels: Vec<_> = rx
+                .iter()
+                .filter_map(|cell| match cell {
+                    Cell::Face(f) => Some(f.label),
+                    _ => None,
+                })
+                .collect();
+            labels.sort_unstable();
+            let mut expected: Vec<_> = cover.faces().into_iter().map(|f| f.label).collect();
+            expected.sort_unstable();
+            assert_eq!(labels, expected);
+        });
+    }
+
+    #[cfg(feature = "stats")]
+    #[global_allocator]
+    static ALLOC: crate::stats::CountingAllocator = crate::stats::CountingAllocator;
+
+    #[cfg(feature = "stats")]
+    #[test]
+    fn build_stats()
+    {
+        let (cover, timings) = DynatomicCoverBuilder::new(7, 1).build_timed();
+        let stats = timings.stats;
+        assert_eq!(
+            (stats.num_vertices, stats.num_edges, stats.num_faces),
+            (cover.num_vertices(), cover.num_edges(), cover.num_faces())
+        );
+        assert!(stats.edges.allocations > 0 && stats.faces.allocations > 0);
+        assert!(stats.peak_bytes() > 0);
+    }
+
+    #[test]
+    fn map_periods()
+    {
+        let periods = [6, 3, 8, 5];
+        for crit_period in [1, 2] {
+            let counts: Vec<_> = periods
+                .iter()
+                .map(|&period| MarkedCycleCover::new(period, crit_period).counts())
+                .collect();
+            assert_eq!(
+                MarkedCycleCover::counts_for_periods(&periods, crit_period),
+                counts
+            );
+
+            let genera = DynatomicCover::map_periods(&periods, crit_period, DynatomicCover::genus);
+            for (genus, &period) in genera.into_iter().zip(&periods) {
+                assert_eq!(genus, DynatomicCover::new(period, crit_period).genus());
+            }
+        }
+    }
+
+    #[test]
+    fn parse_angles()
+    {
+        let third = RatAngle::new(1, 3);
+        assert_eq!(parse_angle("1/(2^2-1)"), Ok(third));
+        assert_eq!(parse_angle("5/15"), Ok(third));
+        assert_eq!(parse_angle("01"), Ok(third));
+        assert_eq!(parse_angle("0b0101"), Ok(third));
+        assert_eq!(parse_angle("4/3"), Ok(third));
+        assert!(parse_angle("1/0").is_err());
+        assert!(parse_angle("one third").is_err());
+
+        assert_eq!(doubling_period(third), Some(2));
+        assert_eq!(doubling_period(RatAngle::new(6, 31)), Some(5));
+        assert_eq!(doubling_period(RatAngle::new(0, 1)), Some(1));
+        assert_eq!(doubling_period(RatAngle::new(1, 4)), None);
+    }
+
+    #[test]
+    fn face_containing()
+    {
+        for (crit_period, period) in [1, 2].into_iter().flat_map(|c| (3..9).map(move |p| (c, p))) {
+            let mc = MarkedCycleCover::new(period, crit_period);
+            let context = mc.context;
+            let mut sheets: HashMap<AbstractCycleClass, Period> = HashMap::new();
+            for &cycle in &mc.vertices {
+                let face = mc
+                    .face_containing(cycle)
+                    .expect("every cycle lies on a face");
+                *sheets.entry(face.label).or_default() += 1;
+                assert_eq!(
+                    AbstractCycle::new_compute_in(cycle.rep.rotate_in(1, context), context),
+                    cycle,
+                    "cycle representative is not orbit-minimal"
+                );
+            }
+            for face in &mc.faces() {
+                assert_eq!(sheets.get(&face.label).copied(), Some(face.degree));
+            }
+        }
+    }
+
+    #[test]
+    fn graphml()
+    {
+        let mc = MarkedCycleCover::new(6, 1);
+        let xml = mc.to_graphml(true);
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.trim_end().ends_with("</graphml>"));
+        assert_eq!(
+            xml.matches("<node ").count(),
+            mc.num_vertices() + mc.num_faces()
+        );
+        assert_eq!(xml.matches(">edge</data>").count(), mc.num_edges());
+        assert!(!xml.contains("<data key=\"label\"><"));
+
+        let dyn5 = DynatomicCover::new(5, 1);
+        let xml = dyn5.to_graphml(false);
+        assert_eq!(xml.matches("<node ").count(), dyn5.num_vertices());
+        assert_eq!(
+            xml.matches("<data key=\"real\">true</data>").count(),
+            dyn5.edges.iter().filter(|e| e.is_real()).count()
+        );
+    }
+
+    #[test]
+    fn gexf()
+    {
+        let mc = MarkedCycleCover::new(6, 1);
+        let xml = mc.to_gexf(true);
+        assert!(xml.contains(r#"<gexf xmlns="http://gexf.net/1.3" version="1.3">"#));
+        assert!(xml.trim_end().ends_with("</gexf>"));
+        assert_eq!(
+            xml.matches("<node ").count(),
+            mc.num_vertices() + mc.num_faces()
+        );
+        assert_eq!(
+            xml.matches(r#"<attvalue for="kind" value="edge"/>"#)
+                .count(),
+            mc.num_edges()
+        );
+        assert_eq!(
+            xml.matches("<edge ").count(),
+            mc.num_edges() + xml.matches(r#"value="boundary""#).count()
+        );
+        assert!(xml.contains(r#"<attribute id="kneading_sequence" title="kneading_sequence""#));
+
+        let dyn5 = DynatomicCover::new(5, 1);
+        let xml = dyn5.to_gexf(false);
+        assert_eq!(xml.matches("<node ").count(), dyn5.num_vertices());
+        assert!(!xml.contains("boundary"));
+    }
+
+    #[test]
+    fn triangulation()
+    {
+        // Every edge of a closed oriented surface lies on two triangles, once in each direction
+        fn check(triangulation: &Triangulation, genus: i64)
+        {
+            let mut directed = HashMap::new();
+            for &[a, b, c] in &triangulation.triangles {
+                assert!(a != b && b != c && c != a);
+                for edge in [(a, b), (b, c), (c, a)] {
+                    *directed.entry(edge).or_insert(0) += 1;
+                }
+            }
+            for (&(a, b), &count) in &directed {
+                assert_eq!(count, 1);
+                assert_eq!(directed.get(&(b, a)), Some(&1));
+            }
+            assert_eq!(triangulation.euler_characteristic(), 2 - 2 * genus);
+        }
+
+        for (period, crit_period) in [(3, 1), (5, 2), (8, 1)] {
+            let mc = MarkedCycleCover::new(period, crit_period);
+            check(&mc.triangulation(), mc.genus());
+        }
+        for (period, crit_period) in [(4, 1), (6, 2)] {
+            let dyn_cover = DynatomicCover::new(period, crit_period);
+            check(&dyn_cover.triangulation(), dyn_cover.genus());
+        }
+
+        let script = MarkedCycleCover::new(5, 1).to_polymake();
+        assert!(script.contains("new SimplicialComplex(FACETS => [\n["));
+        assert!(script.contains("MC_5(Per_1)"));
+    }
+
+    #[test]
+    fn mesh()
+    {
+        let mc = MarkedCycleCover::new(6, 1);
+        let mesh = mc.mesh(50);
+        assert_eq!(mesh.positions.len(), mc.triangulation().num_vertices);
+        assert!(mesh.positions.iter().flatten().all(|x| x.is_finite()));
+        assert_eq!(mesh, mc.mesh(50));
+
+        let obj = mc.to_obj(50);
+        assert_eq!(
+            obj.lines().filter(|l| l.starts_with("v ")).count(),
+            mesh.positions.len()
+        );
+        let faces: Vec<&str> = obj.lines().filter(|l| l.starts_with("f ")).collect();
+        assert_eq!(faces.len(), mesh.triangles.len());
+        assert_eq!(faces[0], {
+            let [a, b, c] = mesh.triangles[0];
+            format!("f {} {} {}", a + 1, b + 1, c + 1)
+        });
+
+        let ply = DynatomicCover::new(5, 1).to_ply(10);
+        let header_end = ply.lines().position(|l| l == "end_header").unwrap();
+        let vertices: usize = ply
+            .lines()
+            .find_map(|l| l.strip_prefix("element vertex "))
+            .and_then(|n| n.parse().ok())
+            .unwrap();
+        let faces = ply.lines().skip(header_end + 1 + vertices);
+        assert!(faces.clone().count() > 0);
+        assert!(faces.into_iter().all(|l| l.starts_with("3 ")));
+    }
+
+    #[cfg(feature = "petgraph")]
+    #[test]
+    fn petgraph()
+    {
+        use petgraph::algo::connected_components;
+        use petgraph::graph::{DiGraph, EdgeIndex, UnGraph};
+
+        let mc = MarkedCycleCover::new(7, 1);
+        let graph = UnGraph::<_, _>::from(&mc);
+        assert_eq!(graph.node_count(), mc.num_vertices());
+        assert_eq!(graph.edge_count(), mc.num_edges());
+        assert_eq!(connected_components(&graph), 1);
+        assert_eq!(graph.node_weight(0.into()), mc.vertices.first());
+
+        let dyn5 = DynatomicCover::new(5, 1);
+        let graph = DiGraph::<_, _>::from(&dyn5);
+        assert_eq!(graph.node_count(), dyn5.num_vertices());
+        assert_eq!(graph.edge_count(), dyn5.num_edges());
+        let e = &dyn5.edges[0];
+        let (i, j) = graph.edge_endpoints(0.into()).unwrap();
+        assert_eq!((graph[i], graph[j]), (e.start, e.end));
+        assert_eq!(graph[EdgeIndex::new(0)], e.wake);
+    }
+
+    #[test]
+    fn cell_filter()
+    {
+        let mc = MarkedCycleCover::new(7, 1);
+        let context = mc.context;
+        let filter = CellFilter {
+            vertex: Some(RatAngle::new(1, 127)),
+            ..CellFilter::default()
+        };
+        let one = AbstractCycle::new_compute_in(AbstractPoint::new(IntAngle(1)), context);
+        assert_eq!(
+            mc.vertices
+                .iter()
+                .filter(|v| filter.keeps_vertex(*v, context))
+                .count(),
+            1
+        );
+        assert!(mc
+            .edges
+            .iter()
+            .filter(|e| filter.keeps_edge(e))
+            .all(|e| e.start == one || e.end == one));
+        assert!(mc.faces().iter().any(|f| filter.keeps_face(f, context)));
+
+        let filter = CellFilter {
+            wake: Some(RatAngle::new(1, 2)),
+            ..CellFilter::default()
+        };
+        assert!(mc
+            .edges
+            .iter()
+            .filter(|e| filter.keeps_edge(e))
+            .all(Edge::is_real));
+
+        let filter = CellFilter {
+            min_size: Some(10),
+            reflexive: true,
+            ..CellFilter::default()
+        };
+        assert!(mc
+            .faces()
+            .iter()
+            .filter(|f| filter.keeps_face(f, context))
+            .all(|f| f.len() >= 10 && f.is_reflexive()));
+        assert!(CellFilter::default().is_empty());
+    }
+
+    #[test]
+    fn cell_listing()
+    {
+        let mc = MarkedCycleCover::new(8, 1);
+        let listing = CellListing {
+            sort: Some(CellOrder::Size),
+            limit: Some(5),
+        };
+        let all_faces = mc.faces();
+        let mut faces: Vec<_> = all_faces.iter().collect();
+        assert_eq!(listing.arrange(&mut faces, mc.context), all_faces.len() - 5);
+        assert_eq!(faces.len(), 5);
+        assert!(faces.windows(2).all(|w| w[0].len() <= w[1].len()));
+        assert_eq!(faces[0].len(), mc.face_sizes().min().unwrap());
+
+        let listing = CellListing {
+            sort: Some(CellOrder::Angle),
+            limit: None,
+        };
+        let mut edges: Vec<_> = mc.edges.iter().collect();
+        assert_eq!(listing.arrange(&mut edges, mc.context), 0);
+        assert!(edges
+            .windows(2)
+            .all(|w| w[0].wake.angle0 <= w[1].wake.angle0));
+        assert!(listing.shows_all(mc.edges.len()));
+    }
+
+    #[test]
+    fn compact_faces()
+    {
+        let mc = MarkedCycleCover::new(7, 1);
+        let faces = mc.faces();
+        assert_eq!(faces.len(), mc.compact_faces.len());
+        for (face, compact) in faces.iter().zip(&mc.compact_faces) {
+            assert_eq!(face.label, compact.label);
+            for (v, id) in face.vertices.iter().zip(&compact.vertices) {
+                assert_eq!(v.vertex, mc.vertices[id.vertex as usize]);
+                assert_eq!(v.data, id.data);
+            }
+        }
+        assert!(
+            size_of::<AugmentedVertex<VertexId>>() < size_of::<AugmentedVertex<AbstractCycle>>()
+        );
+
+        let dc = DynatomicCover::new(5, 1);
+        let resolved = dc.resolve(&dc.satellite_faces[0]);
+        assert_eq!(
+            resolved.vertices[0],
+            dc.vertices[dc.satellite_faces[0].vertices[0] as usize]
+        );
+    }
+
+    #[test]
+    fn summarize_to()
+    {
+        let mc = MarkedCycleCover::new(4, 1);
+        let mut out = Vec::new();
+        mc.summarize_to(&mut out, &SummaryOptions::default())
+            .unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("\n3 vertices:\n    (1)\n"));
+        assert!(text.ends_with("Genus is 0\n"));
+
+        let opts = SummaryOptions {
+            indent: 2,
+            binary: true,
+            ..SummaryOptions::default()
+        };
+        let dc = DynatomicCover::new(4, 1);
+        let mut out = Vec::new();
+        dc.summarize_to(&mut out, &opts).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.contains(&format!("\nGenus is {}\n", dc.genus())));
+        assert!(text.starts_with("\n12 vertices:\n  0001\n"));
+    }
+
+    #[test]
+    fn cover_stats()
+    {
+        for crit_period in [1, 2] {
+            let mc = MarkedCycleCover::new(8, crit_period);
+            let stats = mc.stats();
+            let (vertices, edges, faces, genus) = mc.counts();
+            assert_eq!(
+                (
+                    stats.num_vertices,
+                    stats.num_edges,
+                    stats.num_faces,
+                    stats.genus
+                ),
+                (vertices, edges, faces, genus)
+            );
+            assert_eq!(stats.face_sizes.values().sum::<usize>(), faces);
+            assert_eq!(stats.max_face(), mc.face_sizes().max().unwrap());
+            assert_eq!(stats.min_face(), mc.face_sizes().min().unwrap());
+            assert_eq!(stats.vertex_degrees.values().sum::<usize>(), vertices);
+            let degree_sum: usize = stats.vertex_degrees.iter().map(|(d, n)| d * n).sum();
+            assert_eq!(degree_sum, 2 * edges);
+            assert_eq!(stats.num_odd_irreflexive, mc.num_odd_faces_irreflexive());
+            assert_eq!(
+                stats.num_reflexive + stats.irreflexive_face_sizes.values().sum::<usize>(),
+                faces
+            );
+            let num_real = mc.edges.iter().filter(|e| e.is_real()).count();
+            assert_eq!(stats.num_real_edges, num_real);
+
+            let dc = DynatomicCover::new(6, crit_period);
+            let cover: &dyn Cover = &dc;
+            let stats = cover.stats();
+            assert_eq!(stats.num_odd, dc.num_odd_faces());
+            assert_eq!(stats.num_faces, dc.num_faces());
+            let degree_sum: usize = stats.vertex_degrees.iter().map(|(d, n)| d * n).sum();
+            assert_eq!(degree_sum, 2 * dc.num_edges());
+        }
+    }
+
+    #[test]
+    fn face_canonicalize()
+    {
+        let mc = MarkedCycleCover::new(7, 1);
+        let mut seen = std::collections::HashSet::new();
+        for face in &mc.compact_faces {
+            for (start, end, id) in face.boundary_edges() {
+                let edge = &mc.edges[id];
+                let ends = [
+                    mc.vertices[start.vertex as usize],
+                    mc.vertices[end.vertex as usize],
+                ];
+                assert!(ends == [edge.start, edge.end] || ends == [edge.end, edge.start]);
+                assert!(face.contains(start));
+            }
+            assert_eq!(face.boundary_edges().count(), face.len());
+
+            let mut canonical = face.clone();
+            canonical.canonicalize();
+            assert_eq!(canonical.vertices[0], *face.vertices.iter().min().unwrap());
+            for shift in 1..face.len() {
+                let mut rotated = face.clone();
+                rotated.vertices.rotate_left(shift);
+                rotated.edge_ids.rotate_left(shift);
+                rotated.canonicalize();
+                assert_eq!(rotated, canonical);
+            }
+            assert!(seen.insert(canonical));
+        }
+    }
+
+    #[test]
+    fn fraction_display()
+    {
+        let mc = MarkedCycleCover::new(4, 1);
+        assert_eq!(format!("{:#}", mc.display(&mc.vertices[1])), "(3/15)");
+        assert_eq!(format!("{:#}", mc.edges[0].wake), "3/15 <-> 4/15");
+
+        let opts = SummaryOptions {
+            fractions: true,
+            ..SummaryOptions::default()
+        };
+        let mut out = Vec::new();
+        mc.summarize_to(&mut out, &opts).unwrap();
+        let text = String::from_utf8(out).unwrap();
+        assert!(text.starts_with("\n3 vertices:\n    (1/15)\n"));
+        assert!(text.contains("wake:  3/15 <-> 4/15 "));
+        assert!(text.contains("\n    <1/15> = (+(1/15) -(3/15) +(7/15) ===); deg = 2\n"));
+    }
+
+    #[test]
+    fn canonical_order()
+    {
+        for crit_period in [1, 2] {
+            let mc = MarkedCycleCover::new(9, crit_period);
+            assert!(mc.vertices.is_sorted());
+            assert!(mc
+                .edges
+                .is_sorted_by_key(|e| (e.wake.angle0, e.wake.angle1)));
+            assert!(mc.compact_faces.is_sorted_by_key(|f| f.label));
+
+            let dc = DynatomicCover::new(8, crit_period);
+            assert!(dc
+                .edges
+                .is_sorted_by_key(|e| (e.wake.angle0, e.wake.angle1, e.start)));
+            assert!(dc.primitive_faces.is_sorted_by_key(|f| f.label));
+            assert!(dc.satellite_faces.is_sorted_by_key(|f| f.label));
+        }
+    }
+
+    #[test]
+    fn face_edge_ids()
+    {
+        for period in 3..9 {
+            let mc = MarkedCycleCover::new(period, 1);
+            let mut uses = vec![0; mc.num_edges()];
+            for face in &mc.faces() {
+                let n = face.len();
+                for (i, &id) in face.edge_ids.iter().enumerate() {
+                    let (u, v) = (face.vertices[i].vertex, face.vertices[(i + 1) % n].vertex);
+                    let e = &mc.edges[id];
+                    assert!((e.start, e.end) == (u, v) || (e.start, e.end) == (v, u));
+                    uses[id] += 1;
+                }
+            }
+            assert!(uses.iter().all(|&k| k == 2), "MC_{period}");
+
+            let dc = DynatomicCover::new(period, 1);
+            let mut uses = vec![0; dc.num_edges()];
+            let faces = dc
+                .primitive_faces
+                .iter()
+                .map(|f| (&f.vertices, &f.edge_ids))
+                .chain(
+                    dc.satellite_faces
+                        .iter()
+                        .map(|f| (&f.vertices, &f.edge_ids)),
+                );
+            for (vertices, edge_ids) in faces {
+                let n = vertices.len();
+                for (i, &id) in edge_ids.iter().enumerate() {
+                    let (u, v) = (
+                        dc.vertices[vertices[i] as usize],
+                        dc.vertices[vertices[(i + 1) % n] as usize],
+                    );
+                    let e = &dc.edges[id];
+                    assert!((e.start, e.end) == (u, v) || (e.start, e.end) == (v, u));
+                    uses[id] += 1;
+                }
+            }
+            assert!(uses.iter().all(|&k| k == 2), "Dyn_{period}");
+        }
+    }
+
+    #[test]
+    fn fundamental_polygon()
+    {
+        for period in 4..10 {
+            let mc = MarkedCycleCover::new(period, 2);
+            let polygon = mc.fundamental_polygon();
+
+            let glued_edges = mc.num_faces() - 1;
+            assert_eq!(polygon.num_sides(), 2 * (mc.num_edges() - glued_edges));
+            assert!(polygon
+                .pairing()
+                .iter()
+                .all(|&(i, j)| polygon.is_orientation_reversing(i, j)));
+        }
+
+        let tikz =
+            FundamentalPolygonRenderer::new(MarkedCycleCover::new(5, 1).fundamental_polygon())
+                .generate();
+        assert!(tikz.contains(r"\arrow{<}"));
+    }
 }
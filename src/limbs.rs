@@ -0,0 +1,152 @@
+use crate::arithmetic::gcd;
+use crate::common::cells::{CellKey, Edge};
+use crate::global_state::Context;
+use crate::types::{Period, RatAngle};
+use alloc::{collections::BTreeMap, vec::Vec};
+
+/// The `p/q`-limb of the main cardioid: the part of the parameter plane cut off by the two
+/// parameter rays landing at the root of the `p/q`-satellite. Over `Per_2`, whose lamination
+/// leaves out the `1/2`-limb, the others are the limbs of the central component in the same
+/// angles; vertices may still mark angles in the `1/2`-limb, but no edges lie there.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Limb
+{
+    pub rotation_number: RatAngle,
+}
+
+impl Limb
+{
+    #[must_use]
+    pub const fn new(rotation_number: RatAngle) -> Self
+    {
+        Self { rotation_number }
+    }
+
+    /// The angles bounding the wake of the limb. They are two adjacent angles of the cycle of
+    /// period `q` on which doubling acts as rotation by `p/q`: the `k`-th binary digit of the
+    /// `i`-th angle of the cycle is 1 when `i + kp` is at least `q - p` modulo `q`, and the
+    /// wake is bounded by the angles numbered `p - 1` and `p`.
+    #[must_use]
+    pub fn wake(&self) -> (RatAngle, RatAngle)
+    {
+        let (p, q) = (*self.rotation_number.numer(), *self.rotation_number.denom());
+        let angle = |i: Period| {
+            let numerator =
+                (0..q).fold(0, |acc, k| 2 * acc + Period::from((i + k * p) % q >= q - p));
+            RatAngle::new(numerator, (1 << q) - 1)
+        };
+        (angle(p - 1), angle(p))
+    }
+
+    /// Whether `angle` lies in the closed wake of the limb
+    #[must_use]
+    pub fn contains(&self, angle: RatAngle) -> bool
+    {
+        let (lo, hi) = self.wake();
+        lo <= angle && angle <= hi
+    }
+
+    /// The limbs whose roots have period at most `max_period`, in order of rotation number,
+    /// which is also the order of their wakes
+    #[must_use]
+    pub fn up_to_period(max_period: Period) -> Vec<Self>
+    {
+        let mut limbs: Vec<Self> = (2..=max_period)
+            .flat_map(|q| {
+                (1..q)
+                    .filter(move |&p| gcd(p, q) == 1)
+                    .map(move |p| Self::new(RatAngle::new(p, q)))
+            })
+            .collect();
+        limbs.sort_unstable();
+        limbs
+    }
+}
+
+impl core::fmt::Display for Limb
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        write!(f, "{}-limb", self.rotation_number)
+    }
+}
+
+/// The cells of a cover grouped by the limb they lie in. The limb of a vertex is the one
+/// holding the wake whose boundary ray has the angle the vertex marks, and that of an edge
+/// is the one holding its wake. A face is listed under every limb holding one of its edges.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LimbDecomposition
+{
+    /// Indices into the cover's vertex list, in increasing order
+    pub vertices: BTreeMap<Limb, Vec<usize>>,
+    /// Indices into the cover's edge list, in increasing order
+    pub edges: BTreeMap<Limb, Vec<usize>>,
+    /// Indices into the cover's face list, in increasing order
+    pub faces: BTreeMap<Limb, Vec<usize>>,
+}
+
+impl LimbDecomposition
+{
+    /// Group the cells of a cover of the period of `context` whose faces have the boundary
+    /// edges `face_edges`, by id. Cells at the angle zero, which lies in no limb, are left out.
+    pub fn new<'a, V: CellKey>(
+        context: Context,
+        vertices: &[V],
+        edges: &[Edge<V>],
+        face_edges: impl IntoIterator<Item = &'a [usize]>,
+    ) -> Self
+    {
+        let limbs = Limb::up_to_period(context.period);
+        let wakes: Vec<(RatAngle, RatAngle)> = limbs.iter().map(Limb::wake).collect();
+        // The wakes are disjoint and in order, so the only candidate is the last one starting
+        // at or before the angle
+        let limb_of = |angle: RatAngle| {
+            let k = wakes
+                .partition_point(|&(lo, _)| lo <= angle)
+                .checked_sub(1)?;
+            (angle <= wakes[k].1).then_some(limbs[k])
+        };
+        let max_angle = context.max_angle.0;
+
+        let mut decomposition = Self::default();
+        for (i, v) in vertices.iter().enumerate() {
+            if let Some(limb) = limb_of(RatAngle::new(v.angle(context).0, max_angle)) {
+                decomposition.vertices.entry(limb).or_default().push(i);
+            }
+        }
+        let edge_limbs: Vec<Option<Limb>> = edges
+            .iter()
+            .map(|e| limb_of(e.wake.rat_angles().0))
+            .collect();
+        for (id, limb) in edge_limbs.iter().enumerate() {
+            if let Some(limb) = limb {
+                decomposition.edges.entry(*limb).or_default().push(id);
+            }
+        }
+        for (k, ids) in face_edges.into_iter().enumerate() {
+            let mut face_limbs: Vec<Limb> = ids.iter().filter_map(|&id| edge_limbs[id]).collect();
+            face_limbs.sort_unstable();
+            face_limbs.dedup();
+            for limb in face_limbs {
+                decomposition.faces.entry(limb).or_default().push(k);
+            }
+        }
+        decomposition
+    }
+
+    /// The limbs holding any cell, in order
+    #[must_use]
+    pub fn limbs(&self) -> Vec<Limb>
+    {
+        let mut limbs: Vec<Limb> = self
+            .vertices
+            .keys()
+            .chain(self.edges.keys())
+            .chain(self.faces.keys())
+            .copied()
+            .collect();
+        limbs.sort_unstable();
+        limbs.dedup();
+        limbs
+    }
+}
@@ -1,110 +1,2614 @@
 #![allow(dead_code)]
 
-use clap::Parser;
+use clap::{
+    parser::ValueSource, ArgMatches, Args, CommandFactory, FromArgMatches, Parser, Subcommand,
+    ValueEnum,
+};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::Display;
+use std::fs::File;
+use std::hash::Hash;
+use std::io::{self, BufRead, BufWriter, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 
+use marked_cycles::abstract_cycles::{
+    AbstractCycle, AbstractCycleClass, AbstractPoint, ShiftedCycle,
+};
+use marked_cycles::arithmetic::{doubling_period, parse_angle};
+#[cfg(feature = "parquet")]
+use marked_cycles::columnar::{self, CellBatches, ColumnarFormat};
 use marked_cycles::combinatorics::{dynatomic, marked_cycle, Combinatorics};
-use marked_cycles::dynatomic_cover::DynatomicCover;
-use marked_cycles::marked_cycle_cover::MarkedCycleCover;
-use marked_cycles::tikz::TikzRenderer;
-use marked_cycles::types::Period;
+use marked_cycles::common::{
+    cells::{
+        AugmentedVertex, CellFilter, CellKey, CellListing, CellOrder, ContainsAngle, Edge, Face,
+        SummaryOptions, VertexId,
+    },
+    get_orbit_in, Phase,
+};
+use marked_cycles::config::{Config, CONFIG_FILE};
+use marked_cycles::cover;
+use marked_cycles::csv::{CsvTables, InvariantRecord, RecordWriter, Separator};
+use marked_cycles::dynatomic_cover::{DynFaceLabel, DynatomicCover, DynatomicCoverBuilder};
+use marked_cycles::global_state::{Context, DisplayIn};
+use marked_cycles::json::JsonSummary;
+use marked_cycles::lamination::Lamination;
+use marked_cycles::layout::Extreme;
+use marked_cycles::limbs::{Limb, LimbDecomposition};
+use marked_cycles::marked_cycle_cover::{MarkedCycleCover, MarkedCycleCoverBuilder};
+use marked_cycles::matrices::{CoverMatrices, MatrixFormat};
+use marked_cycles::monodromy::{MonodromyGroup, Permutation};
+use marked_cycles::progress::ProgressSink;
+use marked_cycles::real_axis::{real_components, real_crossings, RealCrossingStats};
+#[cfg(feature = "stats")]
+use marked_cycles::stats::CountingAllocator;
+use marked_cycles::svg::SvgRenderer;
+use marked_cycles::tikz::{lamination::LaminationRenderer, TikzRenderer, TikzStyle};
+use marked_cycles::topology::{EndCell, Ends};
+use marked_cycles::types::{CritPeriod, IntAngle, MarkedPeriod, Period, RatAngle};
+use marked_cycles::typst::{self, TypstRenderer};
+
+/// Counts the allocations of each build phase for `bench`
+#[cfg(feature = "stats")]
+#[global_allocator]
+static ALLOC: CountingAllocator = CountingAllocator;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
-struct Args
+struct Cli
+{
+    #[command(subcommand)]
+    command: Command,
+
+    /// Write output to this file instead of stdout, creating directories as needed. For
+    /// `export --format csv`, and the other formats made of several tables or matrices, this
+    /// is the directory to write them into.
+    #[arg(short, long, global = true)]
+    output: Option<PathBuf>,
+
+    /// Compress output with gzip, appending `.gz` to the output file name if missing
+    #[arg(long, global = true, default_value_t = false)]
+    gzip: bool,
+
+    /// Read default options from this file instead of `marked-cycles.toml` in the working
+    /// directory. Options given on the command line take precedence.
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Print the size of each cover the command would build, estimated from the closed-form
+    /// formulas, and exit without building anything
+    #[arg(long, global = true, default_value_t = false)]
+    dry_run: bool,
+}
+
+/// Destination of command output: stdout or a file, optionally gzip-compressed
+enum Output
 {
-    /// Period of the marked cycle (0 to skip)
-    #[arg(short, long, default_value_t = 0)]
-    marked_period: Period,
+    Plain(BufWriter<Box<dyn Write>>),
+    Gzip(GzEncoder<BufWriter<Box<dyn Write>>>),
+}
 
-    /// Period of the critical cycle (must be 1 or 2 for now)
-    #[arg(short, long, default_value_t = 1)]
-    crit_period: Period,
+impl Output
+{
+    fn open(path: Option<&Path>, gzip: bool) -> io::Result<Self>
+    {
+        let sink: Box<dyn Write> = match path {
+            Some(path) => {
+                let mut path = path.to_path_buf();
+                if gzip && path.extension().is_none_or(|ext| ext != "gz") {
+                    path.as_mut_os_string().push(".gz");
+                }
+                if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(dir)?;
+                }
+                Box::new(File::create(path)?)
+            }
+            None => Box::new(io::stdout()),
+        };
+        let sink = BufWriter::new(sink);
+        Ok(if gzip {
+            Self::Gzip(GzEncoder::new(sink, Compression::default()))
+        } else {
+            Self::Plain(sink)
+        })
+    }
 
-    /// Max period of data table (0 to skip)
-    #[arg(short, long, default_value_t = 0)]
-    table_max_period: Period,
+    /// Flush everything, writing the gzip trailer if compressing
+    fn finish(self) -> io::Result<()>
+    {
+        match self {
+            Self::Plain(mut w) => w.flush(),
+            Self::Gzip(w) => w.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for Output
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize>
+    {
+        match self {
+            Self::Plain(w) => w.write(buf),
+            Self::Gzip(w) => w.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()>
+    {
+        match self {
+            Self::Plain(w) => w.flush(),
+            Self::Gzip(w) => w.flush(),
+        }
+    }
+}
+
+/// Which curve to build, and over which critical period
+#[derive(Args, Clone, Debug)]
+struct CoverArgs
+{
+    /// Period of the marked cycle
+    period: MarkedPeriod,
+
+    /// Period of the critical cycle (1 or 2)
+    #[arg(short, long, default_value_t = CritPeriod::ONE)]
+    crit_period: CritPeriod,
 
     /// Compute dynatomic curve instead of marked cycle curve
     #[arg(short, long, default_value_t = false)]
     dynatomic: bool,
+}
 
-    /// Display cell ids in binary
-    #[arg(short, long, default_value_t = false)]
-    binary: bool,
+/// An inclusive range of periods, written as `n`, `a..b`, or `a..=b`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct PeriodRange
+{
+    start: MarkedPeriod,
+    end: MarkedPeriod,
+}
+
+impl PeriodRange
+{
+    fn periods(self) -> impl Iterator<Item = MarkedPeriod>
+    {
+        (self.start.get()..=self.end.get()).filter_map(|period| MarkedPeriod::new(period).ok())
+    }
+
+    const fn is_single(self) -> bool
+    {
+        self.start.get() == self.end.get()
+    }
+}
+
+impl std::str::FromStr for PeriodRange
+{
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err>
+    {
+        let parse = |t: &str| {
+            t.trim()
+                .parse::<Period>()
+                .map_err(|e| format!("invalid period {t:?}: {e}"))
+        };
+        let (start, end) = if let Some((a, b)) = s.split_once("..=") {
+            (parse(a)?, parse(b)?)
+        } else if let Some((a, b)) = s.split_once("..") {
+            (parse(a)?, parse(b)? - 1)
+        } else {
+            let n = parse(s)?;
+            (n, n)
+        };
+        if start > end {
+            return Err(format!("empty range of periods {s:?}"));
+        }
+        Ok(Self {
+            start: MarkedPeriod::new(start)?,
+            end: MarkedPeriod::new(end)?,
+        })
+    }
+}
+
+/// Which cells to list in a text summary, and in what order
+#[derive(Args, Clone, Debug)]
+struct FilterArgs
+{
+    /// Only list the vertex marking this angle, and the edges and faces that meet it
+    #[arg(long, value_parser = parse_angle)]
+    vertex: Option<RatAngle>,
+
+    /// Only list edges whose wake contains this angle
+    #[arg(long, value_parser = parse_angle)]
+    wake: Option<RatAngle>,
+
+    /// Only list faces with at least this many vertices
+    #[arg(long)]
+    min_size: Option<usize>,
 
-    /// How far to indent the cell descriptions
-    #[arg(long, default_value_t = 4)]
-    indent: usize,
+    /// Only list faces with at most this many vertices
+    #[arg(long)]
+    max_size: Option<usize>,
 
-    /// Generate tikz
+    /// Only list reflexive faces
     #[arg(long, default_value_t = false)]
-    tikz: bool,
+    reflexive: bool,
+
+    /// Order in which to list cells, instead of the order they were built in
+    #[arg(long, value_enum)]
+    sort: Option<SortOrder>,
+
+    /// List at most this many cells of each kind, or every cell if 0
+    #[arg(long, default_value_t = 100)]
+    limit: usize,
+}
+
+impl From<&FilterArgs> for CellFilter
+{
+    fn from(args: &FilterArgs) -> Self
+    {
+        Self {
+            vertex: args.vertex,
+            wake: args.wake,
+            min_size: args.min_size,
+            max_size: args.max_size,
+            reflexive: args.reflexive,
+        }
+    }
+}
+
+impl From<&FilterArgs> for CellListing
+{
+    fn from(args: &FilterArgs) -> Self
+    {
+        Self {
+            sort: args.sort.map(CellOrder::from),
+            limit: (args.limit > 0).then_some(args.limit),
+        }
+    }
+}
+
+/// Invariants a curve is expected to have, for scripted regression checks
+#[derive(Args, Clone, Debug)]
+struct ExpectArgs
+{
+    /// Exit with a nonzero status unless the curve has this many vertices
+    #[arg(long)]
+    expect_vertices: Option<usize>,
+
+    /// Exit with a nonzero status unless the curve has this many edges
+    #[arg(long)]
+    expect_edges: Option<usize>,
+
+    /// Exit with a nonzero status unless the curve has this many faces
+    #[arg(long)]
+    expect_faces: Option<usize>,
+
+    /// Exit with a nonzero status unless the curve has this genus
+    #[arg(long, allow_hyphen_values = true)]
+    expect_genus: Option<i64>,
+}
+
+impl ExpectArgs
+{
+    /// Compare the counts and genus of a cover with the expected ones, reporting each mismatch
+    /// on stderr. Returns whether all of them match.
+    fn check(&self, cover: &CoverArgs, (v, e, f, g): (usize, usize, usize, i64)) -> bool
+    {
+        let checks = [
+            ("vertices", self.expect_vertices.map(|x| x as i64), v as i64),
+            ("edges", self.expect_edges.map(|x| x as i64), e as i64),
+            ("faces", self.expect_faces.map(|x| x as i64), f as i64),
+            ("genus", self.expect_genus, g),
+        ];
+        let mut ok = true;
+        for (name, expected, found) in checks {
+            if let Some(expected) = expected.filter(|&x| x != found) {
+                eprintln!("{}: expected {name} {expected}, found {found}", stem(cover));
+                ok = false;
+            }
+        }
+        ok
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum SortOrder
+{
+    /// Faces by number of vertices, edges by width of their wake
+    Size,
+    /// By cycle or face label
+    Label,
+    /// By smallest angle
+    Angle,
+}
+
+impl From<SortOrder> for CellOrder
+{
+    fn from(order: SortOrder) -> Self
+    {
+        match order {
+            SortOrder::Size => Self::Size,
+            SortOrder::Label => Self::Label,
+            SortOrder::Angle => Self::Angle,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum FaceChoice
+{
+    Largest,
+    Smallest,
+    All,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum OutputFormat
+{
+    Text,
+    Json,
+    /// One JSON object per cell, written as each cover is built
+    Ndjson,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum TableFormat
+{
+    /// Fixed-width columns
+    Text,
+    /// GitHub-flavored Markdown
+    Markdown,
+    /// Typst `table`
+    Typst,
+    /// Comma-separated rows of every closed-form invariant, with a header naming each column
+    Csv,
+    /// Like `csv`, with tab-separated fields
+    Tsv,
+    /// Parquet file with every closed-form invariant, best written to a file with `--output`
+    #[cfg(feature = "parquet")]
+    Parquet,
+    /// Arrow IPC file with every closed-form invariant, best written to a file with `--output`
+    #[cfg(feature = "parquet")]
+    Arrow,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum PermutationFormat
+{
+    /// Cycle notation, leaving out fixed points
+    Cycles,
+    /// The image of each point, one per line
+    Images,
+    /// Permutation matrix in Matrix Market format
+    Mtx,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum LeafFormat
+{
+    /// TikZ chord diagram
+    Tikz,
+    /// Endpoints as reduced fractions
+    Fraction,
+    /// Endpoints as repeating binary digits, one period long
+    Binary,
+    /// Endpoints as integers k standing for k/(2^n-1)
+    Integer,
 }
 
-fn print_combinatorics(args: &Args)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum ExportFormat
 {
-    if args.marked_period > 0 {
-        println!(
-            "Computing combinatorics of (c,lambda) -> c cover for marked period {}, critical period {}",
-            args.marked_period, args.crit_period
+    Dot,
+    Graphml,
+    Gexf,
+    Polymake,
+    Obj,
+    Ply,
+    Json,
+    Csv,
+    /// Like `csv`, with tab-separated fields
+    Tsv,
+    /// Parquet tables of the cells, written into the directory given by `--output`
+    #[cfg(feature = "parquet")]
+    Parquet,
+    /// Arrow IPC tables of the cells, written into the directory given by `--output`
+    #[cfg(feature = "parquet")]
+    Arrow,
+    /// Boundary operators and adjacency matrix in Matrix Market format, written into the
+    /// directory given by `--output`
+    Mtx,
+    /// Boundary operators and adjacency matrix as `scipy.sparse` `.npz` files, written into the
+    /// directory given by `--output`
+    #[cfg(feature = "npz")]
+    Npz,
+    Tikz,
+    Svg,
+    /// Every face, side by side, as a standalone Typst document
+    Typst,
+}
+
+impl ExportFormat
+{
+    /// Whether the format is a set of files written into a directory
+    fn writes_directory(self) -> bool
+    {
+        match self {
+            Self::Csv | Self::Tsv | Self::Mtx => true,
+            #[cfg(feature = "parquet")]
+            Self::Parquet | Self::Arrow => true,
+            #[cfg(feature = "npz")]
+            Self::Npz => true,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum Command
+{
+    /// Build a cover and print its vertices, edges, and faces. Given a range of periods, print
+    /// one row per period instead.
+    Curve
+    {
+        /// Period of the marked cycle, or a range of periods such as `3..=18`
+        periods: PeriodRange,
+
+        /// Period of the critical cycle (1 or 2)
+        #[arg(short, long, default_value_t = CritPeriod::ONE)]
+        crit_period: CritPeriod,
+
+        /// Compute dynatomic curve instead of marked cycle curve
+        #[arg(short, long, default_value_t = false)]
+        dynatomic: bool,
+
+        /// Display cell ids in binary
+        #[arg(short, long, default_value_t = false)]
+        binary: bool,
+
+        /// Display angles as fractions `k/(2^n-1)`
+        #[arg(long, default_value_t = false, conflicts_with = "binary")]
+        fractions: bool,
+
+        /// How far to indent the cell descriptions
+        #[arg(long, default_value_t = 4)]
+        indent: usize,
+
+        /// Output format of the summary
+        #[arg(long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        /// List every vertex, edge, and face in JSON output
+        #[arg(long, default_value_t = false)]
+        cells: bool,
+
+        /// Also write the vertices, edges, and faces as CSV files into this directory
+        #[arg(long)]
+        csv: Option<PathBuf>,
+
+        /// Also print a histogram of face sizes
+        #[arg(long, default_value_t = false)]
+        histogram: bool,
+
+        #[command(flatten)]
+        expect: ExpectArgs,
+
+        #[command(flatten)]
+        filter: FilterArgs,
+
+        /// Number of periods to build at once, or 0 to use every available core
+        #[arg(short, long, default_value_t = 1)]
+        jobs: usize,
+
+        /// Print the progress of each phase of each build to stderr
+        #[arg(long, default_value_t = false)]
+        progress: bool,
+    },
+
+    /// Print cell counts and genus for a range of periods, from closed-form formulas
+    Table
+    {
+        /// Largest period in the table
+        max_period: MarkedPeriod,
+
+        /// Period of the critical cycle (1 or 2)
+        #[arg(short, long, default_value_t = CritPeriod::ONE)]
+        crit_period: CritPeriod,
+
+        /// Use the dynatomic curve instead of the marked cycle curve
+        #[arg(short, long, default_value_t = false)]
+        dynatomic: bool,
+
+        /// Layout of the table
+        #[arg(long, value_enum, default_value_t = TableFormat::Text)]
+        table_format: TableFormat,
+    },
+
+    /// Draw faces of a cover as TikZ
+    Tikz
+    {
+        #[command(flatten)]
+        cover: CoverArgs,
+
+        /// Which faces to draw
+        #[arg(long, value_enum, default_value_t = FaceChoice::Smallest)]
+        face: FaceChoice,
+
+        /// Draw only the face with this label, written as shown in the summary (e.g. `<3>`) or
+        /// as the angle of its cycle class in any notation `query` accepts
+        #[arg(long, conflicts_with = "face")]
+        label: Option<String>,
+
+        /// Emit a complete LaTeX document
+        #[arg(long, default_value_t = false)]
+        standalone: bool,
+    },
+
+    /// Write a cover in one of the supported export formats
+    Export
+    {
+        #[command(flatten)]
+        cover: CoverArgs,
+
+        /// Format to export
+        #[arg(short, long, value_enum)]
+        format: ExportFormat,
+
+        /// Leave out faces (for DOT and GraphML) or cell lists (for JSON)
+        #[arg(long, default_value_t = false)]
+        skeleton: bool,
+    },
+
+    /// Draw the leaves of the lamination as a TikZ chord diagram, or list their endpoints
+    Lamination
+    {
+        /// Period of the leaves
+        period: MarkedPeriod,
+
+        /// Period of the critical cycle (1 or 2)
+        #[arg(short, long, default_value_t = CritPeriod::ONE)]
+        crit_period: CritPeriod,
+
+        /// Draw leaves as hyperbolic geodesics
+        #[arg(long, default_value_t = false)]
+        geodesics: bool,
+
+        /// Include leaves of every period up to the given one
+        #[arg(long, default_value_t = false)]
+        all_periods: bool,
+
+        /// Emit a complete LaTeX document
+        #[arg(long, default_value_t = false)]
+        standalone: bool,
+
+        /// Draw the leaves, or list one leaf per line in the given notation
+        #[arg(short, long, value_enum, default_value_t = LeafFormat::Tikz)]
+        format: LeafFormat,
+
+        /// Only list leaves lying in the wake between these two angles
+        #[arg(long, num_args = 2, value_names = ["ANGLE0", "ANGLE1"], value_parser = parse_angle)]
+        wake: Option<Vec<RatAngle>>,
+    },
+
+    /// Build covers and check their cell counts against the closed-form formulas
+    Verify
+    {
+        /// Largest period to check
+        max_period: MarkedPeriod,
+
+        /// Period of the critical cycle (1 or 2)
+        #[arg(short, long, default_value_t = CritPeriod::ONE)]
+        crit_period: CritPeriod,
+
+        /// Check the dynatomic curve instead of the marked cycle curve
+        #[arg(short, long, default_value_t = false)]
+        dynatomic: bool,
+
+        /// Number of periods to build at once, or 0 to use every available core
+        #[arg(short, long, default_value_t = 1)]
+        jobs: usize,
+    },
+
+    /// Build a cover once, then answer queries about it read from stdin
+    Repl
+    {
+        #[command(flatten)]
+        cover: CoverArgs,
+    },
+
+    /// Check whether two angles form a leaf of the lamination, and describe the edge it gives
+    Wake
+    {
+        /// One endpoint, written as `k/(2^n-1)`, as a fraction `p/q`, or as binary digits
+        angle0: String,
+
+        /// The other endpoint, in the same notation
+        angle1: String,
+
+        /// Period of the critical cycle (1 or 2)
+        #[arg(short, long, default_value_t = CritPeriod::ONE)]
+        crit_period: CritPeriod,
+    },
+
+    /// Describe a periodic angle and where it sits in the marked cycle curve
+    Query
+    {
+        /// The angle, written as `k/(2^n-1)`, as a fraction `p/q`, or as binary digits
+        angle: String,
+
+        /// Period of the critical cycle (1 or 2)
+        #[arg(short, long, default_value_t = CritPeriod::ONE)]
+        crit_period: CritPeriod,
+    },
+
+    /// Print the doubling map on the angles `k/(2^n-1)` of period dividing `n`, by numerator,
+    /// or on the vertices of the dynatomic curve, by index, as a permutation
+    Doubling
+    {
+        /// Period `n` of the angles
+        period: MarkedPeriod,
+
+        /// Act on the vertices of the dynatomic curve, in the order it lists them
+        #[arg(short, long, default_value_t = false)]
+        dynatomic: bool,
+
+        /// Period of the critical cycle of the dynatomic curve (1 or 2)
+        #[arg(short, long, default_value_t = CritPeriod::ONE, requires = "dynatomic")]
+        crit_period: CritPeriod,
+
+        /// Notation for the permutation
+        #[arg(short, long, value_enum, default_value_t = PermutationFormat::Cycles)]
+        format: PermutationFormat,
+    },
+
+    /// Compare the cell counts, genus, and face sizes of two covers, exiting with a nonzero
+    /// status if they differ
+    Diff
+    {
+        /// A cover written as `mc_<PERIOD>[_<CRIT_PERIOD>]` or `dyn_<PERIOD>[_<CRIT_PERIOD>]`,
+        /// or a JSON summary saved from `curve --format json` (optionally gzipped)
+        left: String,
+
+        /// The other cover, in the same notation
+        right: String,
+    },
+
+    /// Time each phase of building a cover. With the `stats` feature, also report the
+    /// allocations and peak heap usage of each phase of the first build.
+    Bench
+    {
+        #[command(flatten)]
+        cover: CoverArgs,
+
+        /// Build the cover this many times and report the fastest time for each phase
+        #[arg(short, long, default_value_t = 1)]
+        runs: usize,
+    },
+
+    /// Print one invariant for each period in OEIS b-file format, from closed-form formulas
+    Sequence
+    {
+        /// Invariant to print
+        #[arg(value_enum)]
+        invariant: Invariant,
+
+        /// Largest period in the sequence
+        max_period: MarkedPeriod,
+
+        /// Smallest period in the sequence
+        #[arg(long, default_value = "2")]
+        start: MarkedPeriod,
+
+        /// Period of the critical cycle (1 or 2)
+        #[arg(short, long, default_value_t = CritPeriod::ONE)]
+        crit_period: CritPeriod,
+
+        /// Use the dynatomic curve instead of the marked cycle curve
+        #[arg(short, long, default_value_t = false)]
+        dynatomic: bool,
+    },
+}
+
+/// Invariants that `sequence` can print
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Invariant
+{
+    /// Vertices of the curve
+    Vertices,
+    /// Edges of the curve
+    Edges,
+    /// Faces of the curve
+    Faces,
+    /// Genus of the curve
+    Genus,
+    /// Periodic cycles of the given period
+    Cycles,
+    /// Hyperbolic components of the given period
+    Components,
+    /// Primitive hyperbolic components
+    PrimitiveComponents,
+    /// Satellite hyperbolic components
+    SatelliteComponents,
+    /// Faces fixed by complex conjugation
+    SelfConjugateFaces,
+}
+
+impl Invariant
+{
+    fn eval(self, comb: &dyn Combinatorics, period: Period) -> i64
+    {
+        match self {
+            Self::Vertices => comb.vertices(period),
+            Self::Edges => comb.edges(period),
+            Self::Faces => comb.faces(period),
+            Self::Genus => comb.genus(period),
+            Self::Cycles => comb.cycles(period),
+            Self::Components => comb.hyperbolic_components(period),
+            Self::PrimitiveComponents => comb.primitive_components(period),
+            Self::SatelliteComponents => comb.satellite_components(period),
+            Self::SelfConjugateFaces => comb.self_conjugate_faces(period),
+        }
+    }
+}
+
+impl Command
+{
+    fn crit_period_mut(&mut self) -> Option<&mut CritPeriod>
+    {
+        match self {
+            Self::Curve { crit_period, .. }
+            | Self::Table { crit_period, .. }
+            | Self::Lamination { crit_period, .. }
+            | Self::Verify { crit_period, .. }
+            | Self::Wake { crit_period, .. }
+            | Self::Query { crit_period, .. }
+            | Self::Doubling { crit_period, .. }
+            | Self::Sequence { crit_period, .. } => Some(crit_period),
+            Self::Tikz { cover, .. }
+            | Self::Export { cover, .. }
+            | Self::Repl { cover }
+            | Self::Bench { cover, .. } => Some(&mut cover.crit_period),
+            Self::Diff { .. } => None,
+        }
+    }
+
+    /// Fill in options left at their built-in defaults with those from the configuration file.
+    /// `matches` are the parsed arguments of this subcommand.
+    fn apply_config(&mut self, config: &Config, matches: &ArgMatches) -> Result<(), String>
+    {
+        let unset = |id: &str| matches.value_source(id) == Some(ValueSource::DefaultValue);
+
+        if let (Some(value), Some(crit_period)) = (config.crit_period, self.crit_period_mut()) {
+            if unset("crit_period") {
+                *crit_period = CritPeriod::new(value)?;
+            }
+        }
+        if let Self::Curve { format, indent, .. } = self {
+            if let Some(value) = config.format.as_deref().filter(|_| unset("format")) {
+                *format = OutputFormat::from_str(value, true)
+                    .map_err(|_| format!("unknown output format `{value}`"))?;
+            }
+            if let Some(value) = config.indent.filter(|_| unset("indent")) {
+                *indent = value;
+            }
+        }
+        Ok(())
+    }
+
+    /// The covers this command builds, as far as can be told before running it
+    fn planned_covers(&self) -> Vec<CoverArgs>
+    {
+        match self {
+            Self::Curve {
+                periods,
+                crit_period,
+                dynatomic,
+                ..
+            } => periods
+                .periods()
+                .map(|period| CoverArgs {
+                    period,
+                    crit_period: *crit_period,
+                    dynatomic: *dynatomic,
+                })
+                .collect(),
+            Self::Verify {
+                max_period,
+                crit_period,
+                dynatomic,
+                ..
+            } => verified_periods(*max_period)
+                .map(|period| CoverArgs {
+                    period,
+                    crit_period: *crit_period,
+                    dynatomic: *dynatomic,
+                })
+                .collect(),
+            Self::Tikz { cover, .. }
+            | Self::Export { cover, .. }
+            | Self::Repl { cover }
+            | Self::Bench { cover, .. } => vec![cover.clone()],
+            Self::Doubling {
+                period,
+                dynatomic: true,
+                crit_period,
+                ..
+            } => vec![CoverArgs {
+                period: *period,
+                crit_period: *crit_period,
+                dynatomic: true,
+            }],
+            Self::Query { angle, crit_period } => parse_angle(angle)
+                .ok()
+                .and_then(doubling_period)
+                .and_then(|period| MarkedPeriod::new(period).ok())
+                .map(|period| CoverArgs {
+                    period,
+                    crit_period: *crit_period,
+                    dynatomic: false,
+                })
+                .into_iter()
+                .collect(),
+            Self::Diff { left, right } => [left, right]
+                .into_iter()
+                .filter_map(|spec| parse_cover_spec(spec))
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// Above this many bytes, ask before building a cover from an interactive terminal
+const CONFIRM_BYTES: f64 = (1_u64 << 30) as f64;
+
+/// Cell counts of a cover from the closed-form formulas, and a rough estimate of the memory
+/// needed to build it
+struct SizeEstimate
+{
+    vertices: i64,
+    edges: i64,
+    faces: i64,
+    bytes: f64,
+}
+
+impl SizeEstimate
+{
+    fn new(cover: &CoverArgs) -> io::Result<Self>
+    {
+        let period = cover.period.get();
+        let comb = combinatorics(cover.crit_period, cover.dynatomic);
+        check_closed_form(comb.as_ref(), period)?;
+        let (vertices, edges, faces) = (
+            comb.vertices(period),
+            comb.edges(period),
+            comb.faces(period),
         );
 
-        if args.dynatomic {
-            let cov = DynatomicCover::new(args.marked_period, args.crit_period);
-            cov.summarize(args.indent, args.binary);
+        // The lamination keeps the endpoints of leaves of every period up to this one, and each
+        // leaf of the current period becomes two entries of the builder's adjacency map
+        let leaves = marked_cycle::Comb::new(cover.crit_period.get());
+        let endpoints: f64 = (1..=period).map(|k| 2.0 * leaves.edges(k) as f64).sum();
+        let lamination = endpoints * Lamination::ENDPOINT_BYTES as f64;
+        let adjacency = 2.0 * leaves.edges(period) as f64;
+
+        let cells = if cover.dynatomic {
+            cell_bytes::<ShiftedCycle, DynFaceLabel>(adjacency, [vertices, edges, faces])
         } else {
-            let cov = MarkedCycleCover::new(args.marked_period, args.crit_period);
-            cov.summarize(args.indent, args.binary);
+            cell_bytes::<AbstractCycle, AbstractCycleClass>(adjacency, [vertices, edges, faces])
+        };
+
+        Ok(Self {
+            vertices,
+            edges,
+            faces,
+            bytes: lamination + cells,
+        })
+    }
+}
+
+/// Bytes taken by the adjacency map and the cells
+fn cell_bytes<V, L>(adjacency: f64, [v, e, f]: [i64; 3]) -> f64
+{
+    let size = |bytes: usize| bytes as f64;
+    // Hash maps keep some spare capacity, so count each entry twice
+    let adjacency = 2.0 * adjacency * size(size_of::<(V, IntAngle, bool, usize)>());
+    let vertex_ids = 2.0 * v as f64 * size(size_of::<(V, VertexId)>());
+    let vertices = v as f64 * size(size_of::<V>()) + vertex_ids;
+    let edges = e as f64 * size(size_of::<Edge<V>>());
+    // Each edge lies on two sides of faces, each side contributing a vertex id and an edge id
+    let faces = f as f64 * size(size_of::<Face<AugmentedVertex<VertexId>, L>>())
+        + 2.0 * e as f64 * size(size_of::<AugmentedVertex<VertexId>>() + size_of::<usize>());
+    adjacency + vertices + edges + faces
+}
+
+fn human_bytes(bytes: f64) -> String
+{
+    let units = ["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    let mut value = bytes;
+    let mut unit = 0;
+    while value >= 1024.0 && unit + 1 < units.len() {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{value:.1} {}", units[unit])
+}
+
+/// Print the estimated size of each planned cover
+fn print_estimates(covers: &[CoverArgs]) -> io::Result<()>
+{
+    let mut out = io::stdout().lock();
+    if covers.is_empty() {
+        writeln!(out, "This command does not build a cover")?;
+    }
+    for cover in covers {
+        let estimate = SizeEstimate::new(cover)?;
+        writeln!(
+            out,
+            "{}: {} vertices, {} edges, {} faces, about {} of memory",
+            stem(cover),
+            estimate.vertices,
+            estimate.edges,
+            estimate.faces,
+            human_bytes(estimate.bytes)
+        )?;
+    }
+    Ok(())
+}
+
+/// If the largest planned cover needs more than `CONFIRM_BYTES` and stdin is a terminal, ask
+/// whether to go ahead. Returns whether to run the command.
+fn confirm_size(covers: &[CoverArgs]) -> io::Result<bool>
+{
+    let estimates = covers
+        .iter()
+        .map(|c| Ok((c, SizeEstimate::new(c)?.bytes)))
+        .collect::<io::Result<Vec<_>>>()?;
+    let Some((cover, bytes)) = estimates.into_iter().max_by(|a, b| a.1.total_cmp(&b.1)) else {
+        return Ok(true);
+    };
+    if bytes <= CONFIRM_BYTES || !io::stdin().is_terminal() {
+        return Ok(true);
+    }
+
+    eprint!(
+        "Building {} will need about {} of memory. Continue? [y/N] ",
+        stem(cover),
+        human_bytes(bytes)
+    );
+    io::stderr().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
+
+fn combinatorics(crit_period: CritPeriod, dynatomic: bool) -> Box<dyn Combinatorics>
+{
+    if dynatomic {
+        Box::new(dynatomic::Comb::new(crit_period.get()))
+    } else {
+        Box::new(marked_cycle::Comb::new(crit_period.get()))
+    }
+}
+
+/// Fail unless the closed-form counts of `comb` fit up to `max_period`
+fn check_closed_form(comb: &dyn Combinatorics, max_period: Period) -> io::Result<()>
+{
+    if max_period > comb.max_period() {
+        return Err(io::Error::other(format!(
+            "the closed-form counts of this curve overflow past period {}",
+            comb.max_period()
+        )));
+    }
+    Ok(())
+}
+
+/// A built cover of either kind
+enum Cover
+{
+    Marked(MarkedCycleCover),
+    Dynatomic(DynatomicCover),
+}
+
+impl Cover
+{
+    fn new(cover: &CoverArgs) -> Self
+    {
+        if cover.dynatomic {
+            Self::Dynatomic(DynatomicCover::from_periods(
+                cover.period,
+                cover.crit_period,
+            ))
+        } else {
+            Self::Marked(MarkedCycleCover::from_periods(
+                cover.period,
+                cover.crit_period,
+            ))
+        }
+    }
+
+    /// Build the cover, printing the progress of each phase to stderr if `progress` is set
+    fn build(cover: &CoverArgs, progress: bool) -> Self
+    {
+        if !progress {
+            return Self::new(cover);
+        }
+        let sink = Arc::new(StderrProgress::new(stem(cover)));
+        if cover.dynatomic {
+            let builder = DynatomicCoverBuilder::from_periods(cover.period, cover.crit_period);
+            Self::Dynatomic(builder.with_progress(sink).build())
+        } else {
+            let builder = MarkedCycleCoverBuilder::from_periods(cover.period, cover.crit_period);
+            Self::Marked(builder.with_progress(sink).build())
+        }
+    }
+
+    /// Every face as a standalone TikZ document
+    fn to_tikz(&self, style: &TikzStyle) -> String
+    {
+        let _context = self.context().enter();
+        match self {
+            Self::Marked(cov) => TikzRenderer::new(cov.faces())
+                .with_edges(cov.edges.clone())
+                .with_style(style.clone())
+                .standalone(true)
+                .generate(),
+            Self::Dynatomic(cov) => TikzRenderer::new(cov.faces())
+                .with_edges(cov.edges.clone())
+                .with_style(style.clone())
+                .standalone(true)
+                .generate(),
+        }
+    }
+
+    /// Every face, side by side, as SVG
+    fn to_svg(&self) -> String
+    {
+        let _context = self.context().enter();
+        match self {
+            Self::Marked(cov) => SvgRenderer::new(cov.faces())
+                .with_edges(cov.edges.clone())
+                .generate(),
+            Self::Dynatomic(cov) => SvgRenderer::new(cov.faces())
+                .with_edges(cov.edges.clone())
+                .generate(),
         }
     }
+
+    /// Every face, side by side, as a standalone Typst document
+    fn to_typst(&self) -> String
+    {
+        let _context = self.context().enter();
+        match self {
+            Self::Marked(cov) => TypstRenderer::new(cov.faces())
+                .with_edges(cov.edges.clone())
+                .standalone(true)
+                .generate(),
+            Self::Dynatomic(cov) => TypstRenderer::new(cov.faces())
+                .with_edges(cov.edges.clone())
+                .standalone(true)
+                .generate(),
+        }
+    }
+}
+
+impl std::ops::Deref for Cover
+{
+    type Target = dyn cover::Cover;
+
+    fn deref(&self) -> &Self::Target
+    {
+        match self {
+            Self::Marked(cov) => cov,
+            Self::Dynatomic(cov) => cov,
+        }
+    }
+}
+
+/// File name stem for the outputs of a cover, e.g. `mc_6_1`
+fn stem(cover: &CoverArgs) -> String
+{
+    let curve = if cover.dynatomic { "dyn" } else { "mc" };
+    format!("{curve}_{}_{}", cover.period, cover.crit_period)
+}
+
+fn write_csv(cover: &CoverArgs, tables: &CsvTables, dir: &Path) -> io::Result<()>
+{
+    let stem = stem(cover);
+    tables.write(dir, &stem)?;
+    eprintln!(
+        "Wrote {stem}_{{vertices,edges,faces}}.{} to {}",
+        tables.separator.extension(),
+        dir.display()
+    );
+    Ok(())
+}
+
+#[cfg(feature = "parquet")]
+fn write_columnar(
+    cover: &CoverArgs,
+    tables: &CellBatches,
+    dir: &Path,
+    format: ColumnarFormat,
+) -> io::Result<()>
+{
+    let stem = stem(cover);
+    tables.write(dir, &stem, format)?;
+    eprintln!(
+        "Wrote {stem}_{{vertices,edges,faces}}.{} to {}",
+        format.extension(),
+        dir.display()
+    );
+    Ok(())
+}
+
+fn write_matrices(
+    cover: &CoverArgs,
+    matrices: &CoverMatrices,
+    dir: &Path,
+    format: MatrixFormat,
+) -> io::Result<()>
+{
+    let stem = stem(cover);
+    matrices.write(dir, &stem, format)?;
+    eprintln!(
+        "Wrote {stem}_{{boundary1,boundary2,adjacency}}.{} to {}",
+        format.extension(),
+        dir.display()
+    );
+    Ok(())
+}
+
+fn need_directory(what: &str) -> io::Error
+{
+    io::Error::other(format!("{what} need a directory given by --output"))
+}
+
+fn print_combinatorics(
+    out: &mut Output,
+    cover: &CoverArgs,
+    cov: &Cover,
+    opts: &CurveOptions,
+) -> io::Result<()>
+{
+    writeln!(
+        out,
+        "Computing combinatorics of (c,lambda) -> c cover for marked period {}, critical period {}",
+        cover.period, cover.crit_period
+    )?;
+    cov.summarize_to(out, &opts.summary)?;
+    if opts.histogram {
+        writeln!(out, "\nFace size distribution:")?;
+        write_histogram(out, &cov.face_sizes(), opts.summary.indent)?;
+    }
+    Ok(())
+}
+
+/// Width in characters of the longest bar of a histogram
+const HISTOGRAM_WIDTH: usize = 50;
+
+/// Write one bar per face size that occurs, scaled so that the most common size fills
+/// `HISTOGRAM_WIDTH` columns
+fn write_histogram(out: &mut Output, sizes: &[usize], indent: usize) -> io::Result<()>
+{
+    let mut counts = BTreeMap::new();
+    for &size in sizes {
+        *counts.entry(size).or_insert(0) += 1;
+    }
+    let most = counts.values().copied().max().unwrap_or(1);
+    let label_width = sizes.iter().max().map_or(0, |m| m.to_string().len());
+
+    for (size, count) in counts {
+        let bar = "#".repeat((count * HISTOGRAM_WIDTH).div_ceil(most));
+        writeln!(out, "{:indent$}{size:>label_width$} | {bar} {count}", "")?;
+    }
+    Ok(())
+}
+
+macro_rules! print_batch_row {
+    ($out: expr, $a: expr, $b: expr, $c: expr, $d: expr, $e: expr, $f: expr, $g: expr) => {
+        writeln!(
+            $out,
+            "{:>8} | {:>12} {:>12} {:>12} {:>12} {:>12} {:>12}",
+            $a, $b, $c, $d, $e, $f, $g
+        )?
+    };
 }
 
 macro_rules! print_row {
-    ($a: expr, $b: expr, $c: expr, $d: expr, $e: expr) => {
-        println!("{:>8} | {:>12} {:>12} {:>12} {:>12}", $a, $b, $c, $d, $e)
+    ($out: expr, $a: expr, $b: expr, $c: expr, $d: expr, $e: expr) => {
+        writeln!(
+            $out,
+            "{:>8} | {:>12} {:>12} {:>12} {:>12}",
+            $a, $b, $c, $d, $e
+        )?
     };
 }
 
-fn print_data_table(args: &Args)
+/// Write a table, either in the fixed-width layout of `print_row!`, or as a GitHub-flavored
+/// Markdown or Typst table with right-aligned columns
+fn write_table(
+    out: &mut Output,
+    headers: &[&str],
+    rows: &[Vec<String>],
+    format: TableFormat,
+) -> io::Result<()>
 {
-    let p2: Box<dyn Combinatorics> = if args.dynatomic {
-        Box::new(dynatomic::Comb::new(args.crit_period))
-    } else {
-        Box::new(marked_cycle::Comb::new(args.crit_period))
+    if format == TableFormat::Typst {
+        return writeln!(out, "{}", typst::table(headers, rows));
+    }
+    if format == TableFormat::Markdown {
+        let widths: Vec<usize> = headers
+            .iter()
+            .enumerate()
+            .map(|(i, h)| rows.iter().map(|r| r[i].len()).fold(h.len(), usize::max))
+            .collect();
+        let line = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+        let pad = |cells: &[String]| {
+            let padded = cells.iter().zip(&widths).map(|(c, w)| format!("{c:>w$}"));
+            line(padded.collect())
+        };
+        let headers: Vec<String> = headers.iter().map(ToString::to_string).collect();
+        writeln!(out, "{}", pad(&headers))?;
+        writeln!(
+            out,
+            "{}",
+            line(
+                widths
+                    .iter()
+                    .map(|w| format!("{}:", "-".repeat(w - 1)))
+                    .collect()
+            )
+        )?;
+        for row in rows {
+            writeln!(out, "{}", pad(row))?;
+        }
+        return Ok(());
+    }
+
+    let line = |cells: &[String]| {
+        let (first, rest) = cells.split_first().expect("Table rows must be nonempty");
+        let rest: Vec<String> = rest.iter().map(|c| format!("{c:>12}")).collect();
+        format!("{first:>8} | {}", rest.join(" "))
     };
+    let headers: Vec<String> = headers.iter().map(ToString::to_string).collect();
+    writeln!(out, "{}", line(&headers))?;
+    for row in rows {
+        writeln!(out, "{}", line(row))?;
+    }
+    Ok(())
+}
+
+fn print_data_table(
+    out: &mut Output,
+    max_period: MarkedPeriod,
+    crit_period: CritPeriod,
+    dynatomic: bool,
+    format: TableFormat,
+) -> io::Result<()>
+{
+    let max_period = max_period.get();
+    let p2 = combinatorics(crit_period, dynatomic);
+    check_closed_form(p2.as_ref(), max_period)?;
+    let dyn_comb = dynatomic::Comb::new(crit_period.get());
+
+    #[cfg(feature = "parquet")]
+    if let Some(columnar) = match format {
+        TableFormat::Parquet => Some(ColumnarFormat::Parquet),
+        TableFormat::Arrow => Some(ColumnarFormat::Arrow),
+        _ => None,
+    } {
+        let table = columnar::invariant_table(p2.as_ref(), 2..=max_period);
+        let mut bytes = Vec::new();
+        columnar::write_batch(&table, columnar, &mut bytes)?;
+        return out.write_all(&bytes);
+    }
+
+    if let Some(separator) = match format {
+        TableFormat::Csv => Some(Separator::Comma),
+        TableFormat::Tsv => Some(Separator::Tab),
+        _ => None,
+    } {
+        let mut writer = RecordWriter::new(out, separator);
+        for period in 2..=max_period {
+            writer.write(&InvariantRecord::new(p2.as_ref(), period))?;
+        }
+        return writer.finish().map(drop);
+    }
+
+    let mut headers = vec!["period", "vertices", "edges", "faces", "genus"];
+    if dynatomic {
+        headers.extend(["prim faces", "sat faces", "prim comps", "sat comps"]);
+    }
 
-    if args.table_max_period > 0 {
-        print_row!("period", "vertices", "edges", "faces", "genus");
-        for period in 2..=args.table_max_period {
-            print_row!(
+    let rows: Vec<Vec<String>> = (2..=max_period)
+        .map(|period| {
+            let mut row = vec![
                 period,
                 p2.vertices(period),
                 p2.edges(period),
                 p2.faces(period),
-                p2.genus(period)
+                p2.genus(period),
+            ];
+            if dynatomic {
+                row.extend([
+                    dyn_comb.primitive_faces(period),
+                    dyn_comb.satellite_faces(period),
+                    dyn_comb.primitive_components(period),
+                    dyn_comb.satellite_components(period),
+                ]);
+            }
+            row.iter().map(ToString::to_string).collect()
+        })
+        .collect();
+
+    write_table(out, &headers, &rows, format)
+}
+
+/// Whether a face label, as displayed, is the one written as `wanted`: either the same label up to
+/// brackets, or the angle of its cycle class in `context`.
+fn face_label_matches(label: &str, wanted: &str, context: Context) -> bool
+{
+    if bare_label(label) == bare_label(wanted) {
+        return true;
+    }
+    parse_angle(wanted).is_ok_and(|theta| {
+        let scaled = theta * context.max_angle.0;
+        scaled.is_integer() && bare_label(label) == scaled.to_integer().to_string()
+    })
+}
+
+fn draw_faces(
+    cover: &CoverArgs,
+    face: FaceChoice,
+    label: Option<&str>,
+    standalone: bool,
+    style: &TikzStyle,
+) -> io::Result<String>
+{
+    macro_rules! render {
+        ($cov: expr) => {{
+            let cov = $cov;
+            // The renderers label the faces through their ambient `Display`
+            let _context = cov.context.enter();
+            let faces = cov.faces();
+            let wanted = label
+                .map(|wanted| {
+                    faces
+                        .iter()
+                        .find(|f| {
+                            let shown = cov.display(&f.label).to_string();
+                            face_label_matches(&shown, wanted, cov.context)
+                        })
+                        .map(|f| f.label)
+                        .ok_or_else(|| io::Error::other(format!("no face labeled {wanted}")))
+                })
+                .transpose()?;
+            let renderer = TikzRenderer::new(faces)
+                .with_style(style.clone())
+                .standalone(standalone);
+            Ok(match (wanted, face) {
+                (Some(label), _) => renderer
+                    .draw_face_by_label(&label)
+                    .expect("Face was found above"),
+                (None, FaceChoice::Largest) => renderer.draw_extreme_faces(1, Extreme::Largest),
+                (None, FaceChoice::Smallest) => renderer.draw_extreme_faces(1, Extreme::Smallest),
+                (None, FaceChoice::All) => renderer.generate(),
+            })
+        }};
+    }
+
+    match Cover::new(cover) {
+        Cover::Marked(cov) => render!(cov),
+        Cover::Dynatomic(cov) => render!(cov),
+    }
+}
+
+/// Options for the `curve` subcommand that apply to each period
+struct CurveOptions
+{
+    summary: SummaryOptions,
+    format: OutputFormat,
+    cells: bool,
+    csv: Option<PathBuf>,
+    histogram: bool,
+    expect: ExpectArgs,
+    jobs: usize,
+    progress: bool,
+}
+
+/// Apply `f` to each period on up to `jobs` worker threads, returning the results in order.
+/// The period and angle bounds are thread-local, so each worker builds its covers independently.
+fn par_map<P, T, F>(periods: &[P], jobs: usize, f: F) -> Vec<T>
+where
+    P: Copy + Sync,
+    T: Send,
+    F: Fn(P) -> T + Sync,
+{
+    let jobs = if jobs == 0 {
+        std::thread::available_parallelism().map_or(1, usize::from)
+    } else {
+        jobs
+    };
+    if jobs <= 1 || periods.len() <= 1 {
+        return periods.iter().map(|&p| f(p)).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<(usize, T)> = std::thread::scope(|s| {
+        let workers: Vec<_> = (0..jobs.min(periods.len()))
+            .map(|_| {
+                s.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(&period) = periods.get(i) else {
+                            break;
+                        };
+                        done.push((i, f(period)));
+                    }
+                    done
+                })
+            })
+            .collect();
+        workers
+            .into_iter()
+            .flat_map(|w| w.join().expect("worker thread panicked"))
+            .collect()
+    });
+    results.sort_by_key(|(i, _)| *i);
+    results.into_iter().map(|(_, x)| x).collect()
+}
+
+/// Progress of one build, printed to stderr: a percentage for each phase whose size is known,
+/// updated every tenth of the way, and the number of items and time taken once it finishes
+struct StderrProgress
+{
+    name: String,
+    total: AtomicUsize,
+    done: AtomicUsize,
+    started: Mutex<Instant>,
+}
+
+impl StderrProgress
+{
+    fn new(name: String) -> Self
+    {
+        Self {
+            name,
+            total: AtomicUsize::new(0),
+            done: AtomicUsize::new(0),
+            started: Mutex::new(Instant::now()),
+        }
+    }
+}
+
+impl ProgressSink for StderrProgress
+{
+    fn phase_started(&self, _phase: Phase, total: Option<usize>)
+    {
+        self.total.store(total.unwrap_or(0), Ordering::Relaxed);
+        self.done.store(0, Ordering::Relaxed);
+        *self.started.lock().expect("progress clock poisoned") = Instant::now();
+    }
+
+    fn items_processed(&self, phase: Phase, count: usize)
+    {
+        let total = self.total.load(Ordering::Relaxed);
+        let done = self.done.fetch_add(count, Ordering::Relaxed);
+        if total == 0 {
+            return;
+        }
+        let (before, after) = (10 * done / total, 10 * (done + count) / total);
+        if after > before && after < 10 {
+            eprintln!("{} {}: {}%", self.name, phase.name(), 10 * after);
+        }
+    }
+
+    fn phase_finished(&self, phase: Phase)
+    {
+        let elapsed = self
+            .started
+            .lock()
+            .expect("progress clock poisoned")
+            .elapsed();
+        eprintln!(
+            "{} {}: {} done in {elapsed:.2?}",
+            self.name,
+            phase.name(),
+            self.done.load(Ordering::Relaxed)
+        );
+    }
+}
+
+/// What `curve` reports for one period in a range
+struct PeriodReport
+{
+    cover: CoverArgs,
+    counts: (usize, usize, usize, i64),
+    row: [String; 7],
+    json: Option<String>,
+    csv: Option<CsvTables>,
+}
+
+fn run_curve(
+    out: &mut Output,
+    periods: PeriodRange,
+    crit_period: CritPeriod,
+    dynatomic: bool,
+    opts: &CurveOptions,
+) -> io::Result<bool>
+{
+    let cover_args = |period| CoverArgs {
+        period,
+        crit_period,
+        dynatomic,
+    };
+
+    if periods.is_single() && opts.format == OutputFormat::Text {
+        let cover = cover_args(periods.start);
+        let cov = Cover::build(&cover, opts.progress);
+        print_combinatorics(out, &cover, &cov, opts)?;
+        if let Some(dir) = &opts.csv {
+            write_csv(&cover, &cov.to_csv(), dir)?;
+        }
+        return Ok(opts.expect.check(&cover, cov.counts()));
+    }
+
+    if opts.format == OutputFormat::Ndjson {
+        let mut ok = true;
+        for period in periods.periods() {
+            let cover = cover_args(period);
+            let cov = Cover::build(&cover, opts.progress);
+            cov.write_ndjson(out, &opts.summary.filter)?;
+            if let Some(dir) = &opts.csv {
+                write_csv(&cover, &cov.to_csv(), dir)?;
+            }
+            ok &= opts.expect.check(&cover, cov.counts());
+        }
+        return Ok(ok);
+    }
+
+    let period_list: Vec<MarkedPeriod> = periods.periods().collect();
+    let reports = par_map(&period_list, opts.jobs, |period| {
+        let cover = cover_args(period);
+        let cov = Cover::build(&cover, opts.progress);
+        let counts = cov.counts();
+        let (v, e, f, g) = counts;
+        let sizes = cov.face_sizes();
+        let extreme = |size: Option<&usize>| size.map_or("-".to_owned(), usize::to_string);
+        let row = [
+            period.to_string(),
+            v.to_string(),
+            e.to_string(),
+            f.to_string(),
+            g.to_string(),
+            extreme(sizes.iter().min()),
+            extreme(sizes.iter().max()),
+        ];
+        PeriodReport {
+            json: (opts.format == OutputFormat::Json).then(|| cov.to_json(opts.cells)),
+            csv: opts.csv.as_ref().map(|_| cov.to_csv()),
+            cover,
+            counts,
+            row,
+        }
+    });
+
+    if opts.format == OutputFormat::Text {
+        print_batch_row!(
+            out, "period", "vertices", "edges", "faces", "genus", "smallest", "largest"
+        );
+    }
+    let mut json = Vec::new();
+    let mut ok = true;
+    for report in reports {
+        ok &= opts.expect.check(&report.cover, report.counts);
+        match report.json {
+            Some(obj) => json.push(obj),
+            None => {
+                let [p, v, e, f, g, min, max] = &report.row;
+                print_batch_row!(out, p, v, e, f, g, min, max);
+            }
+        }
+        if let (Some(dir), Some(tables)) = (&opts.csv, &report.csv) {
+            write_csv(&report.cover, tables, dir)?;
+        }
+    }
+
+    if opts.format == OutputFormat::Json {
+        if periods.is_single() {
+            writeln!(out, "{}", json.concat())?;
+        } else {
+            writeln!(out, "[\n{}\n]", json.join(",\n"))?;
+        }
+    }
+    Ok(ok)
+}
+
+const REPL_HELP: &str = "\
+Commands:
+    face <label>        show the face with this label, e.g. `<3>` or `3`
+    faces <angle>       list the faces around the vertex marking this angle
+    neighbors <angle>   list the vertices joined to the vertex marking this angle
+    edge <id>           show an edge, its wake, and its kneading sequence
+    ends                list the ends over the puncture of the base and their local degrees
+    monodromy           show the order and orbits of the monodromy group
+    real                list the real components in order along the real axis, and count
+                        the faces by how often they cross it along real edges
+    limbs               count the vertices, edges, and faces in each limb of the main cardioid
+    summary             show cell counts and genus
+    help                show this message
+    quit                leave the REPL";
+
+/// Strip the brackets from a cell label, so that `<3>`, `[3]`, and `3` all compare equal
+fn bare_label(label: &str) -> &str
+{
+    label.trim_matches(|c| matches!(c, '<' | '>' | '[' | ']' | '(' | ')'))
+}
+
+/// The vertex marking `text`, parsed as an angle of the period of `context`
+fn find_vertex<'a, V: ContainsAngle>(
+    vertices: &'a [V],
+    text: &str,
+    context: Context,
+) -> Result<&'a V, String>
+{
+    let theta = parse_angle(text)?;
+    let scaled = theta * context.max_angle.0;
+    if !scaled.is_integer() {
+        return Err(format!(
+            "{theta} does not have period dividing {}",
+            context.period
+        ));
+    }
+    let angle = IntAngle(scaled.to_integer());
+    vertices
+        .iter()
+        .find(|v| v.contains_angle(angle, context))
+        .ok_or_else(|| format!("no vertex marks {theta}"))
+}
+
+/// The cells of a cover, as queried by the REPL
+struct ReplCover<'a, V, F>
+{
+    context: Context,
+    vertices: &'a [V],
+    edges: &'a [Edge<V>],
+    faces: &'a [Face<AugmentedVertex<V>, F>],
+    ends: Option<&'a Ends>,
+}
+
+/// Run one REPL command, returning whether to keep going. Angles are read, and cells printed,
+/// in the cover's context.
+fn repl_eval<V, F>(out: &mut Output, line: &str, cover: &ReplCover<V, F>) -> io::Result<bool>
+where
+    V: ContainsAngle + CellKey + DisplayIn + Copy + Eq + Hash,
+    F: DisplayIn,
+{
+    let ReplCover {
+        context,
+        vertices,
+        edges,
+        faces,
+        ends,
+    } = *cover;
+    let mut words = line.split_whitespace();
+    let (Some(command), arg) = (words.next(), words.next()) else {
+        return Ok(true);
+    };
+
+    let reply = match (command, arg) {
+        ("quit" | "exit", _) => return Ok(false),
+        ("help", _) => Ok(REPL_HELP.to_owned()),
+        ("summary", _) => Ok(format!(
+            "{} vertices, {} edges, {} faces",
+            vertices.len(),
+            edges.len(),
+            faces.len()
+        )),
+        ("face", Some(label)) => faces
+            .iter()
+            .find(|f| bare_label(&context.display(&f.label).to_string()) == bare_label(label))
+            .map(|f| format!("{}; size = {}", context.display(f), f.len()))
+            .ok_or_else(|| format!("no face labeled {label}")),
+        ("faces", Some(angle)) => find_vertex(vertices, angle, context).map(|v| {
+            let lines: Vec<String> = faces
+                .iter()
+                .filter(|f| f.vertices.iter().any(|u| u.vertex == *v))
+                .map(|f| context.display(f).to_string())
+                .collect();
+            lines.join("\n")
+        }),
+        ("neighbors", Some(angle)) => find_vertex(vertices, angle, context).map(|v| {
+            let lines: Vec<String> = edges
+                .iter()
+                .filter_map(|e| {
+                    let other = if e.start == *v {
+                        &e.end
+                    } else if e.end == *v {
+                        &e.start
+                    } else {
+                        return None;
+                    };
+                    Some(format!("{} \twake: {}", context.display(other), e.wake))
+                })
+                .collect();
+            lines.join("\n")
+        }),
+        ("edge", Some(id)) => id
+            .parse::<usize>()
+            .map_err(|e| format!("invalid edge id {id:?}: {e}"))
+            .and_then(|i| {
+                edges
+                    .get(i)
+                    .map(|e| context.display(e).to_string())
+                    .ok_or_else(|| format!("no edge with id {i}"))
+            }),
+        ("ends", _) => ends
+            .ok_or_else(|| "ends are only known over Per_1 and Per_2".to_owned())
+            .map(|ends| {
+                let counts: Vec<String> = ends
+                    .degree_counts()
+                    .iter()
+                    .map(|(degree, count)| format!("{count} of degree {degree}"))
+                    .collect();
+                let mut lines = vec![format!("{} ends: {}", ends.len(), counts.join(", "))];
+                lines.extend(ends.ends.iter().map(|end| {
+                    let cell = match end.cell {
+                        EndCell::Vertex(v) => context.display(&vertices[v]).to_string(),
+                        EndCell::Face(k) => context.display(&faces[k].label).to_string(),
+                    };
+                    format!("{cell} \tlocal degree: {}", end.local_degree)
+                }));
+                lines.join("\n")
+            }),
+        ("monodromy", _) => {
+            let group = MonodromyGroup::from_edges(vertices, edges);
+            let orbits = group.orbits();
+            Ok(format!(
+                "acts on {} vertices with {} generators; order {}; {}",
+                group.degree(),
+                group.generators.len(),
+                group.order(),
+                if orbits.len() == 1 {
+                    "transitive".to_owned()
+                } else {
+                    format!("{} orbits", orbits.len())
+                }
+            ))
+        }
+        ("real", _) => {
+            let components = real_components(edges);
+            let mut lines = vec![format!(
+                "{} real components, from c = 1/4 towards c = -2:",
+                components.len()
+            )];
+            lines.extend(components.iter().map(|c| {
+                format!(
+                    "{} \tkneading sequence: {} \tedges: {:?}",
+                    c.wake, c.kneading_sequence, c.edge_ids
+                )
+            }));
+            let crossings = real_crossings(edges, faces.iter().map(|f| &f.edge_ids[..]));
+            let stats = RealCrossingStats::new(&crossings);
+            let counts: Vec<String> = stats
+                .faces_by_crossings
+                .iter()
+                .map(|(crossings, n)| format!("{crossings}: {n}"))
+                .collect();
+            lines.push(format!(
+                "faces by crossings along real edges: {} ({} bi-real)",
+                counts.join(", "),
+                stats.num_bi_real()
+            ));
+            Ok(lines.join("\n"))
+        }
+        ("limbs", _) => {
+            let limbs = LimbDecomposition::new(
+                context,
+                vertices,
+                edges,
+                faces.iter().map(|f| &f.edge_ids[..]),
             );
+            let count =
+                |cells: &BTreeMap<Limb, Vec<usize>>, limb| cells.get(&limb).map_or(0, Vec::len);
+            let lines: Vec<String> = limbs
+                .limbs()
+                .into_iter()
+                .map(|limb| {
+                    format!(
+                        "{limb}: {} vertices, {} edges, {} faces",
+                        count(&limbs.vertices, limb),
+                        count(&limbs.edges, limb),
+                        count(&limbs.faces, limb)
+                    )
+                })
+                .collect();
+            Ok(lines.join("\n"))
         }
+        ("face" | "faces" | "neighbors" | "edge", None) => {
+            Err(format!("`{command}` needs an argument; try `help`"))
+        }
+        _ => Err(format!("unknown command `{command}`; try `help`")),
+    };
+
+    match reply {
+        Ok(text) => writeln!(out, "{text}")?,
+        Err(e) => writeln!(out, "error: {e}")?,
+    }
+    out.flush()?;
+    Ok(true)
+}
+
+/// Read commands from stdin until it closes or the user quits
+fn repl_loop<V, F>(out: &mut Output, cover: &ReplCover<V, F>) -> io::Result<()>
+where
+    V: ContainsAngle + CellKey + DisplayIn + Copy + Eq + Hash,
+    F: DisplayIn,
+{
+    let stdin = io::stdin();
+    loop {
+        eprint!("> ");
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 || !repl_eval(out, line.trim(), cover)? {
+            return Ok(());
+        }
+    }
+}
+
+fn repl(out: &mut Output, cover: &CoverArgs) -> io::Result<()>
+{
+    let cov = Cover::new(cover);
+    let (v, e, f, g) = cov.counts();
+    eprintln!(
+        "Built cover with {v} vertices, {e} edges, {f} faces, genus {g}. Type `help` for commands."
+    );
+
+    match &cov {
+        Cover::Marked(cov) => repl_loop(
+            out,
+            &ReplCover {
+                context: cov.context,
+                vertices: &cov.vertices,
+                edges: &cov.edges,
+                faces: &cov.faces(),
+                ends: cov.ends_at_infinity().as_ref(),
+            },
+        ),
+        Cover::Dynatomic(cov) => repl_loop(
+            out,
+            &ReplCover {
+                context: cov.context,
+                vertices: &cov.vertices,
+                edges: &cov.edges,
+                faces: &cov.faces(),
+                ends: cov.ends_at_infinity().as_ref(),
+            },
+        ),
+    }
+}
+
+/// Force-directed iterations used to place the 1-skeleton of exported meshes
+const MESH_ITERATIONS: usize = 200;
+
+/// Export a cover to `out`, or for tables and matrices into the directory `dir` if given. Only
+/// CSV and TSV tables can be written without it.
+fn export(
+    out: &mut Output,
+    cover: &CoverArgs,
+    format: ExportFormat,
+    dir: Option<&Path>,
+    skeleton: bool,
+    style: &TikzStyle,
+) -> io::Result<()>
+{
+    let cov = Cover::new(cover);
+    let text = match format {
+        ExportFormat::Csv | ExportFormat::Tsv => {
+            let separator = if format == ExportFormat::Tsv {
+                Separator::Tab
+            } else {
+                Separator::Comma
+            };
+            let tables = cov.records().to_tables(separator);
+            if let Some(dir) = dir {
+                return write_csv(cover, &tables, dir);
+            }
+            [tables.vertices, tables.edges, tables.faces].join("\n")
+        }
+        #[cfg(feature = "parquet")]
+        ExportFormat::Parquet | ExportFormat::Arrow => {
+            let columnar = if format == ExportFormat::Parquet {
+                ColumnarFormat::Parquet
+            } else {
+                ColumnarFormat::Arrow
+            };
+            let dir = dir.ok_or_else(|| need_directory("Parquet and Arrow tables"))?;
+            return write_columnar(cover, &cov.to_columnar(), dir, columnar);
+        }
+        ExportFormat::Mtx => {
+            let dir = dir.ok_or_else(|| need_directory("Matrices"))?;
+            return write_matrices(cover, &cov.matrices(), dir, MatrixFormat::MatrixMarket);
+        }
+        #[cfg(feature = "npz")]
+        ExportFormat::Npz => {
+            let dir = dir.ok_or_else(|| need_directory("Matrices"))?;
+            return write_matrices(cover, &cov.matrices(), dir, MatrixFormat::Npz);
+        }
+        ExportFormat::Dot => cov.to_dot(!skeleton),
+        ExportFormat::Graphml => cov.to_graphml(!skeleton),
+        ExportFormat::Gexf => cov.to_gexf(!skeleton),
+        ExportFormat::Polymake => cov.to_polymake(),
+        ExportFormat::Obj => cov.to_obj(MESH_ITERATIONS),
+        ExportFormat::Ply => cov.to_ply(MESH_ITERATIONS),
+        ExportFormat::Json => cov.to_json(!skeleton),
+        ExportFormat::Tikz => cov.to_tikz(style),
+        ExportFormat::Svg => cov.to_svg(),
+        ExportFormat::Typst => cov.to_typst(),
+    };
+
+    writeln!(out, "{text}")
+}
+
+/// The periods checked by `verify`, from 2 up to `max_period`
+fn verified_periods(max_period: MarkedPeriod) -> impl Iterator<Item = MarkedPeriod>
+{
+    (2..=max_period.get()).filter_map(|period| MarkedPeriod::new(period).ok())
+}
+
+/// Returns whether every period matched
+fn verify(
+    out: &mut Output,
+    max_period: MarkedPeriod,
+    crit_period: CritPeriod,
+    dynatomic: bool,
+    jobs: usize,
+) -> io::Result<bool>
+{
+    let p2 = combinatorics(crit_period, dynatomic);
+    let periods: Vec<MarkedPeriod> = verified_periods(max_period).collect();
+    let counts = par_map(&periods, jobs, |period| {
+        Cover::new(&CoverArgs {
+            period,
+            crit_period,
+            dynatomic,
+        })
+        .counts()
+    });
+    let mut all_ok = true;
+
+    print_row!(out, "period", "vertices", "edges", "faces", "genus");
+    for (period, (v, e, f, g)) in periods.into_iter().zip(counts) {
+        let period = period.get();
+        let check = |computed: i64, expected: i64| {
+            if computed == expected {
+                computed.to_string()
+            } else {
+                format!("{computed} != {expected}")
+            }
+        };
+        let ok = v as i64 == p2.vertices(period)
+            && e as i64 == p2.edges(period)
+            && f as i64 == p2.faces(period)
+            && g == p2.genus(period);
+        all_ok &= ok;
+        print_row!(
+            out,
+            period,
+            check(v as i64, p2.vertices(period)),
+            check(e as i64, p2.edges(period)),
+            check(f as i64, p2.faces(period)),
+            check(g, p2.genus(period))
+        );
     }
+    Ok(all_ok)
 }
 
-fn draw_largest_face(args: &Args)
+/// Write one leaf of the lamination per line, prefixed by its period if several periods are
+/// listed
+fn list_leaves(
+    out: &mut Output,
+    period: MarkedPeriod,
+    crit_period: CritPeriod,
+    all_periods: bool,
+    format: LeafFormat,
+    wake: Option<&[RatAngle]>,
+) -> io::Result<()>
 {
-    if args.tikz {
-        let cov = MarkedCycleCover::new(args.marked_period, args.crit_period);
-        let tikz = TikzRenderer::new(cov.faces).draw_smallest_face();
-        // let tikz = TikzRenderer::new(cov.faces).draw_largest_face();
-        println!("{tikz}");
+    let period = period.get();
+    let wake = wake.map(|w| (w[0].min(w[1]), w[0].max(w[1])));
+    let arcs = Lamination::new()
+        .with_crit_period(crit_period.get())
+        .into_arcs(period);
+    let first = if all_periods { 1 } else { period };
+
+    for per in first..=period {
+        let Some(leaves) = arcs.get(per as usize) else {
+            continue;
+        };
+        let denom = (1_i64 << per) - 1;
+        let show = |theta: RatAngle| {
+            let k = (theta * denom).to_integer();
+            match format {
+                LeafFormat::Binary => format!("{k:0width$b}", width = per as usize),
+                LeafFormat::Integer => k.to_string(),
+                LeafFormat::Fraction | LeafFormat::Tikz => theta.to_string(),
+            }
+        };
+        for &(a, b) in leaves {
+            if wake.is_some_and(|(w0, w1)| a < w0 || b > w1) {
+                continue;
+            }
+            if all_periods {
+                write!(out, "{per} ")?;
+            }
+            writeln!(out, "{} {}", show(a), show(b))?;
+        }
     }
+    Ok(())
+}
+
+/// Build a cover `runs` times and print the fastest time for each phase, with its share of the
+/// total
+fn bench(out: &mut Output, cover: &CoverArgs, runs: usize) -> io::Result<()>
+{
+    let build = || {
+        if cover.dynatomic {
+            let (cov, timings) =
+                DynatomicCoverBuilder::from_periods(cover.period, cover.crit_period).build_timed();
+            (cov.num_faces(), timings)
+        } else {
+            let (cov, timings) =
+                MarkedCycleCoverBuilder::from_periods(cover.period, cover.crit_period)
+                    .build_timed();
+            (cov.num_faces(), timings)
+        }
+    };
+
+    let (faces, mut best) = build();
+    for _ in 1..runs {
+        let (_, timings) = build();
+        best.lamination = best.lamination.min(timings.lamination);
+        best.cycles = best.cycles.min(timings.cycles);
+        best.edges = best.edges.min(timings.edges);
+        best.faces = best.faces.min(timings.faces);
+    }
+
+    let name = if cover.dynatomic { "Dyn" } else { "MC" };
+    let total = best.total();
+    let fastest = if runs > 1 {
+        format!(" (fastest of {runs} runs)")
+    } else {
+        String::new()
+    };
+    writeln!(
+        out,
+        "Built {name}_{}(Per_{}) with {faces} faces in {:.3} ms{fastest}",
+        cover.period,
+        cover.crit_period,
+        total.as_secs_f64() * 1e3
+    )?;
+    for (phase, time) in best.phases() {
+        let share = 100.0 * time.as_secs_f64() / total.as_secs_f64().max(f64::MIN_POSITIVE);
+        writeln!(
+            out,
+            "    {phase:<12} {:>12.3} ms {share:>6.1}%",
+            time.as_secs_f64() * 1e3
+        )?;
+    }
+
+    // The heap figures are those of the first build
+    #[cfg(feature = "stats")]
+    {
+        let stats = best.stats;
+        writeln!(
+            out,
+            "Made {} allocations, peaking at {} in use (estimated {})",
+            stats.allocations(),
+            human_bytes(stats.peak_bytes() as f64),
+            human_bytes(SizeEstimate::new(cover)?.bytes)
+        )?;
+        for (phase, heap) in stats.phases() {
+            writeln!(
+                out,
+                "    {phase:<12} {:>12} allocations {:>12} peak",
+                heap.allocations,
+                human_bytes(heap.peak_bytes as f64)
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Parse a cover named as in the CSV file stems, `mc_<PERIOD>[_<CRIT_PERIOD>]` or
+/// `dyn_<PERIOD>[_<CRIT_PERIOD>]`
+fn parse_cover_spec(spec: &str) -> Option<CoverArgs>
+{
+    let parts: Vec<&str> = spec.split('_').collect();
+    let [curve @ ("mc" | "dyn"), period, ref crit @ ..] = parts[..] else {
+        return None;
+    };
+    if crit.len() > 1 {
+        return None;
+    }
+    Some(CoverArgs {
+        period: period.parse().ok()?,
+        crit_period: crit
+            .first()
+            .map_or(Some(CritPeriod::ONE), |c| c.parse().ok())?,
+        dynatomic: curve == "dyn",
+    })
+}
+
+/// Read one side of a `diff`, building the cover if it is named as in the CSV file stems and
+/// reading a saved JSON summary otherwise
+fn load_summary(spec: &str) -> io::Result<JsonSummary>
+{
+    let cover = parse_cover_spec(spec);
+
+    if let Some(cover) = cover {
+        let cov = Cover::new(&cover);
+        let (vertices, edges, faces, genus) = cov.counts();
+        return Ok(JsonSummary {
+            curve: if cover.dynatomic { "Dyn" } else { "MC" }.to_owned(),
+            period: cover.period.get(),
+            crit_period: cover.crit_period.get(),
+            vertices,
+            edges,
+            faces,
+            genus,
+            face_sizes: cov.face_sizes(),
+        });
+    }
+
+    let mut text = String::new();
+    let file = File::open(spec).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("{spec} is neither a cover such as `mc_10_2` nor a readable file: {e}"),
+        )
+    })?;
+    if spec.ends_with(".gz") {
+        GzDecoder::new(file).read_to_string(&mut text)?;
+    } else {
+        io::BufReader::new(file).read_to_string(&mut text)?;
+    }
+    JsonSummary::parse(&text).map_err(|e| io::Error::other(format!("{spec}: {e}")))
+}
+
+/// Report how two covers differ, returning whether they agree
+fn diff(out: &mut Output, left: &str, right: &str) -> io::Result<bool>
+{
+    let sides = [load_summary(left)?, load_summary(right)?];
+    let names = sides
+        .each_ref()
+        .map(|s| format!("{}_{}(Per_{})", s.curve, s.period, s.crit_period));
+    let change = |a: i64, b: i64| match b - a {
+        0 => String::new(),
+        d => format!("{d:+}"),
+    };
+    let row = |label: &str, [a, b]: [i64; 2]| {
+        vec![label.to_owned(), a.to_string(), b.to_string(), change(a, b)]
+    };
+
+    let [l, r] = &sides;
+    let rows = vec![
+        row("vertices", [l.vertices as i64, r.vertices as i64]),
+        row("edges", [l.edges as i64, r.edges as i64]),
+        row("faces", [l.faces as i64, r.faces as i64]),
+        row("genus", [l.genus, r.genus]),
+    ];
+    let headers = ["", &names[0], &names[1], "change"];
+    write_table(out, &headers, &rows, TableFormat::Text)?;
+
+    let [left_sizes, right_sizes] = sides.each_ref().map(|s| {
+        let mut counts = BTreeMap::new();
+        for &size in &s.face_sizes {
+            *counts.entry(size).or_insert(0) += 1;
+        }
+        counts
+    });
+    let sizes: BTreeSet<usize> = left_sizes
+        .keys()
+        .chain(right_sizes.keys())
+        .copied()
+        .collect();
+    let size_rows: Vec<Vec<String>> = sizes
+        .into_iter()
+        .map(|size| {
+            let count = |m: &BTreeMap<usize, i64>| m.get(&size).copied().unwrap_or(0);
+            row(&size.to_string(), [count(&left_sizes), count(&right_sizes)])
+        })
+        .filter(|row| !row[3].is_empty())
+        .collect();
+
+    if size_rows.is_empty() {
+        writeln!(out, "\nFace sizes agree")?;
+    } else {
+        writeln!(out, "\nFaces of each size, where they differ:")?;
+        let headers = ["size", &names[0], &names[1], "change"];
+        write_table(out, &headers, &size_rows, TableFormat::Text)?;
+    }
+
+    Ok(rows.iter().all(|row| row[3].is_empty()) && size_rows.is_empty())
+}
+
+/// Print the doubling map on the angles of period dividing `period`, or on the vertices of
+/// the dynatomic curve with critical period `crit_period` if given
+fn doubling(
+    out: &mut Output,
+    period: MarkedPeriod,
+    crit_period: Option<CritPeriod>,
+    format: PermutationFormat,
+) -> io::Result<()>
+{
+    let (permutation, points) = match crit_period {
+        Some(crit_period) => (
+            DynatomicCover::from_periods(period, crit_period).doubling_permutation(),
+            format!("the vertices of Dyn_{period}(Per_{crit_period})"),
+        ),
+        None => (
+            Permutation::doubling(period.get()),
+            format!("the angles k/(2^{period}-1)"),
+        ),
+    };
+    match format {
+        PermutationFormat::Cycles => writeln!(out, "{permutation}"),
+        PermutationFormat::Images => {
+            for image in permutation.images() {
+                writeln!(out, "{image}")?;
+            }
+            Ok(())
+        }
+        PermutationFormat::Mtx => write!(
+            out,
+            "{}",
+            permutation
+                .matrix()
+                .to_matrix_market(&format!("doubling map on {points}"))
+        ),
+    }
+}
+
+fn query(out: &mut Output, text: &str, crit_period: CritPeriod) -> io::Result<()>
+{
+    let theta = parse_angle(text).map_err(io::Error::other)?;
+    let period = doubling_period(theta)
+        .ok_or_else(|| io::Error::other(format!("{theta} is not periodic under doubling")))?;
+
+    let cov = MarkedCycleCover::new(period, crit_period.get());
+    let context = cov.context;
+    let max_angle = context.max_angle;
+    let point = AbstractPoint::new(max_angle.scale_by_ratio(&theta));
+    let cycle = AbstractCycle::new_compute_in(point, context);
+    let orbit: Vec<String> = get_orbit_in(context, point.angle)
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+
+    let partner = Lamination::new()
+        .with_crit_period(crit_period.get())
+        .into_arcs_of_period(period)
+        .into_iter()
+        .find_map(|(a, b)| {
+            if a == theta {
+                Some(b)
+            } else if b == theta {
+                Some(a)
+            } else {
+                None
+            }
+        });
+
+    if *theta.denom() == max_angle.0 {
+        writeln!(out, "angle:             {theta}")?;
+    } else {
+        writeln!(
+            out,
+            "angle:             {theta} = {}/{max_angle}",
+            point.angle
+        )?;
+    }
+    writeln!(out, "period:            {period}")?;
+    writeln!(out, "binary:            {:b}", context.display(&point))?;
+    writeln!(out, "orbit:             {}", orbit.join(" "))?;
+    writeln!(
+        out,
+        "kneading sequence: {}",
+        point.kneading_sequence_in(context)
+    )?;
+    writeln!(out, "cycle:             {}", context.display(&cycle))?;
+    writeln!(
+        out,
+        "cycle class:       {}",
+        context.display(&cycle.compute_cycle_class_in(context))
+    )?;
+    match partner {
+        Some(other) => {
+            let other_angle = max_angle.scale_by_ratio(&other);
+            let kind = if point.angle + other_angle == max_angle {
+                "real"
+            } else {
+                "non-real"
+            };
+            writeln!(out, "wake partner:      {other} ({kind})")?;
+        }
+        None => writeln!(out, "wake partner:      none")?,
+    }
+    let face_title = format!("face of MC_{period}:");
+    match cov.face_containing(cycle) {
+        Some(face) => writeln!(
+            out,
+            "{face_title:<19}{} (size {}, degree {})",
+            context.display(&face.label),
+            face.len(),
+            face.degree
+        )?,
+        None => writeln!(out, "{face_title:<19}none")?,
+    }
+    Ok(())
+}
+
+/// Run a subcommand, returning whether it succeeded
+fn run(
+    command: Command,
+    out: &mut Output,
+    output: Option<&Path>,
+    tikz_style: &TikzStyle,
+) -> io::Result<bool>
+{
+    match command {
+        Command::Curve {
+            periods,
+            crit_period,
+            dynatomic,
+            binary,
+            fractions,
+            indent,
+            format,
+            cells,
+            csv,
+            histogram,
+            expect,
+            filter,
+            jobs,
+            progress,
+        } => {
+            let opts = CurveOptions {
+                summary: SummaryOptions {
+                    indent,
+                    binary,
+                    fractions,
+                    filter: (&filter).into(),
+                    listing: (&filter).into(),
+                },
+                format,
+                cells,
+                csv,
+                histogram,
+                expect,
+                jobs,
+                progress,
+            };
+            return run_curve(out, periods, crit_period, dynatomic, &opts);
+        }
+        Command::Table {
+            max_period,
+            crit_period,
+            dynatomic,
+            table_format,
+        } => print_data_table(out, max_period, crit_period, dynatomic, table_format)?,
+        Command::Tikz {
+            cover,
+            face,
+            label,
+            standalone,
+        } => writeln!(
+            out,
+            "{}",
+            draw_faces(&cover, face, label.as_deref(), standalone, tikz_style)?
+        )?,
+        Command::Export {
+            cover,
+            format,
+            skeleton,
+        } => export(out, &cover, format, output, skeleton, tikz_style)?,
+        Command::Lamination {
+            period,
+            crit_period,
+            geodesics,
+            all_periods,
+            standalone,
+            format: LeafFormat::Tikz,
+            wake,
+        } => {
+            if wake.is_some() {
+                return Err(io::Error::other("--wake only applies to listed leaves"));
+            }
+            let lamination = Lamination::new().with_crit_period(crit_period.get());
+            let tikz = LaminationRenderer::new(lamination, period.get())
+                .geodesics(geodesics)
+                .all_periods(all_periods)
+                .standalone(standalone)
+                .generate();
+            writeln!(out, "{tikz}")?;
+        }
+        Command::Lamination {
+            period,
+            crit_period,
+            all_periods,
+            format,
+            wake,
+            ..
+        } => list_leaves(
+            out,
+            period,
+            crit_period,
+            all_periods,
+            format,
+            wake.as_deref(),
+        )?,
+        Command::Verify {
+            max_period,
+            crit_period,
+            dynatomic,
+            jobs,
+        } => return verify(out, max_period, crit_period, dynatomic, jobs),
+        Command::Repl { cover } => repl(out, &cover)?,
+        Command::Wake {
+            angle0,
+            angle1,
+            crit_period,
+        } => wake(out, [&angle0, &angle1], crit_period)?,
+        Command::Query { angle, crit_period } => query(out, &angle, crit_period)?,
+        Command::Doubling {
+            period,
+            dynatomic,
+            crit_period,
+            format,
+        } => doubling(out, period, dynatomic.then_some(crit_period), format)?,
+        Command::Bench { cover, runs } => bench(out, &cover, runs)?,
+        Command::Diff { left, right } => return diff(out, &left, &right),
+        Command::Sequence {
+            invariant,
+            max_period,
+            start,
+            crit_period,
+            dynatomic,
+        } => {
+            let comb = combinatorics(crit_period, dynatomic);
+            check_closed_form(comb.as_ref(), max_period.get())?;
+            for period in start.get()..=max_period.get() {
+                writeln!(out, "{period} {}", invariant.eval(comb.as_ref(), period))?;
+            }
+        }
+    }
+    Ok(true)
+}
+
+fn wake(out: &mut Output, texts: [&str; 2], crit_period: CritPeriod) -> io::Result<()>
+{
+    let mut thetas = [RatAngle::default(); 2];
+    let mut periods = [0; 2];
+    for (k, text) in texts.into_iter().enumerate() {
+        thetas[k] = parse_angle(text).map_err(io::Error::other)?;
+        periods[k] = doubling_period(thetas[k]).ok_or_else(|| {
+            io::Error::other(format!("{} is not periodic under doubling", thetas[k]))
+        })?;
+    }
+    let [theta0, theta1] = thetas;
+    writeln!(out, "angles:            {theta0}, {theta1}")?;
+
+    if periods[0] != periods[1] {
+        writeln!(
+            out,
+            "leaf:              no; the angles have periods {} and {}",
+            periods[0], periods[1]
+        )?;
+        return Ok(());
+    }
+    let period = periods[0];
+    let context = Context::new(period);
+
+    let (lo, hi) = (theta0.min(theta1), theta0.max(theta1));
+    let is_leaf = Lamination::new()
+        .with_crit_period(crit_period.get())
+        .into_arcs_of_period(period)
+        .into_iter()
+        .any(|(a, b)| (a.min(b), a.max(b)) == (lo, hi));
+
+    let max_angle = context.max_angle;
+    let points = [lo, hi].map(|theta| AbstractPoint::new(max_angle.scale_by_ratio(&theta)));
+    let cycles = points.map(|point| AbstractCycle::new_compute_in(point, context));
+    let real = points[0].angle + points[1].angle == max_angle;
+    let satellite = cycles[0] == cycles[1];
+
+    writeln!(out, "period:            {period}")?;
+    writeln!(
+        out,
+        "leaf:              {}",
+        if is_leaf { "yes" } else { "no" }
+    )?;
+    writeln!(
+        out,
+        "cycles:            {} -- {}",
+        context.display(&cycles[0]),
+        context.display(&cycles[1])
+    )?;
+    if is_leaf {
+        writeln!(
+            out,
+            "edge:              {}, {}",
+            if satellite { "satellite" } else { "primitive" },
+            if real { "real" } else { "non-real" }
+        )?;
+        writeln!(
+            out,
+            "kneading sequence: {}",
+            points[0].kneading_sequence_in(context)
+        )?;
+    }
+    Ok(())
+}
+
+/// Read the configuration file given with `--config`, or `marked-cycles.toml` if it exists
+fn load_config(path: Option<&Path>) -> io::Result<Config>
+{
+    let path = match path {
+        Some(path) => path,
+        None if Path::new(CONFIG_FILE).is_file() => Path::new(CONFIG_FILE),
+        None => return Ok(Config::default()),
+    };
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", path.display())))?;
+    Config::parse(&text).map_err(|e| io::Error::other(format!("{}: {e}", path.display())))
+}
+
+fn fail(e: impl Display) -> !
+{
+    eprintln!("Error: {e}");
+    std::process::exit(1);
 }
 
 fn main()
 {
-    let args = Args::parse();
+    let matches = Cli::command().get_matches();
+    let mut cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
-    if args.tikz {
-        draw_largest_face(&args);
+    let config = load_config(cli.config.as_deref()).unwrap_or_else(|e| fail(e));
+    if let Some((_, sub_matches)) = matches.subcommand() {
+        cli.command
+            .apply_config(&config, sub_matches)
+            .unwrap_or_else(|e| fail(e));
+    }
+
+    let planned = cli.command.planned_covers();
+    if cli.dry_run {
+        print_estimates(&planned).unwrap_or_else(|e| fail(e));
         return;
     }
-    print_combinatorics(&args);
-    print_data_table(&args);
+    if !confirm_size(&planned).unwrap_or_else(|e| fail(e)) {
+        std::process::exit(1);
+    }
+
+    // Tables and matrices go to a directory of files rather than a single output stream
+    let dir_export = matches!(
+        cli.command,
+        Command::Export { format, .. } if format.writes_directory()
+    );
+    let file = cli.output.as_deref().filter(|_| !dir_export);
+
+    let result = Output::open(file, cli.gzip).and_then(|mut out| {
+        let ok = run(cli.command, &mut out, cli.output.as_deref(), &config.tikz)?;
+        out.finish()?;
+        Ok(ok)
+    });
+
+    match result {
+        Ok(true) => {}
+        Ok(false) => std::process::exit(1),
+        Err(e) => fail(e),
+    }
 }
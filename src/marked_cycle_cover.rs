@@ -1,23 +1,69 @@
 use crate::abstract_cycles::{AbstractCycle, AbstractCycleClass, AbstractPoint};
-use crate::common::cells::{AugmentedVertex, HalfPlane, VertexData};
-use crate::common::{cells, get_orbit};
-use crate::global_state::{set_period, MAX_ANGLE, PERIOD};
+#[cfg(feature = "parquet")]
+use crate::columnar::CellBatches;
+#[cfg(feature = "std")]
+use crate::common::cells::Cell;
+use crate::common::cells::{AugmentedVertex, HalfPlane, VertexData, VertexId, VertexList};
+#[cfg(feature = "std")]
+use crate::common::cells::{CellFilter, SummaryOptions};
+#[cfg(feature = "std")]
+use crate::common::{build_periods, stream_faces};
+use crate::common::{
+    cells, cycle_position, cycle_reps, traverse_faces, BuildTimings, FxHashMap, Phase, PhaseClock,
+};
+use crate::cover::CoverStats;
+#[cfg(feature = "std")]
+use crate::csv::{CellRecords, CsvFace, CsvTables};
+#[cfg(feature = "std")]
+use crate::dot::{self, DotFace};
+#[cfg(feature = "std")]
+use crate::fundamental_polygon::FundamentalPolygon;
+#[cfg(feature = "std")]
+use crate::gexf;
+use crate::global_state::{Context, DisplayIn, InContext};
+#[cfg(feature = "std")]
+use crate::graphml;
+#[cfg(feature = "std")]
+use crate::json::{self, JsonCellFace, JsonHeader};
 use crate::lamination::Lamination;
-use crate::types::{IntAngle, Period};
-use std::collections::{HashMap, HashSet};
+use crate::limbs::LimbDecomposition;
+#[cfg(feature = "std")]
+use crate::matrices::CoverMatrices;
+use crate::monodromy::MonodromyGroup;
+use crate::progress::{Progress, ProgressSink};
+use crate::real_axis::{real_components, real_crossings, RealComponent, RealCrossingStats};
+#[cfg(feature = "std")]
+use crate::surface::{Mesh, Triangulation};
+use crate::topology::{
+    count_components, BaseCurve, Compactification, Ends, RelativeTopology, Spine,
+};
+use crate::types::{CritPeriod, IntAngle, MarkedPeriod, Period, RatAngle};
+use alloc::{format, string::String, sync::Arc, vec::Vec};
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+#[cfg(feature = "std")]
+use std::sync::mpsc::Sender;
 
 pub type MCVertex = AbstractCycle;
 pub type MCEdge = cells::Edge<MCVertex>;
 pub type MCFace = cells::Face<AugmentedVertex<MCVertex>, AbstractCycleClass>;
+/// Face as stored in the cover, with each boundary vertex given by its index in `vertices`
+pub type MCCompactFace = cells::Face<AugmentedVertex<VertexId>, AbstractCycleClass>;
+/// Cell as sent by [`MarkedCycleCoverBuilder::build_streaming`]
+pub type MCCell = cells::Cell<MCVertex, MCFace>;
 
 use self::cells::Wake;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq)]
 pub struct MarkedCycleCoverBuilder
 {
     pub period: Period,
     pub crit_period: Period,
-    adjacency_map: HashMap<AbstractCycle, Vec<(AbstractCycle, IntAngle, bool)>>,
+    context: Context,
+    adjacency_map: FxHashMap<AbstractCycle, Vec<(AbstractCycle, IntAngle, bool, usize)>>,
+    /// Lamination to extend instead of computing one from scratch; see [`Self::with_lamination`]
+    lamination: Option<Lamination>,
+    progress: Progress,
 }
 
 impl MarkedCycleCoverBuilder
@@ -28,131 +74,316 @@ impl MarkedCycleCoverBuilder
         Self {
             period,
             crit_period,
-            adjacency_map: HashMap::new(),
+            context: Context::new(period),
+            adjacency_map: FxHashMap::default(),
+            lamination: None,
+            progress: Progress::default(),
         }
     }
 
+    /// The builder for periods checked to be in range, as by the CLI
+    #[must_use]
+    pub fn from_periods(period: MarkedPeriod, crit_period: CritPeriod) -> Self
+    {
+        Self::new(period.get(), crit_period.get())
+    }
+
+    /// Take the leaves from `lamination`, extending it as needed, instead of computing the
+    /// lamination from scratch. It is kept when the cover is built, and can be taken back with
+    /// [`Self::take_lamination`] to build the cover of the next period.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lamination` is for a different critical period.
+    #[must_use]
+    pub fn with_lamination(mut self, lamination: Lamination) -> Self
+    {
+        assert_eq!(
+            lamination.crit_period, self.crit_period,
+            "lamination has the wrong critical period"
+        );
+        self.lamination = Some(lamination);
+        self
+    }
+
+    /// The lamination given to [`Self::with_lamination`], extended to this period once the
+    /// cover is built
+    pub fn take_lamination(&mut self) -> Option<Lamination>
+    {
+        self.lamination.take()
+    }
+
+    /// Period of the cover being built and denominator of its angles, in which the cells sent
+    /// by [`Self::build_streaming`] are read
+    #[must_use]
+    pub fn context(&self) -> Context
+    {
+        Context::new(self.period)
+    }
+
+    /// Report the progress of each phase of the build to `sink`, including the lamination
+    /// phase, even when the lamination is given by [`Self::with_lamination`]
+    #[must_use]
+    pub fn with_progress(mut self, sink: Arc<dyn ProgressSink>) -> Self
+    {
+        self.progress = Progress::new(sink);
+        self
+    }
+
     #[must_use]
     pub fn build(&mut self) -> MarkedCycleCover
     {
-        set_period(self.period);
-        let cycles = Self::cycles();
-        let vertices = Self::vertices(&cycles);
-        let edges = self.edges(&cycles);
-        let faces = self.faces(&vertices);
+        self.build_timed().0
+    }
 
-        MarkedCycleCover {
-            crit_period: self.crit_period,
-            vertices,
-            edges,
-            faces,
-        }
+    /// Build the cover, recording how long each phase took
+    #[must_use]
+    pub fn build_timed(&mut self) -> (MarkedCycleCover, BuildTimings)
+    {
+        let clock = PhaseClock::start();
+        self.with_arcs(|builder, arcs| {
+            let lamination = clock.stop();
+            let (cover, mut timings) = builder.build_with_arcs(arcs);
+            timings.record(Phase::Lamination, lamination);
+            (cover, timings)
+        })
     }
 
-    fn cycles() -> Vec<Option<AbstractCycle>>
+    /// Build the cover, sending each cell through `cells` as soon as it is known instead of
+    /// keeping it, so that the cells can be written out on another thread while the faces are
+    /// still being traced. The vertices are sent first, in increasing order, then the edges in
+    /// the order of the lamination, then the faces in whatever order they are traced. The
+    /// `edge_ids` of each face index into the edges in the order they were sent. Cells sent
+    /// after the receiver hangs up are dropped.
+    ///
+    /// The consumer should format the cells in [`Self::context`], e.g. with
+    /// [`Context::display`].
+    #[cfg(feature = "std")]
+    pub fn build_streaming(&mut self, cells: &Sender<MCCell>)
     {
-        let mut cycles = vec![
-            None;
-            usize::try_from(MAX_ANGLE.get())
-                .expect("MAX_ANGLE appears to be negative!")
-        ];
-        for theta in 0..MAX_ANGLE.get().into() {
-            let theta_usize = theta as usize;
-            if cycles[theta_usize].is_some() {
-                continue;
+        self.with_arcs(|builder, arcs| {
+            builder.context = builder.context();
+
+            let progress = builder.progress.clone();
+            let sink = progress.sink();
+
+            sink.phase_started(Phase::Cycles, None);
+            let vertices = builder.vertices();
+            for &v in &vertices {
+                cells.send(Cell::Vertex(v)).ok();
             }
+            sink.items_processed(Phase::Cycles, vertices.len());
+            sink.phase_finished(Phase::Cycles);
 
-            let orbit = get_orbit(theta.into());
-            if orbit.len() == PERIOD.get() as usize {
-                let cycle_rep = orbit.iter().min().expect("Orbit is empty");
-                let cycle_rep = AbstractPoint::new(*cycle_rep);
+            sink.phase_started(Phase::Edges, Some(arcs.len()));
+            for edge in builder.edges(arcs) {
+                cells.send(Cell::Edge(edge)).ok();
+            }
+            sink.items_processed(Phase::Edges, arcs.len());
+            sink.phase_finished(Phase::Edges);
 
-                orbit
-                    .iter()
-                    .map(|x| usize::try_from(*x).expect("Negative value in orbit"))
-                    .for_each(|x| {
-                        let cycle = AbstractCycle { rep: cycle_rep };
-                        cycles[x] = Some(cycle);
+            sink.phase_started(Phase::Faces, Some(vertices.len()));
+            let ids = Self::vertex_ids(&vertices);
+            stream_faces(
+                &vertices,
+                |cyc, crossings| builder.traverse_face(cyc, &ids, crossings),
+                |face| {
+                    let face = face.map_vertices(|v| AugmentedVertex {
+                        vertex: vertices[v.vertex as usize],
+                        data: v.data,
                     });
+                    cells.send(Cell::Face(face)).ok();
+                },
+                sink,
+            );
+            sink.phase_finished(Phase::Faces);
+        });
+    }
+
+    /// Apply `f` to the builder and the leaves of this period, taken from the lamination given
+    /// to [`Self::with_lamination`] if there is one
+    fn with_arcs<T>(&mut self, f: impl FnOnce(&mut Self, &[(RatAngle, RatAngle)]) -> T) -> T
+    {
+        let mut lamination = self.lamination.take();
+        // A given lamination reports to the builder's sink while it is extended here
+        let previous = lamination
+            .as_mut()
+            .filter(|_| self.progress.is_set())
+            .map(|lamination| lamination.replace_progress(self.progress.clone()));
+        let owned_arcs;
+        let arcs = match &mut lamination {
+            Some(lamination) => lamination.arcs_of_period(self.period),
+            None => {
+                let mut fresh = Lamination::new().with_crit_period(self.crit_period);
+                fresh.replace_progress(self.progress.clone());
+                owned_arcs = fresh.into_arcs_of_period(self.period);
+                &owned_arcs
             }
+        };
+        let result = f(self, arcs);
+        if let (Some(lamination), Some(previous)) = (&mut lamination, previous) {
+            lamination.replace_progress(previous);
         }
-        if PERIOD.get() == 1 {
-            let alpha_fp = AbstractPoint::new(IntAngle(1));
-            cycles.push(Some(AbstractCycle { rep: alpha_fp }));
+        self.lamination = lamination;
+        result
+    }
+
+    /// Build the cover from `arcs`, the leaves of this period, e.g. as read from a lamination
+    /// shared with other builders through [`Lamination::arcs`]. The lamination phase is left
+    /// out of the timings.
+    #[must_use]
+    pub fn build_with_arcs(
+        &mut self,
+        arcs: &[(RatAngle, RatAngle)],
+    ) -> (MarkedCycleCover, BuildTimings)
+    {
+        self.context = self.context();
+        let mut timings = BuildTimings::default();
+        let progress = self.progress.clone();
+        let sink = progress.sink();
+
+        let clock = PhaseClock::start();
+        sink.phase_started(Phase::Cycles, None);
+        let vertices = self.vertices();
+        sink.items_processed(Phase::Cycles, vertices.len());
+        sink.phase_finished(Phase::Cycles);
+        timings.record(Phase::Cycles, clock.stop());
+
+        let clock = PhaseClock::start();
+        sink.phase_started(Phase::Edges, Some(arcs.len()));
+        let mut edges = self.edges(arcs);
+        sink.items_processed(Phase::Edges, arcs.len());
+        sink.phase_finished(Phase::Edges);
+        timings.record(Phase::Edges, clock.stop());
+
+        let clock = PhaseClock::start();
+        sink.phase_started(Phase::Faces, Some(vertices.len()));
+        let mut faces = self.faces(&vertices);
+        // Put the cells in canonical order, so that covers of the same curve are equal however
+        // they were built. The vertices are already ordered by angle.
+        debug_assert!(vertices.is_sorted());
+        let new_ids = cells::sort_edges(&mut edges, |e| (e.wake.angle0, e.wake.angle1));
+        faces.iter_mut().for_each(|f| f.renumber_edges(&new_ids));
+        faces.sort_by_key(|f| f.label);
+        sink.phase_finished(Phase::Faces);
+        timings.record(Phase::Faces, clock.stop());
+
+        let cover = MarkedCycleCover {
+            context: self.context,
+            crit_period: self.crit_period,
+            vertices,
+            edges,
+            compact_faces: faces,
+        };
+        #[cfg(feature = "stats")]
+        {
+            timings.stats.num_vertices = cover.num_vertices();
+            timings.stats.num_edges = cover.num_edges();
+            timings.stats.num_faces = cover.num_faces();
         }
-        cycles
+        (cover, timings)
     }
 
-    fn vertices(cycles: &[Option<AbstractCycle>]) -> Vec<AbstractCycle>
+    /// The cycle marked by `angle`, if it has exact period
+    fn cycle_at(&self, angle: IntAngle) -> Option<AbstractCycle>
     {
-        // Vertices, labeled by abstract point
-        let mut vertices = cycles.iter().filter_map(|&v| v).collect::<Vec<_>>();
-        vertices.sort_unstable_by_key(|x| x.rep);
-        vertices.dedup();
-        vertices
+        let (rep, _) = cycle_position(self.context, angle)?;
+        Some(AbstractCycle {
+            rep: AbstractPoint::new(rep),
+        })
     }
 
-    fn edges(&mut self, cycles: &[Option<AbstractCycle>]) -> Vec<MCEdge>
+    /// Vertices, labeled by abstract point, in increasing order
+    fn vertices(&self) -> Vec<AbstractCycle>
     {
-        Lamination::new()
-            .with_crit_period(self.crit_period)
-            .into_arcs_of_period(PERIOD.get())
+        cycle_reps(self.context)
             .into_iter()
-            .filter_map(|(theta0, theta1)| {
-                let angle0 = MAX_ANGLE.get().scale_by_ratio(&theta0);
-                let angle1 = MAX_ANGLE.get().scale_by_ratio(&theta1);
+            .map(|rep| AbstractCycle {
+                rep: AbstractPoint::new(rep),
+            })
+            .collect()
+    }
 
-                let k0 = usize::try_from(angle0).ok()?;
-                let k1 = usize::try_from(angle1).ok()?;
+    fn edges(&mut self, arcs: &[(RatAngle, RatAngle)]) -> Vec<MCEdge>
+    {
+        let edges: Vec<MCEdge> = arcs
+            .iter()
+            .filter_map(|&(theta0, theta1)| {
+                let angle0 = self.context.max_angle.scale_by_ratio(&theta0);
+                let angle1 = self.context.max_angle.scale_by_ratio(&theta1);
 
-                let cyc0 = cycles[k0]?;
-                let cyc1 = cycles[k1]?;
+                let cyc0 = self.cycle_at(angle0)?;
+                let cyc1 = self.cycle_at(angle1)?;
 
                 if cyc0 == cyc1 {
                     return None;
                 }
 
-                let tag = angle0.max(angle1);
-                self.adjacency_map.entry(cyc0).or_default().push((
-                    cyc1,
-                    tag,
-                    angle0 + angle1 == MAX_ANGLE.get(),
-                ));
-                self.adjacency_map.entry(cyc1).or_default().push((
-                    cyc0,
-                    tag,
-                    angle0 + angle1 == MAX_ANGLE.get(),
-                ));
-
                 Some(MCEdge {
                     start: cyc0,
                     end: cyc1,
-                    wake: Wake { angle0, angle1 },
+                    wake: Wake {
+                        angle0,
+                        angle1,
+                        context: self.context,
+                    },
+                    kneading_sequence: AbstractPoint::new(angle0)
+                        .kneading_sequence_in(self.context),
                 })
             })
-            .collect()
+            .collect();
+        self.link_edges(&edges);
+        edges
     }
 
-    fn faces(&self, vertices: &[AbstractCycle]) -> Vec<MCFace>
+    /// Record each edge in the adjacency of both its ends, by its index in `edges`, and sort
+    /// each vertex's wakes by angle, so that the next one can be found by bisection
+    fn link_edges(&mut self, edges: &[MCEdge])
+    {
+        for (edge_id, e) in edges.iter().enumerate() {
+            let Wake { angle0, angle1, .. } = e.wake;
+            let tag = angle0.max(angle1);
+            let neg_edge = angle0 + angle1 == self.context.max_angle;
+            self.adjacency_map
+                .entry(e.start)
+                .or_default()
+                .push((e.end, tag, neg_edge, edge_id));
+            self.adjacency_map
+                .entry(e.end)
+                .or_default()
+                .push((e.start, tag, neg_edge, edge_id));
+        }
+        for adjacent in self.adjacency_map.values_mut() {
+            adjacent.sort_by_key(|&(_, tag, _, _)| tag);
+        }
+    }
+
+    /// Index of each vertex in `vertices`
+    fn vertex_ids(vertices: &[AbstractCycle]) -> FxHashMap<AbstractCycle, VertexId>
     {
-        let mut visited = HashSet::new();
         vertices
             .iter()
-            .copied()
-            .filter_map(|cyc| {
-                if visited.contains(&cyc) {
-                    return None;
-                }
-                Some(self.traverse_face(cyc, &mut visited))
-            })
+            .enumerate()
+            .map(|(i, &v)| (v, i as VertexId))
             .collect()
     }
 
+    fn faces(&self, vertices: &[AbstractCycle]) -> Vec<MCCompactFace>
+    {
+        let ids = Self::vertex_ids(vertices);
+        traverse_faces(
+            vertices,
+            |cyc, crossings| self.traverse_face(cyc, &ids, crossings),
+            self.progress.sink(),
+        )
+    }
+
     fn traverse_face(
         &self,
         starting_point: AbstractCycle,
-        visited: &mut HashSet<AbstractCycle>,
-    ) -> MCFace
+        ids: &FxHashMap<AbstractCycle, VertexId>,
+        crossings: &mut Vec<VertexId>,
+    ) -> MCCompactFace
     {
         // cycle that is currently marked
         let mut node: AbstractCycle = starting_point;
@@ -160,27 +391,30 @@ impl MarkedCycleCoverBuilder
         // angle of the current parameter
         let mut curr_angle = IntAngle(0);
 
-        let mut vertices: Vec<AugmentedVertex<MCVertex>> = Vec::new();
+        let mut vertices: VertexList<AugmentedVertex<VertexId>> = VertexList::new();
+        let mut edge_ids = Vec::new();
 
         let mut face_degree = 1;
 
         let mut region_0 = HalfPlane::PosReal;
         let mut region_1: HalfPlane;
 
-        while let Some((next_node, next_angle, neg_edge)) =
+        while let Some((next_node, next_angle, neg_edge, edge_id)) =
             self.get_next_vertex_and_angle(node, curr_angle)
         {
             // If we are crossing the real axis
             let data = if curr_angle >= next_angle {
                 if node == starting_point {
                     if neg_edge {
-                        vertices.get_mut(0).map(|v| v.data = VertexData::NegEdgePos);
+                        if let Some(v) = vertices.get_mut(0) {
+                            v.data = VertexData::NegEdgePos;
+                        }
                     }
                     break;
                 }
-                visited.insert(node);
+                crossings.push(ids[&node]);
                 face_degree += 1;
-                region_1 = HalfPlane::from(next_angle);
+                region_1 = HalfPlane::new(next_angle, self.context);
                 // region_1 is guaranteed to be Lower
                 match (region_0, region_1, neg_edge) {
                     (HalfPlane::Lower, _, true) => VertexData::NegEdgePos,
@@ -190,7 +424,7 @@ impl MarkedCycleCoverBuilder
                     _ => VertexData::NegPos,
                 }
             } else {
-                region_1 = HalfPlane::from(next_angle);
+                region_1 = HalfPlane::new(next_angle, self.context);
                 match (region_0, region_1, neg_edge) {
                     (_, _, true) => VertexData::NegEdge,
                     (HalfPlane::Upper, HalfPlane::Lower, _) => VertexData::NegReal,
@@ -200,9 +434,13 @@ impl MarkedCycleCoverBuilder
                 }
             };
 
-            let vertex = AugmentedVertex { vertex: node, data };
+            let vertex = AugmentedVertex {
+                vertex: ids[&node],
+                data,
+            };
 
             vertices.push(vertex);
+            edge_ids.push(edge_id);
             node = next_node;
 
             curr_angle = next_angle;
@@ -211,7 +449,7 @@ impl MarkedCycleCoverBuilder
 
         if vertices.is_empty() {
             let vertex = AugmentedVertex {
-                vertex: node,
+                vertex: ids[&node],
                 data: VertexData::PosReal,
             };
             vertices.push(vertex);
@@ -219,9 +457,10 @@ impl MarkedCycleCoverBuilder
 
         let face_id = AbstractCycleClass::new(starting_point);
 
-        MCFace {
+        MCCompactFace {
             label: face_id,
             vertices,
+            edge_ids,
             degree: face_degree,
         }
     }
@@ -230,23 +469,30 @@ impl MarkedCycleCoverBuilder
         &self,
         node: AbstractCycle,
         curr_angle: IntAngle,
-    ) -> Option<(AbstractCycle, IntAngle, bool)>
+    ) -> Option<(AbstractCycle, IntAngle, bool, usize)>
     {
-        self.adjacency_map
-            .get(&node)?
-            .iter()
-            .min_by_key(|(_, ang, _)| (ang.0 - curr_angle.0 - 1).rem_euclid(MAX_ANGLE.get().0))
-            .copied()
+        // The first wake past the current angle, going around the circle
+        let adjacent = self.adjacency_map.get(&node)?;
+        let next = adjacent.partition_point(|&(_, ang, _, _)| ang <= curr_angle);
+        adjacent.get(next).or(adjacent.first()).copied()
     }
 }
 
 #[derive(Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct MarkedCycleCover
 {
+    /// Period of the cover and denominator of its angles, in which its cells are read and
+    /// formatted
+    pub context: Context,
     pub crit_period: Period,
+    /// Vertices, in order of the angle of their representative
     pub vertices: Vec<AbstractCycle>,
+    /// Edges, in order of their wakes
     pub edges: Vec<MCEdge>,
-    pub faces: Vec<MCFace>,
+    /// Faces in order of label, whose boundaries refer to `vertices` by index; see
+    /// [`Self::faces`]
+    pub compact_faces: Vec<MCCompactFace>,
 }
 
 impl MarkedCycleCover
@@ -257,6 +503,73 @@ impl MarkedCycleCover
         MarkedCycleCoverBuilder::new(period, crit_period).build()
     }
 
+    #[must_use]
+    pub fn from_periods(period: MarkedPeriod, crit_period: CritPeriod) -> Self
+    {
+        MarkedCycleCoverBuilder::from_periods(period, crit_period).build()
+    }
+
+    /// Build the cover for each of `periods` in turn, extending one lamination from each
+    /// period to the next instead of computing it from scratch every time
+    pub fn sweep(
+        periods: impl IntoIterator<Item = Period>,
+        crit_period: Period,
+    ) -> impl Iterator<Item = Self>
+    {
+        let mut lamination = Some(Lamination::new().with_crit_period(crit_period));
+        periods.into_iter().map(move |period| {
+            let mut builder = MarkedCycleCoverBuilder::new(period, crit_period).with_lamination(
+                lamination
+                    .take()
+                    .expect("each build gives back the lamination"),
+            );
+            let cover = builder.build();
+            lamination = builder.take_lamination();
+            cover
+        })
+    }
+
+    /// Build the cover for each of `periods` at once, one thread per period, and apply `f` to
+    /// each. The lamination is extended once and shared between the threads. The results are
+    /// in the order of `periods`.
+    #[cfg(feature = "std")]
+    pub fn map_periods<T, F>(periods: &[Period], crit_period: Period, f: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(&Self) -> T + Sync,
+    {
+        build_periods(periods, crit_period, |period, arcs| {
+            let (cover, _) =
+                MarkedCycleCoverBuilder::new(period, crit_period).build_with_arcs(arcs);
+            f(&cover)
+        })
+    }
+
+    /// The [`Self::counts`] of the cover for each of `periods`, built at once as in
+    /// [`Self::map_periods`]
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn counts_for_periods(
+        periods: &[Period],
+        crit_period: Period,
+    ) -> Vec<(usize, usize, usize, i64)>
+    {
+        Self::map_periods(periods, crit_period, Self::counts)
+    }
+
+    #[must_use]
+    pub fn period(&self) -> Period
+    {
+        self.context.period
+    }
+
+    /// Format a cell of the cover in the cover's context, whatever the ambient context is
+    #[must_use]
+    pub const fn display<'a, T: DisplayIn + ?Sized>(&self, cell: &'a T) -> InContext<'a, T>
+    {
+        self.context.display(cell)
+    }
+
     #[must_use]
     pub fn euler_characteristic(&self) -> i64
     {
@@ -278,29 +591,188 @@ impl MarkedCycleCover
     #[must_use]
     pub fn num_faces(&self) -> usize
     {
-        self.faces.len()
+        self.compact_faces.len()
     }
 
+    /// Genus of the closed surface, assuming that it is connected; see
+    /// [`Self::relative_topology`] for covers that are not
     #[must_use]
     pub fn genus(&self) -> i64
     {
         1 - self.euler_characteristic() / 2
     }
 
+    #[must_use]
+    pub fn num_components(&self) -> usize
+    {
+        count_components(&self.vertices, &self.edges)
+    }
+
+    /// The ends of the cover over the puncture of its base, with their local degrees, or `None`
+    /// if the critical period is not 1 or 2
+    #[must_use]
+    pub fn ends_at_infinity(&self) -> Option<Ends>
+    {
+        let base = BaseCurve::from_crit_period(self.crit_period)?;
+        Some(Ends::new(
+            base,
+            self.num_vertices(),
+            self.compact_faces.iter().map(|f| f.degree),
+        ))
+    }
+
+    /// The monodromy group of the cover over its base, acting on the vertices by index, with
+    /// a generator for each wake
+    #[must_use]
+    pub fn monodromy_group(&self) -> MonodromyGroup
+    {
+        MonodromyGroup::from_edges(&self.vertices, &self.edges)
+    }
+
+    /// The vertices, edges, and faces grouped by the limb of the main cardioid they lie in,
+    /// with faces by index
+    #[must_use]
+    pub fn limbs(&self) -> LimbDecomposition
+    {
+        let face_edges = self.compact_faces.iter().map(|f| &f.edge_ids[..]);
+        LimbDecomposition::new(self.context, &self.vertices, &self.edges, face_edges)
+    }
+
+    /// The components of the cover's period meeting the real axis, with the edges lying in
+    /// them, in order along the axis from `c = 1/4` towards `c = -2`
+    #[must_use]
+    pub fn real_components(&self) -> Vec<RealComponent>
+    {
+        real_components(&self.edges)
+    }
+
+    /// For each face, in order, the number of times its boundary crosses the real axis along a
+    /// real edge
+    #[must_use]
+    pub fn real_crossings(&self) -> Vec<usize>
+    {
+        let face_edges = self.compact_faces.iter().map(|f| &f.edge_ids[..]);
+        real_crossings(&self.edges, face_edges)
+    }
+
+    /// How many faces cross the real axis along real edges each number of times
+    #[must_use]
+    pub fn real_crossing_stats(&self) -> RealCrossingStats
+    {
+        RealCrossingStats::new(&self.real_crossings())
+    }
+
+    /// Counts and histograms of the cells: the numbers of cells, the genus, the face sizes and
+    /// vertex degrees, the reflexive and odd faces, and the real edges
+    #[must_use]
+    pub fn stats(&self) -> CoverStats
+    {
+        let num_real_edges = self.edges.iter().filter(|e| e.is_real()).count();
+        CoverStats::new(
+            self.num_vertices(),
+            &self.edge_endpoints(),
+            num_real_edges,
+            self.compact_faces.iter().map(|f| (f.len(), f.degree)),
+            self.genus(),
+        )
+    }
+
+    /// Where the cover is punctured and ramified over its base, and the genus summed over its
+    /// components, or `None` if the critical period is not 1 or 2
+    #[must_use]
+    pub fn relative_topology(&self) -> Option<RelativeTopology>
+    {
+        let base = BaseCurve::from_crit_period(self.crit_period)?;
+        let (vertices, edges, faces, _) = self.counts();
+        Some(RelativeTopology::new(
+            base,
+            (vertices, edges, faces),
+            self.num_components(),
+            self.compact_faces.iter().map(|f| f.degree),
+        ))
+    }
+
+    /// A graph made of cells of the cover onto which the open cover deformation retracts, or
+    /// `None` if the critical period is not 1 or 2
+    #[must_use]
+    pub fn spine(&self) -> Option<Spine>
+    {
+        let base = BaseCurve::from_crit_period(self.crit_period)?;
+        let edges = self.edge_endpoints();
+        let face_edges = self.compact_faces.iter().map(|f| &f.edge_ids[..]);
+        Some(Spine::new(
+            base,
+            self.num_vertices(),
+            &edges,
+            face_edges,
+            self.compact_faces.len(),
+        ))
+    }
+
+    /// The closed surface of the cover with a cell filling in each of its ends, checked against
+    /// the Riemann-Hurwitz formula for its monodromy
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the critical period is not 1 or 2, or if the compactification
+    /// disagrees with the Riemann-Hurwitz formula for [`Self::monodromy_group`].
+    pub fn compactification(&self) -> Result<Compactification, String>
+    {
+        let Some(ends) = self.ends_at_infinity() else {
+            return Err(format!(
+                "No base curve is known for critical period {}",
+                self.crit_period
+            ));
+        };
+        let faces = self.compact_faces.iter().map(|f| {
+            let corners = f.vertices.iter().map(|v| v.vertex as usize).collect();
+            (corners, &f.edge_ids[..])
+        });
+        let compactification =
+            Compactification::new(&ends, self.num_vertices(), &self.edge_endpoints(), faces);
+        compactification.check(&self.monodromy_group())?;
+        Ok(compactification)
+    }
+
+    /// The vertices joined by each edge, by index
+    fn edge_endpoints(&self) -> Vec<(usize, usize)>
+    {
+        let ids = MarkedCycleCoverBuilder::vertex_ids(&self.vertices);
+        self.edges
+            .iter()
+            .map(|e| (ids[&e.start] as usize, ids[&e.end] as usize))
+            .collect()
+    }
+
+    /// Numbers of vertices, edges, and faces, and the genus
+    #[must_use]
+    pub fn counts(&self) -> (usize, usize, usize, i64)
+    {
+        (
+            self.num_vertices(),
+            self.num_edges(),
+            self.num_faces(),
+            self.genus(),
+        )
+    }
+
     pub fn face_sizes(&self) -> impl Iterator<Item = usize> + '_
     {
-        self.faces.iter().map(MCFace::len)
+        self.compact_faces.iter().map(MCCompactFace::len)
     }
 
     pub fn face_sizes_irreflexive(&self) -> impl Iterator<Item = usize> + '_
     {
-        self.faces.iter().filter(|f| f.degree > 1).map(MCFace::len)
+        self.compact_faces
+            .iter()
+            .filter(|f| f.degree > 1)
+            .map(MCCompactFace::len)
     }
 
     #[must_use]
     pub fn num_odd_faces_irreflexive(&self) -> usize
     {
-        self.faces
+        self.compact_faces
             .iter()
             .filter(|f| f.degree > 1 && f.len() % 2 == 1)
             .count()
@@ -312,40 +784,484 @@ impl MarkedCycleCover
         self.face_sizes().filter(|&s| s % 2 == 1).count()
     }
 
-    pub fn summarize(&self, indent: usize, binary: bool)
+    /// A face with its boundary vertices looked up in `vertices`
+    #[must_use]
+    pub fn resolve(&self, face: &MCCompactFace) -> MCFace
+    {
+        face.map_vertices(|v| AugmentedVertex {
+            vertex: self.vertices[v.vertex as usize],
+            data: v.data,
+        })
+    }
+
+    /// Boundary vertices of a face
+    fn boundary(&self, face: &MCCompactFace) -> Vec<MCVertex>
+    {
+        face.vertices
+            .iter()
+            .map(|v| self.vertices[v.vertex as usize])
+            .collect()
+    }
+
+    /// Every face, with its boundary vertices looked up in `vertices`
+    #[must_use]
+    pub fn faces(&self) -> Vec<MCFace>
+    {
+        self.compact_faces.iter().map(|f| self.resolve(f)).collect()
+    }
+
+    /// The face around whose puncture the marked cycle is `cycle` for large positive `c`.
+    /// Walking counterclockwise around the puncture from angle zero, the marked cycle changes
+    /// each time a wake is crossed; the face is labeled by the smallest cycle marked at angle
+    /// zero on any of its sheets.
+    #[must_use]
+    pub fn face_containing(&self, cycle: AbstractCycle) -> Option<MCFace>
+    {
+        let ids = MarkedCycleCoverBuilder::vertex_ids(&self.vertices);
+        let start = *ids.get(&cycle)?;
+
+        // Trace the face from `cycle` as the builder does; it is kept under whichever of its
+        // crossing vertices comes first
+        let mut builder = MarkedCycleCoverBuilder::new(self.period(), self.crit_period);
+        builder.link_edges(&self.edges);
+        let mut crossings = Vec::new();
+        builder.traverse_face(cycle, &ids, &mut crossings);
+        let first = crossings.into_iter().fold(start, VertexId::min);
+        let label = self.vertices[first as usize];
+
+        self.compact_faces
+            .iter()
+            .find(|f| f.label.rep == label.rep)
+            .map(|f| self.resolve(f))
+    }
+
+    /// Faces on either side of each edge, as indices into `faces`, keyed by edge id
+    #[must_use]
+    pub fn dual_graph(&self) -> Vec<(usize, (usize, usize))>
+    {
+        cells::dual_edges(&self.compact_faces)
+    }
+}
+
+/// Exporters and summaries, which need `std`
+#[cfg(feature = "std")]
+impl MarkedCycleCover
+{
+    /// Glue all faces into a single polygon whose side pairing recovers the cover
+    #[must_use]
+    pub fn fundamental_polygon(&self) -> FundamentalPolygon<MCVertex>
+    {
+        FundamentalPolygon::new(
+            self.compact_faces
+                .iter()
+                .map(|f| (self.boundary(f), f.edge_ids.clone())),
+        )
+    }
+
+    /// Triangulation of the surface, with every face subdivided so that it is a simplicial
+    /// complex
+    #[must_use]
+    pub fn triangulation(&self) -> Triangulation
+    {
+        Triangulation::new(
+            &self.vertices,
+            &self.edges,
+            self.compact_faces
+                .iter()
+                .map(|f| (self.boundary(f), f.edge_ids.clone())),
+        )
+    }
+
+    /// Boundary operators and adjacency matrix of the cover
+    #[must_use]
+    pub fn matrices(&self) -> CoverMatrices
+    {
+        CoverMatrices::new(
+            &self.vertices,
+            &self.edges,
+            self.compact_faces
+                .iter()
+                .map(|f| (self.boundary(f), f.edge_ids.clone())),
+        )
+    }
+
+    /// polymake script building the surface from [`Self::triangulation`] and printing its
+    /// genus and whether it is orientable, to check the genus computed here
+    #[must_use]
+    pub fn to_polymake(&self) -> String
+    {
+        self.triangulation()
+            .to_polymake(&self.surface_name(), self.genus())
+    }
+
+    /// The surface placed in space, with the 1-skeleton relaxed for the given number of
+    /// iterations
+    #[must_use]
+    pub fn mesh(&self, iterations: usize) -> Mesh
+    {
+        Mesh::new(&self.triangulation(), iterations)
+    }
+
+    /// Wavefront OBJ mesh of the surface; see [`Self::mesh`]
+    #[must_use]
+    pub fn to_obj(&self, iterations: usize) -> String
+    {
+        self.mesh(iterations).to_obj(&self.surface_name())
+    }
+
+    /// PLY mesh of the surface; see [`Self::mesh`]
+    #[must_use]
+    pub fn to_ply(&self, iterations: usize) -> String
+    {
+        self.mesh(iterations).to_ply(&self.surface_name())
+    }
+
+    /// Name of the curve, as in `MC_5(Per_1)`
+    pub(crate) fn surface_name(&self) -> String
+    {
+        format!("MC_{}(Per_{})", self.context.period, self.crit_period)
+    }
+
+    /// The label of a face, in the cover's context
+    fn label(&self, label: &AbstractCycleClass) -> String
+    {
+        self.context.display(label).to_string()
+    }
+
+    /// Label and boundary vertices of each face, for the graph exporters
+    fn labeled_boundaries(&self) -> Vec<DotFace<MCVertex>>
+    {
+        self.compact_faces
+            .iter()
+            .map(|f| {
+                let boundary = self.boundary(f);
+                (self.label(&f.label), boundary)
+            })
+            .collect()
+    }
+
+    /// Graphviz description of the 1-skeleton, optionally with one cluster per face
+    #[must_use]
+    pub fn to_dot(&self, with_faces: bool) -> String
+    {
+        let faces = with_faces.then(|| self.labeled_boundaries());
+        dot::to_dot(
+            "MC",
+            self.context,
+            &self.vertices,
+            &self.edges,
+            faces.as_deref(),
+        )
+    }
+
+    /// GraphML description of the 1-skeleton, optionally with one node per face
+    #[must_use]
+    pub fn to_graphml(&self, with_faces: bool) -> String
+    {
+        let faces = with_faces.then(|| self.labeled_boundaries());
+        graphml::to_graphml(
+            "MC",
+            self.context,
+            &self.vertices,
+            &self.edges,
+            faces.as_deref(),
+        )
+    }
+
+    /// GEXF description of the 1-skeleton with the same attributes as [`Self::to_graphml`],
+    /// optionally with one node per face
+    #[must_use]
+    pub fn to_gexf(&self, with_faces: bool) -> String
+    {
+        let faces = with_faces.then(|| self.labeled_boundaries());
+        gexf::to_gexf(
+            "MC",
+            self.context,
+            &self.vertices,
+            &self.edges,
+            faces.as_deref(),
+        )
+    }
+
+    /// JSON summary of the cover, optionally listing every cell
+    #[must_use]
+    pub fn to_json(&self, with_cells: bool) -> String
+    {
+        let header = JsonHeader {
+            curve: "MC",
+            context: self.context,
+            crit_period: self.crit_period,
+            genus: self.genus(),
+        };
+        let faces: Vec<_> = self
+            .compact_faces
+            .iter()
+            .map(|f| JsonCellFace {
+                vertex_data: Some(f.vertices.iter().map(|v| v.data).collect()),
+                ..JsonCellFace::new(
+                    (self.label(&f.label), self.boundary(f), f.degree),
+                    f.edge_ids.clone(),
+                    self.context,
+                )
+            })
+            .collect();
+        json::to_json(header, &self.vertices, &self.edges, faces, with_cells)
+    }
+
+    /// Read a cover back from the output of [`Self::to_json`] with its cells, laid out as in
+    /// [`json::SCHEMA_VERSION`]
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `text` is not such a document for a marked cycle curve, or if its
+    /// cells do not fit together.
+    pub fn from_json(text: &str) -> Result<Self, String>
     {
-        let indent_str = " ".repeat(indent);
+        let cells = json::read_cells(text, "MC")?;
+        let context = Context::new(cells.period);
+
+        let vertex = |label: &str| match json::label_numbers(label, '(', ')')?[..] {
+            [angle] => Ok(AbstractCycle {
+                rep: AbstractPoint::new(IntAngle(angle)),
+            }),
+            _ => Err(format!("malformed vertex label `{label}`")),
+        };
+        let vertices = cells
+            .vertices
+            .iter()
+            .map(|v| vertex(v))
+            .collect::<Result<Vec<_>, String>>()?;
+        let ids = MarkedCycleCoverBuilder::vertex_ids(&vertices);
+        let vertex_id = |label: &str| {
+            let v = vertex(label)?;
+            ids.get(&v)
+                .copied()
+                .ok_or_else(|| format!("{label} is not a vertex of the cover"))
+        };
+
+        let edges = cells
+            .edges
+            .iter()
+            .map(|e| {
+                let [angle0, angle1] = e.wake.map(IntAngle);
+                Ok(MCEdge {
+                    start: vertices[vertex_id(&e.start)? as usize],
+                    end: vertices[vertex_id(&e.end)? as usize],
+                    wake: Wake {
+                        angle0,
+                        angle1,
+                        context,
+                    },
+                    kneading_sequence: AbstractPoint::new(angle0).kneading_sequence_in(context),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        let compact_faces = cells
+            .faces
+            .into_iter()
+            .map(|f| {
+                let [angle] = json::label_numbers(&f.label, '<', '>')?[..] else {
+                    return Err(format!("malformed face label `{}`", f.label));
+                };
+                let data = f.vertex_data.ok_or("a face has no vertex data")?;
+                if data.len() != f.vertices.len() {
+                    return Err(format!("face {} has the wrong vertex data", f.label));
+                }
+                let vertices = f
+                    .vertices
+                    .iter()
+                    .zip(data)
+                    .map(|(v, data)| {
+                        Ok(AugmentedVertex {
+                            vertex: vertex_id(v)?,
+                            data,
+                        })
+                    })
+                    .collect::<Result<_, String>>()?;
+                Ok(MCCompactFace {
+                    label: AbstractCycleClass {
+                        rep: AbstractPoint::new(IntAngle(angle)),
+                    },
+                    vertices,
+                    edge_ids: f.edge_ids,
+                    degree: f.degree,
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self {
+            context,
+            crit_period: cells.crit_period,
+            vertices,
+            edges,
+            compact_faces,
+        })
+    }
+
+    /// Stream the cells that pass `filter` as newline-delimited JSON, one object per line
+    pub fn write_ndjson<W: Write>(&self, w: &mut W, filter: &CellFilter) -> io::Result<()>
+    {
+        let header = JsonHeader {
+            curve: "MC",
+            context: self.context,
+            crit_period: self.crit_period,
+            genus: self.genus(),
+        };
+        let vertices = self
+            .vertices
+            .iter()
+            .filter(|v| filter.keeps_vertex(*v, self.context));
+        let edges = self.edges.iter().filter(|e| filter.keeps_edge(e));
+        let faces = self
+            .compact_faces
+            .iter()
+            .filter(|f| filter.keeps_face(&self.resolve(f), self.context))
+            .map(|f| (self.label(&f.label), self.boundary(f), f.degree));
+        json::write_ndjson(w, header, vertices, edges, faces)
+    }
+
+    /// Label, boundary, edge ids, and degree of each face, for the tabular exports
+    fn table_faces(&self) -> Vec<CsvFace<AbstractCycle>>
+    {
+        self.compact_faces
+            .iter()
+            .map(|f| {
+                (
+                    self.label(&f.label),
+                    self.boundary(f),
+                    f.edge_ids.clone(),
+                    f.degree,
+                )
+            })
+            .collect()
+    }
+
+    /// One CSV table each for the vertices, edges, and faces
+    #[must_use]
+    pub fn to_csv(&self) -> CsvTables
+    {
+        CsvTables::new(
+            self.context,
+            &self.vertices,
+            &self.edges,
+            &self.table_faces(),
+        )
+    }
+
+    /// Typed rows of the vertex, edge, and face tables, for writing with
+    /// [`crate::csv::RecordWriter`] or serializing some other way
+    #[must_use]
+    pub fn records(&self) -> CellRecords
+    {
+        CellRecords::new(
+            self.context,
+            &self.vertices,
+            &self.edges,
+            &self.table_faces(),
+        )
+    }
+
+    /// One Parquet or Arrow table each for the vertices, edges, and faces
+    #[cfg(feature = "parquet")]
+    #[must_use]
+    pub fn to_columnar(&self) -> CellBatches
+    {
+        CellBatches::new(
+            self.context,
+            &self.vertices,
+            &self.edges,
+            &self.table_faces(),
+        )
+    }
+
+    /// Print the cells, face sizes, and genus to stdout
+    pub fn summarize(&self, opts: &SummaryOptions)
+    {
+        self.summarize_to(&mut std::io::stdout().lock(), opts)
+            .expect("Failed to write summary to stdout");
+    }
+
+    /// Write the cells that pass `opts.filter`, ordered and truncated according to
+    /// `opts.listing`, then the face sizes and genus
+    pub fn summarize_to<W: Write>(&self, w: &mut W, opts: &SummaryOptions) -> io::Result<()>
+    {
+        let faces = self.faces();
+        let indent_str = " ".repeat(opts.indent);
         macro_rules! print_elements {
-            ($title: expr, $iter: expr, $count: expr) => {
-                if $count > crate::MAX_DISPLAY_ITEMS {
-                    println!("\n{} {}", $count, $title);
+            ($title: expr, $iter: expr, $total: expr) => {
+                let mut items: Vec<_> = $iter.collect();
+                let count = if opts.filter.is_empty() {
+                    items.len().to_string()
                 } else {
-                    println!("\n{} {}:", $count, $title);
-                    for elem in $iter {
-                        if binary {
-                            println!("{indent_str}{elem:b}",);
-                        } else {
-                            println!("{indent_str}{elem}");
-                        }
+                    format!("{} of {}", items.len(), $total)
+                };
+                let hidden = opts.listing.arrange(&mut items, self.context);
+                writeln!(w, "\n{count} {}:", $title)?;
+                for elem in items.into_iter().map(|elem| self.context.display(elem)) {
+                    if opts.binary {
+                        writeln!(w, "{indent_str}{elem:b}")?;
+                    } else if opts.fractions {
+                        writeln!(w, "{indent_str}{elem:#}")?;
+                    } else {
+                        writeln!(w, "{indent_str}{elem}")?;
                     }
                 }
+                if hidden > 0 {
+                    writeln!(w, "{indent_str}... and {hidden} more")?;
+                }
             };
         }
 
-        print_elements!("vertices", &self.vertices, self.vertices.len());
-        print_elements!("edges", &self.edges, self.edges.len());
-        print_elements!("faces", &self.faces, self.faces.len());
+        print_elements!(
+            "vertices",
+            self.vertices
+                .iter()
+                .filter(|v| opts.filter.keeps_vertex(*v, self.context)),
+            self.vertices.len()
+        );
+        print_elements!(
+            "edges",
+            self.edges.iter().filter(|e| opts.filter.keeps_edge(e)),
+            self.edges.len()
+        );
+        print_elements!(
+            "faces",
+            faces
+                .iter()
+                .filter(|f| opts.filter.keeps_face(f, self.context)),
+            faces.len()
+        );
 
-        if self.faces.len() < crate::MAX_DISPLAY_ITEMS {
-            println!("\nFace sizes:");
-            println!("{}{:?}", indent_str, self.face_sizes().collect::<Vec<_>>());
+        if opts.listing.shows_all(faces.len()) {
+            writeln!(w, "\nFace sizes:")?;
+            writeln!(
+                w,
+                "{}{:?}",
+                indent_str,
+                self.face_sizes().collect::<Vec<_>>()
+            )?;
         }
 
-        println!(
+        writeln!(
+            w,
             "\nSmallest face: {}",
             self.face_sizes().min().unwrap_or(usize::MAX)
-        );
-        println!("\nLargest face: {}", self.face_sizes().max().unwrap_or(0));
-        println!("\nGenus is {}", self.genus());
+        )?;
+        writeln!(
+            w,
+            "\nLargest face: {}",
+            self.face_sizes().max().unwrap_or(0)
+        )?;
+        writeln!(w, "\nGenus is {}", self.genus())?;
+        if let Some(topology) = self.relative_topology().filter(|t| t.components > 1) {
+            writeln!(
+                w,
+                "\nThe surface has {} components, of total genus {}",
+                topology.components,
+                topology.genus()
+            )?;
+        }
+        Ok(())
     }
 }
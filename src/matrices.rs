@@ -0,0 +1,262 @@
+use crate::common::cells::Edge;
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    io,
+    path::Path,
+};
+
+/// A sparse integer matrix, stored as its nonzero entries sorted by row and then column
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SparseMatrix
+{
+    pub rows: usize,
+    pub cols: usize,
+    pub entries: Vec<(usize, usize, i64)>,
+}
+
+impl SparseMatrix
+{
+    /// Build a matrix from entries in any order, adding up repeated positions and dropping
+    /// the ones that cancel
+    pub fn from_entries(
+        rows: usize,
+        cols: usize,
+        entries: impl IntoIterator<Item = (usize, usize, i64)>,
+    ) -> Self
+    {
+        let mut sums = BTreeMap::new();
+        for (i, j, value) in entries {
+            *sums.entry((i, j)).or_insert(0) += value;
+        }
+        Self {
+            rows,
+            cols,
+            entries: sums
+                .into_iter()
+                .filter(|&(_, value)| value != 0)
+                .map(|((i, j), value)| (i, j, value))
+                .collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, i: usize, j: usize) -> i64
+    {
+        self.entries
+            .binary_search_by_key(&(i, j), |&(i, j, _)| (i, j))
+            .map_or(0, |k| self.entries[k].2)
+    }
+
+    /// Matrix product `self * other`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the inner dimensions differ.
+    #[must_use]
+    pub fn mul(&self, other: &Self) -> Self
+    {
+        assert_eq!(self.cols, other.rows, "Inner dimensions must agree");
+        let mut other_rows: HashMap<usize, Vec<(usize, i64)>> = HashMap::new();
+        for &(k, j, value) in &other.entries {
+            other_rows.entry(k).or_default().push((j, value));
+        }
+        let products = self.entries.iter().flat_map(|&(i, k, a)| {
+            other_rows
+                .get(&k)
+                .into_iter()
+                .flatten()
+                .map(move |&(j, b)| (i, j, a * b))
+        });
+        Self::from_entries(self.rows, other.cols, products)
+    }
+
+    /// Matrix Market coordinate format, as read by `scipy.io.mmread`, with a comment line
+    #[must_use]
+    pub fn to_matrix_market(&self, comment: &str) -> String
+    {
+        let mut lines = vec![
+            "%%MatrixMarket matrix coordinate integer general".to_owned(),
+            format!("% {comment}"),
+            format!("{} {} {}", self.rows, self.cols, self.entries.len()),
+        ];
+        // Matrix Market counts rows and columns from one
+        for (i, j, value) in &self.entries {
+            lines.push(format!("{} {} {value}", i + 1, j + 1));
+        }
+        lines.join("\n") + "\n"
+    }
+
+    /// Write the matrix in the COO layout of `scipy.sparse.save_npz`, so that it can be read
+    /// with `scipy.sparse.load_npz`
+    #[cfg(feature = "npz")]
+    pub fn write_npz<W: io::Write + io::Seek>(&self, out: W) -> io::Result<()>
+    {
+        use io::Write;
+        use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+        let column = |f: fn(&(usize, usize, i64)) -> i64| -> Vec<i64> {
+            self.entries.iter().map(f).collect()
+        };
+        let arrays = [
+            ("row", npy_i64(&column(|e| e.0 as i64))),
+            ("col", npy_i64(&column(|e| e.1 as i64))),
+            ("data", npy_i64(&column(|e| e.2))),
+            ("shape", npy_i64(&[self.rows as i64, self.cols as i64])),
+            ("format", npy("|S3", "()", b"coo")),
+        ];
+
+        let mut zip = ZipWriter::new(out);
+        let options = SimpleFileOptions::default().compression_method(CompressionMethod::Stored);
+        for (name, bytes) in arrays {
+            zip.start_file(format!("{name}.npy"), options)
+                .map_err(io::Error::other)?;
+            zip.write_all(&bytes)?;
+        }
+        zip.finish().map_err(io::Error::other)?;
+        Ok(())
+    }
+}
+
+/// An array in NumPy's `.npy` format, version 1.0, with the given dtype, shape, and raw data
+#[cfg(feature = "npz")]
+fn npy(descr: &str, shape: &str, data: &[u8]) -> Vec<u8>
+{
+    let mut header = format!("{{'descr': '{descr}', 'fortran_order': False, 'shape': {shape}, }}");
+    // The magic string, version, and header length take 10 bytes, and the header is padded
+    // with spaces so that the data starts on a multiple of 64 bytes
+    let unpadded = 10 + header.len() + 1;
+    header.push_str(&" ".repeat(unpadded.next_multiple_of(64) - unpadded));
+    header.push('\n');
+
+    let mut bytes = b"\x93NUMPY\x01\x00".to_vec();
+    bytes.extend((header.len() as u16).to_le_bytes());
+    bytes.extend(header.as_bytes());
+    bytes.extend(data);
+    bytes
+}
+
+#[cfg(feature = "npz")]
+fn npy_i64(values: &[i64]) -> Vec<u8>
+{
+    let data: Vec<u8> = values.iter().flat_map(|x| x.to_le_bytes()).collect();
+    npy("<i8", &format!("({},)", values.len()), &data)
+}
+
+/// File format of exported matrices
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum MatrixFormat
+{
+    /// Matrix Market coordinate format
+    MatrixMarket,
+    /// Sparse matrix as saved by `scipy.sparse.save_npz`
+    #[cfg(feature = "npz")]
+    Npz,
+}
+
+impl MatrixFormat
+{
+    #[must_use]
+    pub const fn extension(self) -> &'static str
+    {
+        match self {
+            Self::MatrixMarket => "mtx",
+            #[cfg(feature = "npz")]
+            Self::Npz => "npz",
+        }
+    }
+}
+
+/// The boundary operators of the cell complex of a cover, over the integers, and the adjacency
+/// matrix of its 1-skeleton. Vertices, edges, and faces are indexed in the cover's order.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CoverMatrices
+{
+    /// Edges to vertices: the column of an edge has -1 at its start and +1 at its end
+    pub boundary1: SparseMatrix,
+    /// Faces to edges: the column of a face has +1 at each edge it runs along in the edge's
+    /// direction and -1 at each edge it runs along against it
+    pub boundary2: SparseMatrix,
+    /// Number of edges between each pair of vertices, with each loop counted twice
+    pub adjacency: SparseMatrix,
+}
+
+impl CoverMatrices
+{
+    /// Build the matrices of a cover with the given vertices and edges and with faces given as
+    /// their boundary vertices and edge ids, as for [`crate::surface::Triangulation::new`]. For
+    /// `boundary2` to send the sum of the faces to zero, the faces must be oriented coherently.
+    pub fn new<V, I>(vertices: &[V], edges: &[Edge<V>], faces: I) -> Self
+    where
+        V: Copy + Eq + Hash,
+        I: IntoIterator<Item = (Vec<V>, Vec<usize>)>,
+    {
+        let indices: HashMap<V, usize> =
+            vertices.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+        let endpoints: Vec<(usize, usize)> = edges
+            .iter()
+            .map(|e| (indices[&e.start], indices[&e.end]))
+            .collect();
+
+        let boundary1 = SparseMatrix::from_entries(
+            vertices.len(),
+            edges.len(),
+            endpoints
+                .iter()
+                .enumerate()
+                .flat_map(|(k, &(start, end))| [(start, k, -1), (end, k, 1)]),
+        );
+
+        let mut num_faces = 0;
+        let mut sides = Vec::new();
+        for (boundary, edge_ids) in faces {
+            for (&v, &edge_id) in boundary.iter().zip(&edge_ids) {
+                let sign = if edges[edge_id].start == v { 1 } else { -1 };
+                sides.push((edge_id, num_faces, sign));
+            }
+            num_faces += 1;
+        }
+        let boundary2 = SparseMatrix::from_entries(edges.len(), num_faces, sides);
+
+        let adjacency = SparseMatrix::from_entries(
+            vertices.len(),
+            vertices.len(),
+            endpoints
+                .iter()
+                .flat_map(|&(start, end)| [(start, end, 1), (end, start, 1)]),
+        );
+
+        Self {
+            boundary1,
+            boundary2,
+            adjacency,
+        }
+    }
+
+    /// Write `{stem}_boundary1`, `{stem}_boundary2`, and `{stem}_adjacency` into `dir` with the
+    /// extension of `format`, creating it if needed
+    pub fn write(&self, dir: impl AsRef<Path>, stem: &str, format: MatrixFormat) -> io::Result<()>
+    {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        for (name, matrix) in [
+            ("boundary1", &self.boundary1),
+            ("boundary2", &self.boundary2),
+            ("adjacency", &self.adjacency),
+        ] {
+            let path = dir.join(format!("{stem}_{name}.{}", format.extension()));
+            match format {
+                MatrixFormat::MatrixMarket => {
+                    std::fs::write(path, matrix.to_matrix_market(&format!("{stem} {name}")))?;
+                }
+                #[cfg(feature = "npz")]
+                MatrixFormat::Npz => {
+                    let mut file = io::BufWriter::new(std::fs::File::create(path)?);
+                    matrix.write_npz(&mut file)?;
+                    io::Write::flush(&mut file)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
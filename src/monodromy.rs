@@ -0,0 +1,461 @@
+use crate::common::cells::Edge;
+use crate::common::FxHashMap;
+#[cfg(feature = "std")]
+use crate::matrices::SparseMatrix;
+use crate::types::Period;
+use alloc::{string::String, vec, vec::Vec};
+use core::fmt::{self, Display, Write};
+use core::hash::Hash;
+use num_bigint::BigUint;
+
+/// A permutation of `0..degree`, given by the image of each point
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Permutation
+{
+    images: Vec<usize>,
+}
+
+impl Permutation
+{
+    #[must_use]
+    pub fn identity(degree: usize) -> Self
+    {
+        Self {
+            images: (0..degree).collect(),
+        }
+    }
+
+    /// The permutation sending each `k` to `images[k]`, or `None` if `images` is not a
+    /// rearrangement of `0..images.len()`
+    #[must_use]
+    pub fn from_images(images: Vec<usize>) -> Option<Self>
+    {
+        let mut seen = vec![false; images.len()];
+        for &p in &images {
+            if p >= images.len() || core::mem::replace(&mut seen[p], true) {
+                return None;
+            }
+        }
+        Some(Self { images })
+    }
+
+    /// The doubling map on the angles `k/(2^n - 1)` whose period divides `n`, acting on their
+    /// numerators `k` in `0..2^n - 1`
+    #[must_use]
+    pub fn doubling(period: Period) -> Self
+    {
+        let max_angle = (1usize << period) - 1;
+        Self {
+            images: (0..max_angle).map(|k| (2 * k) % max_angle).collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn degree(&self) -> usize
+    {
+        self.images.len()
+    }
+
+    #[must_use]
+    pub fn images(&self) -> &[usize]
+    {
+        &self.images
+    }
+
+    #[must_use]
+    pub fn apply(&self, point: usize) -> usize
+    {
+        self.images[point]
+    }
+
+    #[must_use]
+    pub fn is_identity(&self) -> bool
+    {
+        self.images.iter().enumerate().all(|(k, &p)| k == p)
+    }
+
+    /// The permutation applying `self` and then `other`
+    #[must_use]
+    pub fn then(&self, other: &Self) -> Self
+    {
+        Self {
+            images: self.images.iter().map(|&p| other.images[p]).collect(),
+        }
+    }
+
+    #[must_use]
+    pub fn inverse(&self) -> Self
+    {
+        let mut images = vec![0; self.degree()];
+        for (k, &p) in self.images.iter().enumerate() {
+            images[p] = k;
+        }
+        Self { images }
+    }
+
+    /// The cycles, each starting from its least point, in order of that point. Fixed points
+    /// are included as cycles of length 1.
+    #[must_use]
+    pub fn cycles(&self) -> Vec<Vec<usize>>
+    {
+        let mut seen = vec![false; self.degree()];
+        let mut cycles = Vec::new();
+        for first in 0..self.degree() {
+            let mut cycle = Vec::new();
+            let mut p = first;
+            while !seen[p] {
+                seen[p] = true;
+                cycle.push(p);
+                p = self.images[p];
+            }
+            if !cycle.is_empty() {
+                cycles.push(cycle);
+            }
+        }
+        cycles
+    }
+
+    /// The permutation matrix, with a 1 in row `self.apply(k)` of each column `k`, so that it
+    /// sends the `k`-th basis vector to the image of `k`
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn matrix(&self) -> SparseMatrix
+    {
+        SparseMatrix::from_entries(
+            self.degree(),
+            self.degree(),
+            self.images.iter().enumerate().map(|(k, &p)| (p, k, 1)),
+        )
+    }
+
+    /// Contribution to the Riemann-Hurwitz formula of a branch point with this local
+    /// monodromy, the degree less the number of cycles
+    #[must_use]
+    pub fn ramification(&self) -> usize
+    {
+        self.degree() - self.cycles().len()
+    }
+
+    /// Lengths of the cycles, in increasing order
+    #[must_use]
+    pub fn cycle_type(&self) -> Vec<usize>
+    {
+        let mut lengths: Vec<usize> = self.cycles().iter().map(Vec::len).collect();
+        lengths.sort_unstable();
+        lengths
+    }
+}
+
+/// Cycle notation, leaving out fixed points, with `()` for the identity
+impl Display for Permutation
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result
+    {
+        let mut text = String::new();
+        for cycle in self.cycles().iter().filter(|c| c.len() > 1) {
+            text.push('(');
+            for (i, p) in cycle.iter().enumerate() {
+                if i > 0 {
+                    text.push(' ');
+                }
+                write!(text, "{p}")?;
+            }
+            text.push(')');
+        }
+        if text.is_empty() {
+            text.push_str("()");
+        }
+        f.write_str(&text)
+    }
+}
+
+/// The monodromy group of a cover, acting on its vertices by index. There is one generator for
+/// each wake, the monodromy of a loop around the root of the wake's components, which sends the
+/// start of each edge in the wake to its end. When the wake's edges join the points of two
+/// cycles, as for primitive components, the generator swaps the ends of each edge; when they
+/// run around one cycle, as for satellite components, it rotates the cycle.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MonodromyGroup
+{
+    degree: usize,
+    /// One generator per wake, in order of the wakes
+    pub generators: Vec<Permutation>,
+}
+
+impl MonodromyGroup
+{
+    /// The group acting on `vertices` by index, with a generator for each run of edges sharing
+    /// a wake. The edges must be in order of their wakes.
+    #[must_use]
+    pub fn from_edges<V>(vertices: &[V], edges: &[Edge<V>]) -> Self
+    where
+        V: Copy + Eq + Hash,
+    {
+        let index: FxHashMap<V, usize> =
+            vertices.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+        let generators = edges
+            .chunk_by(|a, b| a.wake == b.wake)
+            .map(|wake_edges| {
+                let mut images: Vec<usize> = (0..vertices.len()).collect();
+                let mut is_start = vec![false; vertices.len()];
+                for e in wake_edges {
+                    is_start[index[&e.start]] = true;
+                }
+                for e in wake_edges {
+                    let (start, end) = (index[&e.start], index[&e.end]);
+                    images[start] = end;
+                    if !is_start[end] {
+                        images[end] = start;
+                    }
+                }
+                Permutation::from_images(images)
+                    .expect("The edges in a wake match up or run around cycles")
+            })
+            .collect();
+        Self {
+            degree: vertices.len(),
+            generators,
+        }
+    }
+
+    /// Number of points acted on, the degree of the cover
+    #[must_use]
+    pub fn degree(&self) -> usize
+    {
+        self.degree
+    }
+
+    /// The generators applied one after another in order of their wakes. This is the
+    /// monodromy around the point of the base that the faces are traced around, so that its
+    /// cycles have the lengths of the degrees of those faces.
+    #[must_use]
+    pub fn product(&self) -> Permutation
+    {
+        self.generators
+            .iter()
+            .fold(Permutation::identity(self.degree), |acc, g| acc.then(g))
+    }
+
+    /// The orbits of the group, each in increasing order, in order of their least points
+    #[must_use]
+    pub fn orbits(&self) -> Vec<Vec<usize>>
+    {
+        let mut orbit_of = vec![usize::MAX; self.degree];
+        let mut orbits = Vec::new();
+        for first in 0..self.degree {
+            if orbit_of[first] != usize::MAX {
+                continue;
+            }
+            let mut orbit = vec![first];
+            orbit_of[first] = orbits.len();
+            let mut i = 0;
+            while let Some(&p) = orbit.get(i) {
+                for g in &self.generators {
+                    let q = g.apply(p);
+                    if orbit_of[q] == usize::MAX {
+                        orbit_of[q] = orbits.len();
+                        orbit.push(q);
+                    }
+                }
+                i += 1;
+            }
+            orbit.sort_unstable();
+            orbits.push(orbit);
+        }
+        orbits
+    }
+
+    /// Whether the group has a single orbit, that is, whether the cover is connected
+    #[must_use]
+    pub fn is_transitive(&self) -> bool
+    {
+        self.degree == 0 || self.orbits().len() == 1
+    }
+
+    /// Order of the group. A group generated by transpositions, as for the marked cycle
+    /// curves, is the product of the symmetric groups on its orbits; otherwise the order is
+    /// found by building a stabilizer chain with the Schreier-Sims algorithm, whose work grows
+    /// quickly with the degree.
+    #[must_use]
+    pub fn order(&self) -> BigUint
+    {
+        let is_transposition = |g: &Permutation| {
+            g.images()
+                .iter()
+                .enumerate()
+                .filter(|&(k, &p)| k != p)
+                .count()
+                == 2
+        };
+        if self.generators.iter().all(is_transposition) {
+            return self
+                .orbits()
+                .iter()
+                .flat_map(|orbit| 1..=orbit.len())
+                .map(BigUint::from)
+                .product();
+        }
+        StabilizerChain::new(self.degree, &self.generators).order()
+    }
+}
+
+/// One level of a stabilizer chain: the subgroup fixing the earlier base points, given by
+/// its generators, and the orbit of its base point under them
+struct Level
+{
+    base: usize,
+    generators: Vec<Permutation>,
+    orbit: Vec<usize>,
+    /// For each point of the orbit other than the base point, the point it is reached from
+    /// and the generator taking it there
+    parent: Vec<Option<(usize, usize)>>,
+    /// The Schreier generators from the first `checked.0` points of the orbit and the first
+    /// `checked.1` generators are known to lie in the next level
+    checked: (usize, usize),
+}
+
+impl Level
+{
+    fn new(degree: usize, base: usize) -> Self
+    {
+        Self {
+            base,
+            generators: Vec::new(),
+            orbit: vec![base],
+            parent: vec![None; degree],
+            checked: (0, 0),
+        }
+    }
+
+    fn contains(&self, point: usize) -> bool
+    {
+        point == self.base || self.parent[point].is_some()
+    }
+
+    /// Add a generator and extend the orbit by it. The paths to the points already in the
+    /// orbit are kept, so that the Schreier generators already checked stay valid.
+    fn push_generator(&mut self, g: Permutation)
+    {
+        self.generators.push(g);
+        let mut i = 0;
+        while let Some(&p) = self.orbit.get(i) {
+            for (k, g) in self.generators.iter().enumerate() {
+                let q = g.apply(p);
+                if !self.contains(q) {
+                    self.parent[q] = Some((p, k));
+                    self.orbit.push(q);
+                }
+            }
+            i += 1;
+        }
+    }
+
+    /// An element of the level's group taking the base point to `point`, which must lie in
+    /// the orbit
+    fn transversal(&self, mut point: usize) -> Permutation
+    {
+        let mut path = Vec::new();
+        while let Some((p, k)) = self.parent[point] {
+            path.push(k);
+            point = p;
+        }
+        path.iter()
+            .rev()
+            .fold(Permutation::identity(self.parent.len()), |acc, &k| {
+                acc.then(&self.generators[k])
+            })
+    }
+}
+
+struct StabilizerChain
+{
+    degree: usize,
+    levels: Vec<Level>,
+}
+
+impl StabilizerChain
+{
+    fn new(degree: usize, generators: &[Permutation]) -> Self
+    {
+        let mut chain = Self {
+            degree,
+            levels: Vec::new(),
+        };
+        for g in generators {
+            let (residue, depth) = chain.sift(g.clone(), 0);
+            if !residue.is_identity() {
+                chain.add_generator(residue, 0, depth);
+            }
+        }
+        chain
+    }
+
+    /// Strip `g` through the levels from `from` on, returning what is left of it and the level
+    /// at which it fell out of the chain
+    fn sift(&self, mut g: Permutation, from: usize) -> (Permutation, usize)
+    {
+        for (i, level) in self.levels.iter().enumerate().skip(from) {
+            let image = g.apply(level.base);
+            if !level.contains(image) {
+                return (g, i);
+            }
+            g = g.then(&level.transversal(image).inverse());
+        }
+        (g, self.levels.len())
+    }
+
+    /// Add `g`, which fixes the base points before level `to`, to the generators of the
+    /// levels `from..=to`, and complete those levels
+    fn add_generator(&mut self, g: Permutation, from: usize, to: usize)
+    {
+        if to == self.levels.len() {
+            let base = (0..self.degree)
+                .find(|&p| g.apply(p) != p)
+                .expect("Only nontrivial elements are added");
+            self.levels.push(Level::new(self.degree, base));
+        }
+        for level in &mut self.levels[from..=to] {
+            level.push_generator(g.clone());
+        }
+        for i in (from..=to).rev() {
+            self.complete(i);
+        }
+    }
+
+    /// Make level `i` the full stabilizer of the earlier base points, assuming that the later
+    /// levels are complete, by sifting its Schreier generators through them
+    fn complete(&mut self, i: usize)
+    {
+        let (checked_points, checked_generators) = self.levels[i].checked;
+        let (num_points, num_generators) =
+            (self.levels[i].orbit.len(), self.levels[i].generators.len());
+        for k in 0..num_points {
+            let first = if k < checked_points {
+                checked_generators
+            } else {
+                0
+            };
+            for s in first..num_generators {
+                let level = &self.levels[i];
+                let u = level.transversal(level.orbit[k]).then(&level.generators[s]);
+                let q = u.apply(level.base);
+                let schreier = u.then(&level.transversal(q).inverse());
+                if !schreier.is_identity() {
+                    let (residue, depth) = self.sift(schreier, i + 1);
+                    if !residue.is_identity() {
+                        self.add_generator(residue, i + 1, depth);
+                    }
+                }
+            }
+        }
+        self.levels[i].checked = (num_points, num_generators);
+    }
+
+    fn order(&self) -> BigUint
+    {
+        self.levels
+            .iter()
+            .map(|level| BigUint::from(level.orbit.len()))
+            .product()
+    }
+}
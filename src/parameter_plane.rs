@@ -0,0 +1,248 @@
+use crate::common::cells::Edge;
+use crate::types::RatAngle;
+use num::complex::Complex64;
+use plotters::coord::Shift;
+use plotters::prelude::*;
+use std::{f64::consts::TAU, io, path::Path};
+
+/// Parameters rays are started from this far out, where the Böttcher coordinate is
+/// well approximated by iterates of the critical value
+const RAY_ESCAPE_RADIUS: f64 = 1000.0;
+
+/// Colors cycled through for the wakes of successive edges
+const WAKE_PALETTE: [RGBColor; 6] = [
+    RGBColor(31, 119, 180),
+    RGBColor(255, 127, 14),
+    RGBColor(44, 160, 44),
+    RGBColor(148, 103, 189),
+    RGBColor(140, 86, 75),
+    RGBColor(227, 119, 194),
+];
+
+/// `2^n * angle` modulo one
+fn double_angle(angle: RatAngle, n: usize) -> RatAngle
+{
+    let (mut p, q) = (angle.numer().rem_euclid(*angle.denom()), *angle.denom());
+    for _ in 0..n {
+        p = (2 * p) % q;
+    }
+    RatAngle::new(p, q)
+}
+
+/// Points along the external ray of the Mandelbrot set at the given angle, from far out towards
+/// its landing point. Uses Newton's method to follow the equipotentials inward, taking
+/// `sharpness` steps each time the potential halves, for `depth` halvings.
+#[must_use]
+pub fn external_ray(angle: RatAngle, depth: usize, sharpness: usize) -> Vec<Complex64>
+{
+    let sharpness = sharpness.max(1);
+    let arg = |n: usize| {
+        let theta = double_angle(angle, n);
+        TAU * (*theta.numer() as f64) / (*theta.denom() as f64)
+    };
+
+    let mut c = Complex64::from_polar(RAY_ESCAPE_RADIUS, arg(0));
+    let mut points = vec![c];
+
+    for k in 1..=depth * sharpness {
+        // Since Φ(c) ≈ f_c^m(c)^(1/2^m), the point with potential R^(2^(-k/S)) is found by
+        // solving f_c^m(c) = target, with m chosen so that m - k/S lies in (0, 1]
+        let m = k.div_ceil(sharpness);
+        let exponent = (m as f64) - (k as f64) / (sharpness as f64);
+        let target = Complex64::from_polar(RAY_ESCAPE_RADIUS.powf(exponent.exp2()), arg(m));
+
+        for _ in 0..8 {
+            let (mut z, mut dz) = (c, Complex64::new(1.0, 0.0));
+            for _ in 0..m {
+                dz = 2.0 * z * dz + 1.0;
+                z = z * z + c;
+            }
+            let step = (z - target) / dz;
+            if !step.is_finite() {
+                break;
+            }
+            c -= step;
+            if step.norm() < 1e-12 {
+                break;
+            }
+        }
+        points.push(c);
+    }
+    points
+}
+
+/// An escape-time picture of the Mandelbrot set with the wakes of a cover's edges drawn on top.
+/// Each wake is bounded by the two parameter rays at the edge's angles, which land together at
+/// the root of the corresponding hyperbolic component.
+pub struct RayOverlay
+{
+    /// Center of the picture
+    pub center: Complex64,
+    /// Width of the picture in the parameter plane
+    pub width: f64,
+    /// Size of the image, in pixels
+    pub size: (u32, u32),
+    pub max_iter: u32,
+    /// Number of times the potential is halved when following a ray inward
+    pub ray_depth: usize,
+    /// Newton steps per halving of the potential
+    pub ray_sharpness: usize,
+    wakes: Vec<(RatAngle, RatAngle, bool)>,
+}
+
+impl Default for RayOverlay
+{
+    fn default() -> Self
+    {
+        Self {
+            center: Complex64::new(-0.75, 0.0),
+            width: 3.0,
+            size: (800, 800),
+            max_iter: 256,
+            ray_depth: 40,
+            ray_sharpness: 8,
+            wakes: Vec::new(),
+        }
+    }
+}
+
+impl RayOverlay
+{
+    #[must_use]
+    pub fn new() -> Self
+    {
+        Self::default()
+    }
+
+    /// Overlay the wakes of the given edges
+    #[must_use]
+    pub fn with_edges<V>(mut self, edges: &[Edge<V>]) -> Self
+    {
+        self.wakes.extend(edges.iter().map(|e| {
+            let (theta0, theta1) = e.wake.rat_angles();
+            (theta0, theta1, e.is_real())
+        }));
+        self
+    }
+
+    /// Overlay the wake bounded by the rays at two arbitrary angles
+    #[must_use]
+    pub fn with_wake(mut self, theta0: RatAngle, theta1: RatAngle) -> Self
+    {
+        self.wakes.push((theta0, theta1, false));
+        self
+    }
+
+    #[must_use]
+    pub const fn with_view(mut self, center: Complex64, width: f64) -> Self
+    {
+        self.center = center;
+        self.width = width;
+        self
+    }
+
+    #[must_use]
+    pub const fn with_size(mut self, width: u32, height: u32) -> Self
+    {
+        self.size = (width, height);
+        self
+    }
+
+    fn height(&self) -> f64
+    {
+        self.width * f64::from(self.size.1) / f64::from(self.size.0.max(1))
+    }
+
+    /// Number of iterations for the critical orbit of `c` to escape, or `None` if it has not
+    /// escaped after `max_iter`
+    #[must_use]
+    pub fn escape_time(&self, c: Complex64) -> Option<u32>
+    {
+        let mut z = Complex64::new(0.0, 0.0);
+        for n in 0..self.max_iter {
+            if z.norm_sqr() > 4.0 {
+                return Some(n);
+            }
+            z = z * z + c;
+        }
+        None
+    }
+
+    fn shade(&self, escape_time: Option<u32>) -> RGBColor
+    {
+        escape_time.map_or(BLACK, |n| {
+            let t = f64::from(n + 1).ln() / f64::from(self.max_iter + 1).ln();
+            let v = (255.0 * (1.0 - 0.8 * t)) as u8;
+            RGBColor(v, v, v)
+        })
+    }
+
+    /// Draw the picture on any plotters backend
+    pub fn draw<DB: DrawingBackend>(&self, area: &DrawingArea<DB, Shift>) -> io::Result<()>
+    {
+        let (w, h) = self.size;
+        let height = self.height();
+        let x0 = self.center.re - self.width / 2.0;
+        let y1 = self.center.im + height / 2.0;
+        let to_c = |i: u32, j: u32| {
+            Complex64::new(
+                (f64::from(i) + 0.5).mul_add(self.width / f64::from(w), x0),
+                (f64::from(j) + 0.5).mul_add(-height / f64::from(h), y1),
+            )
+        };
+
+        for j in 0..h {
+            for i in 0..w {
+                let color = self.shade(self.escape_time(to_c(i, j)));
+                area.draw_pixel((i as i32, j as i32), &color)
+                    .map_err(|e| io::Error::other(e.to_string()))?;
+            }
+        }
+
+        let to_px = |c: Complex64| {
+            (
+                ((c.re - x0) / self.width * f64::from(w)) as i32,
+                ((y1 - c.im) / height * f64::from(h)) as i32,
+            )
+        };
+        let max_radius = 2.0 * (self.width + height);
+
+        for (k, &(theta0, theta1, real)) in self.wakes.iter().enumerate() {
+            let color = if real {
+                RED
+            } else {
+                WAKE_PALETTE[k % WAKE_PALETTE.len()]
+            };
+            for theta in [theta0, theta1] {
+                let points: Vec<(i32, i32)> =
+                    external_ray(theta, self.ray_depth, self.ray_sharpness)
+                        .into_iter()
+                        .filter(|c| (c - self.center).norm() < max_radius)
+                        .map(to_px)
+                        .collect();
+                area.draw(&PathElement::new(points, color.stroke_width(1)))
+                    .map_err(|e| io::Error::other(e.to_string()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Render to a file, as SVG if the extension is `svg` and as a bitmap otherwise
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()>
+    {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            std::fs::create_dir_all(dir)?;
+        }
+
+        if path.extension().is_some_and(|ext| ext == "svg") {
+            let area = SVGBackend::new(path, self.size).into_drawing_area();
+            self.draw(&area)?;
+            area.present().map_err(|e| io::Error::other(e.to_string()))
+        } else {
+            let area = BitMapBackend::new(path, self.size).into_drawing_area();
+            self.draw(&area)?;
+            area.present().map_err(|e| io::Error::other(e.to_string()))
+        }
+    }
+}
@@ -1,5 +1,16 @@
 pub use crate::combinatorics;
+pub use crate::cover::{Cover, CoverStats};
 pub use crate::dynatomic_cover::DynatomicCover;
+#[cfg(feature = "std")]
+pub use crate::layout::{Extreme, FaceColoring, GraphPlacement};
+pub use crate::limbs::{Limb, LimbDecomposition};
 pub use crate::marked_cycle_cover::MarkedCycleCover;
-pub use crate::tikz::TikzRenderer;
+pub use crate::monodromy::{MonodromyGroup, Permutation};
+pub use crate::real_axis::{RealComponent, RealCrossingStats};
+pub use crate::ribbon::RibbonGraph;
+#[cfg(feature = "render")]
+pub use crate::svg::{SvgRenderer, SvgStyle};
+#[cfg(feature = "render")]
+pub use crate::tikz::{TikzRenderer, TikzStyle};
+pub use crate::topology::{BaseCurve, Compactification, Ends, RelativeTopology, Spine};
 pub use crate::types::*;
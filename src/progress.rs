@@ -0,0 +1,67 @@
+use crate::common::Phase;
+use alloc::sync::Arc;
+
+/// Receives progress reports while a lamination is extended or a cover is built, e.g. to drive
+/// a progress bar. Each phase of [`Phase`] is started, advanced by some number of items, and
+/// finished, in order. The items are periods for the lamination, vertices for the cycles,
+/// leaves for the edges, and starting vertices for the faces. Faces are traced on several
+/// threads, so `items_processed` can be called concurrently.
+pub trait ProgressSink: Send + Sync
+{
+    /// `phase` has begun, with `total` items to process if known in advance
+    fn phase_started(&self, _phase: Phase, _total: Option<usize>) {}
+
+    /// `count` more items of `phase` have been processed
+    fn items_processed(&self, _phase: Phase, _count: usize) {}
+
+    fn phase_finished(&self, _phase: Phase) {}
+}
+
+/// Sink that ignores every report
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct NoProgress;
+
+impl ProgressSink for NoProgress {}
+
+/// The sink held by a lamination or a builder, if any. It has no bearing on what is built, so
+/// any two compare equal.
+#[derive(Clone, Default)]
+pub(crate) struct Progress(Option<Arc<dyn ProgressSink>>);
+
+impl Progress
+{
+    pub(crate) fn new(sink: Arc<dyn ProgressSink>) -> Self
+    {
+        Self(Some(sink))
+    }
+
+    pub(crate) const fn is_set(&self) -> bool
+    {
+        self.0.is_some()
+    }
+
+    pub(crate) fn sink(&self) -> &dyn ProgressSink
+    {
+        self.0.as_deref().unwrap_or(&NoProgress)
+    }
+}
+
+impl core::fmt::Debug for Progress
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        f.write_str(if self.is_set() {
+            "Progress(..)"
+        } else {
+            "Progress(None)"
+        })
+    }
+}
+
+impl PartialEq for Progress
+{
+    fn eq(&self, _other: &Self) -> bool
+    {
+        true
+    }
+}
@@ -0,0 +1,109 @@
+use crate::common::cells::{Edge, Wake};
+use crate::types::{KneadingSequence, RatAngle};
+use alloc::{collections::BTreeMap, vec, vec::Vec};
+
+/// A hyperbolic component of the cover's period that meets the real axis, given by its wake,
+/// which is symmetric under complex conjugation, and the edges of the cover lying in it
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RealComponent
+{
+    pub wake: Wake,
+    /// Angle of the parameter ray landing at the root of the component from above the real
+    /// axis, in `(0, 1/2)`
+    pub angle: RatAngle,
+    /// Kneading sequence of the first angle of the wake
+    pub kneading_sequence: KneadingSequence,
+    /// Indices into the cover's edge list, in increasing order
+    pub edge_ids: Vec<usize>,
+}
+
+/// The components whose wakes hold real edges among `edges`, in the order in which they lie
+/// along the real axis, from the cusp at `c = 1/4` towards the tip at `c = -2`. Moving left
+/// along the axis, the angle of the ray landing at a root from above increases, so this is
+/// the order of the first angles of the wakes, and that of the kneading sequences in the
+/// ordering of unimodal maps. The context of the cover's period must be installed.
+pub fn real_components<V>(edges: &[Edge<V>]) -> Vec<RealComponent>
+{
+    let mut components: Vec<RealComponent> = Vec::new();
+    for (id, e) in edges.iter().enumerate().filter(|(_, e)| e.is_real()) {
+        match components.iter_mut().find(|c| c.wake == e.wake) {
+            Some(component) => component.edge_ids.push(id),
+            None => components.push(RealComponent {
+                wake: e.wake.clone(),
+                angle: e.wake.rat_angles().0,
+                kneading_sequence: e.kneading_sequence,
+                edge_ids: vec![id],
+            }),
+        }
+    }
+    components.sort_by_key(|c| c.angle);
+    components
+}
+
+/// The number of times the boundary of each face, given by its edge ids, crosses the real axis
+/// along a real edge. Going around the puncture, a face of degree `d` crosses the negative real
+/// axis `d` times, each time either at a vertex or along an edge lying in a real component; the
+/// latter are counted here, so that a face crossing twice meets the real locus of the curve in
+/// two places. The context of the cover's period must be installed.
+pub fn real_crossings<'a, V>(
+    edges: &[Edge<V>],
+    face_edges: impl IntoIterator<Item = &'a [usize]>,
+) -> Vec<usize>
+{
+    face_edges
+        .into_iter()
+        .map(|ids| ids.iter().filter(|&&id| edges[id].is_real()).count())
+        .collect()
+}
+
+/// How many faces of a cover cross the real axis along real edges each number of times
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RealCrossingStats
+{
+    /// Number of faces with each count of crossings, omitting counts no face has
+    pub faces_by_crossings: BTreeMap<usize, usize>,
+}
+
+impl RealCrossingStats
+{
+    #[must_use]
+    pub fn new(crossings: &[usize]) -> Self
+    {
+        let mut faces_by_crossings = BTreeMap::new();
+        for &count in crossings {
+            *faces_by_crossings.entry(count).or_default() += 1;
+        }
+        Self { faces_by_crossings }
+    }
+
+    /// Number of faces whose boundary crosses the real axis along real edges `count` times
+    #[must_use]
+    pub fn num_faces_crossing(&self, count: usize) -> usize
+    {
+        self.faces_by_crossings
+            .get(&count)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Number of faces meeting no real edge
+    #[must_use]
+    pub fn num_non_real(&self) -> usize
+    {
+        self.num_faces_crossing(0)
+    }
+
+    /// Number of bi-real faces, which cross the real axis along real edges at least twice
+    #[must_use]
+    pub fn num_bi_real(&self) -> usize
+    {
+        self.faces_by_crossings.range(2..).map(|(_, n)| n).sum()
+    }
+
+    /// Total number of faces
+    #[must_use]
+    pub fn num_faces(&self) -> usize
+    {
+        self.faces_by_crossings.values().sum()
+    }
+}
@@ -0,0 +1,250 @@
+use crate::common::cells::{AugmentedVertex, Edge, Face, VertexId, Wake};
+use crate::global_state::Context;
+#[cfg(feature = "std")]
+use crate::matrices::CoverMatrices;
+#[cfg(feature = "std")]
+use crate::surface::Triangulation;
+use crate::topology::count_components;
+use crate::types::{IntAngle, KneadingSequence};
+use alloc::{format, string::String, vec, vec::Vec};
+
+/// A graph embedded in an oriented surface, given by its edges and the cyclic order of the
+/// edges around each vertex, with the faces of the embedding traced from them. This carries
+/// a graph computed outside this crate through the same cell types as the covers, so that it
+/// can be drawn by the renderers and triangulated.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RibbonGraph
+{
+    num_vertices: usize,
+    pub edges: Vec<Edge<VertexId>>,
+    /// Each face is labeled by its index, and has degree 1
+    pub faces: Vec<Face<VertexId, usize>>,
+    /// Number of connected components
+    components: usize,
+}
+
+impl RibbonGraph
+{
+    /// Build the ribbon graph on vertices `0..num_vertices` with the given edges, where
+    /// `rotations[v]` lists the ids of the edges at `v` in counterclockwise order. A loop at `v`
+    /// is listed twice, first at its start and then at its end. Each face is traced by leaving
+    /// every vertex along the edge that follows, in this order, the edge it was entered by.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an edge has an endpoint out of range, or if the rotations do not list
+    /// each end of each edge exactly once at the vertex it lies on, or if a vertex meets no
+    /// edges.
+    pub fn new(
+        num_vertices: usize,
+        edges: Vec<Edge<VertexId>>,
+        rotations: &[Vec<usize>],
+    ) -> Result<Self, String>
+    {
+        if rotations.len() != num_vertices {
+            return Err(format!(
+                "Expected {num_vertices} rotations, one per vertex, but found {}",
+                rotations.len()
+            ));
+        }
+        if let Some((k, _)) = edges
+            .iter()
+            .enumerate()
+            .find(|(_, e)| e.start as usize >= num_vertices || e.end as usize >= num_vertices)
+        {
+            return Err(format!("Edge {k} has an endpoint out of range"));
+        }
+
+        // Dart `2k` runs along edge `k` from its start, and dart `2k + 1` from its end
+        let mut next = vec![usize::MAX; 2 * edges.len()];
+        for (v, rotation) in rotations.iter().enumerate() {
+            if rotation.is_empty() {
+                return Err(format!("Vertex {v} meets no edges"));
+            }
+            let mut darts = Vec::with_capacity(rotation.len());
+            for &k in rotation {
+                let edge = edges
+                    .get(k)
+                    .ok_or_else(|| format!("Vertex {v} lists edge {k}, which does not exist"))?;
+                let dart = if edge.start as usize == v && !darts.contains(&(2 * k)) {
+                    2 * k
+                } else if edge.end as usize == v && !darts.contains(&(2 * k + 1)) {
+                    2 * k + 1
+                } else {
+                    return Err(format!(
+                        "Vertex {v} lists edge {k} more often than it meets it"
+                    ));
+                };
+                darts.push(dart);
+            }
+            for (i, &dart) in darts.iter().enumerate() {
+                next[dart] = darts[(i + 1) % darts.len()];
+            }
+        }
+        let tail = |dart: usize| {
+            let edge = &edges[dart / 2];
+            if dart.is_multiple_of(2) {
+                edge.start
+            } else {
+                edge.end
+            }
+        };
+        if let Some(dart) = next.iter().position(|&d| d == usize::MAX) {
+            return Err(format!(
+                "Edge {} is missing from the rotation at vertex {}",
+                dart / 2,
+                tail(dart)
+            ));
+        }
+
+        let mut seen = vec![false; next.len()];
+        let mut faces = Vec::new();
+        for first in 0..next.len() {
+            let mut dart = first;
+            let mut vertices = Vec::new();
+            let mut edge_ids = Vec::new();
+            while !seen[dart] {
+                seen[dart] = true;
+                vertices.push(tail(dart));
+                edge_ids.push(dart / 2);
+                dart = next[dart ^ 1];
+            }
+            if !vertices.is_empty() {
+                faces.push(Face {
+                    label: faces.len(),
+                    vertices: vertices.into_iter().collect(),
+                    edge_ids,
+                    degree: 1,
+                });
+            }
+        }
+
+        let vertices: Vec<VertexId> = (0..num_vertices as VertexId).collect();
+        let components = count_components(&vertices, &edges);
+        Ok(Self {
+            num_vertices,
+            edges,
+            faces,
+            components,
+        })
+    }
+
+    /// Build the ribbon graph with edges given only by their endpoints, as for [`Self::new`].
+    /// The edges get an empty wake and kneading sequence, so they are drawn as non-real.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error under the same conditions as [`Self::new`].
+    pub fn from_endpoints(
+        num_vertices: usize,
+        endpoints: &[(VertexId, VertexId)],
+        rotations: &[Vec<usize>],
+    ) -> Result<Self, String>
+    {
+        let edges = endpoints
+            .iter()
+            .map(|&(start, end)| Edge {
+                start,
+                end,
+                wake: Wake {
+                    angle0: IntAngle(0),
+                    angle1: IntAngle(0),
+                    context: Context::default(),
+                },
+                kneading_sequence: KneadingSequence::default(),
+            })
+            .collect();
+        Self::new(num_vertices, edges, rotations)
+    }
+
+    /// The vertices, in order
+    #[must_use]
+    pub fn vertices(&self) -> Vec<VertexId>
+    {
+        (0..self.num_vertices as VertexId).collect()
+    }
+
+    #[must_use]
+    pub fn num_vertices(&self) -> usize
+    {
+        self.num_vertices
+    }
+
+    #[must_use]
+    pub fn num_edges(&self) -> usize
+    {
+        self.edges.len()
+    }
+
+    #[must_use]
+    pub fn num_faces(&self) -> usize
+    {
+        self.faces.len()
+    }
+
+    #[must_use]
+    pub fn num_components(&self) -> usize
+    {
+        self.components
+    }
+
+    #[must_use]
+    pub fn euler_characteristic(&self) -> i64
+    {
+        self.num_vertices() as i64 - self.num_edges() as i64 + self.num_faces() as i64
+    }
+
+    /// Genus of the surface, summed over its connected components
+    #[must_use]
+    pub fn genus(&self) -> i64
+    {
+        self.components as i64 - self.euler_characteristic() / 2
+    }
+
+    /// Numbers of vertices, edges, and faces, and the genus
+    #[must_use]
+    pub fn counts(&self) -> (usize, usize, usize, i64)
+    {
+        (
+            self.num_vertices(),
+            self.num_edges(),
+            self.num_faces(),
+            self.genus(),
+        )
+    }
+
+    pub fn face_sizes(&self) -> impl Iterator<Item = usize> + '_
+    {
+        self.faces.iter().map(Face::len)
+    }
+
+    /// The faces with every vertex marked as non-real, as taken by the renderers
+    #[must_use]
+    pub fn augmented_faces(&self) -> Vec<Face<AugmentedVertex<VertexId>, usize>>
+    {
+        self.faces.iter().cloned().map(Face::augmented).collect()
+    }
+
+    fn face_boundaries(&self) -> impl Iterator<Item = (Vec<VertexId>, Vec<usize>)> + '_
+    {
+        self.faces
+            .iter()
+            .map(|f| (f.vertices.to_vec(), f.edge_ids.clone()))
+    }
+
+    /// Triangulation of the surface, as for the covers. The graph must have no loops.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn triangulation(&self) -> Triangulation
+    {
+        Triangulation::new(&self.vertices(), &self.edges, self.face_boundaries())
+    }
+
+    /// Boundary operators and adjacency matrix of the cell complex
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn matrices(&self) -> CoverMatrices
+    {
+        CoverMatrices::new(&self.vertices(), &self.edges, self.face_boundaries())
+    }
+}
@@ -0,0 +1,161 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static LIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+/// The system allocator, counting allocations and following the number of bytes in use. The
+/// builders read its counters into [`BuildStats`], but it only counts once a program installs
+/// it with `#[global_allocator]`; otherwise every count reads zero. The counters are shared by
+/// the whole program, so builds running at the same time on different threads are counted
+/// together.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CountingAllocator;
+
+fn record_alloc(size: usize)
+{
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+    ALLOCATED.fetch_add(size, Ordering::Relaxed);
+    let live = LIVE.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(live, Ordering::Relaxed);
+}
+
+fn record_dealloc(size: usize)
+{
+    LIVE.fetch_sub(size, Ordering::Relaxed);
+}
+
+unsafe impl GlobalAlloc for CountingAllocator
+{
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8
+    {
+        let ptr = unsafe { System.alloc(layout) };
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8
+    {
+        let ptr = unsafe { System.alloc_zeroed(layout) };
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout)
+    {
+        unsafe { System.dealloc(ptr, layout) };
+        record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8
+    {
+        let new_ptr = unsafe { System.realloc(ptr, layout, new_size) };
+        if !new_ptr.is_null() {
+            // Counted as a fresh allocation, since it may well have moved
+            record_dealloc(layout.size());
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// Heap usage of one phase of a build
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HeapStats
+{
+    /// Number of allocations, counting each reallocation as one
+    pub allocations: usize,
+    /// Total bytes allocated, whether or not they were freed again
+    pub allocated_bytes: usize,
+    /// Most bytes in use at any one time
+    pub peak_bytes: usize,
+    /// Bytes in use at the end
+    pub live_bytes: usize,
+}
+
+/// Heap usage of each phase of a build, and the number of cells built
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BuildStats
+{
+    pub lamination: HeapStats,
+    pub cycles: HeapStats,
+    pub edges: HeapStats,
+    pub faces: HeapStats,
+    pub num_vertices: usize,
+    pub num_edges: usize,
+    pub num_faces: usize,
+}
+
+impl BuildStats
+{
+    /// The phases with their names, in the order they run
+    #[must_use]
+    pub fn phases(&self) -> [(&'static str, HeapStats); 4]
+    {
+        [
+            ("lamination", self.lamination),
+            ("cycles", self.cycles),
+            ("edges", self.edges),
+            ("faces", self.faces),
+        ]
+    }
+
+    /// Most bytes in use at any point of the build
+    #[must_use]
+    pub fn peak_bytes(&self) -> usize
+    {
+        self.phases()
+            .iter()
+            .map(|(_, heap)| heap.peak_bytes)
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Number of allocations over the whole build
+    #[must_use]
+    pub fn allocations(&self) -> usize
+    {
+        self.phases().iter().map(|(_, heap)| heap.allocations).sum()
+    }
+}
+
+/// Counters of the allocator at the start of a phase
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct HeapCounter
+{
+    allocations: usize,
+    allocated: usize,
+}
+
+impl HeapCounter
+{
+    /// Start counting, with the peak brought down to the bytes now in use
+    pub(crate) fn start() -> Self
+    {
+        PEAK.store(LIVE.load(Ordering::Relaxed), Ordering::Relaxed);
+        Self {
+            allocations: ALLOCATIONS.load(Ordering::Relaxed),
+            allocated: ALLOCATED.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn stop(self) -> HeapStats
+    {
+        HeapStats {
+            allocations: ALLOCATIONS
+                .load(Ordering::Relaxed)
+                .wrapping_sub(self.allocations),
+            allocated_bytes: ALLOCATED
+                .load(Ordering::Relaxed)
+                .wrapping_sub(self.allocated),
+            peak_bytes: PEAK.load(Ordering::Relaxed),
+            live_bytes: LIVE.load(Ordering::Relaxed),
+        }
+    }
+}
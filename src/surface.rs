@@ -0,0 +1,309 @@
+use crate::common::cells::Edge;
+use crate::layout::polygon_radius;
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+/// A point in space
+pub type Point3 = [f32; 3];
+
+/// A triangulation of a cover, fine enough to be a simplicial complex even where a face meets
+/// itself along an edge or two faces share more than one edge. Each edge of the cover is cut
+/// into three segments, and each face into a ring of triangles along its boundary, a second
+/// ring inside it, and a fan around a new center. Triangles are listed with the orientation of
+/// the faces they come from.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Triangulation
+{
+    /// Number of vertices: first those of the cover, in order, then two cut points on each
+    /// edge, and then the points added inside each face
+    pub num_vertices: usize,
+    pub triangles: Vec<[usize; 3]>,
+    /// Number of vertices of the cover
+    cover_vertices: usize,
+    /// Endpoints of each edge of the cover
+    skeleton: Vec<(usize, usize)>,
+    /// Points around the boundary of each face, in order
+    rings: Vec<Vec<usize>>,
+}
+
+impl Triangulation
+{
+    /// Triangulate the faces, given as their boundary vertices and edge ids, of a cover with
+    /// the given vertices and edges. `vertices[i]` and `edge_ids[i]` of a face describe the
+    /// side from `vertices[i]` to `vertices[i + 1]`. An edge is assumed not to be a loop, so
+    /// that the direction of each side can be read from its endpoints.
+    pub fn new<V, I>(vertices: &[V], edges: &[Edge<V>], faces: I) -> Self
+    where
+        V: Copy + Eq + Hash,
+        I: IntoIterator<Item = (Vec<V>, Vec<usize>)>,
+    {
+        let indices: HashMap<V, usize> =
+            vertices.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+        // The point cutting an edge nearer its start, or nearer its end
+        let cut = |edge_id: usize, near_start: bool| {
+            vertices.len() + 2 * edge_id + usize::from(!near_start)
+        };
+
+        let skeleton = edges
+            .iter()
+            .map(|e| (indices[&e.start], indices[&e.end]))
+            .collect();
+        let mut num_vertices = vertices.len() + 2 * edges.len();
+        let mut triangles = Vec::new();
+        let mut rings = Vec::new();
+        for (boundary, edge_ids) in faces {
+            let n = boundary.len();
+            let mut ring = Vec::with_capacity(3 * n);
+            for (i, &edge_id) in edge_ids.iter().enumerate() {
+                let start = boundary[i];
+                let forward = edges[edge_id].start == start;
+                ring.push(indices[&start]);
+                ring.push(cut(edge_id, forward));
+                ring.push(cut(edge_id, !forward));
+            }
+
+            let m = ring.len();
+            let inner = num_vertices;
+            let center = inner + m;
+            num_vertices = center + 1;
+            for j in 0..m {
+                let k = (j + 1) % m;
+                triangles.push([ring[j], ring[k], inner + j]);
+                triangles.push([ring[k], inner + k, inner + j]);
+                triangles.push([inner + j, inner + k, center]);
+            }
+            rings.push(ring);
+        }
+
+        Self {
+            num_vertices,
+            triangles,
+            cover_vertices: vertices.len(),
+            skeleton,
+            rings,
+        }
+    }
+
+    /// Place every point in space given the positions of the vertices of the cover: the cut
+    /// points evenly along each edge, the center of each face at the mean of its boundary
+    /// points, and each point of the inner ring halfway to the center
+    #[must_use]
+    pub fn embed(&self, vertex_positions: &[Point3]) -> Vec<Point3>
+    {
+        let lerp =
+            |a: Point3, b: Point3, t: f32| core::array::from_fn(|i| (b[i] - a[i]).mul_add(t, a[i]));
+        let mut positions = vertex_positions.to_vec();
+        for &(start, end) in &self.skeleton {
+            let (a, b) = (vertex_positions[start], vertex_positions[end]);
+            positions.push(lerp(a, b, 1.0 / 3.0));
+            positions.push(lerp(a, b, 2.0 / 3.0));
+        }
+        for ring in &self.rings {
+            let boundary: Vec<Point3> = ring.iter().map(|&j| positions[j]).collect();
+            let center: Point3 = core::array::from_fn(|i| {
+                boundary.iter().map(|p| p[i]).sum::<f32>() / (boundary.len() as f32)
+            });
+            positions.extend(boundary.iter().map(|&p| lerp(p, center, 0.5)));
+            positions.push(center);
+        }
+        positions
+    }
+
+    #[must_use]
+    pub fn num_edges(&self) -> usize
+    {
+        self.triangles
+            .iter()
+            .flat_map(|&[a, b, c]| [(a, b), (b, c), (c, a)])
+            .map(|(a, b)| (a.min(b), a.max(b)))
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    #[must_use]
+    pub fn euler_characteristic(&self) -> i64
+    {
+        self.num_vertices as i64 - self.num_edges() as i64 + self.triangles.len() as i64
+    }
+
+    /// A polymake script that loads the triangulation into `topaz` as a `SimplicialComplex`,
+    /// and prints its Euler characteristic, whether it is an orientable surface, and its genus,
+    /// so that these can be checked against `genus`. Run it with `polymake --script`.
+    #[must_use]
+    pub fn to_polymake(&self, name: &str, genus: i64) -> String
+    {
+        let facets: Vec<String> = self
+            .triangles
+            .iter()
+            .map(|[a, b, c]| format!("[{a},{b},{c}]"))
+            .collect();
+        let print = |label: &str, property: &str| {
+            format!("print \"{label}: \", $surface->{property}, \"\\n\";")
+        };
+        [
+            format!("# Triangulation of {name}, which has genus {genus}"),
+            "use application \"topaz\";".to_owned(),
+            format!(
+                "my $surface = new SimplicialComplex(FACETS => [\n{}\n]);",
+                facets.join(",\n")
+            ),
+            format!("$surface->name = \"{name}\";"),
+            print("euler characteristic", "EULER_CHARACTERISTIC"),
+            print("surface", "SURFACE"),
+            print("orientable", "ORIENTED_PSEUDO_MANIFOLD"),
+            print("genus", "GENUS"),
+            format!("print \"expected genus: {genus}\\n\";"),
+        ]
+        .join("\n")
+    }
+}
+
+/// A surface placed in space, for viewing in 3D. The vertices of the cover are placed by a
+/// force-directed layout of its 1-skeleton, and the rest of the triangulation follows them, so
+/// the surface generally passes through itself.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Mesh
+{
+    pub positions: Vec<Point3>,
+    pub triangles: Vec<[usize; 3]>,
+}
+
+impl Mesh
+{
+    /// Embed a triangulation, relaxing the layout of the 1-skeleton for the given number of
+    /// iterations
+    #[must_use]
+    pub fn new(triangulation: &Triangulation, iterations: usize) -> Self
+    {
+        let vertices = layout_skeleton(
+            triangulation.cover_vertices,
+            &triangulation.skeleton,
+            iterations,
+        );
+        Self {
+            positions: triangulation.embed(&vertices),
+            triangles: triangulation.triangles.clone(),
+        }
+    }
+
+    /// Wavefront OBJ description of the mesh, as a single object
+    #[must_use]
+    pub fn to_obj(&self, name: &str) -> String
+    {
+        let mut lines = vec![format!("o {name}")];
+        for [x, y, z] in &self.positions {
+            lines.push(format!("v {x:.6} {y:.6} {z:.6}"));
+        }
+        // OBJ counts vertices from one
+        for [a, b, c] in &self.triangles {
+            lines.push(format!("f {} {} {}", a + 1, b + 1, c + 1));
+        }
+        lines.join("\n")
+    }
+
+    /// ASCII PLY description of the mesh
+    #[must_use]
+    pub fn to_ply(&self, name: &str) -> String
+    {
+        let mut lines = vec![
+            "ply".to_owned(),
+            "format ascii 1.0".to_owned(),
+            format!("comment {name}"),
+            format!("element vertex {}", self.positions.len()),
+            "property float x".to_owned(),
+            "property float y".to_owned(),
+            "property float z".to_owned(),
+            format!("element face {}", self.triangles.len()),
+            "property list uchar int vertex_indices".to_owned(),
+            "end_header".to_owned(),
+        ];
+        for [x, y, z] in &self.positions {
+            lines.push(format!("{x:.6} {y:.6} {z:.6}"));
+        }
+        for [a, b, c] in &self.triangles {
+            lines.push(format!("3 {a} {b} {c}"));
+        }
+        lines.join("\n")
+    }
+}
+
+/// Place `n` vertices in space by a Fruchterman-Reingold simulation as in
+/// [`crate::layout::GraphLayout::relax`], with unit edges as the ideal length, starting from
+/// evenly spread points on a sphere. The result is deterministic and centered at the origin.
+fn layout_skeleton(n: usize, edges: &[(usize, usize)], iterations: usize) -> Vec<Point3>
+{
+    // Golden-angle spiral from pole to pole
+    let radius = polygon_radius(n, 1.0).max(1.0) / 2.0;
+    let golden_angle = core::f32::consts::PI * (3.0 - 5.0_f32.sqrt());
+    let mut positions: Vec<Point3> = (0..n)
+        .map(|i| {
+            let z = 1.0 - 2.0 * (i as f32 + 0.5) / (n as f32);
+            let r = (1.0 - z * z).sqrt();
+            let theta = golden_angle * (i as f32);
+            [
+                radius * r * theta.cos(),
+                radius * r * theta.sin(),
+                radius * z,
+            ]
+        })
+        .collect();
+    if n < 2 {
+        return positions;
+    }
+
+    let mut temperature = radius / 2.0;
+    let cooling = temperature / (iterations.max(1) as f32);
+    let offset = |a: Point3, b: Point3| {
+        let d: Point3 = core::array::from_fn(|i| a[i] - b[i]);
+        let dist = d.iter().map(|x| x * x).sum::<f32>().sqrt().max(0.01);
+        (d, dist)
+    };
+    for _ in 0..iterations {
+        let mut disp = vec![[0.0_f32; 3]; n];
+
+        for i in 0..n {
+            for j in (i + 1)..n {
+                let (d, dist) = offset(positions[i], positions[j]);
+                let force = 1.0 / dist;
+                for k in 0..3 {
+                    disp[i][k] += d[k] / dist * force;
+                    disp[j][k] -= d[k] / dist * force;
+                }
+            }
+        }
+
+        for &(i, j) in edges {
+            if i == j {
+                continue;
+            }
+            let (d, dist) = offset(positions[i], positions[j]);
+            let force = dist * dist;
+            for k in 0..3 {
+                disp[i][k] -= d[k] / dist * force;
+                disp[j][k] += d[k] / dist * force;
+            }
+        }
+
+        for (p, d) in positions.iter_mut().zip(&disp) {
+            let len = d.iter().map(|x| x * x).sum::<f32>().sqrt();
+            if len > 0.0 {
+                let step = len.min(temperature) / len;
+                for k in 0..3 {
+                    p[k] += d[k] * step;
+                }
+            }
+        }
+        temperature -= cooling;
+    }
+
+    let center: Point3 =
+        core::array::from_fn(|k| positions.iter().map(|p| p[k]).sum::<f32>() / (n as f32));
+    for p in &mut positions {
+        for k in 0..3 {
+            p[k] -= center[k];
+        }
+    }
+    positions
+}
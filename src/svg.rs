@@ -0,0 +1,577 @@
+use crate::common::cells::{AugmentedVertex as Aug, Edge, Face};
+use crate::layout::{
+    extreme_faces, faces_around_face, faces_around_vertex, layout_dual_graph, layout_face,
+    layout_faces, layout_net, orient_edges, place_graph, EdgeLayout, Extreme, FaceColoring,
+    FaceLayout, GraphLayout, GraphPlacement, Point, RenderBackend, SpokeKind,
+};
+use std::{fmt::Display, hash::Hash};
+
+/// Styling options for the generated SVG. Lengths are in layout units unless noted otherwise.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SvgStyle
+{
+    /// Length of each polygon side
+    pub edge_length: f32,
+    /// Pixels per layout unit
+    pub scale: f32,
+    /// Margin around the drawing, in pixels
+    pub margin: f32,
+    /// Stroke color for edges and spokes
+    pub stroke: String,
+    /// Stroke width for edges, in pixels
+    pub stroke_width: f32,
+    /// Font size for labels, in pixels
+    pub font_size: f32,
+    pub font_family: String,
+    /// `stroke-dasharray` for spokes crossing the positive reals
+    pub pos_real_dash: String,
+    /// `stroke-dasharray` for spokes crossing the negative reals
+    pub neg_real_dash: String,
+    /// Fill colors for faces, indexed by the category chosen by a `FaceColoring`
+    pub fill_palette: Vec<String>,
+    /// Color of the dual graph
+    pub dual_stroke: String,
+    /// `stroke-dasharray` for edges of the dual graph
+    pub dual_dash: String,
+}
+
+impl Default for SvgStyle
+{
+    fn default() -> Self
+    {
+        Self {
+            edge_length: 1.46,
+            scale: 40.0,
+            margin: 20.0,
+            stroke: "black".to_owned(),
+            stroke_width: 1.5,
+            font_size: 10.0,
+            font_family: "Arial".to_owned(),
+            pos_real_dash: "6,3".to_owned(),
+            neg_real_dash: "1,3".to_owned(),
+            fill_palette: [
+                "#f4cccc", "#cfe2f3", "#d9ead3", "#fce5cd", "#d9d2e9", "#d0e0e3", "#fff2cc",
+                "#d9d9d9",
+            ]
+            .map(String::from)
+            .to_vec(),
+            dual_stroke: "red".to_owned(),
+            dual_dash: "4,2".to_owned(),
+        }
+    }
+}
+
+fn escape(text: &str) -> String
+{
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Emits SVG elements for precomputed layouts.
+pub struct SvgBackend
+{
+    elements: Vec<String>,
+    style: SvgStyle,
+    min: Point,
+    max: Point,
+    edge_ids: bool,
+}
+
+impl SvgBackend
+{
+    #[must_use]
+    pub const fn new(style: SvgStyle) -> Self
+    {
+        Self {
+            elements: Vec::new(),
+            style,
+            min: Point::new(f32::INFINITY, f32::INFINITY),
+            max: Point::new(f32::NEG_INFINITY, f32::NEG_INFINITY),
+            edge_ids: false,
+        }
+    }
+
+    /// Label each face edge with its index in the cover and mark its orientation with an arrow
+    #[must_use]
+    pub const fn edge_ids(mut self, edge_ids: bool) -> Self
+    {
+        self.edge_ids = edge_ids;
+        self
+    }
+
+    /// Convert layout coordinates to pixels, flipping the y-axis
+    fn px(&mut self, p: Point) -> Point
+    {
+        let q = Point::new(p.x * self.style.scale, -p.y * self.style.scale);
+        self.min = Point::new(self.min.x.min(q.x), self.min.y.min(q.y));
+        self.max = Point::new(self.max.x.max(q.x), self.max.y.max(q.y));
+        q
+    }
+
+    fn line(&mut self, a: Point, b: Point, attrs: &str)
+    {
+        let (a, b) = (self.px(a), self.px(b));
+        self.elements.push(format!(
+            r#"<line x1="{:.2}" y1="{:.2}" x2="{:.2}" y2="{:.2}" {attrs}/>"#,
+            a.x, a.y, b.x, b.y
+        ));
+    }
+
+    fn curve(&mut self, a: Point, b: Point, bend: i32, attrs: &str)
+    {
+        if bend == 0 {
+            self.line(a, b, attrs);
+            return;
+        }
+        // Offset the control point perpendicular to the chord, as TikZ's `bend left` does
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let offset = (15.0 * bend as f32).to_radians().tan() / 2.0;
+        let control = Point::new(
+            dy.mul_add(-offset, (a.x + b.x) / 2.0),
+            dx.mul_add(offset, (a.y + b.y) / 2.0),
+        );
+        let (a, b, c) = (self.px(a), self.px(b), self.px(control));
+        self.elements.push(format!(
+            r#"<path d="M {:.2} {:.2} Q {:.2} {:.2} {:.2} {:.2}" fill="none" {attrs}/>"#,
+            a.x, a.y, c.x, c.y, b.x, b.y
+        ));
+    }
+
+    fn edge_attrs(&self, real: bool) -> Vec<String>
+    {
+        let stroke = &self.style.stroke;
+        let width = self.style.stroke_width;
+        if real {
+            // Mimic TikZ's double lines: a wide stroke overdrawn by a narrow white one
+            vec![
+                format!(r#"stroke="{stroke}" stroke-width="{}""#, 3.0 * width),
+                format!(r#"stroke="white" stroke-width="{width}""#),
+            ]
+        } else {
+            vec![format!(r#"stroke="{stroke}" stroke-width="{width}""#)]
+        }
+    }
+
+    /// Arrowhead at the middle of a face edge and its index just outside the face
+    fn edge_marker(&mut self, face: &FaceLayout, edge: &EdgeLayout, id: usize)
+    {
+        let (a, b) = (
+            face.nodes[edge.source].position,
+            face.nodes[edge.target].position,
+        );
+        let mid = Point::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let norm = dx.hypot(dy);
+        if norm == 0.0 {
+            return;
+        }
+        let sign = if edge.forward { 1.0 } else { -1.0 };
+        let size = 0.08;
+        let (ux, uy) = (sign * dx / norm * size, sign * dy / norm * size);
+
+        let corners = [
+            Point::new(mid.x + ux, mid.y + uy),
+            Point::new(uy.mul_add(0.6, mid.x - ux), ux.mul_add(-0.6, mid.y - uy)),
+            Point::new(uy.mul_add(-0.6, mid.x - ux), ux.mul_add(0.6, mid.y - uy)),
+        ];
+        let points: Vec<String> = corners
+            .into_iter()
+            .map(|p| {
+                let p = self.px(p);
+                format!("{:.2},{:.2}", p.x, p.y)
+            })
+            .collect();
+        self.elements.push(format!(
+            r#"<polygon points="{}" fill="{}"/>"#,
+            points.join(" "),
+            self.style.stroke
+        ));
+
+        // Push the label away from the center of the face
+        let (ox, oy) = (mid.x - face.center.x, mid.y - face.center.y);
+        let dist = ox.hypot(oy).max(f32::EPSILON);
+        let label_pos = Point::new(
+            (ox / dist).mul_add(0.2, mid.x),
+            (oy / dist).mul_add(0.2, mid.y),
+        );
+        self.text(label_pos, &id.to_string());
+    }
+
+    fn text(&mut self, p: Point, label: &str)
+    {
+        self.colored_text(p, label, "black");
+    }
+
+    fn colored_text(&mut self, p: Point, label: &str, color: &str)
+    {
+        let p = self.px(p);
+        self.elements.push(format!(
+            r#"<text x="{:.2}" y="{:.2}" text-anchor="middle" dominant-baseline="middle" font-family="{}" font-size="{}" fill="{color}">{}</text>"#,
+            p.x,
+            p.y,
+            self.style.font_family,
+            self.style.font_size,
+            escape(label)
+        ));
+    }
+}
+
+impl RenderBackend for SvgBackend
+{
+    type Output = String;
+
+    fn draw_face(&mut self, face: &FaceLayout)
+    {
+        // Fill first so that the boundary and labels are drawn on top
+        if let Some(color) = face
+            .color
+            .and_then(|c| {
+                self.style
+                    .fill_palette
+                    .get(c % self.style.fill_palette.len().max(1))
+            })
+            .cloned()
+        {
+            let points: Vec<String> = face
+                .nodes
+                .iter()
+                .map(|n| {
+                    let p = self.px(n.position);
+                    format!("{:.2},{:.2}", p.x, p.y)
+                })
+                .collect();
+            self.elements.push(format!(
+                r#"<polygon points="{}" fill="{color}" stroke="none"/>"#,
+                points.join(" ")
+            ));
+        }
+
+        for spoke in &face.spokes {
+            let dash = match spoke.kind {
+                SpokeKind::PosReal => &self.style.pos_real_dash,
+                SpokeKind::NegReal => &self.style.neg_real_dash,
+            };
+            let attrs = format!(
+                r#"stroke="{}" stroke-width="{}" stroke-dasharray="{dash}""#,
+                self.style.stroke, self.style.stroke_width
+            );
+            self.line(face.nodes[spoke.node].position, face.center, &attrs);
+        }
+
+        for edge in &face.edges {
+            for attrs in self.edge_attrs(edge.real) {
+                self.line(
+                    face.nodes[edge.source].position,
+                    face.nodes[edge.target].position,
+                    &attrs,
+                );
+            }
+            if let Some(id) = edge.id.filter(|_| self.edge_ids) {
+                self.edge_marker(face, edge, id);
+            }
+        }
+
+        self.text(face.center, &face.label);
+        for node in &face.nodes {
+            self.text(node.position, &node.label);
+        }
+    }
+
+    fn draw_graph(&mut self, graph: &GraphLayout)
+    {
+        for edge in &graph.edges {
+            for attrs in self.edge_attrs(edge.real) {
+                self.curve(
+                    graph.nodes[edge.source].position,
+                    graph.nodes[edge.target].position,
+                    edge.bend,
+                    &attrs,
+                );
+            }
+        }
+
+        for node in &graph.nodes {
+            self.text(node.position, &node.label);
+        }
+    }
+
+    fn draw_dual_graph(&mut self, graph: &GraphLayout)
+    {
+        let color = self.style.dual_stroke.clone();
+        let attrs = format!(
+            r#"stroke="{color}" stroke-width="{}" stroke-dasharray="{}""#,
+            self.style.stroke_width, self.style.dual_dash
+        );
+
+        for edge in &graph.edges {
+            let (a, b) = (
+                graph.nodes[edge.source].position,
+                graph.nodes[edge.target].position,
+            );
+            if edge.source == edge.target {
+                // Draw loops as small circles sitting on top of the node
+                let r = 0.15 * (edge.bend.abs() + 1) as f32;
+                let c = self.px(Point::new(a.x, a.y + r));
+                self.elements.push(format!(
+                    r#"<circle cx="{:.2}" cy="{:.2}" r="{:.2}" fill="none" {attrs}/>"#,
+                    c.x,
+                    c.y,
+                    r * self.style.scale
+                ));
+            } else {
+                self.curve(a, b, edge.bend, &attrs);
+            }
+        }
+
+        for node in &graph.nodes {
+            self.colored_text(node.position, &node.label, &color);
+        }
+    }
+
+    fn finish(self) -> String
+    {
+        let margin = self.style.margin;
+        let (min, max) = if self.elements.is_empty() {
+            (Point::default(), Point::default())
+        } else {
+            (self.min, self.max)
+        };
+        let width = 2.0f32.mul_add(margin, max.x - min.x);
+        let height = 2.0f32.mul_add(margin, max.y - min.y);
+
+        let mut lines = vec![format!(
+            r#"<svg width="{width:.2}" height="{height:.2}" viewBox="{:.2} {:.2} {width:.2} {height:.2}" xmlns="http://www.w3.org/2000/svg">"#,
+            min.x - margin,
+            min.y - margin,
+        )];
+        lines.extend(self.elements.into_iter().map(|e| format!("  {e}")));
+        lines.push("</svg>".to_owned());
+        lines.join("\n")
+    }
+}
+
+pub struct SvgRenderer<V, F>
+{
+    edges: Vec<Edge<V>>,
+    faces: Vec<Face<Aug<V>, F>>,
+    style: SvgStyle,
+    coloring: FaceColoring,
+    placement: GraphPlacement,
+    identify_edges: bool,
+}
+impl<V, F> SvgRenderer<V, F>
+where
+    V: Display,
+    F: Display,
+{
+    #[must_use]
+    pub fn new(faces: Vec<Face<Aug<V>, F>>) -> Self
+    {
+        Self {
+            edges: Vec::new(),
+            faces,
+            style: SvgStyle::default(),
+            coloring: FaceColoring::None,
+            placement: GraphPlacement::Circle,
+            identify_edges: false,
+        }
+    }
+
+    #[must_use]
+    pub fn with_style(mut self, style: SvgStyle) -> Self
+    {
+        self.style = style;
+        self
+    }
+
+    #[must_use]
+    pub fn with_edges(mut self, edges: Vec<Edge<V>>) -> Self
+    {
+        self.edges = edges;
+        self
+    }
+
+    /// Fill faces according to the given rule, using colors from `SvgStyle::fill_palette`
+    #[must_use]
+    pub const fn with_coloring(mut self, coloring: FaceColoring) -> Self
+    {
+        self.coloring = coloring;
+        self
+    }
+
+    /// Choose how vertices are placed by `draw_graph`
+    #[must_use]
+    pub const fn with_placement(mut self, placement: GraphPlacement) -> Self
+    {
+        self.placement = placement;
+        self
+    }
+
+    /// Label every edge with its index in the cover and draw arrows along it, so that the
+    /// two copies of each edge show how the faces are glued together.
+    #[must_use]
+    pub const fn identify_edges(mut self, identify_edges: bool) -> Self
+    {
+        self.identify_edges = identify_edges;
+        self
+    }
+
+    fn backend(&self) -> SvgBackend
+    {
+        SvgBackend::new(self.style.clone()).edge_ids(self.identify_edges)
+    }
+
+    fn prepare(&self, layouts: &mut [FaceLayout])
+    {
+        self.coloring.assign(layouts);
+        if self.identify_edges {
+            orient_edges(layouts);
+        }
+    }
+
+    fn render_face(&self, face: Option<&Face<Aug<V>, F>>) -> String
+    {
+        let mut backend = self.backend();
+        if let Some(face) = face {
+            let mut layout = [layout_face(face, self.style.edge_length, Point::default())];
+            self.prepare(&mut layout);
+            backend.draw_face(&layout[0]);
+        }
+        backend.finish()
+    }
+
+    #[must_use]
+    pub fn draw_largest_face(self) -> String
+    {
+        let max_size = self.faces.iter().map(Face::len).max().unwrap_or_default();
+        self.render_face(self.faces.iter().find(|f| f.len() == max_size))
+    }
+
+    #[must_use]
+    pub fn draw_smallest_face(self) -> String
+    {
+        let min_size = self.faces.iter().map(Face::len).min().unwrap_or_default();
+        self.render_face(self.faces.iter().find(|f| f.len() == min_size))
+    }
+
+    /// Draw the face with the given label surrounded by every face adjacent to it, each unfolded
+    /// across a shared edge. Returns `None` if no face carries that label.
+    #[must_use]
+    pub fn draw_net(self, label: &F) -> Option<String>
+    where
+        V: Copy + Eq,
+        F: PartialEq,
+    {
+        let root = self.faces.iter().position(|f| f.label == *label)?;
+        Some(self.render_net(&faces_around_face(&self.faces, root), None))
+    }
+
+    /// Draw every face with `vertex` on its boundary, unfolded around that vertex. Returns
+    /// `None` if no face contains it.
+    #[must_use]
+    pub fn draw_vertex_star(self, vertex: V) -> Option<String>
+    where
+        V: Copy + Eq,
+    {
+        let faces = faces_around_vertex(&self.faces, vertex);
+        if faces.is_empty() {
+            return None;
+        }
+        Some(self.render_net(&faces, Some(vertex)))
+    }
+
+    fn render_net(&self, faces: &[&Face<Aug<V>, F>], pivot: Option<V>) -> String
+    where
+        V: Copy + Eq,
+    {
+        let mut layouts = layout_net(faces, self.style.edge_length, pivot);
+        self.prepare(&mut layouts);
+        let mut backend = self.backend();
+        for face in &layouts {
+            backend.draw_face(face);
+        }
+        backend.finish()
+    }
+
+    /// Draw the face with the given label, or return `None` if no face carries that label.
+    #[must_use]
+    pub fn draw_face_by_label(self, label: &F) -> Option<String>
+    where
+        F: PartialEq,
+    {
+        let face = self.faces.iter().find(|f| f.label == *label)?;
+        Some(self.render_face(Some(face)))
+    }
+
+    /// Draw every face, side by side.
+    #[must_use]
+    pub fn generate(self) -> String
+    {
+        self.render_faces(&self.faces)
+    }
+
+    /// Draw the `k` largest or smallest faces side by side, in order of size.
+    #[must_use]
+    pub fn draw_extreme_faces(self, k: usize, extreme: Extreme) -> String
+    {
+        self.render_faces(extreme_faces(&self.faces, k, extreme))
+    }
+
+    fn render_faces<'a, I>(&self, faces: I) -> String
+    where
+        I: IntoIterator<Item = &'a Face<Aug<V>, F>>,
+        V: 'a,
+        F: 'a,
+    {
+        let mut layouts = layout_faces(faces, self.style.edge_length);
+        self.prepare(&mut layouts);
+        let mut backend = self.backend();
+        for face in &layouts {
+            backend.draw_face(face);
+        }
+        backend.finish()
+    }
+
+    /// Draw the whole 1-skeleton of the cover in a single picture. Requires edges to have been
+    /// supplied via `with_edges`.
+    #[must_use]
+    pub fn draw_graph(self) -> String
+    where
+        V: Copy + Eq + Hash,
+    {
+        let mut backend = self.backend();
+        backend.draw_graph(&place_graph(
+            &self.faces,
+            &self.edges,
+            self.style.edge_length,
+            self.placement,
+        ));
+        backend.finish()
+    }
+
+    /// Draw the dual graph, with a node for each face and an edge across each edge of the
+    /// cover. With `overlay`, the 1-skeleton is drawn as well (requiring edges to have been
+    /// supplied via `with_edges`) and each face node sits at the centroid of its vertices.
+    #[must_use]
+    pub fn draw_dual_graph(self, overlay: bool) -> String
+    where
+        V: Copy + Eq + Hash,
+    {
+        let mut backend = self.backend();
+        let dual = if overlay {
+            let primal = place_graph(
+                &self.faces,
+                &self.edges,
+                self.style.edge_length,
+                self.placement,
+            );
+            backend.draw_graph(&primal);
+            layout_dual_graph(&self.faces, Some(&primal), self.style.edge_length)
+        } else {
+            layout_dual_graph(&self.faces, None, self.style.edge_length)
+        };
+        backend.draw_dual_graph(&dual);
+        backend.finish()
+    }
+}
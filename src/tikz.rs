@@ -1,159 +1,745 @@
 use crate::common::cells::{AugmentedVertex as Aug, Edge, Face};
+use crate::layout::{
+    extreme_faces, faces_around_face, faces_around_vertex, fit_edge_length, layout_dual_graph,
+    layout_face, layout_faces, layout_net, orient_edges, paginate_faces, place_graph, Extreme,
+    FaceColoring, FaceLayout, GraphLayout, GraphPlacement, Point, RenderBackend, SpokeKind,
+};
 use lazy_static::lazy_static;
 use regex::Regex;
-use std::{f32::consts::PI, fmt::Display};
+use serde::{de, Deserialize, Deserializer};
+use std::{
+    fmt::Display,
+    hash::Hash,
+    io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+pub mod fundamental_polygon;
+pub mod lamination;
+pub mod pinched_disk;
 
 lazy_static! {
     static ref RE_DEL: Regex = Regex::new(r"^\((.*)\)$").expect("Invalid regex");
     static ref RE_ABR: Regex = Regex::new(r"^<(.*)>$").expect("Invalid regex");
 }
 
-pub struct TikzRenderer<V, F>
+/// Styling options for the generated TikZ. Every `*_style` field is a raw TikZ option list
+/// (e.g. `"thick,blue"`); empty strings produce unadorned commands. Fields left out when it is
+/// deserialized, as from the `[tikz]` table of a configuration file, keep their defaults.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TikzStyle
 {
-    commands: Vec<String>,
-    edges: Vec<Edge<V>>,
-    faces: Vec<Face<Aug<V>, F>>,
+    /// Length of each polygon side, in TikZ units
+    pub edge_length: f32,
+    /// Options applied to every vertex node
+    pub node_style: String,
+    /// Options applied to the node at the center of each face
+    pub face_label_style: String,
+    /// Options applied to ordinary edges
+    pub edge_style: String,
+    /// Options applied to real edges (those along the negative real axis)
+    pub real_edge_style: String,
+    /// Options for the spoke from a vertex to its face center when it crosses the positive reals
+    pub pos_real_style: String,
+    /// Options for the spoke from a vertex to its face center when it crosses the negative reals
+    pub neg_real_style: String,
+    /// Font command used for all nodes, e.g. `\small`
+    pub font_size: Option<String>,
+    /// Color of all lines
+    pub line_color: Option<String>,
+    /// Color of all node text
+    pub text_color: Option<String>,
+    /// Fill colors for faces, indexed by the category chosen by a `FaceColoring`
+    pub fill_palette: Vec<String>,
+    /// Options applied to the nodes of the dual graph
+    pub dual_node_style: String,
+    /// Options applied to the edges of the dual graph
+    pub dual_edge_style: String,
+    /// Maximum width of a single picture, in TikZ units. Faces too large to fit are scaled
+    /// down, and `generate` starts a new picture whenever the next face would overflow.
+    /// `None` keeps the fixed edge length and puts everything in one picture, written as
+    /// `page_width = false` when deserialized.
+    #[serde(deserialize_with = "deserialize_page_width")]
+    pub page_width: Option<f32>,
 }
-impl<V, F> TikzRenderer<V, F>
-where
-    V: Display,
-    F: Display,
+
+/// A page width, or `false` for none
+fn deserialize_page_width<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<f32>, D::Error>
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum PageWidth
+    {
+        Width(f32),
+        Off(bool),
+    }
+    match PageWidth::deserialize(deserializer)? {
+        PageWidth::Width(width) => Ok(Some(width)),
+        PageWidth::Off(false) => Ok(None),
+        PageWidth::Off(true) => Err(de::Error::custom(
+            "expected a page width, or `false` for none",
+        )),
+    }
+}
+
+impl Default for TikzStyle
+{
+    fn default() -> Self
+    {
+        Self {
+            edge_length: 1.46,
+            node_style: String::new(),
+            face_label_style: String::new(),
+            edge_style: String::new(),
+            real_edge_style: "double,double distance=2pt".to_owned(),
+            pos_real_style: "dashed".to_owned(),
+            neg_real_style: "dotted".to_owned(),
+            font_size: None,
+            line_color: None,
+            text_color: None,
+            fill_palette: [
+                "red!20",
+                "blue!20",
+                "green!20",
+                "orange!25",
+                "violet!20",
+                "cyan!20",
+                "yellow!30",
+                "gray!25",
+            ]
+            .map(String::from)
+            .to_vec(),
+            page_width: Some(16.0),
+            dual_node_style: "draw=red,fill=red!10,circle,inner sep=1pt".to_owned(),
+            dual_edge_style: "red,densely dashed".to_owned(),
+        }
+    }
+}
+
+impl TikzStyle
+{
+    fn picture_options(&self) -> String
+    {
+        let mut options = Vec::new();
+        if let Some(color) = &self.line_color {
+            options.push(format!("draw={color}"));
+        }
+        let mut node_options = Vec::new();
+        if let Some(font) = &self.font_size {
+            node_options.push(format!("font={font}"));
+        }
+        if let Some(color) = &self.text_color {
+            node_options.push(format!("text={color}"));
+        }
+        if !node_options.is_empty() {
+            options.push(format!("every node/.style={{{}}}", node_options.join(",")));
+        }
+        options.join(",")
+    }
+}
+
+/// Wrap a nonempty option list in brackets
+fn opts(style: &str) -> String
+{
+    if style.is_empty() {
+        String::new()
+    } else {
+        format!("[{style}]")
+    }
+}
+
+/// Append the option bending an edge to the given side of the straight line, if any
+fn with_bend(style: &str, bend: i32) -> String
+{
+    let mut style = style.to_owned();
+    if bend != 0 {
+        if !style.is_empty() {
+            style.push(',');
+        }
+        let side = if bend > 0 { "left" } else { "right" };
+        style.push_str(&format!("bend {side}={}", 15 * bend.abs()));
+    }
+    style
+}
+
+/// Options placing an arrowhead halfway along a path, pointing backwards if `reversed`
+fn arrow_style(reversed: bool) -> String
+{
+    let arrow = if reversed { "<" } else { ">" };
+    format!(
+        r"postaction={{decorate}},decoration={{markings,mark=at position 0.5 with {{\arrow{{{arrow}}}}}}}"
+    )
+}
+
+/// Preamble defining the macros used in generated labels
+const PREAMBLE: &str = r"\documentclass[tikz,border=5pt]{standalone}
+\usetikzlibrary{decorations.markings}
+\newcommand{\abr}[1]{\left\langle #1 \right\rangle}
+\newcommand{\del}[1]{\left( #1 \right)}";
+
+/// Wrap one or more `tikzpicture`s in a standalone document that compiles as is.
+#[must_use]
+pub fn standalone_document(pictures: &str) -> String
+{
+    format!("{PREAMBLE}\n\\begin{{document}}\n{pictures}\n\\end{{document}}\n")
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LatexEngine
+{
+    #[default]
+    Pdflatex,
+    Tectonic,
+}
+
+/// Write a LaTeX document to `path`, creating parent directories as needed.
+pub fn write_document(document: &str, path: impl AsRef<Path>) -> io::Result<()>
+{
+    let path = path.as_ref();
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, document)
+}
+
+/// Compile a `.tex` file with the given engine, returning the path of the resulting PDF. The
+/// PDF is written next to the source file.
+pub fn compile(tex_path: impl AsRef<Path>, engine: LatexEngine) -> io::Result<PathBuf>
+{
+    let tex_path = tex_path.as_ref();
+    let out_dir = tex_path
+        .parent()
+        .filter(|d| !d.as_os_str().is_empty())
+        .unwrap_or_else(|| Path::new("."));
+
+    let status = match engine {
+        LatexEngine::Pdflatex => Command::new("pdflatex")
+            .arg("-interaction=nonstopmode")
+            .arg("-halt-on-error")
+            .arg("-output-directory")
+            .arg(out_dir)
+            .arg(tex_path)
+            .status()?,
+        LatexEngine::Tectonic => Command::new("tectonic")
+            .arg("--outdir")
+            .arg(out_dir)
+            .arg(tex_path)
+            .status()?,
+    };
+
+    if !status.success() {
+        return Err(io::Error::other(format!(
+            "{engine:?} failed on {} ({status})",
+            tex_path.display()
+        )));
+    }
+    Ok(tex_path.with_extension("pdf"))
+}
+
+/// Emits TikZ commands for precomputed layouts.
+pub struct TikzBackend
 {
-    const EDGE_LENGTH: f32 = 1.46;
+    commands: Vec<String>,
+    style: TikzStyle,
+    standalone: bool,
+    edge_ids: bool,
+    num_faces: usize,
+}
 
-    // pub fn new(edges: Vec<Edge<V>>, faces: Vec<Face<V, F>>) -> Self
+impl TikzBackend
+{
     #[must_use]
-    pub fn new(faces: Vec<Face<Aug<V>, F>>) -> Self
+    pub const fn new(style: TikzStyle) -> Self
     {
-        let commands = vec![
-            r"\begin{tikzpicture}".to_owned(),
-            r"    \def\edgelength{1.8cm}".to_owned(),
-        ];
         Self {
-            commands,
-            edges: Vec::new(),
-            faces,
+            commands: Vec::new(),
+            style,
+            standalone: false,
+            edge_ids: false,
+            num_faces: 0,
         }
     }
 
-    fn draw_face(&mut self, face: &Face<Aug<V>, F>)
+    /// Emit a complete LaTeX document instead of a bare `tikzpicture`
+    #[must_use]
+    pub const fn standalone(mut self, standalone: bool) -> Self
+    {
+        self.standalone = standalone;
+        self
+    }
+
+    /// Label each face edge with its index in the cover and mark its orientation with an arrow
+    #[must_use]
+    pub const fn edge_ids(mut self, edge_ids: bool) -> Self
     {
-        let n = face.len();
+        self.edge_ids = edge_ids;
+        self
+    }
 
-        let half_angle = PI / (n as f32);
-        let radius = Self::EDGE_LENGTH / (2.0 * half_angle.sin());
-        let offset_x = radius * half_angle.cos();
+    fn coords(p: Point) -> String
+    {
+        format!("({:.4}, {:.4})", p.x, p.y)
+    }
 
-        self.commands.push("\n".to_owned());
-        self.commands
-            .push(format!(r"    \def\baseangle{{180/{n}}}"));
-        self.commands
-            .push(format!(r"    \def\anchorx{{{offset_x}}}"));
-        self.commands.push(String::new());
+    fn face_label(label: &str) -> String
+    {
+        RE_ABR
+            .captures(label)
+            .map_or_else(|| format!("${label}$"), |c| format!(r"$\abr{{{}}}$", &c[1]))
+    }
 
-        let face_str = face.label.to_string();
-        let face_idx = RE_ABR.replace_all(&face_str, r"$1").to_string();
-        let face_label = format!(r"$\abr{{{face_idx}}}$");
-        let face_id = format!(r"(face{face_idx})");
+    fn vertex_label(label: &str) -> String
+    {
+        RE_DEL.replace_all(label, r"$\del{$1}$").to_string()
+    }
+}
 
-        self.commands.push(format!(
-            r"    \node {face_id} at (\anchorx, 0) {{{face_label}}};"
-        ));
+impl RenderBackend for TikzBackend
+{
+    type Output = String;
 
-        let label = format!("{}", face.vertices[0].vertex);
-        let label = RE_DEL.replace_all(&label, r"$\del{$1}$").to_string();
+    fn draw_face(&mut self, face: &FaceLayout)
+    {
+        let face_label = Self::face_label(&face.label);
+        // Name nodes by position rather than by label, so that names are valid TikZ and do not
+        // depend on how labels happen to be printed
+        let face_idx = self.num_faces;
+        self.num_faces += 1;
+        let face_id = format!("(face-{face_idx})");
+        let node_opts = opts(&self.style.node_style);
+
+        self.commands.push(String::new());
+        // Fill first so that the boundary and labels are drawn on top
+        if let Some(color) = face.color.and_then(|c| {
+            self.style
+                .fill_palette
+                .get(c % self.style.fill_palette.len().max(1))
+        }) {
+            let boundary: Vec<String> = face
+                .nodes
+                .iter()
+                .map(|n| Self::coords(n.position))
+                .collect();
+            self.commands.push(format!(
+                r"    \fill[{color}] {} -- cycle;",
+                boundary.join(" -- ")
+            ));
+        }
         self.commands.push(format!(
-            r"    \node (node-{face_idx}-0) at (${face_id}+(\baseangle:{radius})$) {{{label}}};",
+            r"    \node{face_opts} {face_id} at {center} {{{face_label}}};",
+            face_opts = opts(&self.style.face_label_style),
+            center = Self::coords(face.center),
         ));
 
-        for (i, node) in face.vertices.iter().enumerate().skip(1) {
-            let label = node.vertex.to_string();
-            let label = RE_DEL.replace_all(&label, r"$\del{$1}$").to_string();
+        for (i, node) in face.nodes.iter().enumerate() {
             self.commands.push(format!(
-                // r"    \node (node-{face_idx}-{i}) at ($(node-{face_idx}-{prev})+({{\baseangle - 90 - {i}*\anglestep}}:)$) {{{label}}};",
-                r"    \node (node-{face_idx}-{i}) at ($(node-{face_idx}-{prev})+({angle} + \baseangle:{dist})$) {{{label}}};",
-                angle = (-90. + (i as f32).mul_add(-360., 180.) / (n as f32)).rem_euclid(360.),
-                dist = Self::EDGE_LENGTH,
-                prev = i-1
+                r"    \node{node_opts} (node-{face_idx}-{i}) at {pos} {{{label}}};",
+                pos = Self::coords(node.position),
+                label = Self::vertex_label(&node.label),
             ));
         }
 
         // draw the edges between the nodes
-        for i in 0..n {
-            let next = (i + 1) % n;
+        for edge in &face.edges {
+            let mut style = if edge.real {
+                self.style.real_edge_style.clone()
+            } else {
+                self.style.edge_style.clone()
+            };
+            let mut label = String::new();
+            if let Some(id) = edge.id.filter(|_| self.edge_ids) {
+                if !style.is_empty() {
+                    style.push(',');
+                }
+                style.push_str(&arrow_style(!edge.forward));
+                // Vertices run clockwise, so the right-hand side is outside the face
+                label = format!(r" node[midway,auto=right,font=\tiny] {{${id}$}}");
+            }
+            self.commands.push(format!(
+                r"    \draw{} (node-{face_idx}-{}) -- (node-{face_idx}-{}){label};",
+                opts(&style),
+                edge.source,
+                edge.target
+            ));
+        }
 
-            let data = face.vertices[i].data;
+        for spoke in &face.spokes {
+            let spoke_opts = match spoke.kind {
+                SpokeKind::PosReal => opts(&self.style.pos_real_style),
+                SpokeKind::NegReal => opts(&self.style.neg_real_style),
+            };
+            self.commands.push(format!(
+                r"    \draw{spoke_opts} (node-{face_idx}-{}) -- {face_id};",
+                spoke.node
+            ));
+        }
+    }
+
+    fn draw_graph(&mut self, graph: &GraphLayout)
+    {
+        let node_opts = opts(&self.style.node_style);
 
-            if data.neg_edge() {
-                self.commands.push(format!(
-                    r"    \draw[double,double distance=2pt] (node-{face_idx}-{i}) -- (node-{face_idx}-{next});"
-                ));
+        self.commands.push(String::new());
+        for (i, node) in graph.nodes.iter().enumerate() {
+            self.commands.push(format!(
+                r"    \node{node_opts} (vertex-{i}) at {pos} {{{label}}};",
+                pos = Self::coords(node.position),
+                label = Self::vertex_label(&node.label),
+            ));
+        }
+
+        for edge in &graph.edges {
+            let style = if edge.real {
+                &self.style.real_edge_style
             } else {
-                self.commands.push(format!(
-                    r"    \draw (node-{face_idx}-{i}) -- (node-{face_idx}-{next});"
-                ));
-            }
+                &self.style.edge_style
+            };
+            self.commands.push(format!(
+                r"    \draw{} (vertex-{}) to (vertex-{});",
+                opts(&with_bend(style, edge.bend)),
+                edge.source,
+                edge.target
+            ));
+        }
+    }
 
-            if data.pos_vertex() {
-                self.commands.push(format!(
-                    r"    \draw[dashed] (node-{face_idx}-{i}) -- {face_id};"
-                ));
-            }
+    fn draw_dual_graph(&mut self, graph: &GraphLayout)
+    {
+        let node_opts = opts(&self.style.dual_node_style);
+
+        self.commands.push(String::new());
+        for (i, node) in graph.nodes.iter().enumerate() {
+            let label = Self::face_label(&node.label);
+            self.commands.push(format!(
+                r"    \node{node_opts} (dual-{i}) at {pos} {{{label}}};",
+                pos = Self::coords(node.position),
+            ));
+        }
 
-            if data.neg_vertex() {
-                self.commands.push(format!(
-                    r"    \draw[dotted] (node-{face_idx}-{i}) -- {face_id};"
-                ));
+        for edge in &graph.edges {
+            let mut style = with_bend(&self.style.dual_edge_style, edge.bend);
+            if edge.source == edge.target {
+                if !style.is_empty() {
+                    style.push(',');
+                }
+                style.push_str("loop above");
             }
+            self.commands.push(format!(
+                r"    \draw{} (dual-{}) to (dual-{});",
+                opts(&style),
+                edge.source,
+                edge.target
+            ));
+        }
+    }
+
+    fn finish(self) -> String
+    {
+        let header = [
+            format!(
+                r"\begin{{tikzpicture}}{}",
+                opts(&self.style.picture_options())
+            ),
+            format!(r"    \def\edgelength{{{}cm}}", self.style.edge_length),
+        ];
+        let picture = header
+            .into_iter()
+            .chain(self.commands)
+            .chain(std::iter::once(r"\end{tikzpicture}".to_owned()))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if self.standalone {
+            standalone_document(&picture)
+        } else {
+            picture
         }
     }
+}
 
+pub struct TikzRenderer<V, F>
+{
+    edges: Vec<Edge<V>>,
+    faces: Vec<Face<Aug<V>, F>>,
+    style: TikzStyle,
+    coloring: FaceColoring,
+    placement: GraphPlacement,
+    standalone: bool,
+    identify_edges: bool,
+}
+impl<V, F> TikzRenderer<V, F>
+where
+    V: Display,
+    F: Display + Ord,
+{
+    /// Faces are sorted by label, so that the output does not depend on the order in which the
+    /// cover happened to produce them.
     #[must_use]
-    pub fn draw_largest_face(mut self) -> String
+    pub fn new(mut faces: Vec<Face<Aug<V>, F>>) -> Self
     {
-        let max_size = self.faces.iter().map(Face::len).max().unwrap_or_default();
-        let faces = std::mem::take(&mut self.faces);
-        for f in &faces {
-            if f.len() == max_size {
-                self.draw_face(f);
-                break;
-            }
+        faces.sort_by(|a, b| a.label.cmp(&b.label));
+        Self {
+            edges: Vec::new(),
+            faces,
+            style: TikzStyle::default(),
+            coloring: FaceColoring::None,
+            placement: GraphPlacement::Circle,
+            standalone: false,
+            identify_edges: false,
         }
-        self.commands.push(r"\end{tikzpicture}".to_owned());
-        self.commands.join("\n")
     }
 
+    /// Produce complete LaTeX documents, including definitions of the `\abr` and `\del` macros
+    /// used in labels, rather than bare `tikzpicture`s.
     #[must_use]
-    pub fn draw_smallest_face(mut self) -> String
+    pub const fn standalone(mut self, standalone: bool) -> Self
     {
-        let min_size = self.faces.iter().map(Face::len).min().unwrap_or_default();
-        let faces = std::mem::take(&mut self.faces);
-        for f in &faces {
-            if f.len() == min_size {
-                self.draw_face(f);
-                break;
-            }
+        self.standalone = standalone;
+        self
+    }
+
+    /// Choose how vertices are placed by `draw_graph`
+    #[must_use]
+    pub const fn with_placement(mut self, placement: GraphPlacement) -> Self
+    {
+        self.placement = placement;
+        self
+    }
+
+    /// Label every edge with its index in the cover and draw arrows along it, so that the
+    /// two copies of each edge show how the faces are glued together.
+    #[must_use]
+    pub const fn identify_edges(mut self, identify_edges: bool) -> Self
+    {
+        self.identify_edges = identify_edges;
+        self
+    }
+
+    fn backend(&self) -> TikzBackend
+    {
+        TikzBackend::new(self.style.clone())
+            .standalone(self.standalone)
+            .edge_ids(self.identify_edges)
+    }
+
+    fn prepare(&self, layouts: &mut [FaceLayout])
+    {
+        self.coloring.assign(layouts);
+        if self.identify_edges {
+            orient_edges(layouts);
+        }
+    }
+
+    #[must_use]
+    pub fn with_style(mut self, style: TikzStyle) -> Self
+    {
+        self.style = style;
+        self
+    }
+
+    #[must_use]
+    pub fn with_edges(mut self, edges: Vec<Edge<V>>) -> Self
+    {
+        self.edges = edges;
+        self
+    }
+
+    /// Fill faces according to the given rule, using colors from `TikzStyle::fill_palette`
+    #[must_use]
+    pub const fn with_coloring(mut self, coloring: FaceColoring) -> Self
+    {
+        self.coloring = coloring;
+        self
+    }
+
+    fn render_face(&self, face: &Face<Aug<V>, F>) -> String
+    {
+        let edge_length = self
+            .style
+            .page_width
+            .map_or(self.style.edge_length, |width| {
+                fit_edge_length(face.len(), self.style.edge_length, width)
+            });
+        let mut layout = [layout_face(face, edge_length, Point::default())];
+        self.prepare(&mut layout);
+        let mut backend = self.backend();
+        backend.draw_face(&layout[0]);
+        backend.finish()
+    }
+
+    #[must_use]
+    pub fn draw_largest_face(self) -> String
+    {
+        self.draw_extreme_face(Extreme::Largest)
+    }
+
+    #[must_use]
+    pub fn draw_smallest_face(self) -> String
+    {
+        self.draw_extreme_face(Extreme::Smallest)
+    }
+
+    fn draw_extreme_face(&self, extreme: Extreme) -> String
+    {
+        extreme_faces(&self.faces, 1, extreme)
+            .first()
+            .map_or_else(|| self.backend().finish(), |f| self.render_face(f))
+    }
+
+    /// Draw the face with the given label surrounded by every face adjacent to it, each unfolded
+    /// across a shared edge. Returns `None` if no face carries that label.
+    #[must_use]
+    pub fn draw_net(self, label: &F) -> Option<String>
+    where
+        V: Copy + Eq,
+        F: PartialEq,
+    {
+        let root = self.faces.iter().position(|f| f.label == *label)?;
+        Some(self.render_net(&faces_around_face(&self.faces, root), None))
+    }
+
+    /// Draw every face with `vertex` on its boundary, unfolded around that vertex. Returns
+    /// `None` if no face contains it.
+    #[must_use]
+    pub fn draw_vertex_star(self, vertex: V) -> Option<String>
+    where
+        V: Copy + Eq,
+    {
+        let faces = faces_around_vertex(&self.faces, vertex);
+        if faces.is_empty() {
+            return None;
+        }
+        Some(self.render_net(&faces, Some(vertex)))
+    }
+
+    fn render_net(&self, faces: &[&Face<Aug<V>, F>], pivot: Option<V>) -> String
+    where
+        V: Copy + Eq,
+    {
+        let mut layouts = layout_net(faces, self.style.edge_length, pivot);
+        self.prepare(&mut layouts);
+        let mut backend = self.backend();
+        for face in &layouts {
+            backend.draw_face(face);
         }
-        self.commands.push(r"\end{tikzpicture}".to_owned());
-        self.commands.join("\n")
+        backend.finish()
     }
 
+    /// Draw the face with the given label, or return `None` if no face carries that label.
     #[must_use]
-    pub fn generate(mut self) -> String
+    pub fn draw_face_by_label(self, label: &F) -> Option<String>
+    where
+        F: PartialEq,
     {
-        let faces = std::mem::take(&mut self.faces);
-        for f in &faces {
-            self.draw_face(f);
+        let face = self.faces.iter().find(|f| f.label == *label)?;
+        Some(self.render_face(face))
+    }
+
+    /// Draw every face, side by side. If `TikzStyle::page_width` is set, the faces are split
+    /// across as many pictures as needed; in a standalone document each becomes its own page.
+    #[must_use]
+    pub fn generate(self) -> String
+    {
+        self.document(&self.generate_pages())
+    }
+
+    /// Draw every face, side by side, returning one bare `tikzpicture` per page.
+    #[must_use]
+    pub fn generate_pages(&self) -> Vec<String>
+    {
+        self.render_pages(&self.faces.iter().collect::<Vec<_>>())
+    }
+
+    /// Draw the `k` largest or smallest faces side by side, in order of size. Faces of equal
+    /// size are taken in label order.
+    #[must_use]
+    pub fn draw_extreme_faces(self, k: usize, extreme: Extreme) -> String
+    {
+        self.document(&self.render_pages(&extreme_faces(&self.faces, k, extreme)))
+    }
+
+    fn document(&self, pictures: &[String]) -> String
+    {
+        let pictures = pictures.join("\n\n");
+        if self.standalone {
+            standalone_document(&pictures)
+        } else {
+            pictures
         }
-        self.commands.push(r"\end{tikzpicture}".to_owned());
-        self.commands.join("\n")
     }
 
-    // fn draw_edge(&mut self, edge: Edge<V>) {
-    //     todo!()
-    // }
-    //
-    // fn layout_face(&mut self, face: Face<V, F>) {
-    //     todo!()
-    // }
+    fn render_pages(&self, faces: &[&Face<Aug<V>, F>]) -> Vec<String>
+    {
+        let pages = match self.style.page_width {
+            Some(width) => paginate_faces(faces.iter().copied(), self.style.edge_length, width),
+            None => vec![layout_faces(faces.iter().copied(), self.style.edge_length)],
+        };
+        // Colors and edge orientations must agree across pages
+        let sizes: Vec<usize> = pages.iter().map(Vec::len).collect();
+        let mut layouts = pages.concat();
+        self.prepare(&mut layouts);
+
+        let mut layouts = layouts.iter();
+        let mut offset = 0;
+        sizes
+            .into_iter()
+            .map(|size| {
+                // Keep face ids unique across pages
+                let mut backend = self.backend().standalone(false);
+                backend.num_faces = offset;
+                offset += size;
+                for face in layouts.by_ref().take(size) {
+                    backend.draw_face(face);
+                }
+                backend.finish()
+            })
+            .collect()
+    }
+
+    /// Draw the whole 1-skeleton of the cover in a single picture, with each vertex placed once
+    /// on a circle and every edge drawn between its endpoints. Requires edges to have been
+    /// supplied via `with_edges`.
+    #[must_use]
+    pub fn draw_graph(self) -> String
+    where
+        V: Copy + Eq + Hash,
+    {
+        let mut backend = self.backend();
+        backend.draw_graph(&place_graph(
+            &self.faces,
+            &self.edges,
+            self.style.edge_length,
+            self.placement,
+        ));
+        backend.finish()
+    }
+
+    /// Draw the dual graph, with a node for each face and an edge across each edge of the
+    /// cover. With `overlay`, the 1-skeleton is drawn as well (requiring edges to have been
+    /// supplied via `with_edges`) and each face node sits at the centroid of its vertices.
+    #[must_use]
+    pub fn draw_dual_graph(self, overlay: bool) -> String
+    where
+        V: Copy + Eq + Hash,
+    {
+        let mut backend = self.backend();
+        let dual = if overlay {
+            let primal = place_graph(
+                &self.faces,
+                &self.edges,
+                self.style.edge_length,
+                self.placement,
+            );
+            backend.draw_graph(&primal);
+            layout_dual_graph(&self.faces, Some(&primal), self.style.edge_length)
+        } else {
+            layout_dual_graph(&self.faces, None, self.style.edge_length)
+        };
+        backend.draw_dual_graph(&dual);
+        backend.finish()
+    }
 }
@@ -0,0 +1,180 @@
+use super::{arrow_style, opts, standalone_document, RE_DEL};
+use crate::fundamental_polygon::FundamentalPolygon;
+use std::{collections::HashMap, f64::consts::PI, fmt::Display};
+
+/// Draws a fundamental polygon as a regular hyperbolic polygon in the Poincaré disk, with
+/// identified sides sharing a label and arrows showing how they are glued.
+pub struct FundamentalPolygonRenderer<V>
+{
+    polygon: FundamentalPolygon<V>,
+    /// Radius of the disk, in TikZ units
+    pub radius: f64,
+    /// Euclidean distance of the corners from the center, as a fraction of the disk radius
+    pub corner_radius: f64,
+    /// Label each corner with the vertex of the cover that it represents
+    pub vertex_labels: bool,
+    /// Options applied to every side
+    pub side_style: String,
+    standalone: bool,
+}
+
+/// Label for the k-th pair of sides: a, b, ..., z, a_1, b_1, ...
+fn pair_label(k: usize) -> String
+{
+    let letter = char::from(b'a' + (k % 26) as u8);
+    match k / 26 {
+        0 => letter.to_string(),
+        i => format!("{letter}_{{{i}}}"),
+    }
+}
+
+/// Center and radius of the circle through three points
+fn circumcircle(p: (f64, f64), q: (f64, f64), r: (f64, f64)) -> Option<((f64, f64), f64)>
+{
+    let d = 2.0 * (p.0 * (q.1 - r.1) + q.0 * (r.1 - p.1) + r.0 * (p.1 - q.1));
+    if d.abs() < 1e-12 {
+        return None;
+    }
+    let (pp, qq, rr) = (
+        p.0.mul_add(p.0, p.1 * p.1),
+        q.0.mul_add(q.0, q.1 * q.1),
+        r.0.mul_add(r.0, r.1 * r.1),
+    );
+    let cx = (pp * (q.1 - r.1) + qq * (r.1 - p.1) + rr * (p.1 - q.1)) / d;
+    let cy = (pp * (r.0 - q.0) + qq * (p.0 - r.0) + rr * (q.0 - p.0)) / d;
+    Some(((cx, cy), (p.0 - cx).hypot(p.1 - cy)))
+}
+
+impl<V> FundamentalPolygonRenderer<V>
+where
+    V: Copy + Eq + Display,
+{
+    #[must_use]
+    pub fn new(polygon: FundamentalPolygon<V>) -> Self
+    {
+        Self {
+            polygon,
+            radius: 3.0,
+            corner_radius: 0.85,
+            vertex_labels: true,
+            side_style: String::new(),
+            standalone: false,
+        }
+    }
+
+    #[must_use]
+    pub const fn vertex_labels(mut self, vertex_labels: bool) -> Self
+    {
+        self.vertex_labels = vertex_labels;
+        self
+    }
+
+    #[must_use]
+    pub const fn standalone(mut self, standalone: bool) -> Self
+    {
+        self.standalone = standalone;
+        self
+    }
+
+    fn corner(&self, i: usize) -> (f64, f64)
+    {
+        let n = self.polygon.num_sides().max(1) as f64;
+        let theta = 2.0 * PI * (i as f64) / n;
+        (
+            self.corner_radius * theta.cos(),
+            self.corner_radius * theta.sin(),
+        )
+    }
+
+    /// TikZ path for the geodesic segment between two points of the unit disk, scaled to the
+    /// disk radius
+    fn geodesic(&self, p: (f64, f64), q: (f64, f64)) -> String
+    {
+        let s = self.radius;
+        let norm = p.0.mul_add(p.0, p.1 * p.1);
+        let inverse = (p.0 / norm, p.1 / norm);
+
+        // Geodesics through the origin are diameters; all others lie on circles orthogonal to
+        // the boundary, which pass through the inversion of either endpoint
+        match circumcircle(p, q, inverse) {
+            Some((c, r)) if norm > 1e-12 => {
+                let start = (p.1 - c.1).atan2(p.0 - c.0).to_degrees();
+                let mut end = (q.1 - c.1).atan2(q.0 - c.0).to_degrees();
+                if end - start > 180. {
+                    end -= 360.;
+                } else if start - end > 180. {
+                    end += 360.;
+                }
+                format!(
+                    "({:.4}, {:.4}) arc[start angle={start:.4}, end angle={end:.4}, radius={:.4}]",
+                    s * p.0,
+                    s * p.1,
+                    s * r
+                )
+            }
+            _ => format!(
+                "({:.4}, {:.4}) -- ({:.4}, {:.4})",
+                s * p.0,
+                s * p.1,
+                s * q.0,
+                s * q.1
+            ),
+        }
+    }
+
+    #[must_use]
+    pub fn generate(self) -> String
+    {
+        let n = self.polygon.num_sides();
+
+        let mut labels: HashMap<usize, String> = HashMap::new();
+        let mut reversed = vec![false; n];
+        for (k, (i, j)) in self.polygon.pairing().into_iter().enumerate() {
+            let label = pair_label(k);
+            labels.insert(i, label.clone());
+            labels.insert(j, label);
+            // Arrows on the two sides point the way the shared edge runs
+            reversed[j] = self.polygon.is_orientation_reversing(i, j);
+        }
+
+        let mut commands = vec![
+            r"\begin{tikzpicture}".to_owned(),
+            format!(r"    \draw[gray] (0, 0) circle ({});", self.radius),
+        ];
+
+        for (i, side) in self.polygon.sides.iter().enumerate() {
+            let path = self.geodesic(self.corner(i), self.corner((i + 1) % n));
+            let label = labels
+                .get(&i)
+                .map_or_else(String::new, |l| format!("${l}$"));
+            let mut style = arrow_style(reversed[i]);
+            if !self.side_style.is_empty() {
+                style = format!("{},{style}", self.side_style);
+            }
+            commands.push(format!(
+                r"    \draw{} {path} node[midway, auto=right] {{{label}}};",
+                opts(&style)
+            ));
+
+            if self.vertex_labels {
+                let (x, y) = self.corner(i);
+                let vertex = side.start.to_string();
+                let vertex = RE_DEL.replace_all(&vertex, r"$\del{$1}$");
+                let angle = y.atan2(x).to_degrees();
+                commands.push(format!(
+                    r"    \node[font=\tiny] at ({angle:.4}:{:.4}) {{{vertex}}};",
+                    self.radius * (self.corner_radius + 0.08)
+                ));
+            }
+        }
+
+        commands.push(r"\end{tikzpicture}".to_owned());
+        let picture = commands.join("\n");
+
+        if self.standalone {
+            standalone_document(&picture)
+        } else {
+            picture
+        }
+    }
+}
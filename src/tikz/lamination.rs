@@ -0,0 +1,191 @@
+use super::{opts, standalone_document};
+use crate::lamination::Lamination;
+use crate::types::{Period, RatAngle};
+use std::f64::consts::PI;
+
+/// Colors cycled through when leaves are colored by period
+const PALETTE: [&str; 8] = [
+    "black", "red", "blue", "teal", "orange", "violet", "olive", "cyan",
+];
+
+pub(super) fn to_degrees(angle: RatAngle) -> f64
+{
+    360. * (*angle.numer() as f64) / (*angle.denom() as f64)
+}
+
+/// TikZ path for the hyperbolic geodesic joining two boundary angles, given in degrees, of the
+/// disk with the given radius, together with the midpoint of the geodesic
+pub(super) fn geodesic(radius: f64, alpha: f64, beta: f64) -> (String, (f64, f64))
+{
+    let r = radius;
+    let (ax, ay) = (r * alpha.to_radians().cos(), r * alpha.to_radians().sin());
+    let (bx, by) = (r * beta.to_radians().cos(), r * beta.to_radians().sin());
+
+    let delta = (beta - alpha).rem_euclid(360.);
+    let delta = delta.min(360. - delta);
+
+    let mut mid = f64::midpoint(alpha, beta).to_radians();
+    if (beta - alpha).abs() > 180. {
+        mid += PI;
+    }
+
+    // Diameters are straight lines through the center
+    if (180. - delta).abs() < 1e-9 {
+        return (
+            format!("({ax:.4}, {ay:.4}) -- ({bx:.4}, {by:.4})"),
+            (0., 0.),
+        );
+    }
+
+    // Otherwise the geodesic is an arc of the circle orthogonal to the boundary through both
+    // points
+    let half = delta.to_radians() / 2.;
+    let (cx, cy) = (r * mid.cos() / half.cos(), r * mid.sin() / half.cos());
+    let arc_radius = r * half.tan();
+
+    let start = (ay - cy).atan2(ax - cx).to_degrees();
+    let mut end = (by - cy).atan2(bx - cx).to_degrees();
+    if end - start > 180. {
+        end -= 360.;
+    } else if start - end > 180. {
+        end += 360.;
+    }
+
+    let depth = r / half.cos() - arc_radius;
+    (
+        format!(
+            "({ax:.4}, {ay:.4}) arc[start angle={start:.4}, end angle={end:.4}, radius={arc_radius:.4}]"
+        ),
+        (depth * mid.cos(), depth * mid.sin()),
+    )
+}
+
+/// Draws the leaves of the lamination in the unit disk, either as straight chords or as
+/// hyperbolic geodesics.
+pub struct LaminationRenderer
+{
+    lamination: Lamination,
+    period: Period,
+    /// Radius of the disk, in TikZ units
+    pub radius: f64,
+    /// Draw leaves as geodesics in the Poincaré disk instead of straight chords
+    pub geodesics: bool,
+    /// Include leaves of every period up to `period`, not just those of exactly `period`
+    pub all_periods: bool,
+    /// Color each leaf according to its period
+    pub color_by_period: bool,
+    /// Options applied to every leaf
+    pub leaf_style: String,
+    standalone: bool,
+}
+
+impl LaminationRenderer
+{
+    #[must_use]
+    pub fn new(lamination: Lamination, period: Period) -> Self
+    {
+        Self {
+            lamination,
+            period,
+            radius: 3.0,
+            geodesics: false,
+            all_periods: false,
+            color_by_period: false,
+            leaf_style: String::new(),
+            standalone: false,
+        }
+    }
+
+    #[must_use]
+    pub const fn geodesics(mut self, geodesics: bool) -> Self
+    {
+        self.geodesics = geodesics;
+        self
+    }
+
+    #[must_use]
+    pub const fn all_periods(mut self, all_periods: bool) -> Self
+    {
+        self.all_periods = all_periods;
+        self
+    }
+
+    #[must_use]
+    pub const fn color_by_period(mut self, color_by_period: bool) -> Self
+    {
+        self.color_by_period = color_by_period;
+        self
+    }
+
+    #[must_use]
+    pub const fn standalone(mut self, standalone: bool) -> Self
+    {
+        self.standalone = standalone;
+        self
+    }
+
+    #[must_use]
+    pub fn color(period: Period) -> &'static str
+    {
+        PALETTE[(period.max(1) as usize - 1) % PALETTE.len()]
+    }
+
+    /// TikZ path for the leaf joining two boundary angles, given in degrees
+    fn leaf_path(&self, alpha: f64, beta: f64) -> String
+    {
+        if self.geodesics {
+            geodesic(self.radius, alpha, beta).0
+        } else {
+            let r = self.radius;
+            let (ax, ay) = (r * alpha.to_radians().cos(), r * alpha.to_radians().sin());
+            let (bx, by) = (r * beta.to_radians().cos(), r * beta.to_radians().sin());
+            format!("({ax:.4}, {ay:.4}) -- ({bx:.4}, {by:.4})")
+        }
+    }
+
+    #[must_use]
+    pub fn generate(mut self) -> String
+    {
+        let period = self.period;
+        let arcs = std::mem::take(&mut self.lamination).into_arcs(period);
+        let first = if self.all_periods { 1 } else { period.max(1) };
+
+        let mut commands = vec![
+            r"\begin{tikzpicture}".to_owned(),
+            format!(r"    \draw (0, 0) circle ({});", self.radius),
+        ];
+
+        for per in first..=period {
+            let Some(leaves) = arcs.get(per as usize) else {
+                continue;
+            };
+            let mut style = self.leaf_style.clone();
+            if self.color_by_period {
+                if !style.is_empty() {
+                    style.push(',');
+                }
+                style.push_str(Self::color(per));
+            }
+            let style = opts(&style);
+
+            for (theta0, theta1) in leaves {
+                let (alpha, beta) = (to_degrees(*theta0), to_degrees(*theta1));
+                // The period 1 leaf joins 0 to 1, a single point on the circle
+                if (beta - alpha).rem_euclid(360.) < 1e-9 {
+                    continue;
+                }
+                let path = self.leaf_path(alpha, beta);
+                commands.push(format!(r"    \draw{style} {path};"));
+            }
+        }
+
+        commands.push(r"\end{tikzpicture}".to_owned());
+        let picture = commands.join("\n");
+
+        if self.standalone {
+            standalone_document(&picture)
+        } else {
+            picture
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use super::lamination::{geodesic, to_degrees};
+use super::{opts, standalone_document};
+use crate::lamination::Lamination;
+use crate::types::Period;
+
+/// Draws the pinched disk model of the lamination: the closed disk with every leaf collapsed to
+/// a point. Each leaf is drawn as a cut through the shaded disk, thinner for higher periods,
+/// and the point where its two boundary angles are identified is marked with a dot.
+pub struct PinchedDiskRenderer
+{
+    lamination: Lamination,
+    period: Period,
+    /// Radius of the disk, in TikZ units
+    pub radius: f64,
+    /// Fill color of the disk
+    pub fill: String,
+    /// Color of the cuts along the leaves
+    pub pinch_color: String,
+    /// Width of the cuts along period 2 leaves, in points; leaves of period `p` get
+    /// `2 * pinch_width / p`
+    pub pinch_width: f64,
+    /// Mark the pinch point of each leaf
+    pub pinch_points: bool,
+    standalone: bool,
+}
+
+impl PinchedDiskRenderer
+{
+    #[must_use]
+    pub fn new(lamination: Lamination, period: Period) -> Self
+    {
+        Self {
+            lamination,
+            period,
+            radius: 3.0,
+            fill: "gray!40".to_owned(),
+            pinch_color: "white".to_owned(),
+            pinch_width: 2.0,
+            pinch_points: true,
+            standalone: false,
+        }
+    }
+
+    #[must_use]
+    pub const fn pinch_points(mut self, pinch_points: bool) -> Self
+    {
+        self.pinch_points = pinch_points;
+        self
+    }
+
+    #[must_use]
+    pub const fn standalone(mut self, standalone: bool) -> Self
+    {
+        self.standalone = standalone;
+        self
+    }
+
+    #[must_use]
+    pub fn generate(mut self) -> String
+    {
+        let period = self.period;
+        let arcs = std::mem::take(&mut self.lamination).into_arcs(period);
+
+        let mut commands = vec![
+            r"\begin{tikzpicture}".to_owned(),
+            format!(
+                r"    \fill{} (0, 0) circle ({});",
+                opts(&self.fill),
+                self.radius
+            ),
+        ];
+        let mut dots = Vec::new();
+
+        for per in 1..=period {
+            let Some(leaves) = arcs.get(per as usize) else {
+                continue;
+            };
+            let width = 2.0 * self.pinch_width / (per.max(1) as f64);
+            let style = opts(&format!("{},line width={width:.3}pt", self.pinch_color));
+
+            for (theta0, theta1) in leaves {
+                let (alpha, beta) = (to_degrees(*theta0), to_degrees(*theta1));
+                // The period 1 leaf joins 0 to 1, a single point on the circle
+                if (beta - alpha).rem_euclid(360.) < 1e-9 {
+                    continue;
+                }
+                let (path, (x, y)) = geodesic(self.radius, alpha, beta);
+                commands.push(format!(r"    \draw{style} {path};"));
+                dots.push(format!(
+                    r"    \fill ({x:.4}, {y:.4}) circle ({width:.3}pt);"
+                ));
+            }
+        }
+
+        if self.pinch_points {
+            commands.extend(dots);
+        }
+        commands.push(format!(r"    \draw (0, 0) circle ({});", self.radius));
+        commands.push(r"\end{tikzpicture}".to_owned());
+        let picture = commands.join("\n");
+
+        if self.standalone {
+            standalone_document(&picture)
+        } else {
+            picture
+        }
+    }
+}
@@ -0,0 +1,594 @@
+use crate::common::cells::Edge;
+use crate::common::FxHashMap;
+use crate::monodromy::{MonodromyGroup, Permutation};
+use crate::types::Period;
+use alloc::{collections::BTreeMap, format, string::String, vec, vec::Vec};
+use core::hash::Hash;
+use num_rational::Rational64;
+
+/// The curve `Per_p(0)` that a cover lies over. Both bases are planes, with a single puncture,
+/// but the faces of a cover are traced around different points of them, so that the open
+/// cover is punctured in different places.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BaseCurve
+{
+    /// The quadratic polynomials `z^2 + c`, punctured at `c = ∞`. The faces of a cover are
+    /// traced around the puncture, so that each face holds one puncture of the cover.
+    Per1,
+    /// The quadratic rational maps with a critical 2-cycle, punctured where they degenerate.
+    /// The faces of a cover are traced around the class of `1/z^2`, which commutes with
+    /// rotation by a cube root of unity and so is an orbifold point of order 3; the puncture
+    /// lies in the part of the base collapsed to the vertices, so that each vertex holds one
+    /// puncture of the cover.
+    Per2,
+}
+
+impl BaseCurve
+{
+    /// The base of the covers with the given critical period, if it is one of those supported
+    #[must_use]
+    pub const fn from_crit_period(crit_period: Period) -> Option<Self>
+    {
+        match crit_period {
+            1 => Some(Self::Per1),
+            2 => Some(Self::Per2),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub const fn crit_period(self) -> Period
+    {
+        match self {
+            Self::Per1 => 1,
+            Self::Per2 => 2,
+        }
+    }
+
+    #[must_use]
+    pub const fn punctures(self) -> usize
+    {
+        1
+    }
+
+    /// Order of the orbifold point around which the faces are traced, if there is one
+    #[must_use]
+    pub const fn orbifold_order(self) -> Option<Period>
+    {
+        match self {
+            Self::Per1 => None,
+            Self::Per2 => Some(3),
+        }
+    }
+
+    /// Euler characteristic of the punctured base
+    #[must_use]
+    pub const fn euler_characteristic(self) -> i64
+    {
+        2 - self.punctures() as i64
+    }
+
+    /// Euler characteristic of the punctured base, less `1 - 1/m` for an orbifold point of
+    /// order `m`
+    #[must_use]
+    pub fn orbifold_euler_characteristic(self) -> Rational64
+    {
+        let chi = Rational64::from_integer(self.euler_characteristic());
+        match self.orbifold_order() {
+            Some(m) => chi - Rational64::new(m - 1, m),
+            None => chi,
+        }
+    }
+}
+
+/// The topology of a cover relative to its base: the closed surface given by its cells, where
+/// the open cover is punctured, and which of its points are orbifold points
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct RelativeTopology
+{
+    pub base: BaseCurve,
+    /// Degree of the cover, the sum of the degrees of the faces traced around the distinguished
+    /// point of the base
+    pub sheets: usize,
+    /// Number of connected components
+    pub components: usize,
+    /// Euler characteristic of the closed surface, `V - E + F`
+    pub closed_euler_characteristic: i64,
+    /// Number of points of the closed surface missing from the cover
+    pub punctures: usize,
+    /// Number of faces of degree 1 around the orbifold point of the base, which are orbifold
+    /// points of the same order
+    pub orbifold_points: usize,
+}
+
+impl RelativeTopology
+{
+    /// Topology of a cover of `base` with the given cell counts and components, where
+    /// `degrees` lists the degree of each face traced around the puncture or orbifold point
+    /// of the base. Other faces, such as satellite faces, are counted in `faces` only.
+    #[must_use]
+    pub fn new(
+        base: BaseCurve,
+        (vertices, edges, faces): (usize, usize, usize),
+        components: usize,
+        degrees: impl IntoIterator<Item = Period>,
+    ) -> Self
+    {
+        let degrees: Vec<Period> = degrees.into_iter().collect();
+        let orbifold_points = match base {
+            BaseCurve::Per1 => 0,
+            BaseCurve::Per2 => degrees.iter().filter(|&&d| d == 1).count(),
+        };
+        Self {
+            base,
+            sheets: degrees.iter().sum::<Period>() as usize,
+            components,
+            closed_euler_characteristic: vertices as i64 - edges as i64 + faces as i64,
+            punctures: Ends::new(base, vertices, degrees).len(),
+            orbifold_points,
+        }
+    }
+
+    /// Euler characteristic of the open cover, with its punctures removed
+    #[must_use]
+    pub const fn euler_characteristic(&self) -> i64
+    {
+        self.closed_euler_characteristic - self.punctures as i64
+    }
+
+    /// Euler characteristic of the open cover, less `1 - 1/m` for each orbifold point
+    #[must_use]
+    pub fn orbifold_euler_characteristic(&self) -> Rational64
+    {
+        let chi = Rational64::from_integer(self.euler_characteristic());
+        match self.base.orbifold_order() {
+            Some(m) => chi - Rational64::new(m - 1, m) * self.orbifold_points as i64,
+            None => chi,
+        }
+    }
+
+    /// Total ramification over the points of the base other than its puncture and orbifold
+    /// point, found from the Riemann-Hurwitz formula: the cover's orbifold Euler characteristic
+    /// falls short of `sheets` times that of the base by this much
+    #[must_use]
+    pub fn ramification(&self) -> i64
+    {
+        let expected = self.base.orbifold_euler_characteristic() * self.sheets as i64;
+        (expected - self.orbifold_euler_characteristic()).to_integer()
+    }
+
+    /// Sum of the genera of the components of the closed surface. Unlike the covers' `genus`,
+    /// this is right for the covers over `Per_2` of small period that fall into several
+    /// spheres.
+    #[must_use]
+    pub const fn genus(&self) -> i64
+    {
+        self.components as i64 - self.closed_euler_characteristic / 2
+    }
+}
+
+/// The cell of the closed surface holding an end of a cover, by its index among the cover's
+/// vertices or faces
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum EndCell
+{
+    Vertex(usize),
+    Face(usize),
+}
+
+/// A point of the closed surface over the puncture of the base, and the local degree of the
+/// cover there
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct End
+{
+    pub cell: EndCell,
+    pub local_degree: Period,
+}
+
+/// The ends of a cover over the puncture of its base, which are added to compactify it. Over
+/// `Per_1`, there is one end in each face traced around the puncture, with the degree of that
+/// face as its local degree. Over `Per_2`, there is one end in each vertex, where the cover is
+/// unramified.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Ends
+{
+    pub base: BaseCurve,
+    pub ends: Vec<End>,
+}
+
+impl Ends
+{
+    /// Ends of a cover of `base` with `vertices` vertices, where `degrees` lists the degree of
+    /// each face traced around the puncture or orbifold point of the base. These faces must
+    /// come first among the cover's faces.
+    #[must_use]
+    pub fn new(base: BaseCurve, vertices: usize, degrees: impl IntoIterator<Item = Period>)
+        -> Self
+    {
+        let ends = match base {
+            BaseCurve::Per1 => degrees
+                .into_iter()
+                .enumerate()
+                .map(|(k, local_degree)| End {
+                    cell: EndCell::Face(k),
+                    local_degree,
+                })
+                .collect(),
+            BaseCurve::Per2 => (0..vertices)
+                .map(|v| End {
+                    cell: EndCell::Vertex(v),
+                    local_degree: 1,
+                })
+                .collect(),
+        };
+        Self { base, ends }
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize
+    {
+        self.ends.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool
+    {
+        self.ends.is_empty()
+    }
+
+    /// Degree of the cover, the sum of the local degrees
+    #[must_use]
+    pub fn sheets(&self) -> Period
+    {
+        self.ends.iter().map(|e| e.local_degree).sum()
+    }
+
+    /// Contribution of the ends to the Riemann-Hurwitz formula, the sum of one less than each
+    /// local degree
+    #[must_use]
+    pub fn ramification(&self) -> Period
+    {
+        self.ends.iter().map(|e| e.local_degree - 1).sum()
+    }
+
+    /// Number of ends of each local degree
+    #[must_use]
+    pub fn degree_counts(&self) -> BTreeMap<Period, usize>
+    {
+        let mut counts = BTreeMap::new();
+        for e in &self.ends {
+            *counts.entry(e.local_degree).or_insert(0) += 1;
+        }
+        counts
+    }
+}
+
+/// A graph onto which the open cover deformation retracts, made of cells of the cover, so that
+/// its Euler characteristic is that of the open cover. Over `Per_1`, where the punctures lie in
+/// the faces traced around them, it is the 1-skeleton less one edge of each other face, through
+/// which that face is collapsed. Over `Per_2`, where the punctures lie in the vertices, it is
+/// the dual graph, with a node in each face and an edge across each edge of the cover.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Spine
+{
+    pub base: BaseCurve,
+    /// Number of nodes: the cover's vertices over `Per_1`, and its faces over `Per_2`, by
+    /// index
+    pub num_nodes: usize,
+    /// The id of the cover's edge that each edge lies along or crosses, and the nodes it joins,
+    /// in order of edge id
+    pub edges: Vec<(usize, (usize, usize))>,
+}
+
+impl Spine
+{
+    /// Spine of a cover of `base` whose edges join the vertices `edges[k]`, by index, and whose
+    /// faces have the boundary edges `face_edges`, by id. The first `punctured` faces are those
+    /// traced around the puncture or orbifold point of the base.
+    pub(crate) fn new<'a>(
+        base: BaseCurve,
+        num_vertices: usize,
+        edges: &[(usize, usize)],
+        face_edges: impl IntoIterator<Item = &'a [usize]>,
+        punctured: usize,
+    ) -> Self
+    {
+        // The faces on either side of each edge
+        let mut sides = vec![Vec::with_capacity(2); edges.len()];
+        let mut num_faces = 0;
+        for (k, ids) in face_edges.into_iter().enumerate() {
+            for &id in ids {
+                sides[id].push(k);
+            }
+            num_faces += 1;
+        }
+
+        match base {
+            BaseCurve::Per1 => {
+                // Collapse the unpunctured faces along a spanning forest of the dual graph
+                // rooted at the punctured ones
+                let mut reached: Vec<bool> = (0..num_faces).map(|k| k < punctured).collect();
+                let mut collapsed = vec![false; edges.len()];
+                let mut changed = true;
+                while changed {
+                    changed = false;
+                    for (id, faces) in sides.iter().enumerate() {
+                        if let [a, b] = faces[..] {
+                            if reached[a] != reached[b] {
+                                reached[a] = true;
+                                reached[b] = true;
+                                collapsed[id] = true;
+                                changed = true;
+                            }
+                        }
+                    }
+                }
+                Self {
+                    base,
+                    num_nodes: num_vertices,
+                    edges: edges
+                        .iter()
+                        .enumerate()
+                        .filter(|&(id, _)| !collapsed[id])
+                        .map(|(id, &ends)| (id, ends))
+                        .collect(),
+                }
+            }
+            BaseCurve::Per2 => Self {
+                base,
+                num_nodes: num_faces,
+                edges: sides
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(id, faces)| match faces[..] {
+                        [a, b] => Some((id, (a, b))),
+                        _ => None,
+                    })
+                    .collect(),
+            },
+        }
+    }
+
+    #[must_use]
+    pub fn num_edges(&self) -> usize
+    {
+        self.edges.len()
+    }
+
+    /// Euler characteristic, `V - E`, which is that of the open cover
+    #[must_use]
+    pub fn euler_characteristic(&self) -> i64
+    {
+        self.num_nodes as i64 - self.num_edges() as i64
+    }
+
+    #[must_use]
+    pub fn num_components(&self) -> usize
+    {
+        count_components_of_pairs(self.num_nodes, self.edges.iter().map(|&(_, ends)| ends))
+    }
+
+    /// Rank of the fundamental group of the open cover, summed over its components: the number
+    /// of edges outside a spanning forest
+    #[must_use]
+    pub fn rank(&self) -> usize
+    {
+        self.num_edges() + self.num_components() - self.num_nodes
+    }
+}
+
+/// The closed surface of a cover, built from its cells with a cell filling in each end, so
+/// that the ends are cells of the complex rather than points inside them. Over `Per_1`, the
+/// end in each face traced around the puncture becomes a new vertex, joined by an edge to each
+/// corner of the face, which is split into triangles around it. Over `Per_2`, the ends are the
+/// vertices of the cover, and its cells are kept as they are.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Compactification
+{
+    pub base: BaseCurve,
+    pub num_vertices: usize,
+    /// The vertices each edge joins, by index. The cover's edges come first, in order of edge
+    /// id, followed by those added around the ends.
+    pub edges: Vec<(usize, usize)>,
+    /// Boundary of each face, by edge index
+    pub faces: Vec<Vec<usize>>,
+    /// Each end of the cover, in order, and the vertex filling it in
+    pub ends: Vec<(End, usize)>,
+}
+
+impl Compactification
+{
+    /// Compactify a cover with the given ends, whose edges join the vertices `edges[k]`, by
+    /// index, and whose faces have the corners and boundary edges `faces`, by index, in order.
+    pub(crate) fn new<'a>(
+        ends: &Ends,
+        num_vertices: usize,
+        edges: &[(usize, usize)],
+        faces: impl IntoIterator<Item = (Vec<usize>, &'a [usize])>,
+    ) -> Self
+    {
+        let mut compactification = Self {
+            base: ends.base,
+            num_vertices,
+            edges: edges.to_vec(),
+            faces: Vec::new(),
+            ends: Vec::with_capacity(ends.len()),
+        };
+        let mut coned: Vec<Option<End>> = Vec::new();
+        for end in &ends.ends {
+            match end.cell {
+                EndCell::Vertex(v) => compactification.ends.push((*end, v)),
+                EndCell::Face(k) => {
+                    if coned.len() <= k {
+                        coned.resize(k + 1, None);
+                    }
+                    coned[k] = Some(*end);
+                }
+            }
+        }
+        for (k, (corners, boundary)) in faces.into_iter().enumerate() {
+            match coned.get(k).copied().flatten() {
+                Some(end) => compactification.cone(end, &corners, boundary),
+                None => compactification.faces.push(boundary.to_vec()),
+            }
+        }
+        compactification
+    }
+
+    /// Fill in `end` with a vertex joined to each of the `corners` of its face, replacing the
+    /// face by a triangle on each edge of its `boundary`. A face without edges, made of a single
+    /// corner, becomes a disc bounded by the new edge run there and back.
+    fn cone(&mut self, end: End, corners: &[usize], boundary: &[usize])
+    {
+        let center = self.num_vertices;
+        self.num_vertices += 1;
+        self.ends.push((end, center));
+        let first_spoke = self.edges.len();
+        self.edges.extend(corners.iter().map(|&v| (center, v)));
+        let spoke = |i: usize| first_spoke + i % corners.len();
+        for i in 0..corners.len() {
+            let mut face = vec![spoke(i)];
+            face.extend(boundary.get(i));
+            face.push(spoke(i + 1));
+            self.faces.push(face);
+        }
+    }
+
+    #[must_use]
+    pub fn num_edges(&self) -> usize
+    {
+        self.edges.len()
+    }
+
+    #[must_use]
+    pub fn num_faces(&self) -> usize
+    {
+        self.faces.len()
+    }
+
+    /// Euler characteristic, `V - E + F`
+    #[must_use]
+    pub fn euler_characteristic(&self) -> i64
+    {
+        self.num_vertices as i64 - self.num_edges() as i64 + self.num_faces() as i64
+    }
+
+    #[must_use]
+    pub fn num_components(&self) -> usize
+    {
+        count_components_of_pairs(self.num_vertices, self.edges.iter().copied())
+    }
+
+    /// Genus, summed over the connected components
+    #[must_use]
+    pub fn genus(&self) -> i64
+    {
+        self.num_components() as i64 - self.euler_characteristic() / 2
+    }
+
+    /// Check the compactification against the Riemann-Hurwitz formula for the cover's
+    /// `monodromy` over the compactified base, a sphere. The cover is branched over the root of
+    /// each wake, over the point its faces are traced around, and, over `Per_2`, over the
+    /// puncture, where the local degrees are those of the ends. Over `Per_1`, whose faces are
+    /// traced around the puncture, the ends must match the cycles of the monodromy there. The
+    /// components must also be the orbits of the monodromy group.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error describing the first invariant that disagrees.
+    pub fn check(&self, monodromy: &MonodromyGroup) -> Result<(), String>
+    {
+        let sheets = monodromy.degree();
+        let end_degrees: Vec<usize> = self
+            .ends
+            .iter()
+            .map(|(end, _)| end.local_degree as usize)
+            .collect();
+        let end_sheets: usize = end_degrees.iter().sum();
+        if end_sheets != sheets {
+            return Err(format!(
+                "The ends cover {end_sheets} sheets, but the cover has {sheets}"
+            ));
+        }
+
+        let around = monodromy.product();
+        let mut ramification: usize = monodromy
+            .generators
+            .iter()
+            .map(Permutation::ramification)
+            .sum();
+        ramification += around.ramification();
+        match self.base {
+            BaseCurve::Per1 => {
+                let mut degrees = end_degrees;
+                degrees.sort_unstable();
+                if degrees != around.cycle_type() {
+                    return Err(format!(
+                        "The ends have local degrees {degrees:?}, but the monodromy around the \
+                         puncture has cycles of lengths {:?}",
+                        around.cycle_type()
+                    ));
+                }
+            }
+            BaseCurve::Per2 => {
+                ramification += end_degrees.iter().map(|d| d - 1).sum::<usize>();
+            }
+        }
+
+        let expected = 2 * sheets as i64 - ramification as i64;
+        if self.euler_characteristic() != expected {
+            return Err(format!(
+                "Compactification has Euler characteristic {}, but the Riemann-Hurwitz formula \
+                 gives {expected}",
+                self.euler_characteristic()
+            ));
+        }
+        let orbits = monodromy.orbits().len();
+        if self.num_components() != orbits {
+            return Err(format!(
+                "Compactification has {} components, but the monodromy group has {orbits} orbits",
+                self.num_components()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Number of connected components of the graph with the given vertices and edges
+pub(crate) fn count_components<V>(vertices: &[V], edges: &[Edge<V>]) -> usize
+where
+    V: Copy + Eq + Hash,
+{
+    let index: FxHashMap<V, usize> = vertices.iter().enumerate().map(|(i, &v)| (v, i)).collect();
+    count_components_of_pairs(
+        vertices.len(),
+        edges.iter().map(|e| (index[&e.start], index[&e.end])),
+    )
+}
+
+/// Number of connected components of the graph on `0..num_vertices` with the given edges
+fn count_components_of_pairs(
+    num_vertices: usize,
+    edges: impl IntoIterator<Item = (usize, usize)>,
+) -> usize
+{
+    fn root(parent: &mut [usize], mut v: usize) -> usize
+    {
+        while parent[v] != v {
+            parent[v] = parent[parent[v]];
+            v = parent[v];
+        }
+        v
+    }
+
+    let mut parent: Vec<usize> = (0..num_vertices).collect();
+    let mut components = num_vertices;
+    for (a, b) in edges {
+        let (a, b) = (root(&mut parent, a), root(&mut parent, b));
+        if a != b {
+            parent[a] = b;
+            components -= 1;
+        }
+    }
+    components
+}
@@ -1,9 +1,12 @@
-use std::num::TryFromIntError;
+use core::num::TryFromIntError;
 
 use derive_more::*;
 use num_rational::Rational64;
 
-use crate::global_state::PERIOD;
+use crate::arithmetic::MAX_ANGLE_PERIOD;
+use crate::global_state::Context;
+use crate::topology::BaseCurve;
+use alloc::{format, string::String};
 
 pub type Period = i64;
 pub type UPeriod = u64;
@@ -33,6 +36,7 @@ pub type RatAngle = Rational64;
     Binary,
     Display,
 )]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct IntAngle(pub Period);
 
 impl IntAngle
@@ -43,9 +47,50 @@ impl IntAngle
         let theta = (ratio * self.0).to_integer();
         Self(theta)
     }
+
+    /// Display the angle as its numerator `k`, or, if `fraction` is set, as the fraction
+    /// `k/(2^n - 1)` of a full turn, where `n` is the ambient period
+    #[must_use]
+    pub fn display(self, fraction: bool) -> AngleDisplay
+    {
+        self.display_in(Context::current(), fraction)
+    }
+
+    /// Display the angle as its numerator `k`, or, if `fraction` is set, as the fraction
+    /// `k/(2^n - 1)` of a full turn, where `n` is the period of `context`
+    #[must_use]
+    pub const fn display_in(self, context: Context, fraction: bool) -> AngleDisplay
+    {
+        AngleDisplay {
+            angle: self,
+            max_angle: context.max_angle,
+            fraction,
+        }
+    }
 }
 
-impl std::ops::Shl<Period> for IntAngle
+/// An [`IntAngle`] formatted by [`IntAngle::display_in`], padded to the formatter's width
+#[derive(Clone, Copy, Debug)]
+pub struct AngleDisplay
+{
+    angle: IntAngle,
+    max_angle: IntAngle,
+    fraction: bool,
+}
+
+impl core::fmt::Display for AngleDisplay
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
+    {
+        if self.fraction {
+            f.pad(&format!("{}/{}", self.angle, self.max_angle))
+        } else {
+            core::fmt::Display::fmt(&self.angle, f)
+        }
+    }
+}
+
+impl core::ops::Shl<Period> for IntAngle
 {
     type Output = Self;
     fn shl(self, rhs: Period) -> Self::Output
@@ -54,7 +99,7 @@ impl std::ops::Shl<Period> for IntAngle
     }
 }
 
-impl std::ops::Shr<Period> for IntAngle
+impl core::ops::Shr<Period> for IntAngle
 {
     type Output = Self;
     fn shr(self, rhs: Period) -> Self::Output
@@ -63,7 +108,7 @@ impl std::ops::Shr<Period> for IntAngle
     }
 }
 
-impl std::ops::Rem for IntAngle
+impl core::ops::Rem for IntAngle
 {
     type Output = Self;
     fn rem(self, rhs: Self) -> Self::Output
@@ -81,14 +126,28 @@ impl TryFrom<IntAngle> for usize
     }
 }
 
+/// Kneading sequence of an angle, written with one digit for each of the first `period - 1`
+/// points of its orbit
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "std", derive(serde::Serialize, serde::Deserialize))]
 pub struct KneadingSequence
 {
     itinerary: i64,
+    period: Period,
 }
 
 impl KneadingSequence
 {
+    /// The empty kneading sequence of an angle of period `period`
+    #[must_use]
+    pub const fn new(period: Period) -> Self
+    {
+        Self {
+            itinerary: 0,
+            period,
+        }
+    }
+
     #[inline]
     pub fn increment(&mut self)
     {
@@ -102,15 +161,136 @@ impl KneadingSequence
     }
 }
 
-impl std::fmt::Display for KneadingSequence
+impl core::fmt::Display for KneadingSequence
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result
     {
         write!(
             f,
             "{:0>width$b}*",
             self.itinerary >> 1,
-            width = (PERIOD.get() - 1) as usize
+            width = (self.period - 1).max(0) as usize
         )
     }
 }
+
+/// Period of the marked cycle of a cover, checked to lie in `1..=MAX_ANGLE_PERIOD`, so that
+/// the angles of its cycles fit in an [`IntAngle`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Display, Into)]
+pub struct MarkedPeriod(Period);
+
+impl MarkedPeriod
+{
+    /// # Errors
+    ///
+    /// Returns an error if `period` is not in `1..=MAX_ANGLE_PERIOD`.
+    pub fn new(period: Period) -> Result<Self, String>
+    {
+        if (1..=MAX_ANGLE_PERIOD).contains(&period) {
+            Ok(Self(period))
+        } else {
+            Err(format!(
+                "period {period} is out of range 1..={MAX_ANGLE_PERIOD}"
+            ))
+        }
+    }
+
+    #[must_use]
+    pub const fn get(self) -> Period
+    {
+        self.0
+    }
+}
+
+impl TryFrom<Period> for MarkedPeriod
+{
+    type Error = String;
+
+    fn try_from(period: Period) -> Result<Self, Self::Error>
+    {
+        Self::new(period)
+    }
+}
+
+impl core::str::FromStr for MarkedPeriod
+{
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err>
+    {
+        let period = text
+            .trim()
+            .parse::<Period>()
+            .map_err(|e| format!("invalid period {text:?}: {e}"))?;
+        Self::new(period)
+    }
+}
+
+/// Period of the critical cycle of the maps a cover lies over, checked to be one of those
+/// whose base curve is supported: 1, for `Per_1`, or 2, for `Per_2`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Display, Into)]
+pub struct CritPeriod(Period);
+
+impl CritPeriod
+{
+    pub const ONE: Self = Self(1);
+    pub const TWO: Self = Self(2);
+
+    /// # Errors
+    ///
+    /// Returns an error if no base curve is supported for `crit_period`.
+    pub fn new(crit_period: Period) -> Result<Self, String>
+    {
+        BaseCurve::from_crit_period(crit_period)
+            .map(Self::from)
+            .ok_or_else(|| format!("critical period {crit_period} is not supported; use 1 or 2"))
+    }
+
+    #[must_use]
+    pub const fn get(self) -> Period
+    {
+        self.0
+    }
+
+    /// The curve the covers with this critical period lie over
+    #[must_use]
+    pub const fn base(self) -> BaseCurve
+    {
+        match self.0 {
+            1 => BaseCurve::Per1,
+            _ => BaseCurve::Per2,
+        }
+    }
+}
+
+impl From<BaseCurve> for CritPeriod
+{
+    fn from(base: BaseCurve) -> Self
+    {
+        Self(base.crit_period())
+    }
+}
+
+impl TryFrom<Period> for CritPeriod
+{
+    type Error = String;
+
+    fn try_from(crit_period: Period) -> Result<Self, Self::Error>
+    {
+        Self::new(crit_period)
+    }
+}
+
+impl core::str::FromStr for CritPeriod
+{
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err>
+    {
+        let crit_period = text
+            .trim()
+            .parse::<Period>()
+            .map_err(|e| format!("invalid critical period {text:?}: {e}"))?;
+        Self::new(crit_period)
+    }
+}
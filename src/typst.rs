@@ -0,0 +1,637 @@
+use crate::common::cells::{AugmentedVertex as Aug, Edge, Face};
+use crate::layout::{
+    extreme_faces, faces_around_face, faces_around_vertex, layout_dual_graph, layout_face,
+    layout_faces, layout_net, orient_edges, place_graph, EdgeLayout, Extreme, FaceColoring,
+    FaceLayout, GraphLayout, GraphPlacement, Point, RenderBackend, SpokeKind,
+};
+use std::{fmt::Display, hash::Hash};
+
+/// Styling options for the generated Typst. Lengths are in layout units unless noted otherwise,
+/// and colors and dash patterns are Typst expressions, such as `black`, `rgb("#f4cccc")`, or
+/// `"dashed"`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TypstStyle
+{
+    /// Length of each polygon side
+    pub edge_length: f32,
+    /// Points per layout unit
+    pub scale: f32,
+    /// Margin around the drawing, in points
+    pub margin: f32,
+    /// Stroke color for edges and spokes
+    pub stroke: String,
+    /// Stroke width for edges, in points
+    pub stroke_width: f32,
+    /// Font size for labels, in points
+    pub font_size: f32,
+    /// Dash pattern for spokes crossing the positive reals
+    pub pos_real_dash: String,
+    /// Dash pattern for spokes crossing the negative reals
+    pub neg_real_dash: String,
+    /// Fill colors for faces, indexed by the category chosen by a `FaceColoring`
+    pub fill_palette: Vec<String>,
+    /// Color of the dual graph
+    pub dual_stroke: String,
+    /// Dash pattern for edges of the dual graph
+    pub dual_dash: String,
+}
+
+impl Default for TypstStyle
+{
+    fn default() -> Self
+    {
+        Self {
+            edge_length: 1.46,
+            scale: 30.0,
+            margin: 15.0,
+            stroke: "black".to_owned(),
+            stroke_width: 1.0,
+            font_size: 8.0,
+            pos_real_dash: "\"dashed\"".to_owned(),
+            neg_real_dash: "\"dotted\"".to_owned(),
+            fill_palette: [
+                "#f4cccc", "#cfe2f3", "#d9ead3", "#fce5cd", "#d9d2e9", "#d0e0e3", "#fff2cc",
+                "#d9d9d9",
+            ]
+            .map(|hex| format!("rgb(\"{hex}\")"))
+            .to_vec(),
+            dual_stroke: "red".to_owned(),
+            dual_dash: "\"dashed\"".to_owned(),
+        }
+    }
+}
+
+/// A Typst string literal
+fn quote(text: &str) -> String
+{
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// A Typst table with a header row and right-aligned columns
+#[must_use]
+pub fn table(headers: &[&str], rows: &[Vec<String>]) -> String
+{
+    let cells = |row: &[String]| {
+        row.iter()
+            .map(|c| format!("[{}]", c.replace('[', "\\[").replace(']', "\\]")))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let headers: Vec<String> = headers.iter().map(|h| format!("*{h}*")).collect();
+    let mut lines = vec![
+        "#table(".to_owned(),
+        format!("  columns: {},", headers.len()),
+        "  align: right,".to_owned(),
+        format!("  table.header({}),", cells(&headers)),
+    ];
+    lines.extend(rows.iter().map(|row| format!("  {},", cells(row))));
+    lines.push(")".to_owned());
+    lines.join("\n")
+}
+
+/// Page settings that make a drawing compile as a document of its own, sized to fit it
+const PREAMBLE: &str = "#set page(width: auto, height: auto, margin: 5pt)";
+
+/// A shape to be placed once the extent of the drawing is known. Points are in points, with
+/// the y-axis pointing down.
+enum Element
+{
+    Line(Point, Point, String),
+    Quad(Point, Point, Point, String),
+    Polygon(Vec<Point>, String),
+    Circle(Point, f32, String),
+    Text(Point, String, String),
+}
+
+/// Emits Typst markup for precomputed layouts, as a single `box` of placed shapes.
+pub struct TypstBackend
+{
+    elements: Vec<Element>,
+    style: TypstStyle,
+    min: Point,
+    max: Point,
+    edge_ids: bool,
+}
+
+impl TypstBackend
+{
+    #[must_use]
+    pub const fn new(style: TypstStyle) -> Self
+    {
+        Self {
+            elements: Vec::new(),
+            style,
+            min: Point::new(f32::INFINITY, f32::INFINITY),
+            max: Point::new(f32::NEG_INFINITY, f32::NEG_INFINITY),
+            edge_ids: false,
+        }
+    }
+
+    /// Label each face edge with its index in the cover and mark its orientation with an arrow
+    #[must_use]
+    pub const fn edge_ids(mut self, edge_ids: bool) -> Self
+    {
+        self.edge_ids = edge_ids;
+        self
+    }
+
+    /// Convert layout coordinates to points, flipping the y-axis
+    fn pt(&mut self, p: Point) -> Point
+    {
+        let q = Point::new(p.x * self.style.scale, -p.y * self.style.scale);
+        self.min = Point::new(self.min.x.min(q.x), self.min.y.min(q.y));
+        self.max = Point::new(self.max.x.max(q.x), self.max.y.max(q.y));
+        q
+    }
+
+    fn stroke(&self, paint: &str, width: f32, dash: Option<&str>) -> String
+    {
+        let dash = dash.map(|d| format!(", dash: {d}")).unwrap_or_default();
+        format!("(paint: {paint}, thickness: {width}pt{dash})")
+    }
+
+    fn line(&mut self, a: Point, b: Point, stroke: String)
+    {
+        let (a, b) = (self.pt(a), self.pt(b));
+        self.elements.push(Element::Line(a, b, stroke));
+    }
+
+    fn curve(&mut self, a: Point, b: Point, bend: i32, stroke: String)
+    {
+        if bend == 0 {
+            self.line(a, b, stroke);
+            return;
+        }
+        // Offset the control point perpendicular to the chord, as TikZ's `bend left` does
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let offset = (15.0 * bend as f32).to_radians().tan() / 2.0;
+        let control = Point::new(
+            dy.mul_add(-offset, (a.x + b.x) / 2.0),
+            dx.mul_add(offset, (a.y + b.y) / 2.0),
+        );
+        let (a, b, c) = (self.pt(a), self.pt(b), self.pt(control));
+        self.elements.push(Element::Quad(a, c, b, stroke));
+    }
+
+    fn edge_strokes(&self, real: bool) -> Vec<String>
+    {
+        let (stroke, width) = (&self.style.stroke, self.style.stroke_width);
+        if real {
+            // Mimic TikZ's double lines: a wide stroke overdrawn by a narrow white one
+            vec![
+                self.stroke(stroke, 3.0 * width, None),
+                self.stroke("white", width, None),
+            ]
+        } else {
+            vec![self.stroke(stroke, width, None)]
+        }
+    }
+
+    /// Arrowhead at the middle of a face edge and its index just outside the face
+    fn edge_marker(&mut self, face: &FaceLayout, edge: &EdgeLayout, id: usize)
+    {
+        let (a, b) = (
+            face.nodes[edge.source].position,
+            face.nodes[edge.target].position,
+        );
+        let mid = Point::new((a.x + b.x) / 2.0, (a.y + b.y) / 2.0);
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        let norm = dx.hypot(dy);
+        if norm == 0.0 {
+            return;
+        }
+        let sign = if edge.forward { 1.0 } else { -1.0 };
+        let size = 0.08;
+        let (ux, uy) = (sign * dx / norm * size, sign * dy / norm * size);
+
+        let corners = [
+            Point::new(mid.x + ux, mid.y + uy),
+            Point::new(uy.mul_add(0.6, mid.x - ux), ux.mul_add(-0.6, mid.y - uy)),
+            Point::new(uy.mul_add(-0.6, mid.x - ux), ux.mul_add(0.6, mid.y - uy)),
+        ];
+        let corners = corners.into_iter().map(|p| self.pt(p)).collect();
+        let fill = self.style.stroke.clone();
+        self.elements.push(Element::Polygon(corners, fill));
+
+        // Push the label away from the center of the face
+        let (ox, oy) = (mid.x - face.center.x, mid.y - face.center.y);
+        let dist = ox.hypot(oy).max(f32::EPSILON);
+        let label_pos = Point::new(
+            (ox / dist).mul_add(0.2, mid.x),
+            (oy / dist).mul_add(0.2, mid.y),
+        );
+        self.text(label_pos, &id.to_string(), "black");
+    }
+
+    fn text(&mut self, p: Point, label: &str, color: &str)
+    {
+        let p = self.pt(p);
+        self.elements
+            .push(Element::Text(p, label.to_owned(), color.to_owned()));
+    }
+}
+
+impl RenderBackend for TypstBackend
+{
+    type Output = String;
+
+    fn draw_face(&mut self, face: &FaceLayout)
+    {
+        // Fill first so that the boundary and labels are drawn on top
+        if let Some(color) = face
+            .color
+            .and_then(|c| {
+                self.style
+                    .fill_palette
+                    .get(c % self.style.fill_palette.len().max(1))
+            })
+            .cloned()
+        {
+            let corners = face.nodes.iter().map(|n| self.pt(n.position)).collect();
+            self.elements.push(Element::Polygon(corners, color));
+        }
+
+        for spoke in &face.spokes {
+            let dash = match spoke.kind {
+                SpokeKind::PosReal => &self.style.pos_real_dash,
+                SpokeKind::NegReal => &self.style.neg_real_dash,
+            };
+            let stroke = self.stroke(&self.style.stroke, self.style.stroke_width, Some(dash));
+            self.line(face.nodes[spoke.node].position, face.center, stroke);
+        }
+
+        for edge in &face.edges {
+            for stroke in self.edge_strokes(edge.real) {
+                self.line(
+                    face.nodes[edge.source].position,
+                    face.nodes[edge.target].position,
+                    stroke,
+                );
+            }
+            if let Some(id) = edge.id.filter(|_| self.edge_ids) {
+                self.edge_marker(face, edge, id);
+            }
+        }
+
+        self.text(face.center, &face.label, "black");
+        for node in &face.nodes {
+            self.text(node.position, &node.label, "black");
+        }
+    }
+
+    fn draw_graph(&mut self, graph: &GraphLayout)
+    {
+        for edge in &graph.edges {
+            for stroke in self.edge_strokes(edge.real) {
+                self.curve(
+                    graph.nodes[edge.source].position,
+                    graph.nodes[edge.target].position,
+                    edge.bend,
+                    stroke,
+                );
+            }
+        }
+
+        for node in &graph.nodes {
+            self.text(node.position, &node.label, "black");
+        }
+    }
+
+    fn draw_dual_graph(&mut self, graph: &GraphLayout)
+    {
+        let color = self.style.dual_stroke.clone();
+        let stroke = self.stroke(&color, self.style.stroke_width, Some(&self.style.dual_dash));
+
+        for edge in &graph.edges {
+            let (a, b) = (
+                graph.nodes[edge.source].position,
+                graph.nodes[edge.target].position,
+            );
+            if edge.source == edge.target {
+                // Draw loops as small circles sitting on top of the node
+                let r = 0.15 * (edge.bend.abs() + 1) as f32;
+                let c = self.pt(Point::new(a.x, a.y + r));
+                // Include the top of the circle in the extent of the drawing
+                self.pt(Point::new(a.x, 2.0f32.mul_add(r, a.y)));
+                let r = r * self.style.scale;
+                self.elements.push(Element::Circle(c, r, stroke.clone()));
+            } else {
+                self.curve(a, b, edge.bend, stroke.clone());
+            }
+        }
+
+        for node in &graph.nodes {
+            self.text(node.position, &node.label, &color);
+        }
+    }
+
+    fn finish(self) -> String
+    {
+        let margin = self.style.margin;
+        let (min, max) = if self.elements.is_empty() {
+            (Point::default(), Point::default())
+        } else {
+            (self.min, self.max)
+        };
+        let width = 2.0f32.mul_add(margin, max.x - min.x);
+        let height = 2.0f32.mul_add(margin, max.y - min.y);
+        // Coordinates relative to the top left corner of the box
+        let at = |p: Point| {
+            format!(
+                "({:.2}pt, {:.2}pt)",
+                p.x - min.x + margin,
+                p.y - min.y + margin
+            )
+        };
+
+        let mut lines = vec![format!(
+            "#box(width: {width:.2}pt, height: {height:.2}pt, {{"
+        )];
+        for element in self.elements {
+            let shape = match element {
+                Element::Line(a, b, stroke) => format!(
+                    "place(line(start: {}, end: {}, stroke: {stroke}))",
+                    at(a),
+                    at(b)
+                ),
+                Element::Quad(a, c, b, stroke) => format!(
+                    "place(curve(stroke: {stroke}, curve.move({}), curve.quad({}, {})))",
+                    at(a),
+                    at(c),
+                    at(b)
+                ),
+                Element::Polygon(corners, fill) => {
+                    let corners: Vec<String> = corners.into_iter().map(at).collect();
+                    format!(
+                        "place(polygon(fill: {fill}, stroke: none, {}))",
+                        corners.join(", ")
+                    )
+                }
+                Element::Circle(c, r, stroke) => format!(
+                    "place(dx: {:.2}pt, dy: {:.2}pt, circle(radius: {r:.2}pt, stroke: {stroke}))",
+                    c.x - r - min.x + margin,
+                    c.y - r - min.y + margin
+                ),
+                // Centered on the point, by offsetting from the center of the box
+                Element::Text(p, label, color) => format!(
+                    "place(center + horizon, dx: {:.2}pt, dy: {:.2}pt, text(size: {}pt, fill: \
+                     {color}, {}))",
+                    p.x - min.x + margin - width / 2.0,
+                    p.y - min.y + margin - height / 2.0,
+                    self.style.font_size,
+                    quote(&label)
+                ),
+            };
+            lines.push(format!("  {shape}"));
+        }
+        lines.push("})".to_owned());
+        lines.join("\n")
+    }
+}
+
+pub struct TypstRenderer<V, F>
+{
+    edges: Vec<Edge<V>>,
+    faces: Vec<Face<Aug<V>, F>>,
+    style: TypstStyle,
+    coloring: FaceColoring,
+    placement: GraphPlacement,
+    identify_edges: bool,
+    standalone: bool,
+}
+
+impl<V, F> TypstRenderer<V, F>
+where
+    V: Display,
+    F: Display,
+{
+    #[must_use]
+    pub fn new(faces: Vec<Face<Aug<V>, F>>) -> Self
+    {
+        Self {
+            edges: Vec::new(),
+            faces,
+            style: TypstStyle::default(),
+            coloring: FaceColoring::None,
+            placement: GraphPlacement::Circle,
+            identify_edges: false,
+            standalone: false,
+        }
+    }
+
+    #[must_use]
+    pub fn with_style(mut self, style: TypstStyle) -> Self
+    {
+        self.style = style;
+        self
+    }
+
+    #[must_use]
+    pub fn with_edges(mut self, edges: Vec<Edge<V>>) -> Self
+    {
+        self.edges = edges;
+        self
+    }
+
+    /// Fill faces according to the given rule, using colors from `TypstStyle::fill_palette`
+    #[must_use]
+    pub const fn with_coloring(mut self, coloring: FaceColoring) -> Self
+    {
+        self.coloring = coloring;
+        self
+    }
+
+    /// Choose how vertices are placed by `draw_graph`
+    #[must_use]
+    pub const fn with_placement(mut self, placement: GraphPlacement) -> Self
+    {
+        self.placement = placement;
+        self
+    }
+
+    /// Label every edge with its index in the cover and draw arrows along it, so that the
+    /// two copies of each edge show how the faces are glued together.
+    #[must_use]
+    pub const fn identify_edges(mut self, identify_edges: bool) -> Self
+    {
+        self.identify_edges = identify_edges;
+        self
+    }
+
+    /// Emit a whole document, with the page sized to the drawing, rather than a `box` to
+    /// include in one
+    #[must_use]
+    pub const fn standalone(mut self, standalone: bool) -> Self
+    {
+        self.standalone = standalone;
+        self
+    }
+
+    fn backend(&self) -> TypstBackend
+    {
+        TypstBackend::new(self.style.clone()).edge_ids(self.identify_edges)
+    }
+
+    fn prepare(&self, layouts: &mut [FaceLayout])
+    {
+        self.coloring.assign(layouts);
+        if self.identify_edges {
+            orient_edges(layouts);
+        }
+    }
+
+    fn finish(&self, backend: TypstBackend) -> String
+    {
+        let drawing = backend.finish();
+        if self.standalone {
+            format!("{PREAMBLE}\n{drawing}")
+        } else {
+            drawing
+        }
+    }
+
+    fn render_face(&self, face: Option<&Face<Aug<V>, F>>) -> String
+    {
+        let mut backend = self.backend();
+        if let Some(face) = face {
+            let mut layout = [layout_face(face, self.style.edge_length, Point::default())];
+            self.prepare(&mut layout);
+            backend.draw_face(&layout[0]);
+        }
+        self.finish(backend)
+    }
+
+    #[must_use]
+    pub fn draw_largest_face(self) -> String
+    {
+        let max_size = self.faces.iter().map(Face::len).max().unwrap_or_default();
+        self.render_face(self.faces.iter().find(|f| f.len() == max_size))
+    }
+
+    #[must_use]
+    pub fn draw_smallest_face(self) -> String
+    {
+        let min_size = self.faces.iter().map(Face::len).min().unwrap_or_default();
+        self.render_face(self.faces.iter().find(|f| f.len() == min_size))
+    }
+
+    /// Draw the face with the given label surrounded by every face adjacent to it, each unfolded
+    /// across a shared edge. Returns `None` if no face carries that label.
+    #[must_use]
+    pub fn draw_net(self, label: &F) -> Option<String>
+    where
+        V: Copy + Eq,
+        F: PartialEq,
+    {
+        let root = self.faces.iter().position(|f| f.label == *label)?;
+        Some(self.render_net(&faces_around_face(&self.faces, root), None))
+    }
+
+    /// Draw every face with `vertex` on its boundary, unfolded around that vertex. Returns
+    /// `None` if no face contains it.
+    #[must_use]
+    pub fn draw_vertex_star(self, vertex: V) -> Option<String>
+    where
+        V: Copy + Eq,
+    {
+        let faces = faces_around_vertex(&self.faces, vertex);
+        if faces.is_empty() {
+            return None;
+        }
+        Some(self.render_net(&faces, Some(vertex)))
+    }
+
+    fn render_net(&self, faces: &[&Face<Aug<V>, F>], pivot: Option<V>) -> String
+    where
+        V: Copy + Eq,
+    {
+        let mut layouts = layout_net(faces, self.style.edge_length, pivot);
+        self.prepare(&mut layouts);
+        let mut backend = self.backend();
+        for face in &layouts {
+            backend.draw_face(face);
+        }
+        self.finish(backend)
+    }
+
+    /// Draw the face with the given label, or return `None` if no face carries that label.
+    #[must_use]
+    pub fn draw_face_by_label(self, label: &F) -> Option<String>
+    where
+        F: PartialEq,
+    {
+        let face = self.faces.iter().find(|f| f.label == *label)?;
+        Some(self.render_face(Some(face)))
+    }
+
+    /// Draw every face, side by side.
+    #[must_use]
+    pub fn generate(self) -> String
+    {
+        self.render_faces(&self.faces)
+    }
+
+    /// Draw the `k` largest or smallest faces side by side, in order of size.
+    #[must_use]
+    pub fn draw_extreme_faces(self, k: usize, extreme: Extreme) -> String
+    {
+        self.render_faces(extreme_faces(&self.faces, k, extreme))
+    }
+
+    fn render_faces<'a, I>(&self, faces: I) -> String
+    where
+        I: IntoIterator<Item = &'a Face<Aug<V>, F>>,
+        V: 'a,
+        F: 'a,
+    {
+        let mut layouts = layout_faces(faces, self.style.edge_length);
+        self.prepare(&mut layouts);
+        let mut backend = self.backend();
+        for face in &layouts {
+            backend.draw_face(face);
+        }
+        self.finish(backend)
+    }
+
+    /// Draw the whole 1-skeleton of the cover in a single picture. Requires edges to have been
+    /// supplied via `with_edges`.
+    #[must_use]
+    pub fn draw_graph(self) -> String
+    where
+        V: Copy + Eq + Hash,
+    {
+        let mut backend = self.backend();
+        backend.draw_graph(&place_graph(
+            &self.faces,
+            &self.edges,
+            self.style.edge_length,
+            self.placement,
+        ));
+        self.finish(backend)
+    }
+
+    /// Draw the dual graph, with a node for each face and an edge across each edge of the
+    /// cover. With `overlay`, the 1-skeleton is drawn as well (requiring edges to have been
+    /// supplied via `with_edges`) and each face node sits at the centroid of its vertices.
+    #[must_use]
+    pub fn draw_dual_graph(self, overlay: bool) -> String
+    where
+        V: Copy + Eq + Hash,
+    {
+        let mut backend = self.backend();
+        let dual = if overlay {
+            let primal = place_graph(
+                &self.faces,
+                &self.edges,
+                self.style.edge_length,
+                self.placement,
+            );
+            backend.draw_graph(&primal);
+            layout_dual_graph(&self.faces, Some(&primal), self.style.edge_length)
+        } else {
+            layout_dual_graph(&self.faces, None, self.style.edge_length)
+        };
+        backend.draw_dual_graph(&dual);
+        self.finish(backend)
+    }
+}
@@ -0,0 +1,161 @@
+use crate::dynatomic_cover::DynatomicCover;
+use crate::json;
+use crate::marked_cycle_cover::MarkedCycleCover;
+use crate::types::Period;
+use wasm_bindgen::prelude::*;
+
+/// Largest period accepted from JavaScript. Past it, a cover takes minutes and gigabytes to
+/// build, which is more than a browser tab can be expected to give.
+pub const MAX_PERIOD: Period = 24;
+
+enum Inner
+{
+    Marked(MarkedCycleCover),
+    Dynatomic(DynatomicCover),
+}
+
+/// A marked cycle or dynatomic cover, built for JavaScript. Periods and counts are passed as
+/// numbers, and the cells can be read from the output of `toJson`.
+#[wasm_bindgen]
+pub struct Cover
+{
+    inner: Inner,
+}
+
+/// Check the periods of a cover to be built, so that bad input is reported to JavaScript
+/// rather than aborting the module
+fn check_periods(period: Period, crit_period: Period) -> Result<(), String>
+{
+    if !(1..=2).contains(&crit_period) {
+        return Err(format!("critical period must be 1 or 2, not {crit_period}"));
+    }
+    if !(1..=MAX_PERIOD).contains(&period) {
+        return Err(format!(
+            "period must be between 1 and {MAX_PERIOD}, not {period}"
+        ));
+    }
+    Ok(())
+}
+
+#[wasm_bindgen]
+impl Cover
+{
+    /// Build the marked cycle cover, or with `dynatomic` the dynatomic cover, of the given
+    /// period over `Per_crit_period(0)`
+    ///
+    /// # Errors
+    ///
+    /// Fails if the critical period is not 1 or 2, or the period is not between 1 and
+    /// [`MAX_PERIOD`].
+    #[wasm_bindgen(constructor)]
+    pub fn new(period: u32, crit_period: u32, dynatomic: bool) -> Result<Cover, JsError>
+    {
+        let (period, crit_period) = (Period::from(period), Period::from(crit_period));
+        check_periods(period, crit_period).map_err(|e| JsError::new(&e))?;
+        let inner = if dynatomic {
+            Inner::Dynatomic(DynatomicCover::new(period, crit_period))
+        } else {
+            Inner::Marked(MarkedCycleCover::new(period, crit_period))
+        };
+        Ok(Self { inner })
+    }
+
+    /// Read a cover back from the output of [`Self::to_json`] with its cells
+    ///
+    /// # Errors
+    ///
+    /// Fails if `text` is not such a document, or its cells do not fit together.
+    #[wasm_bindgen(js_name = fromJson)]
+    pub fn from_json(text: &str) -> Result<Cover, JsError>
+    {
+        let inner = match json::read_curve(text) {
+            Ok(curve) if curve == "Dyn" => DynatomicCover::from_json(text).map(Inner::Dynatomic),
+            Ok(_) => MarkedCycleCover::from_json(text).map(Inner::Marked),
+            Err(e) => Err(e),
+        };
+        inner
+            .map(|inner| Self { inner })
+            .map_err(|e| JsError::new(&e))
+    }
+
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn period(&self) -> u32
+    {
+        let period = match &self.inner {
+            Inner::Marked(cov) => cov.context.period,
+            Inner::Dynatomic(cov) => cov.context.period,
+        };
+        period as u32
+    }
+
+    #[wasm_bindgen(getter, js_name = critPeriod)]
+    #[must_use]
+    pub fn crit_period(&self) -> u32
+    {
+        let crit_period = match &self.inner {
+            Inner::Marked(cov) => cov.crit_period,
+            Inner::Dynatomic(cov) => cov.crit_period,
+        };
+        crit_period as u32
+    }
+
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn dynatomic(&self) -> bool
+    {
+        matches!(self.inner, Inner::Dynatomic(_))
+    }
+
+    #[wasm_bindgen(getter, js_name = numVertices)]
+    #[must_use]
+    pub fn num_vertices(&self) -> usize
+    {
+        self.counts().0
+    }
+
+    #[wasm_bindgen(getter, js_name = numEdges)]
+    #[must_use]
+    pub fn num_edges(&self) -> usize
+    {
+        self.counts().1
+    }
+
+    #[wasm_bindgen(getter, js_name = numFaces)]
+    #[must_use]
+    pub fn num_faces(&self) -> usize
+    {
+        self.counts().2
+    }
+
+    /// The genus, as a JavaScript number rather than a `BigInt`
+    #[wasm_bindgen(getter)]
+    #[must_use]
+    pub fn genus(&self) -> f64
+    {
+        self.counts().3 as f64
+    }
+
+    /// JSON summary of the cover, listing every cell if `with_cells`, in the layout of
+    /// [`crate::json::SCHEMA_VERSION`]
+    #[wasm_bindgen(js_name = toJson)]
+    #[must_use]
+    pub fn to_json(&self, with_cells: bool) -> String
+    {
+        match &self.inner {
+            Inner::Marked(cov) => cov.to_json(with_cells),
+            Inner::Dynatomic(cov) => cov.to_json(with_cells),
+        }
+    }
+}
+
+impl Cover
+{
+    fn counts(&self) -> (usize, usize, usize, i64)
+    {
+        match &self.inner {
+            Inner::Marked(cov) => cov.counts(),
+            Inner::Dynatomic(cov) => cov.counts(),
+        }
+    }
+}